@@ -0,0 +1,5 @@
+use criterion::criterion_main;
+
+mod serialize;
+
+criterion_main!(serialize::benches);
@@ -0,0 +1,142 @@
+use core::{any::TypeId, hint::black_box, time::Duration};
+
+use benches::bench;
+use bevy_asset::{AssetPath, LoadFromPath, UntypedHandle};
+use bevy_ecs::{prelude::*, reflect::AppTypeRegistry};
+use bevy_reflect::Reflect;
+use bevy_world_serialization::{
+    serde::{BinaryWorldDeserializer, WorldDeserializer},
+    DynamicWorld,
+};
+use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use serde::de::DeserializeSeed;
+
+criterion_group!(benches, serialize, deserialize);
+
+/// The number of entities in the benchmarked worlds.
+const SIZES: [u32; 3] = [100, 1000, 10000];
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct Position {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct Health(u32);
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct Label {
+    name: String,
+    tags: Vec<String>,
+}
+
+/// Builds a world with `size` entities, each with a few components.
+fn setup(size: u32) -> (DynamicWorld, AppTypeRegistry) {
+    let mut world = World::new();
+    let registry = AppTypeRegistry::default();
+    {
+        let mut registry = registry.write();
+        registry.register::<Position>();
+        registry.register::<Health>();
+        registry.register::<Label>();
+    }
+    for i in 0..size {
+        world.spawn((
+            Position {
+                x: i as f32,
+                y: 2.0 * i as f32,
+                z: -(i as f32),
+            },
+            Health(i),
+            Label {
+                name: format!("entity {i}"),
+                tags: vec!["enemy".to_string()],
+            },
+        ));
+    }
+
+    let dynamic_world = DynamicWorld::from_world_with(&world, &registry.read());
+    (dynamic_world, registry)
+}
+
+fn serialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group(bench!("serialize"));
+    group.warm_up_time(Duration::from_millis(500));
+    group.measurement_time(Duration::from_secs(4));
+
+    for size in SIZES {
+        let (dynamic_world, registry) = setup(size);
+        let registry = registry.read();
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("ron", size), &size, |b, _| {
+            b.iter(|| black_box(&dynamic_world).serialize(&registry).unwrap());
+        });
+        group.bench_with_input(BenchmarkId::new("binary", size), &size, |b, _| {
+            b.iter(|| {
+                black_box(&dynamic_world)
+                    .serialize_binary(&registry)
+                    .unwrap()
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn deserialize(c: &mut Criterion) {
+    let mut group = c.benchmark_group(bench!("deserialize"));
+    group.warm_up_time(Duration::from_millis(500));
+    group.measurement_time(Duration::from_secs(4));
+
+    for size in SIZES {
+        let (dynamic_world, registry) = setup(size);
+        let registry = registry.read();
+        let ron = dynamic_world.serialize(&registry).unwrap();
+        let binary = dynamic_world.serialize_binary(&registry).unwrap();
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("ron", size), &size, |b, _| {
+            b.iter(|| {
+                let mut deserializer = ron::Deserializer::from_str(black_box(&ron)).unwrap();
+                WorldDeserializer {
+                    type_registry: &registry,
+                    load_from_path: &mut NoHandles,
+                }
+                .deserialize(&mut deserializer)
+                .unwrap()
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("binary", size), &size, |b, _| {
+            b.iter(|| {
+                let mut deserializer = postcard::Deserializer::from_bytes(black_box(&binary));
+                BinaryWorldDeserializer {
+                    type_registry: &registry,
+                    load_from_path: &mut NoHandles,
+                }
+                .deserialize(&mut deserializer)
+                .unwrap()
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// The benchmarked worlds don't contain any asset handles.
+struct NoHandles;
+
+impl LoadFromPath for NoHandles {
+    fn load_from_path_erased(
+        &mut self,
+        _type_id: TypeId,
+        _path: AssetPath<'static>,
+    ) -> UntypedHandle {
+        unreachable!()
+    }
+}
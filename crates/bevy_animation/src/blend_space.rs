@@ -0,0 +1,443 @@
+//! Blend spaces, which blend the animations of their children according to
+//! the position of a parameter relative to the children.
+//!
+//! A blend space places each of its children at a coordinate in a 1D or 2D
+//! parameter space. Given a parameter value, such as the velocity of a
+//! character, the weight of each child is computed automatically: linearly
+//! between the two nearest children for 1D blend spaces, and with barycentric
+//! coordinates inside a triangulation of the children for 2D blend spaces.
+//!
+//! See [`AnimationNodeType::BlendSpace1d`] and
+//! [`AnimationNodeType::BlendSpace2d`] for how blend spaces are played.
+//!
+//! [`AnimationNodeType::BlendSpace1d`]: crate::graph::AnimationNodeType::BlendSpace1d
+//! [`AnimationNodeType::BlendSpace2d`]: crate::graph::AnimationNodeType::BlendSpace2d
+
+use bevy_math::Vec2;
+use bevy_reflect::{
+    prelude::{ReflectDefault, ReflectFromReflect},
+    FromReflect, PartialReflect, Reflect, ReflectRef,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use smallvec::SmallVec;
+
+use crate::graph::AnimationNodeIndex;
+
+/// The weights of the children of a blend space, as computed by
+/// [`BlendSpace1d::weights`] and [`BlendSpace2d::weights`].
+///
+/// Children that aren't included have a weight of zero.
+pub type BlendSpaceWeights = SmallVec<[(AnimationNodeIndex, f32); 3]>;
+
+/// A blend space whose children are placed along a line.
+///
+/// The weights of the children are computed by interpolating linearly between
+/// the two children surrounding the parameter. Parameters outside the range
+/// of the children are clamped to it.
+#[derive(Clone, Debug, Default, Reflect, Serialize, Deserialize)]
+#[reflect(Clone, Default)]
+pub struct BlendSpace1d {
+    /// The children of the blend space, and their positions.
+    ///
+    /// These must also be the children of the blend space node in the
+    /// animation graph.
+    pub children: Vec<(AnimationNodeIndex, f32)>,
+
+    /// Whether the playback of the children is synchronized, so that cyclic
+    /// clips of different lengths (such as walking and running) stay in phase.
+    #[serde(default)]
+    pub synchronize: bool,
+}
+
+impl BlendSpace1d {
+    /// Returns the weight of each child for the given parameter.
+    pub fn weights(&self, parameter: f32) -> BlendSpaceWeights {
+        let mut below: Option<(AnimationNodeIndex, f32)> = None;
+        let mut above: Option<(AnimationNodeIndex, f32)> = None;
+        for &(node, position) in &self.children {
+            if position <= parameter && below.is_none_or(|(_, below)| position > below) {
+                below = Some((node, position));
+            }
+            if position >= parameter && above.is_none_or(|(_, above)| position < above) {
+                above = Some((node, position));
+            }
+        }
+
+        match (below, above) {
+            (Some((below, below_position)), Some((above, above_position)))
+                if above_position > below_position =>
+            {
+                let t = (parameter - below_position) / (above_position - below_position);
+                [(below, 1.0 - t), (above, t)].into_iter().collect()
+            }
+            (Some((node, _)), _) | (None, Some((node, _))) => [(node, 1.0)].into_iter().collect(),
+            (None, None) => BlendSpaceWeights::new(),
+        }
+    }
+}
+
+/// A blend space whose children are placed on a plane.
+///
+/// The children are triangulated, and the weights of the children are the
+/// barycentric coordinates of the parameter in the triangle containing it.
+/// Parameters outside of all the triangles are moved to the closest point of
+/// the closest triangle. If all the children are on the same line, the blend
+/// space behaves like a [`BlendSpace1d`] along that line.
+#[derive(Clone, Debug, Default, Reflect, Serialize)]
+#[reflect(Clone, Default, FromReflect, from_reflect = false)]
+pub struct BlendSpace2d {
+    /// The children of the blend space, and their positions.
+    children: Vec<(AnimationNodeIndex, Vec2)>,
+
+    /// Whether the playback of the children is synchronized, so that cyclic
+    /// clips of different lengths (such as walking and running) stay in phase.
+    pub synchronize: bool,
+
+    /// The Delaunay triangulation of the children, as indices into `children`.
+    #[reflect(ignore, clone)]
+    #[serde(skip)]
+    triangles: Vec<[usize; 3]>,
+}
+
+impl BlendSpace2d {
+    /// Creates a blend space with the given children and their positions.
+    ///
+    /// These must also be the children of the blend space node in the
+    /// animation graph.
+    pub fn new(children: Vec<(AnimationNodeIndex, Vec2)>) -> Self {
+        let triangles = triangulate(children.iter().map(|(_, position)| *position));
+        Self {
+            children,
+            synchronize: false,
+            triangles,
+        }
+    }
+
+    /// Adds a child to the blend space at the given position.
+    ///
+    /// The child must also be a child of the blend space node in the animation
+    /// graph.
+    pub fn add_child(&mut self, node: AnimationNodeIndex, position: Vec2) {
+        self.children.push((node, position));
+        self.triangles = triangulate(self.children.iter().map(|(_, position)| *position));
+    }
+
+    /// Returns the children of the blend space, and their positions.
+    pub fn children(&self) -> &[(AnimationNodeIndex, Vec2)] {
+        &self.children
+    }
+
+    /// Returns the weight of each child for the given parameter.
+    pub fn weights(&self, parameter: Vec2) -> BlendSpaceWeights {
+        match self.children.as_slice() {
+            [] => return BlendSpaceWeights::new(),
+            [(node, _)] => return [(*node, 1.0)].into_iter().collect(),
+            _ => {}
+        }
+
+        if self.triangles.is_empty() {
+            return self.collinear_weights(parameter);
+        }
+
+        let position = |index: usize| self.children[index].1;
+        let mut closest = None;
+        for &triangle in &self.triangles {
+            let [a, b, c] = triangle.map(position);
+            let coordinates = closest_barycentric(parameter, a, b, c);
+            let point = a * coordinates[0] + b * coordinates[1] + c * coordinates[2];
+            let distance = point.distance_squared(parameter);
+            if closest.is_none_or(|(closest_distance, _, _)| distance < closest_distance) {
+                closest = Some((distance, triangle, coordinates));
+            }
+        }
+
+        let (_, triangle, coordinates) = closest.unwrap();
+        triangle
+            .into_iter()
+            .zip(coordinates)
+            .map(|(index, weight)| (self.children[index].0, weight))
+            .collect()
+    }
+
+    /// Computes the weights when all the children are on the same line, by
+    /// projecting everything onto that line.
+    fn collinear_weights(&self, parameter: Vec2) -> BlendSpaceWeights {
+        let origin = self.children[0].1;
+        let direction = self
+            .children
+            .iter()
+            .map(|(_, position)| *position - origin)
+            .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+            .unwrap_or_default()
+            .normalize_or_zero();
+        BlendSpace1d {
+            children: self
+                .children
+                .iter()
+                .map(|(node, position)| (*node, (*position - origin).dot(direction)))
+                .collect(),
+            synchronize: self.synchronize,
+        }
+        .weights((parameter - origin).dot(direction))
+    }
+}
+
+impl<'de> Deserialize<'de> for BlendSpace2d {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct SerializedBlendSpace2d {
+            children: Vec<(AnimationNodeIndex, Vec2)>,
+            #[serde(default)]
+            synchronize: bool,
+        }
+
+        let serialized = SerializedBlendSpace2d::deserialize(deserializer)?;
+        Ok(Self {
+            synchronize: serialized.synchronize,
+            ..Self::new(serialized.children)
+        })
+    }
+}
+
+// The triangulation isn't reflected, so it's computed again from the children.
+impl FromReflect for BlendSpace2d {
+    fn from_reflect(reflect: &dyn PartialReflect) -> Option<Self> {
+        let ReflectRef::Struct(reflect) = reflect.reflect_ref() else {
+            return None;
+        };
+        let children = FromReflect::from_reflect(reflect.field("children")?)?;
+        let synchronize = bool::from_reflect(reflect.field("synchronize")?)?;
+        Some(Self {
+            synchronize,
+            ..Self::new(children)
+        })
+    }
+}
+
+/// Returns the barycentric coordinates of the point of the triangle `abc`
+/// which is closest to `p`.
+fn closest_barycentric(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> [f32; 3] {
+    // See "Real-Time Collision Detection" by Christer Ericson, section 5.1.5.
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return [1.0, 0.0, 0.0];
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return [0.0, 1.0, 0.0];
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let v = d1 / (d1 - d3);
+        return [1.0 - v, v, 0.0];
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return [0.0, 0.0, 1.0];
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let w = d2 / (d2 - d6);
+        return [1.0 - w, 0.0, w];
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return [0.0, 1.0 - w, w];
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    [1.0 - v - w, v, w]
+}
+
+/// Computes the Delaunay triangulation of the given points with the
+/// Bowyer-Watson algorithm.
+///
+/// Returns an empty list if the points are all on the same line.
+fn triangulate(points: impl Iterator<Item = Vec2>) -> Vec<[usize; 3]> {
+    let mut points: Vec<Vec2> = points.collect();
+    let count = points.len();
+    if count < 3 {
+        return Vec::new();
+    }
+
+    // Start with a triangle containing all the points.
+    let (min, max) = points
+        .iter()
+        .fold((points[0], points[0]), |(min, max), point| {
+            (min.min(*point), max.max(*point))
+        });
+    let center = (min + max) * 0.5;
+    let size = (max - min).max_element().max(f32::EPSILON) * 20.0;
+    points.extend([
+        center + Vec2::new(-size, -size),
+        center + Vec2::new(size, -size),
+        center + Vec2::new(0.0, size),
+    ]);
+    let mut triangles = vec![[count, count + 1, count + 2]];
+
+    for (index, point) in points.iter().enumerate().take(count) {
+        let (bad, good): (Vec<_>, Vec<_>) = triangles
+            .into_iter()
+            .partition(|&triangle| in_circumcircle(*point, triangle.map(|i| points[i])));
+        triangles = good;
+
+        // The edges of the hole left by the bad triangles are the edges that
+        // belong to a single bad triangle.
+        let edges = bad.iter().flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)]);
+        for (a, b) in edges.clone() {
+            let shared = edges
+                .clone()
+                .filter(|&(c, d)| (a, b) == (c, d) || (a, b) == (d, c))
+                .count()
+                > 1;
+            if !shared {
+                triangles.push([a, b, index]);
+            }
+        }
+    }
+
+    triangles.retain(|triangle| {
+        let [a, b, c] = triangle.map(|i| points[i]);
+        triangle.iter().all(|&i| i < count) && (b - a).perp_dot(c - a).abs() > f32::EPSILON
+    });
+    triangles
+}
+
+/// Returns true if `point` is strictly inside the circumcircle of `triangle`.
+fn in_circumcircle(point: Vec2, triangle: [Vec2; 3]) -> bool {
+    let [a, b, c] = triangle.map(|vertex| vertex - point);
+    let determinant = (a.length_squared()) * b.perp_dot(c) - (b.length_squared()) * a.perp_dot(c)
+        + (c.length_squared()) * a.perp_dot(b);
+    // The sign of the determinant depends on the orientation of the triangle.
+    let orientation = (triangle[1] - triangle[0]).perp_dot(triangle[2] - triangle[0]);
+    determinant * orientation.signum() > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::Vec2;
+
+    use super::*;
+
+    fn weight(weights: &BlendSpaceWeights, node: u32) -> f32 {
+        weights
+            .iter()
+            .filter(|(index, _)| *index == AnimationNodeIndex::new(node as usize))
+            .map(|(_, weight)| *weight)
+            .sum()
+    }
+
+    #[test]
+    fn blend_space_1d_weights() {
+        let blend_space = BlendSpace1d {
+            children: vec![
+                (AnimationNodeIndex::new(3), 5.0),
+                (AnimationNodeIndex::new(1), 0.0),
+                (AnimationNodeIndex::new(2), 1.0),
+            ],
+            synchronize: false,
+        };
+
+        let weights = blend_space.weights(0.25);
+        assert_eq!(weight(&weights, 1), 0.75);
+        assert_eq!(weight(&weights, 2), 0.25);
+        assert_eq!(weight(&weights, 3), 0.0);
+
+        let weights = blend_space.weights(3.0);
+        assert_eq!(weight(&weights, 2), 0.5);
+        assert_eq!(weight(&weights, 3), 0.5);
+
+        // Exactly on a child, and outside of the range.
+        assert_eq!(weight(&blend_space.weights(1.0), 2), 1.0);
+        assert_eq!(weight(&blend_space.weights(-1.0), 1), 1.0);
+        assert_eq!(weight(&blend_space.weights(10.0), 3), 1.0);
+    }
+
+    #[test]
+    fn blend_space_2d_weights() {
+        // A cross of directions around an idle animation in the middle.
+        let blend_space = BlendSpace2d::new(vec![
+            (AnimationNodeIndex::new(1), Vec2::ZERO),
+            (AnimationNodeIndex::new(2), Vec2::X),
+            (AnimationNodeIndex::new(3), Vec2::Y),
+            (AnimationNodeIndex::new(4), Vec2::NEG_X),
+            (AnimationNodeIndex::new(5), Vec2::NEG_Y),
+        ]);
+        assert_eq!(blend_space.triangles.len(), 4);
+
+        for (node, position) in blend_space.children().to_vec() {
+            let weights = blend_space.weights(position);
+            assert!((weight(&weights, node.index() as u32) - 1.0).abs() < 1e-5);
+        }
+
+        let weights = blend_space.weights(Vec2::new(0.25, 0.5));
+        assert!((weight(&weights, 1) - 0.25).abs() < 1e-5);
+        assert!((weight(&weights, 2) - 0.25).abs() < 1e-5);
+        assert!((weight(&weights, 3) - 0.5).abs() < 1e-5);
+        let total: f32 = weights.iter().map(|(_, weight)| weight).sum();
+        assert!((total - 1.0).abs() < 1e-5);
+
+        // Outside of the triangles, the closest point is used.
+        let weights = blend_space.weights(Vec2::new(2.0, 2.0));
+        assert!((weight(&weights, 2) - 0.5).abs() < 1e-5);
+        assert!((weight(&weights, 3) - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn blend_space_2d_collinear() {
+        let mut blend_space = BlendSpace2d::default();
+        blend_space.add_child(AnimationNodeIndex::new(1), Vec2::ZERO);
+        blend_space.add_child(AnimationNodeIndex::new(2), Vec2::new(2.0, 2.0));
+        blend_space.add_child(AnimationNodeIndex::new(3), Vec2::new(1.0, 1.0));
+        assert!(blend_space.triangles.is_empty());
+
+        let weights = blend_space.weights(Vec2::new(1.5, 1.5));
+        assert!((weight(&weights, 2) - 0.5).abs() < 1e-5);
+        assert!((weight(&weights, 3) - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn blend_space_2d_deserialization_triangulates() {
+        let blend_space = BlendSpace2d::new(vec![
+            (AnimationNodeIndex::new(1), Vec2::ZERO),
+            (AnimationNodeIndex::new(2), Vec2::X),
+            (AnimationNodeIndex::new(3), Vec2::Y),
+        ]);
+        let ron = ron::to_string(&blend_space).unwrap();
+        let deserialized: BlendSpace2d = ron::from_str(&ron).unwrap();
+        assert_eq!(deserialized.triangles.len(), 1);
+    }
+
+    #[test]
+    fn blend_space_2d_from_reflect_triangulates() {
+        let mut blend_space = BlendSpace2d::new(vec![
+            (AnimationNodeIndex::new(1), Vec2::ZERO),
+            (AnimationNodeIndex::new(2), Vec2::X),
+            (AnimationNodeIndex::new(3), Vec2::Y),
+        ]);
+        blend_space.synchronize = true;
+
+        let dynamic = blend_space.to_dynamic();
+        let reflected = BlendSpace2d::from_reflect(dynamic.as_partial_reflect()).unwrap();
+        assert_eq!(reflected.triangles, blend_space.triangles);
+        assert!(reflected.synchronize);
+        let weights = reflected.weights(Vec2::new(0.25, 0.25));
+        assert!((weight(&weights, 1) - 0.5).abs() < 1e-5);
+    }
+}
@@ -19,6 +19,7 @@ use bevy_ecs::{
     system::{Res, ResMut},
     template::FromTemplate,
 };
+use bevy_math::Vec2;
 use bevy_platform::collections::HashMap;
 use bevy_reflect::{prelude::ReflectDefault, Reflect, TypePath};
 use derive_more::derive::From;
@@ -31,7 +32,10 @@ use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use thiserror::Error;
 
-use crate::{AnimationClip, AnimationTargetId};
+use crate::{
+    blend_space::{BlendSpace1d, BlendSpace2d},
+    AnimationClip, AnimationTargetId,
+};
 
 /// A graph structure that describes how animation clips are to be blended
 /// together.
@@ -44,12 +48,14 @@ use crate::{AnimationClip, AnimationTargetId};
 /// the root and blends the animations together in a bottom-up fashion to
 /// produce the final pose.
 ///
-/// There are three types of nodes: *blend nodes*, *add nodes*, and *clip
-/// nodes*, all of which can have an associated weight. Blend nodes and add
-/// nodes have no associated animation clip and combine the animations of their
-/// children according to those children's weights. Clip nodes specify an
-/// animation clip to play. When a graph is created, it starts with only a
-/// single blend node, the root node.
+/// There are four types of nodes: *blend nodes*, *add nodes*, *blend space
+/// nodes*, and *clip nodes*, all of which can have an associated weight. Blend
+/// nodes and add nodes have no associated animation clip and combine the
+/// animations of their children according to those children's weights. Blend
+/// space nodes compute the weights of their children automatically, from a
+/// parameter set on the [`AnimationPlayer`](crate::AnimationPlayer). Clip
+/// nodes specify an animation clip to play. When a graph is created, it starts
+/// with only a single blend node, the root node.
 ///
 /// For example, consider the following graph:
 ///
@@ -169,7 +175,8 @@ pub type AnimationNodeIndex = NodeIndex<u32>;
 #[derive(Clone, Reflect, Debug)]
 #[reflect(Clone)]
 pub struct AnimationGraphNode {
-    /// Animation node data specific to the type of node (clip, blend, or add).
+    /// Animation node data specific to the type of node (clip, blend, add, or
+    /// blend space).
     ///
     /// In the case of clip nodes, this contains the actual animation clip
     /// associated with the node.
@@ -204,7 +211,8 @@ pub struct AnimationGraphNode {
     pub weight: f32,
 }
 
-/// Animation node data specific to the type of node (clip, blend, or add).
+/// Animation node data specific to the type of node (clip, blend, add, or
+/// blend space).
 ///
 /// In the case of clip nodes, this contains the actual animation clip
 /// associated with the node.
@@ -235,6 +243,27 @@ pub enum AnimationNodeType {
     /// top of a running animation to produce an animation of a character
     /// attacking while running.
     Add,
+
+    /// A *1D blend space node*, which blends its children according to their
+    /// positions along a line, relative to a parameter.
+    ///
+    /// The parameter is set with
+    /// [`AnimationPlayer::set_blend_space_value`](crate::AnimationPlayer::set_blend_space_value).
+    /// Blend space nodes are played like clips: while a blend space node is
+    /// playing, the [`AnimationPlayer`](crate::AnimationPlayer) plays all of
+    /// its children (which must be clip nodes) and sets their weights, and
+    /// when it stops, its children are stopped too. The weight of the
+    /// [`ActiveAnimation`](crate::ActiveAnimation) of the blend space is
+    /// applied to the result of the blend.
+    BlendSpace1d(BlendSpace1d),
+
+    /// A *2D blend space node*, which blends its children according to their
+    /// positions on a plane, relative to a parameter.
+    ///
+    /// The parameter is set with
+    /// [`AnimationPlayer::set_blend_space_position`](crate::AnimationPlayer::set_blend_space_position).
+    /// See [`AnimationNodeType::BlendSpace1d`] for how blend spaces are played.
+    BlendSpace2d(BlendSpace2d),
 }
 
 /// An [`AssetLoader`] that can load [`AnimationGraph`]s as assets.
@@ -415,6 +444,10 @@ pub enum SerializedAnimationNodeType {
     Blend,
     /// Corresponds to [`AnimationNodeType::Add`].
     Add,
+    /// Corresponds to [`AnimationNodeType::BlendSpace1d`].
+    BlendSpace1d(BlendSpace1d),
+    /// Corresponds to [`AnimationNodeType::BlendSpace2d`].
+    BlendSpace2d(BlendSpace2d),
 }
 
 /// The type of an animation mask bitfield.
@@ -611,6 +644,88 @@ impl AnimationGraph {
         node_index
     }
 
+    /// Adds a 1D blend space node to the animation graph with the given weight
+    /// and returns its index.
+    ///
+    /// The blend space node will be placed under the supplied `parent` node.
+    /// Add children to it with [`Self::add_clip_to_blend_space_1d`].
+    pub fn add_blend_space_1d(
+        &mut self,
+        weight: f32,
+        parent: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.graph.add_node(AnimationGraphNode {
+            node_type: AnimationNodeType::BlendSpace1d(BlendSpace1d::default()),
+            mask: 0,
+            weight,
+        });
+        self.graph.add_edge(parent, node_index, ());
+        node_index
+    }
+
+    /// Adds a 2D blend space node to the animation graph with the given weight
+    /// and returns its index.
+    ///
+    /// The blend space node will be placed under the supplied `parent` node.
+    /// Add children to it with [`Self::add_clip_to_blend_space_2d`].
+    pub fn add_blend_space_2d(
+        &mut self,
+        weight: f32,
+        parent: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.graph.add_node(AnimationGraphNode {
+            node_type: AnimationNodeType::BlendSpace2d(BlendSpace2d::default()),
+            mask: 0,
+            weight,
+        });
+        self.graph.add_edge(parent, node_index, ());
+        node_index
+    }
+
+    /// Adds an [`AnimationClip`] to a 1D blend space at the given position,
+    /// and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `blend_space` isn't a 1D blend space node.
+    pub fn add_clip_to_blend_space_1d(
+        &mut self,
+        clip: Handle<AnimationClip>,
+        position: f32,
+        blend_space: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.add_clip(clip, 1.0, blend_space);
+        let AnimationNodeType::BlendSpace1d(ref mut blend_space) =
+            self.graph[blend_space].node_type
+        else {
+            panic!("{blend_space:?} is not a 1D blend space node");
+        };
+        blend_space.children.push((node_index, position));
+        node_index
+    }
+
+    /// Adds an [`AnimationClip`] to a 2D blend space at the given position,
+    /// and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `blend_space` isn't a 2D blend space node.
+    pub fn add_clip_to_blend_space_2d(
+        &mut self,
+        clip: Handle<AnimationClip>,
+        position: Vec2,
+        blend_space: AnimationNodeIndex,
+    ) -> AnimationNodeIndex {
+        let node_index = self.add_clip(clip, 1.0, blend_space);
+        let AnimationNodeType::BlendSpace2d(ref mut blend_space) =
+            self.graph[blend_space].node_type
+        else {
+            panic!("{blend_space:?} is not a 2D blend space node");
+        };
+        blend_space.add_child(node_index, position);
+        node_index
+    }
+
     /// Adds an edge from the edge `from` to `to`, making `to` a child of
     /// `from`.
     ///
@@ -782,6 +897,12 @@ impl AssetLoader for AnimationGraphAssetLoader {
                     }
                     SerializedAnimationNodeType::Blend => AnimationNodeType::Blend,
                     SerializedAnimationNodeType::Add => AnimationNodeType::Add,
+                    SerializedAnimationNodeType::BlendSpace1d(ref blend_space) => {
+                        AnimationNodeType::BlendSpace1d(blend_space.clone())
+                    }
+                    SerializedAnimationNodeType::BlendSpace2d(ref blend_space) => {
+                        AnimationNodeType::BlendSpace2d(blend_space.clone())
+                    }
                 },
                 mask: serialized_node.mask,
                 weight: serialized_node.weight,
@@ -825,6 +946,12 @@ impl TryFrom<AnimationGraph> for SerializedAnimationGraph {
                     },
                     AnimationNodeType::Blend => SerializedAnimationNodeType::Blend,
                     AnimationNodeType::Add => SerializedAnimationNodeType::Add,
+                    AnimationNodeType::BlendSpace1d(ref blend_space) => {
+                        SerializedAnimationNodeType::BlendSpace1d(blend_space.clone())
+                    }
+                    AnimationNodeType::BlendSpace2d(ref blend_space) => {
+                        SerializedAnimationNodeType::BlendSpace2d(blend_space.clone())
+                    }
                 },
             });
         }
@@ -11,10 +11,12 @@ extern crate alloc;
 
 pub mod animatable;
 pub mod animation_curves;
+pub mod blend_space;
 pub mod gltf_curves;
 pub mod graph;
 #[cfg(feature = "bevy_mesh")]
 mod morph;
+pub mod state_machine;
 pub mod transition;
 
 mod animation_event;
@@ -40,13 +42,15 @@ use crate::{
 use bevy_app::{AnimationSystems, App, Plugin, PostUpdate};
 use bevy_asset::{Asset, AssetApp, AssetEventSystems, Assets};
 use bevy_ecs::{prelude::*, resource::IsResource, world::EntityMutExcept};
-use bevy_math::FloatOrd;
+use bevy_math::{FloatOrd, Vec2};
 use bevy_platform::{collections::HashMap, hash::NoOpHash};
 use bevy_reflect::{prelude::ReflectDefault, Reflect, TypePath};
 use bevy_time::Time;
 use bevy_transform::TransformSystems;
 use bevy_utils::{PreHashMap, PreHashMapExt, TypeIdMap};
+use petgraph::Direction;
 use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 use thread_local::ThreadLocal;
 use tracing::{trace, warn};
 use uuid::Uuid;
@@ -57,14 +61,15 @@ use uuid::Uuid;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        animatable::*, animation_curves::*, graph::*, transition::*, AnimationClip,
-        AnimationPlayer, AnimationPlugin, VariableCurve,
+        animatable::*, animation_curves::*, blend_space::*, graph::*, state_machine::*,
+        transition::*, AnimationClip, AnimationPlayer, AnimationPlugin, VariableCurve,
     };
 }
 
 use crate::{
     animation_curves::AnimationCurve,
     graph::{AnimationGraph, AnimationGraphAssetLoader, AnimationNodeIndex},
+    state_machine::{advance_state_machines, AnimationStateGraph, AnimationStateGraphAssetLoader},
     transition::{advance_transitions, expire_completed_transitions},
 };
 use alloc::sync::Arc;
@@ -678,6 +683,9 @@ impl ActiveAnimation {
 #[reflect(Component, Default, Clone)]
 pub struct AnimationPlayer {
     active_animations: HashMap<AnimationNodeIndex, ActiveAnimation>,
+    /// The parameters of the blend space nodes, as set by
+    /// [`AnimationPlayer::set_blend_space_position`].
+    blend_space_parameters: HashMap<AnimationNodeIndex, Vec2>,
 }
 
 // This is needed since `#[derive(Clone)]` does not generate optimized `clone_from`.
@@ -685,11 +693,14 @@ impl Clone for AnimationPlayer {
     fn clone(&self) -> Self {
         Self {
             active_animations: self.active_animations.clone(),
+            blend_space_parameters: self.blend_space_parameters.clone(),
         }
     }
 
     fn clone_from(&mut self, source: &Self) {
         self.active_animations.clone_from(&source.active_animations);
+        self.blend_space_parameters
+            .clone_from(&source.blend_space_parameters);
     }
 }
 
@@ -930,6 +941,38 @@ impl AnimationPlayer {
     pub fn animation_mut(&mut self, animation: AnimationNodeIndex) -> Option<&mut ActiveAnimation> {
         self.active_animations.get_mut(&animation)
     }
+
+    /// Sets the parameter of the given 1D blend space node, which determines
+    /// the weights of its children.
+    pub fn set_blend_space_value(
+        &mut self,
+        blend_space: AnimationNodeIndex,
+        value: f32,
+    ) -> &mut Self {
+        self.set_blend_space_position(blend_space, Vec2::new(value, 0.0))
+    }
+
+    /// Sets the parameter of the given 2D blend space node, which determines
+    /// the weights of its children.
+    pub fn set_blend_space_position(
+        &mut self,
+        blend_space: AnimationNodeIndex,
+        position: Vec2,
+    ) -> &mut Self {
+        self.blend_space_parameters.insert(blend_space, position);
+        self
+    }
+
+    /// Returns the parameter of the given blend space node.
+    ///
+    /// For 1D blend spaces, only the `x` coordinate is used. If the parameter
+    /// hasn't been set, returns zero.
+    pub fn blend_space_position(&self, blend_space: AnimationNodeIndex) -> Vec2 {
+        self.blend_space_parameters
+            .get(&blend_space)
+            .copied()
+            .unwrap_or_default()
+    }
 }
 
 /// A system that triggers untargeted animation events for the currently-playing animations.
@@ -954,7 +997,10 @@ fn trigger_untargeted_animation_events(
                 .get(*index)
                 .and_then(|node| match &node.node_type {
                     AnimationNodeType::Clip(handle) => Some(handle),
-                    AnimationNodeType::Blend | AnimationNodeType::Add => None,
+                    AnimationNodeType::Blend
+                    | AnimationNodeType::Add
+                    | AnimationNodeType::BlendSpace1d(_)
+                    | AnimationNodeType::BlendSpace2d(_) => None,
                 })
                 .and_then(|id| clips.get(id))
             else {
@@ -993,11 +1039,102 @@ pub fn advance_animations(
 
             let AnimationPlayer {
                 ref mut active_animations,
-                ..
+                ref blend_space_parameters,
             } = *player;
 
+            // Play the children of blend spaces, and advance the ones that are
+            // synchronized.
+            let mut synchronized_nodes = SmallVec::<[AnimationNodeIndex; 8]>::new();
+            for node_index in animation_graph.graph.node_indices() {
+                let parameter = blend_space_parameters
+                    .get(&node_index)
+                    .copied()
+                    .unwrap_or_default();
+                let (weights, synchronize) = match animation_graph[node_index].node_type {
+                    AnimationNodeType::BlendSpace1d(ref blend_space) => {
+                        (blend_space.weights(parameter.x), blend_space.synchronize)
+                    }
+                    AnimationNodeType::BlendSpace2d(ref blend_space) => {
+                        (blend_space.weights(parameter), blend_space.synchronize)
+                    }
+                    _ => continue,
+                };
+                let children = animation_graph
+                    .graph
+                    .neighbors_directed(node_index, Direction::Outgoing);
+
+                let Some(mut blend_space_animation) = active_animations.get(&node_index).copied()
+                else {
+                    for child in children {
+                        active_animations.remove(&child);
+                    }
+                    continue;
+                };
+
+                let child_weight = |child| {
+                    weights
+                        .iter()
+                        .filter(|(node, _)| *node == child)
+                        .map(|(_, weight)| *weight)
+                        .sum::<f32>()
+                };
+                let child_duration = |child| match animation_graph[child].node_type {
+                    AnimationNodeType::Clip(ref clip) => {
+                        animation_clips.get(clip).map(|clip| clip.duration)
+                    }
+                    _ => None,
+                };
+
+                if !synchronize {
+                    for child in children {
+                        let child_animation = active_animations.entry(child).or_default();
+                        child_animation.weight = child_weight(child);
+                        child_animation.repeat = blend_space_animation.repeat;
+                        child_animation.speed = blend_space_animation.speed;
+                        child_animation.paused = blend_space_animation.paused;
+                    }
+                    continue;
+                }
+
+                // The children of synchronized blend spaces are all played at
+                // the same normalized time, which the active animation of the
+                // blend space keeps track of. A cycle lasts for the weighted
+                // average of the durations of the children.
+                let (weighted_duration, total_weight) = children
+                    .clone()
+                    .filter_map(|child| Some((child_duration(child)?, child_weight(child))))
+                    .fold((0.0, 0.0), |(duration, total), (child_duration, weight)| {
+                        (duration + child_duration * weight, total + weight)
+                    });
+                if total_weight > 0.0 && weighted_duration > 0.0 && !blend_space_animation.paused {
+                    let cycle_duration = weighted_duration / total_weight;
+                    blend_space_animation.update(delta_seconds / cycle_duration, 1.0);
+                    active_animations.insert(node_index, blend_space_animation);
+                }
+
+                for child in children {
+                    let duration = child_duration(child).unwrap_or_default();
+                    active_animations.insert(
+                        child,
+                        ActiveAnimation {
+                            weight: child_weight(child),
+                            elapsed: blend_space_animation.elapsed * duration,
+                            seek_time: blend_space_animation.seek_time * duration,
+                            last_seek_time: blend_space_animation
+                                .last_seek_time
+                                .map(|seek_time| seek_time * duration),
+                            ..blend_space_animation
+                        },
+                    );
+                    synchronized_nodes.push(child);
+                }
+            }
+
             for node_index in animation_graph.graph.node_indices() {
                 let node = &animation_graph[node_index];
+                if synchronized_nodes.contains(&node_index) {
+                    continue;
+                }
 
                 if let Some(active_animation) = active_animations.get_mut(&node_index) {
                     // Tick the animation if necessary.
@@ -1085,6 +1222,31 @@ pub fn animate_targets(
                 };
 
                 match animation_graph_node.node_type {
+                    AnimationNodeType::BlendSpace1d(_) | AnimationNodeType::BlendSpace2d(_) => {
+                        // This is a blend space node, which blends its children
+                        // like a blend node, but is faded like a clip node.
+                        for edge_index in threaded_animation_graph.sorted_edge_ranges
+                            [animation_graph_node_index.index()]
+                        .clone()
+                        {
+                            if let Err(err) = evaluation_state.blend_all(
+                                threaded_animation_graph.sorted_edges[edge_index as usize],
+                            ) {
+                                warn!("Failed to blend animation: {:?}", err);
+                            }
+                        }
+
+                        let active_weight = animation_player
+                            .animation(animation_graph_node_index)
+                            .map_or(0.0, ActiveAnimation::weight);
+                        if let Err(err) = evaluation_state.push_blend_register_all(
+                            animation_graph_node.weight * active_weight,
+                            animation_graph_node_index,
+                        ) {
+                            warn!("Animation blending failed: {:?}", err);
+                        }
+                    }
+
                     AnimationNodeType::Blend => {
                         // This is a blend node.
                         for edge_index in threaded_animation_graph.sorted_edge_ranges
@@ -1228,14 +1390,18 @@ impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<AnimationClip>()
             .init_asset::<AnimationGraph>()
+            .init_asset::<AnimationStateGraph>()
             .init_asset_loader::<AnimationGraphAssetLoader>()
+            .init_asset_loader::<AnimationStateGraphAssetLoader>()
             .register_asset_reflect::<AnimationClip>()
             .register_asset_reflect::<AnimationGraph>()
+            .register_asset_reflect::<AnimationStateGraph>()
             .init_resource::<ThreadedAnimationGraphs>()
             .add_systems(
                 PostUpdate,
                 (
                     graph::thread_animation_graphs.before(AssetEventSystems),
+                    advance_state_machines,
                     advance_transitions,
                     advance_animations,
                     // TODO: `animate_targets` can animate anything, so
@@ -1702,4 +1868,70 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_synchronized_blend_space() {
+        use bevy_ecs::system::RunSystemOnce;
+        use core::time::Duration;
+
+        let mut clips = Assets::<AnimationClip>::default();
+        let walk = clips.add(AnimationClip {
+            duration: 1.0,
+            ..Default::default()
+        });
+        let run = clips.add(AnimationClip {
+            duration: 0.5,
+            ..Default::default()
+        });
+
+        let mut graph = AnimationGraph::new();
+        let blend_space = graph.add_blend_space_1d(1.0, graph.root);
+        let walk_node = graph.add_clip_to_blend_space_1d(walk, 1.0, blend_space);
+        let run_node = graph.add_clip_to_blend_space_1d(run, 3.0, blend_space);
+        if let AnimationNodeType::BlendSpace1d(ref mut blend_space) =
+            graph.get_mut(blend_space).unwrap().node_type
+        {
+            blend_space.synchronize = true;
+        }
+
+        let mut graphs = Assets::<AnimationGraph>::default();
+        let graph_handle = graphs.add(graph);
+
+        let mut world = World::new();
+        world.insert_resource(clips);
+        world.insert_resource(graphs);
+        world.insert_resource(Time::<()>::default());
+
+        let mut player = AnimationPlayer::default();
+        player.play(blend_space).repeat();
+        player.set_blend_space_value(blend_space, 2.0);
+        let entity = world
+            .spawn((player, AnimationGraphHandle(graph_handle)))
+            .id();
+
+        // Halfway between the clips, a cycle lasts 0.75 seconds.
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(0.375));
+        world.run_system_once(advance_animations).unwrap();
+
+        let player = world.get::<AnimationPlayer>(entity).unwrap();
+        let walk_animation = player.animation(walk_node).unwrap();
+        let run_animation = player.animation(run_node).unwrap();
+        assert_eq!(walk_animation.weight(), 0.5);
+        assert_eq!(run_animation.weight(), 0.5);
+        assert!((player.animation(blend_space).unwrap().seek_time() - 0.5).abs() < 1e-5);
+        assert!((walk_animation.seek_time() - 0.5).abs() < 1e-5);
+        assert!((run_animation.seek_time() - 0.25).abs() < 1e-5);
+
+        // Stopping the blend space stops its children.
+        world
+            .get_mut::<AnimationPlayer>(entity)
+            .unwrap()
+            .stop(blend_space);
+        world.run_system_once(advance_animations).unwrap();
+        let player = world.get::<AnimationPlayer>(entity).unwrap();
+        assert!(!player.is_playing_animation(walk_node));
+        assert!(!player.is_playing_animation(run_node));
+    }
 }
@@ -0,0 +1,838 @@
+//! Animation state machines, which choose the animation to play from an
+//! [`AnimationGraph`] according to a set of parameters.
+//!
+//! An [`AnimationStateGraph`] asset describes a set of named states, each
+//! playing a node of an [`AnimationGraph`], and the transitions between them.
+//! Transitions happen when all of their conditions, which are expressed over
+//! typed parameters (floats, bools, and triggers), are met. An
+//! [`AnimationStateMachine`] component placed on the same entity as the
+//! [`AnimationPlayer`] evaluates the transitions every frame and plays the
+//! animations of the current state, cross-fading between states with an
+//! [`AnimationTransitions`] component.
+//!
+//! [`AnimationGraph`]: crate::graph::AnimationGraph
+
+use core::{fmt::Write, time::Duration};
+use std::io;
+
+use bevy_asset::{io::Reader, Asset, AssetId, AssetLoader, Assets, Handle, LoadContext};
+use bevy_ecs::{
+    component::Component,
+    reflect::ReflectComponent,
+    system::{Local, Query, Res},
+};
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_reflect::{prelude::ReflectDefault, Reflect, TypePath};
+use bevy_time::Time;
+use ron::de::SpannedError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+use crate::{graph::AnimationNodeIndex, transition::AnimationTransitions, AnimationPlayer};
+
+/// A state machine describing which node of an
+/// [`AnimationGraph`](crate::graph::AnimationGraph) to play, and when to move
+/// from one to another.
+///
+/// The state machine starts in the [`initial_state`](Self::initial_state).
+/// Every frame, the [`transitions`](Self::transitions) leaving the current
+/// state are checked in order, and the first one whose conditions are all met
+/// is taken. Transitions without a source state can be taken from any state
+/// other than their destination, and are checked before the others.
+///
+/// Animation state graphs are assets and can be serialized to and loaded from
+/// [RON] files. Canonically, such files have an `.animstates.ron` extension,
+/// and are kept alongside the `.animgraph.ron` file of the animation graph
+/// they refer to.
+///
+/// [RON]: https://github.com/ron-rs/ron
+#[derive(Asset, Reflect, Clone, Debug, Default, Serialize, Deserialize)]
+#[reflect(Debug, Clone, Default)]
+pub struct AnimationStateGraph {
+    /// The states of the state machine.
+    pub states: Vec<AnimationState>,
+
+    /// The state that the state machine starts in.
+    pub initial_state: AnimationStateIndex,
+
+    /// The transitions between states, in the order they are checked.
+    pub transitions: Vec<AnimationStateTransition>,
+
+    /// The parameters used by the conditions of the transitions, along with
+    /// their default values.
+    ///
+    /// [`AnimationStateMachine`]s start with these values, and can override
+    /// them with [`AnimationStateMachine::set_parameter`] and its helpers.
+    #[serde(default)]
+    pub parameters: HashMap<String, AnimationParameter>,
+}
+
+/// The index of a state in an [`AnimationStateGraph`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+#[reflect(Debug, Clone, Default, PartialEq, Hash)]
+pub struct AnimationStateIndex(pub u32);
+
+/// A single state in an [`AnimationStateGraph`].
+#[derive(Reflect, Clone, Debug, Serialize, Deserialize)]
+#[reflect(Debug, Clone)]
+pub struct AnimationState {
+    /// The name of the state.
+    pub name: String,
+
+    /// The node of the animation graph that is played while in this state.
+    pub node: AnimationNodeIndex,
+
+    /// The playback speed of the animation.
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+
+    /// Whether the animation repeats while in this state.
+    #[serde(default = "default_looping")]
+    pub looping: bool,
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+fn default_looping() -> bool {
+    true
+}
+
+/// A transition between two states of an [`AnimationStateGraph`].
+#[derive(Reflect, Clone, Debug, Serialize, Deserialize)]
+#[reflect(Debug, Clone)]
+pub struct AnimationStateTransition {
+    /// The state this transition leaves from, or `None` if it can be taken
+    /// from any state.
+    #[serde(default)]
+    pub from: Option<AnimationStateIndex>,
+
+    /// The state this transition leads to.
+    pub to: AnimationStateIndex,
+
+    /// The conditions that must all be met for the transition to be taken.
+    ///
+    /// A transition without conditions is taken as soon as its exit time has
+    /// been reached.
+    #[serde(default)]
+    pub conditions: Vec<AnimationCondition>,
+
+    /// The time, in seconds since entering the source state, before which the
+    /// transition can't be taken.
+    ///
+    /// If this is `None`, the transition can be taken at any time.
+    #[serde(default)]
+    pub exit_time: Option<f32>,
+
+    /// The time, in seconds, over which the animation of the source state is
+    /// faded out.
+    #[serde(default)]
+    pub blend_duration: f32,
+}
+
+/// The value of a parameter of an [`AnimationStateMachine`].
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum AnimationParameter {
+    /// A floating-point parameter, such as the speed of a character.
+    Float(f32),
+    /// A boolean parameter, such as whether a character is on the ground.
+    Bool(bool),
+    /// A trigger, such as a character being asked to jump.
+    ///
+    /// Triggers stay set until a transition that depends on them is taken,
+    /// at which point they are reset.
+    Trigger(bool),
+}
+
+/// A condition over the parameters of an [`AnimationStateMachine`], which must
+/// be met for an [`AnimationStateTransition`] to be taken.
+///
+/// Conditions referring to parameters that don't exist, or that have a
+/// different type, are never met.
+#[derive(Reflect, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum AnimationCondition {
+    /// The float parameter is strictly greater than `value`.
+    Greater {
+        /// The name of the parameter.
+        parameter: String,
+        /// The value to compare the parameter with.
+        value: f32,
+    },
+    /// The float parameter is strictly less than `value`.
+    Less {
+        /// The name of the parameter.
+        parameter: String,
+        /// The value to compare the parameter with.
+        value: f32,
+    },
+    /// The bool parameter is equal to `value`.
+    Bool {
+        /// The name of the parameter.
+        parameter: String,
+        /// The value to compare the parameter with.
+        value: bool,
+    },
+    /// The trigger with the given name is set.
+    Trigger(String),
+}
+
+/// An [`AssetLoader`] that can load [`AnimationStateGraph`]s as assets.
+///
+/// The canonical extension for [`AnimationStateGraph`]s is `.animstates.ron`.
+/// Plain `.animstates` is supported as well.
+#[derive(Default, TypePath)]
+pub struct AnimationStateGraphAssetLoader;
+
+/// Errors that can occur when deserializing animation state graphs from RON.
+#[derive(Error, Debug)]
+pub enum AnimationStateGraphLoadError {
+    /// An I/O error occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error occurred in RON deserialization.
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+    /// An error occurred in RON deserialization, and the location of the error
+    /// is supplied.
+    #[error(transparent)]
+    SpannedRon(#[from] SpannedError),
+    /// The state graph referred to a state that doesn't exist.
+    #[error("The AnimationStateGraph refers to state {0:?}, but it only has {1} states")]
+    InvalidState(AnimationStateIndex, usize),
+}
+
+impl AnimationStateGraph {
+    /// Creates a new, empty state graph.
+    ///
+    /// At least one state must be added with [`Self::add_state`] before the
+    /// graph can be used by an [`AnimationStateMachine`]. The first state added
+    /// is the initial state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a state playing the given animation graph node, and returns its
+    /// index.
+    ///
+    /// The state plays its animation at normal speed and loops it.
+    pub fn add_state(
+        &mut self,
+        name: impl Into<String>,
+        node: AnimationNodeIndex,
+    ) -> AnimationStateIndex {
+        let index = AnimationStateIndex(self.states.len() as u32);
+        self.states.push(AnimationState {
+            name: name.into(),
+            node,
+            speed: default_speed(),
+            looping: default_looping(),
+        });
+        index
+    }
+
+    /// Adds a transition from the state `from` to the state `to`, and returns
+    /// a mutable reference to it so that conditions can be added.
+    ///
+    /// Pass `None` as `from` to allow the transition to be taken from any
+    /// state.
+    pub fn add_transition(
+        &mut self,
+        from: impl Into<Option<AnimationStateIndex>>,
+        to: AnimationStateIndex,
+        blend_duration: f32,
+    ) -> &mut AnimationStateTransition {
+        self.transitions.push(AnimationStateTransition {
+            from: from.into(),
+            to,
+            conditions: Vec::new(),
+            exit_time: None,
+            blend_duration,
+        });
+        self.transitions.last_mut().unwrap()
+    }
+
+    /// Declares a parameter along with its default value.
+    pub fn add_parameter(&mut self, name: impl Into<String>, default: AnimationParameter) {
+        self.parameters.insert(name.into(), default);
+    }
+
+    /// Returns the state with the given index.
+    ///
+    /// If no state with the given index exists, returns `None`.
+    pub fn get(&self, state: AnimationStateIndex) -> Option<&AnimationState> {
+        self.states.get(state.0 as usize)
+    }
+
+    /// Returns the index of the state with the given name, if any.
+    pub fn state_by_name(&self, name: &str) -> Option<AnimationStateIndex> {
+        self.states
+            .iter()
+            .position(|state| state.name == name)
+            .map(|index| AnimationStateIndex(index as u32))
+    }
+
+    /// Returns the first transition that can be taken from the state `current`,
+    /// after having spent `time_in_state` seconds in it.
+    ///
+    /// `parameter` returns the current value of the parameter with the given
+    /// name.
+    pub fn find_transition(
+        &self,
+        current: AnimationStateIndex,
+        time_in_state: f32,
+        parameter: impl Fn(&str) -> Option<AnimationParameter>,
+    ) -> Option<&AnimationStateTransition> {
+        let any_state = self
+            .transitions
+            .iter()
+            .filter(|transition| transition.from.is_none() && transition.to != current);
+        let from_current = self
+            .transitions
+            .iter()
+            .filter(|transition| transition.from == Some(current));
+
+        any_state.chain(from_current).find(|transition| {
+            transition
+                .exit_time
+                .is_none_or(|exit_time| time_in_state >= exit_time)
+                && transition
+                    .conditions
+                    .iter()
+                    .all(|condition| condition.is_met(&parameter))
+        })
+    }
+
+    /// Serializes the state graph to the given [`Write`]r in RON format.
+    ///
+    /// If writing to a file, it can later be loaded with the
+    /// [`AnimationStateGraphAssetLoader`] to reconstruct the state graph.
+    pub fn save<W>(&self, writer: &mut W) -> Result<(), ron::Error>
+    where
+        W: Write,
+    {
+        let mut ron_serializer = ron::ser::Serializer::new(writer, None)?;
+        self.serialize(&mut ron_serializer)
+    }
+
+    /// Checks that all the states referred to by the state graph exist.
+    fn validate(&self) -> Result<(), AnimationStateGraphLoadError> {
+        let referenced = self.transitions.iter().flat_map(|transition| {
+            transition
+                .from
+                .into_iter()
+                .chain(core::iter::once(transition.to))
+        });
+        for state in core::iter::once(self.initial_state).chain(referenced) {
+            if self.get(state).is_none() {
+                return Err(AnimationStateGraphLoadError::InvalidState(
+                    state,
+                    self.states.len(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AnimationStateTransition {
+    /// Adds a condition that must be met for this transition to be taken.
+    pub fn with_condition(&mut self, condition: AnimationCondition) -> &mut Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Sets the time, in seconds since entering the source state, before which
+    /// this transition can't be taken.
+    pub fn with_exit_time(&mut self, exit_time: f32) -> &mut Self {
+        self.exit_time = Some(exit_time);
+        self
+    }
+}
+
+impl AnimationCondition {
+    /// Returns true if the condition is met, given a function returning the
+    /// current value of each parameter.
+    pub fn is_met(&self, parameter: impl Fn(&str) -> Option<AnimationParameter>) -> bool {
+        match self {
+            AnimationCondition::Greater {
+                parameter: name,
+                value,
+            } => matches!(parameter(name), Some(AnimationParameter::Float(x)) if x > *value),
+            AnimationCondition::Less {
+                parameter: name,
+                value,
+            } => matches!(parameter(name), Some(AnimationParameter::Float(x)) if x < *value),
+            AnimationCondition::Bool {
+                parameter: name,
+                value,
+            } => parameter(name) == Some(AnimationParameter::Bool(*value)),
+            AnimationCondition::Trigger(name) => {
+                parameter(name) == Some(AnimationParameter::Trigger(true))
+            }
+        }
+    }
+}
+
+impl AssetLoader for AnimationStateGraphAssetLoader {
+    type Asset = AnimationStateGraph;
+
+    type Settings = ();
+
+    type Error = AnimationStateGraphLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)?;
+        let state_graph = AnimationStateGraph::deserialize(&mut deserializer)
+            .map_err(|err| deserializer.span_error(err))?;
+        state_graph.validate()?;
+        Ok(state_graph)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["animstates", "animstates.ron"]
+    }
+}
+
+/// Plays the animations of an [`AnimationStateGraph`] on the
+/// [`AnimationPlayer`] of the same entity.
+///
+/// To use this component, place it on the same entity as the
+/// [`AnimationPlayer`] and [`AnimationGraphHandle`](crate::AnimationGraphHandle)
+/// whose nodes the states refer to. It takes control of the
+/// [`AnimationTransitions`] component (which it requires) to cross-fade
+/// between states, so animations shouldn't be played on that entity by other
+/// means.
+///
+/// Drive the state machine by setting its parameters, for example from the
+/// movement of a character:
+///
+/// ```
+/// # use bevy_animation::state_machine::AnimationStateMachine;
+/// # use bevy_ecs::system::Query;
+/// fn update_animation_parameters(mut state_machines: Query<&mut AnimationStateMachine>) {
+///     for mut state_machine in &mut state_machines {
+///         state_machine.set_float("speed", 2.5);
+///         state_machine.set_bool("grounded", true);
+///     }
+/// }
+/// ```
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default, Clone)]
+#[require(AnimationTransitions)]
+pub struct AnimationStateMachine {
+    /// The state graph to follow.
+    pub graph: Handle<AnimationStateGraph>,
+    /// The values of the parameters which have been set, overriding the
+    /// defaults of the state graph.
+    parameters: HashMap<String, AnimationParameter>,
+    /// The current state, or `None` if the state machine hasn't started yet.
+    current_state: Option<AnimationStateIndex>,
+    /// The time, in seconds, spent in the current state.
+    time_in_state: f32,
+}
+
+impl AnimationStateMachine {
+    /// Creates a new state machine following the given state graph.
+    pub fn new(graph: Handle<AnimationStateGraph>) -> Self {
+        Self {
+            graph,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the current state, or `None` if the state machine hasn't
+    /// started yet (for example because its state graph is still loading).
+    pub fn current_state(&self) -> Option<AnimationStateIndex> {
+        self.current_state
+    }
+
+    /// Returns the time, in seconds, spent in the current state.
+    pub fn time_in_state(&self) -> f32 {
+        self.time_in_state
+    }
+
+    /// Returns the value of the given parameter, if it has been set.
+    ///
+    /// This doesn't include the default values of the state graph.
+    pub fn parameter(&self, name: &str) -> Option<AnimationParameter> {
+        self.parameters.get(name).copied()
+    }
+
+    /// Sets the value of the given parameter.
+    pub fn set_parameter(&mut self, name: impl Into<String>, value: AnimationParameter) {
+        self.parameters.insert(name.into(), value);
+    }
+
+    /// Sets the value of the given float parameter.
+    pub fn set_float(&mut self, name: impl Into<String>, value: f32) {
+        self.set_parameter(name, AnimationParameter::Float(value));
+    }
+
+    /// Sets the value of the given bool parameter.
+    pub fn set_bool(&mut self, name: impl Into<String>, value: bool) {
+        self.set_parameter(name, AnimationParameter::Bool(value));
+    }
+
+    /// Sets the given trigger, which stays set until a transition depending on
+    /// it is taken.
+    pub fn set_trigger(&mut self, name: impl Into<String>) {
+        self.set_parameter(name, AnimationParameter::Trigger(true));
+    }
+
+    /// Resets the given trigger, if it was set.
+    pub fn reset_trigger(&mut self, name: &str) {
+        if let Some(trigger @ AnimationParameter::Trigger(_)) = self.parameters.get_mut(name) {
+            *trigger = AnimationParameter::Trigger(false);
+        }
+    }
+
+    /// Plays the given state, fading out the previous one over
+    /// `blend_duration`.
+    fn enter_state(
+        &mut self,
+        graph: &AnimationStateGraph,
+        state_index: AnimationStateIndex,
+        blend_duration: Duration,
+        transitions: &mut AnimationTransitions,
+        player: &mut AnimationPlayer,
+    ) {
+        let Some(state) = graph.get(state_index) else {
+            return;
+        };
+
+        self.current_state = Some(state_index);
+        self.time_in_state = 0.0;
+
+        let animation = transitions.play(player, state.node, blend_duration);
+        animation.replay();
+        animation.set_speed(state.speed);
+        if state.looping {
+            animation.repeat();
+        }
+    }
+}
+
+/// A system that evaluates the transitions of every [`AnimationStateMachine`],
+/// and plays the animations of the new states.
+///
+/// State machines whose state graph has an initial state that doesn't exist
+/// don't start, and a warning is logged once per state graph.
+pub fn advance_state_machines(
+    mut query: Query<(
+        &mut AnimationStateMachine,
+        &mut AnimationTransitions,
+        &mut AnimationPlayer,
+    )>,
+    state_graphs: Res<Assets<AnimationStateGraph>>,
+    time: Res<Time>,
+    mut invalid_graphs: Local<HashSet<AssetId<AnimationStateGraph>>>,
+) {
+    for (mut state_machine, mut transitions, mut player) in query.iter_mut() {
+        let Some(graph) = state_graphs.get(&state_machine.graph) else {
+            continue;
+        };
+
+        let Some(current_state) = state_machine.current_state else {
+            if graph.get(graph.initial_state).is_none() {
+                if invalid_graphs.insert(state_machine.graph.id()) {
+                    warn!(
+                        "The initial state {:?} of AnimationStateGraph {:?} doesn't exist, as it \
+                        only has {} states; its state machines won't start",
+                        graph.initial_state,
+                        state_machine.graph.id(),
+                        graph.states.len(),
+                    );
+                }
+                continue;
+            }
+            state_machine.enter_state(
+                graph,
+                graph.initial_state,
+                Duration::ZERO,
+                &mut transitions,
+                &mut player,
+            );
+            continue;
+        };
+
+        state_machine.time_in_state += time.delta_secs();
+
+        let transition =
+            graph.find_transition(current_state, state_machine.time_in_state, |name| {
+                state_machine
+                    .parameters
+                    .get(name)
+                    .or_else(|| graph.parameters.get(name))
+                    .copied()
+            });
+        let Some(transition) = transition else {
+            continue;
+        };
+
+        // Consume the triggers the transition depended on.
+        for condition in &transition.conditions {
+            if let AnimationCondition::Trigger(name) = condition {
+                state_machine.set_parameter(name.clone(), AnimationParameter::Trigger(false));
+            }
+        }
+
+        state_machine.enter_state(
+            graph,
+            transition.to,
+            Duration::from_secs_f32(transition.blend_duration),
+            &mut transitions,
+            &mut player,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::Assets;
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+    use bevy_time::Time;
+    use core::time::Duration;
+
+    use super::*;
+
+    fn parameters<'a>(
+        parameters: &'a [(&str, AnimationParameter)],
+    ) -> impl Fn(&str) -> Option<AnimationParameter> + 'a {
+        |name| {
+            parameters
+                .iter()
+                .find(|(parameter, _)| *parameter == name)
+                .map(|(_, value)| *value)
+        }
+    }
+
+    /// Idle, walk and run states, with transitions depending on the speed, and
+    /// a jump state which can be entered from any state.
+    fn locomotion() -> AnimationStateGraph {
+        let mut graph = AnimationStateGraph::new();
+        let idle = graph.add_state("idle", AnimationNodeIndex::new(1));
+        let walk = graph.add_state("walk", AnimationNodeIndex::new(2));
+        let run = graph.add_state("run", AnimationNodeIndex::new(3));
+        let jump = graph.add_state("jump", AnimationNodeIndex::new(4));
+        graph.add_parameter("speed", AnimationParameter::Float(0.0));
+        graph.add_parameter("jump", AnimationParameter::Trigger(false));
+
+        graph
+            .add_transition(idle, walk, 0.2)
+            .with_condition(AnimationCondition::Greater {
+                parameter: "speed".into(),
+                value: 0.1,
+            });
+        graph
+            .add_transition(walk, run, 0.2)
+            .with_condition(AnimationCondition::Greater {
+                parameter: "speed".into(),
+                value: 3.0,
+            });
+        graph
+            .add_transition(walk, idle, 0.2)
+            .with_condition(AnimationCondition::Less {
+                parameter: "speed".into(),
+                value: 0.1,
+            });
+        graph
+            .add_transition(None, jump, 0.1)
+            .with_condition(AnimationCondition::Trigger("jump".into()));
+        graph.add_transition(jump, idle, 0.3).with_exit_time(0.5);
+        graph
+    }
+
+    #[test]
+    fn transitions_follow_conditions_in_order() {
+        let graph = locomotion();
+        let idle = graph.state_by_name("idle").unwrap();
+        let walk = graph.state_by_name("walk").unwrap();
+        let run = graph.state_by_name("run").unwrap();
+        let jump = graph.state_by_name("jump").unwrap();
+
+        let still = [("speed", AnimationParameter::Float(0.0))];
+        let fast = [("speed", AnimationParameter::Float(5.0))];
+        assert!(graph
+            .find_transition(idle, 0.0, parameters(&still))
+            .is_none());
+        assert_eq!(
+            graph
+                .find_transition(idle, 0.0, parameters(&fast))
+                .unwrap()
+                .to,
+            walk
+        );
+        assert_eq!(
+            graph
+                .find_transition(walk, 0.0, parameters(&fast))
+                .unwrap()
+                .to,
+            run
+        );
+
+        // Transitions from any state are checked first, but never to the
+        // current state.
+        let jumping = [
+            ("speed", AnimationParameter::Float(5.0)),
+            ("jump", AnimationParameter::Trigger(true)),
+        ];
+        assert_eq!(
+            graph
+                .find_transition(walk, 0.0, parameters(&jumping))
+                .unwrap()
+                .to,
+            jump
+        );
+        assert!(graph
+            .find_transition(jump, 0.0, parameters(&jumping))
+            .is_none());
+
+        // Conditions on missing or mistyped parameters are never met.
+        let mistyped = [("speed", AnimationParameter::Bool(true))];
+        assert!(graph
+            .find_transition(idle, 0.0, parameters(&mistyped))
+            .is_none());
+        assert!(graph.find_transition(idle, 0.0, parameters(&[])).is_none());
+    }
+
+    #[test]
+    fn exit_time() {
+        let graph = locomotion();
+        let idle = graph.state_by_name("idle").unwrap();
+        let jump = graph.state_by_name("jump").unwrap();
+
+        assert!(graph.find_transition(jump, 0.4, parameters(&[])).is_none());
+        assert_eq!(
+            graph
+                .find_transition(jump, 0.5, parameters(&[]))
+                .unwrap()
+                .to,
+            idle
+        );
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        let graph = locomotion();
+        let mut ron = String::new();
+        graph.save(&mut ron).unwrap();
+
+        let loaded: AnimationStateGraph = ron::from_str(&ron).unwrap();
+        loaded.validate().unwrap();
+        assert_eq!(loaded.states.len(), graph.states.len());
+        assert_eq!(loaded.transitions.len(), graph.transitions.len());
+        assert_eq!(
+            loaded.transitions[0].conditions,
+            graph.transitions[0].conditions
+        );
+        assert_eq!(loaded.parameters, graph.parameters);
+
+        let mut invalid = graph.clone();
+        invalid.add_transition(None, AnimationStateIndex(10), 0.0);
+        assert!(matches!(
+            invalid.validate(),
+            Err(AnimationStateGraphLoadError::InvalidState(
+                AnimationStateIndex(10),
+                4
+            ))
+        ));
+
+        let mut invalid_initial_state = graph;
+        invalid_initial_state.initial_state = AnimationStateIndex(4);
+        assert!(matches!(
+            invalid_initial_state.validate(),
+            Err(AnimationStateGraphLoadError::InvalidState(
+                AnimationStateIndex(4),
+                4
+            ))
+        ));
+    }
+
+    #[test]
+    fn invalid_initial_state_does_not_start() {
+        let mut world = World::new();
+        let mut state_graphs = Assets::<AnimationStateGraph>::default();
+        let mut graph = locomotion();
+        graph.initial_state = AnimationStateIndex(4);
+        let handle = state_graphs.add(graph);
+        world.insert_resource(state_graphs);
+        world.insert_resource(Time::<()>::default());
+
+        let entity = world
+            .spawn((
+                AnimationStateMachine::new(handle),
+                AnimationPlayer::default(),
+            ))
+            .id();
+        world.run_system_once(advance_state_machines).unwrap();
+
+        let state_machine = world.get::<AnimationStateMachine>(entity).unwrap();
+        assert_eq!(state_machine.current_state(), None);
+        let transitions = world.get::<AnimationTransitions>(entity).unwrap();
+        assert_eq!(transitions.get_main_animation(), None);
+    }
+
+    #[test]
+    fn state_machine_drives_player() {
+        let mut world = World::new();
+        let mut state_graphs = Assets::<AnimationStateGraph>::default();
+        let graph = locomotion();
+        let idle_node = graph.states[0].node;
+        let jump_node = graph.states[3].node;
+        let handle = state_graphs.add(graph);
+        world.insert_resource(state_graphs);
+        world.insert_resource(Time::<()>::default());
+
+        let entity = world
+            .spawn((
+                AnimationStateMachine::new(handle),
+                AnimationPlayer::default(),
+            ))
+            .id();
+        let advance = |world: &mut World| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(100));
+            world.run_system_once(advance_state_machines).unwrap();
+        };
+
+        // The state machine starts in the initial state.
+        advance(&mut world);
+        let player = world.get::<AnimationPlayer>(entity).unwrap();
+        assert!(player.is_playing_animation(idle_node));
+
+        // Triggers cause a transition, and are reset once it's taken.
+        world
+            .get_mut::<AnimationStateMachine>(entity)
+            .unwrap()
+            .set_trigger("jump");
+        advance(&mut world);
+        let state_machine = world.get::<AnimationStateMachine>(entity).unwrap();
+        assert_eq!(state_machine.current_state(), Some(AnimationStateIndex(3)));
+        assert_eq!(
+            state_machine.parameter("jump"),
+            Some(AnimationParameter::Trigger(false))
+        );
+        let transitions = world.get::<AnimationTransitions>(entity).unwrap();
+        assert_eq!(transitions.get_main_animation(), Some(jump_node));
+
+        // The jump state goes back to idle after its exit time.
+        for _ in 0..6 {
+            advance(&mut world);
+        }
+        let state_machine = world.get::<AnimationStateMachine>(entity).unwrap();
+        assert_eq!(state_machine.current_state(), Some(AnimationStateIndex(0)));
+    }
+}
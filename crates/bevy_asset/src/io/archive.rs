@@ -0,0 +1,608 @@
+//! Support for reading assets out of a single "asset archive" (pack) file.
+//!
+//! Shipping hundreds of small asset files can be slow to install and load on some platforms.
+//! An [`AssetArchive`] bundles any number of assets (and their `.meta` files) into a single blob
+//! of bytes, which can then be served through an [`ArchiveAssetReader`] as a regular
+//! [`AssetSource`](crate::io::AssetSource). See [`AssetSourceBuilder::archive`] to register one.
+//!
+//! Archives are produced with an [`AssetArchiveBuilder`], either by inserting assets manually,
+//! by walking an existing [`AssetReader`] with [`AssetArchiveBuilder::add_directory`], or
+//! directly from the output of the [`AssetProcessor`](crate::processor::AssetProcessor) with
+//! [`AssetProcessor::pack_processed_source`](crate::processor::AssetProcessor::pack_processed_source).
+//!
+//! # Format
+//!
+//! All integers are little-endian.
+//!
+//! | Field         | Type          | Description                                             |
+//! |---------------|---------------|---------------------------------------------------------|
+//! | magic         | `[u8; 8]`     | Always [`ARCHIVE_MAGIC`].                               |
+//! | version       | `u32`         | Always [`ARCHIVE_VERSION`].                             |
+//! | entry count   | `u32`         | The number of entries in the table that follows.        |
+//! | entries       | see below     | One record per asset or meta file.                      |
+//! | data          | bytes         | The contents of every entry, concatenated.              |
+//!
+//! Each entry record is laid out as follows:
+//!
+//! | Field         | Type          | Description                                             |
+//! |---------------|---------------|---------------------------------------------------------|
+//! | kind          | `u8`          | `0` for an asset, `1` for asset meta.                   |
+//! | path length   | `u32`         | The length in bytes of the path that follows.           |
+//! | path          | UTF-8 bytes   | The `/`-separated path of the asset, relative to root.  |
+//! | offset        | `u64`         | The offset of the entry's bytes in the data section.    |
+//! | length        | `u64`         | The length of the entry's bytes.                        |
+
+use crate::io::{
+    memory::Value, AssetReader, AssetReaderError, AssetSourceBuilder, ErasedAssetReader,
+    PathStream, Reader, SliceReader,
+};
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use bevy_platform::collections::HashMap;
+use core::ops::Range;
+use futures_lite::StreamExt;
+use std::path::{Component, Path};
+use thiserror::Error;
+
+/// The magic bytes at the start of every [`AssetArchive`].
+pub const ARCHIVE_MAGIC: [u8; 8] = *b"BEVYPACK";
+
+/// The version of the [`AssetArchive`] format produced by [`AssetArchiveBuilder`].
+pub const ARCHIVE_VERSION: u32 = 1;
+
+const ENTRY_KIND_ASSET: u8 = 0;
+const ENTRY_KIND_META: u8 = 1;
+
+/// An error that occurs when parsing an [`AssetArchive`].
+#[derive(Error, Debug)]
+pub enum AssetArchiveError {
+    /// The archive does not start with [`ARCHIVE_MAGIC`].
+    #[error("The bytes are not an asset archive: missing magic header")]
+    InvalidMagic,
+    /// The archive was written with a format version this reader does not understand.
+    #[error("Unsupported asset archive version {0}, expected {ARCHIVE_VERSION}")]
+    UnsupportedVersion(u32),
+    /// The archive ended in the middle of the header or entry table.
+    #[error("The asset archive is truncated")]
+    UnexpectedEof,
+    /// An entry has a kind that is neither an asset nor a meta file.
+    #[error("Entry {path} has an unknown kind {kind}")]
+    InvalidEntryKind {
+        /// The path of the entry.
+        path: String,
+        /// The invalid kind.
+        kind: u8,
+    },
+    /// An entry path is not valid UTF-8, or is not a relative path made only of file and
+    /// directory names (for example, it contains `..`).
+    #[error("An entry path in the asset archive is not valid UTF-8, or not a plain relative path")]
+    InvalidPath,
+    /// An entry's byte range lies outside the data section.
+    #[error("Entry {0} points outside of the asset archive's data")]
+    EntryOutOfBounds(String),
+    /// Encountered an I/O error while reading the archive file.
+    #[error("Encountered an I/O error while reading the asset archive: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Default, Debug)]
+struct ArchiveDir {
+    assets: HashMap<Box<str>, Range<usize>>,
+    metadata: HashMap<Box<str>, Range<usize>>,
+    dirs: HashMap<Box<str>, ArchiveDir>,
+}
+
+impl ArchiveDir {
+    fn get_dir(&self, path: &Path) -> Option<&ArchiveDir> {
+        let mut dir = self;
+        for component in path.components() {
+            match component {
+                Component::Normal(name) => {
+                    dir = dir.dirs.get(name.to_str()?)?;
+                }
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+        Some(dir)
+    }
+
+    fn get_or_insert_dir(&mut self, path: &Path) -> &mut ArchiveDir {
+        let mut dir = self;
+        for component in path.components() {
+            let name = component.as_os_str().to_string_lossy();
+            dir = dir.dirs.entry(name.into()).or_default();
+        }
+        dir
+    }
+
+    fn get_entry(&self, path: &Path, is_meta: bool) -> Option<Range<usize>> {
+        let dir = match path.parent() {
+            Some(parent) => self.get_dir(parent)?,
+            None => self,
+        };
+        let name = path.file_name()?.to_str()?;
+        let entries = if is_meta { &dir.metadata } else { &dir.assets };
+        entries.get(name).cloned()
+    }
+}
+
+/// A parsed, immutable asset archive, holding the bytes of every asset and meta file it contains.
+///
+/// See the [module-level documentation](self) for details on the format.
+pub struct AssetArchive {
+    bytes: Value,
+    root: ArchiveDir,
+}
+
+impl AssetArchive {
+    /// Parses the entry table of an archive. `bytes` can be _either_ a `&'static [u8]` (for
+    /// example from [`include_bytes`]) or a [`Vec<u8>`].
+    pub fn from_bytes(bytes: impl Into<Value>) -> Result<Self, AssetArchiveError> {
+        let bytes = bytes.into();
+        let root = parse_entries(value_bytes(&bytes))?;
+        Ok(Self { bytes, root })
+    }
+
+    /// Reads and parses the archive file at `path`, relative to the same base path that
+    /// [`FileAssetReader`](crate::io::file::FileAssetReader) uses.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AssetArchiveError> {
+        let full_path = super::file::get_base_path().join(path.as_ref());
+        let bytes = std::fs::read(full_path)?;
+        Self::from_bytes(bytes)
+    }
+
+    /// Returns the bytes of the asset stored at `path`, if it exists.
+    pub fn get_asset(&self, path: &Path) -> Option<&[u8]> {
+        self.root
+            .get_entry(path, false)
+            .map(|range| &value_bytes(&self.bytes)[range])
+    }
+
+    /// Returns the bytes of the asset meta stored for `path`, if it exists.
+    /// This _should not_ include storage specific extensions like `.meta`.
+    pub fn get_meta(&self, path: &Path) -> Option<&[u8]> {
+        self.root
+            .get_entry(path, true)
+            .map(|range| &value_bytes(&self.bytes)[range])
+    }
+
+    /// Returns true if `path` is a directory in this archive.
+    pub fn is_directory(&self, path: &Path) -> bool {
+        self.root.get_dir(path).is_some()
+    }
+}
+
+fn value_bytes(value: &Value) -> &[u8] {
+    match value {
+        Value::Vec(vec) => vec,
+        Value::Static(value) => value,
+    }
+}
+
+/// A cursor over the header and entry table of an archive.
+struct ArchiveCursor<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ArchiveCursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], AssetArchiveError> {
+        let end = self
+            .position
+            .checked_add(len)
+            .ok_or(AssetArchiveError::UnexpectedEof)?;
+        let slice = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(AssetArchiveError::UnexpectedEof)?;
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, AssetArchiveError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, AssetArchiveError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, AssetArchiveError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+fn parse_entries(bytes: &[u8]) -> Result<ArchiveDir, AssetArchiveError> {
+    let mut cursor = ArchiveCursor { bytes, position: 0 };
+    if cursor.take(ARCHIVE_MAGIC.len())? != ARCHIVE_MAGIC {
+        return Err(AssetArchiveError::InvalidMagic);
+    }
+    let version = cursor.read_u32()?;
+    if version != ARCHIVE_VERSION {
+        return Err(AssetArchiveError::UnsupportedVersion(version));
+    }
+
+    let entry_count = cursor.read_u32()?;
+    let mut entries = Vec::new();
+    for _ in 0..entry_count {
+        let kind = cursor.read_u8()?;
+        let path_len = cursor.read_u32()? as usize;
+        let path =
+            str::from_utf8(cursor.take(path_len)?).map_err(|_| AssetArchiveError::InvalidPath)?;
+        let offset = cursor.read_u64()?;
+        let len = cursor.read_u64()?;
+        entries.push((kind, path, offset, len));
+    }
+
+    let data_start = cursor.position;
+    let mut root = ArchiveDir::default();
+    for (kind, path, offset, len) in entries {
+        let range = usize::try_from(offset)
+            .ok()
+            .zip(usize::try_from(len).ok())
+            .and_then(|(offset, len)| {
+                let start = data_start.checked_add(offset)?;
+                Some(start..start.checked_add(len)?)
+            })
+            .filter(|range| range.end <= bytes.len())
+            .ok_or_else(|| AssetArchiveError::EntryOutOfBounds(path.to_string()))?;
+
+        let path = Path::new(path);
+        // Reject `..`, roots and prefixes, so entries can't escape the archive when its
+        // directories are walked or extracted.
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(AssetArchiveError::InvalidPath);
+        }
+        let Some(file_name) = path.file_name() else {
+            return Err(AssetArchiveError::InvalidPath);
+        };
+        let dir = match path.parent() {
+            Some(parent) => root.get_or_insert_dir(parent),
+            None => &mut root,
+        };
+        let entries = match kind {
+            ENTRY_KIND_ASSET => &mut dir.assets,
+            ENTRY_KIND_META => &mut dir.metadata,
+            kind => {
+                return Err(AssetArchiveError::InvalidEntryKind {
+                    path: path.display().to_string(),
+                    kind,
+                })
+            }
+        };
+        entries.insert(file_name.to_string_lossy().into(), range);
+    }
+
+    Ok(root)
+}
+
+/// An [`AssetReader`] that reads assets and their meta files out of an [`AssetArchive`].
+///
+/// This reader is cheap to clone, as the archive is shared.
+#[derive(Clone)]
+pub struct ArchiveAssetReader {
+    archive: Arc<AssetArchive>,
+}
+
+impl ArchiveAssetReader {
+    /// Creates a new [`ArchiveAssetReader`] for the given `archive`.
+    pub fn new(archive: impl Into<Arc<AssetArchive>>) -> Self {
+        Self {
+            archive: archive.into(),
+        }
+    }
+
+    /// Returns the archive this reader reads from.
+    pub fn archive(&self) -> &Arc<AssetArchive> {
+        &self.archive
+    }
+}
+
+impl AssetReader for ArchiveAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        self.archive
+            .get_asset(path)
+            .map(SliceReader::new)
+            .ok_or_else(|| AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        self.archive
+            .get_meta(path)
+            .map(SliceReader::new)
+            .ok_or_else(|| AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        let dir = self
+            .archive
+            .root
+            .get_dir(path)
+            .ok_or_else(|| AssetReaderError::NotFound(path.to_path_buf()))?;
+        let paths = dir
+            .dirs
+            .keys()
+            .chain(dir.assets.keys())
+            .map(|name| path.join(name.as_ref()))
+            .collect::<Vec<_>>();
+        let stream: Box<PathStream> = Box::new(futures_lite::stream::iter(paths));
+        Ok(stream)
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        Ok(self.archive.is_directory(path))
+    }
+}
+
+impl AssetSourceBuilder {
+    /// Returns a builder whose unprocessed and processed readers both read from `archive`.
+    ///
+    /// Archives are read-only, so the resulting source has no writers or watchers. This makes it
+    /// suitable for shipping either unprocessed assets, or the output of the
+    /// [`AssetProcessor`](crate::processor::AssetProcessor) (see
+    /// [`AssetProcessor::pack_processed_source`](crate::processor::AssetProcessor::pack_processed_source)).
+    pub fn archive(archive: impl Into<Arc<AssetArchive>>) -> Self {
+        let reader = ArchiveAssetReader::new(archive);
+        let processed_reader = reader.clone();
+        Self::new(move || Box::new(reader.clone()))
+            .with_processed_reader(move || Box::new(processed_reader.clone()))
+    }
+}
+
+/// Collects assets and meta files, and writes them out as an [`AssetArchive`].
+#[derive(Default, Debug)]
+pub struct AssetArchiveBuilder {
+    assets: BTreeMap<String, Vec<u8>>,
+    metadata: BTreeMap<String, Vec<u8>>,
+}
+
+impl AssetArchiveBuilder {
+    /// Creates a new, empty [`AssetArchiveBuilder`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the asset `bytes` at `path`, replacing any asset previously inserted there.
+    pub fn insert_asset(&mut self, path: &Path, bytes: impl Into<Vec<u8>>) {
+        self.assets.insert(archive_path(path), bytes.into());
+    }
+
+    /// Inserts the asset meta `bytes` for `path`, replacing any meta previously inserted there.
+    /// This _should not_ include storage specific extensions like `.meta`.
+    pub fn insert_meta(&mut self, path: &Path, bytes: impl Into<Vec<u8>>) {
+        self.metadata.insert(archive_path(path), bytes.into());
+    }
+
+    /// Recursively adds every asset (and its meta file, if any) found in the directory at `path`
+    /// of the given `reader`. Paths in the archive are kept relative to the root of `reader`.
+    pub async fn add_directory(
+        &mut self,
+        reader: &dyn ErasedAssetReader,
+        path: &Path,
+    ) -> Result<(), AssetReaderError> {
+        let mut directories = vec![path.to_owned()];
+        while let Some(directory) = directories.pop() {
+            let mut paths = reader.read_directory(&directory).await?;
+            while let Some(path) = paths.next().await {
+                if reader.is_directory(&path).await? {
+                    directories.push(path);
+                    continue;
+                }
+
+                let mut asset_reader = reader.read(&path).await?;
+                let mut bytes = Vec::new();
+                asset_reader.read_to_end(&mut bytes).await?;
+                self.insert_asset(&path, bytes);
+
+                match reader.read_meta_bytes(&path).await {
+                    Ok(meta) => self.insert_meta(&path, meta),
+                    Err(AssetReaderError::NotFound(_)) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes all inserted entries out in the archive format.
+    pub fn build(&self) -> Vec<u8> {
+        let entries = self
+            .assets
+            .iter()
+            .map(|(path, bytes)| (ENTRY_KIND_ASSET, path, bytes))
+            .chain(
+                self.metadata
+                    .iter()
+                    .map(|(path, bytes)| (ENTRY_KIND_META, path, bytes)),
+            )
+            .collect::<Vec<_>>();
+
+        let mut out = Vec::new();
+        out.extend_from_slice(&ARCHIVE_MAGIC);
+        out.extend_from_slice(&ARCHIVE_VERSION.to_le_bytes());
+        out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        let mut offset = 0u64;
+        for (kind, path, bytes) in &entries {
+            out.push(*kind);
+            out.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            out.extend_from_slice(path.as_bytes());
+            out.extend_from_slice(&offset.to_le_bytes());
+            out.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            offset += bytes.len() as u64;
+        }
+        for (_, _, bytes) in &entries {
+            out.extend_from_slice(bytes);
+        }
+        out
+    }
+}
+
+/// Converts `path` into the platform-independent, `/`-separated form stored in archives.
+fn archive_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ArchiveAssetReader, AssetArchive, AssetArchiveBuilder, AssetArchiveError, ARCHIVE_MAGIC,
+        ARCHIVE_VERSION, ENTRY_KIND_ASSET,
+    };
+    use crate::io::{
+        memory::{Dir, MemoryAssetReader},
+        AssetReader, AssetReaderError, Reader,
+    };
+    use alloc::{vec, vec::Vec};
+    use bevy_tasks::block_on;
+    use futures_lite::StreamExt;
+    use std::path::{Path, PathBuf};
+
+    fn read_to_vec(mut reader: impl Reader) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        block_on(reader.read_to_end(&mut bytes)).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trip_assets_and_meta() {
+        let mut builder = AssetArchiveBuilder::new();
+        builder.insert_asset(Path::new("a.txt"), b"a".to_vec());
+        builder.insert_meta(Path::new("a.txt"), b"ameta".to_vec());
+        builder.insert_asset(Path::new("x/y/b.txt"), b"b".to_vec());
+
+        let reader = ArchiveAssetReader::new(AssetArchive::from_bytes(builder.build()).unwrap());
+
+        let asset = block_on(reader.read(Path::new("a.txt"))).unwrap();
+        assert_eq!(read_to_vec(asset), b"a");
+        let meta = block_on(reader.read_meta(Path::new("a.txt"))).unwrap();
+        assert_eq!(read_to_vec(meta), b"ameta");
+        let asset = block_on(reader.read(Path::new("x/y/b.txt"))).unwrap();
+        assert_eq!(read_to_vec(asset), b"b");
+
+        assert!(matches!(
+            block_on(reader.read_meta(Path::new("x/y/b.txt"))),
+            Err(AssetReaderError::NotFound(_))
+        ));
+        assert!(matches!(
+            block_on(reader.read(Path::new("x/missing.txt"))),
+            Err(AssetReaderError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn read_directory_lists_dirs_and_assets() {
+        let mut builder = AssetArchiveBuilder::new();
+        builder.insert_asset(Path::new("x/a.txt"), b"a".to_vec());
+        builder.insert_meta(Path::new("x/a.txt"), b"ameta".to_vec());
+        builder.insert_asset(Path::new("x/y/b.txt"), b"b".to_vec());
+
+        let reader = ArchiveAssetReader::new(AssetArchive::from_bytes(builder.build()).unwrap());
+
+        assert!(block_on(reader.is_directory(Path::new(""))).unwrap());
+        assert!(block_on(reader.is_directory(Path::new("x/y"))).unwrap());
+        assert!(!block_on(reader.is_directory(Path::new("x/a.txt"))).unwrap());
+
+        let mut paths = block_on(async {
+            reader
+                .read_directory(Path::new("x"))
+                .await
+                .unwrap()
+                .collect::<Vec<_>>()
+                .await
+        });
+        paths.sort();
+        assert_eq!(paths, vec![PathBuf::from("x/a.txt"), PathBuf::from("x/y")]);
+    }
+
+    #[test]
+    fn add_directory_copies_reader_contents() {
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("a.txt"), "a");
+        dir.insert_meta_text(Path::new("a.txt"), "ameta");
+        dir.insert_asset_text(Path::new("x/b.txt"), "b");
+        let memory_reader = MemoryAssetReader { root: dir };
+
+        let mut builder = AssetArchiveBuilder::new();
+        block_on(builder.add_directory(&memory_reader, Path::new(""))).unwrap();
+        let archive = AssetArchive::from_bytes(builder.build()).unwrap();
+
+        assert_eq!(archive.get_asset(Path::new("a.txt")).unwrap(), b"a");
+        assert_eq!(archive.get_meta(Path::new("a.txt")).unwrap(), b"ameta");
+        assert_eq!(archive.get_asset(Path::new("x/b.txt")).unwrap(), b"b");
+        assert!(archive.get_meta(Path::new("x/b.txt")).is_none());
+    }
+
+    #[test]
+    fn invalid_archives_are_rejected() {
+        assert!(matches!(
+            AssetArchive::from_bytes(b"NOTAPACK\x01\0\0\0\0\0\0\0".to_vec()),
+            Err(AssetArchiveError::InvalidMagic)
+        ));
+
+        let mut bytes = ARCHIVE_MAGIC.to_vec();
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        assert!(matches!(
+            AssetArchive::from_bytes(bytes),
+            Err(AssetArchiveError::UnsupportedVersion(2))
+        ));
+
+        let mut builder = AssetArchiveBuilder::new();
+        builder.insert_asset(Path::new("a.txt"), b"abc".to_vec());
+        let mut bytes = builder.build();
+        bytes.pop();
+        assert!(matches!(
+            AssetArchive::from_bytes(bytes),
+            Err(AssetArchiveError::EntryOutOfBounds(_))
+        ));
+    }
+
+    #[test]
+    fn entries_outside_of_the_archive_are_rejected() {
+        // The builder normalizes paths, so write the entry table by hand.
+        for path in [
+            "../escape.txt",
+            "dir/../../escape.txt",
+            "/etc/escape.txt",
+            "./a.txt",
+        ] {
+            let mut bytes = ARCHIVE_MAGIC.to_vec();
+            bytes.extend_from_slice(&ARCHIVE_VERSION.to_le_bytes());
+            bytes.extend_from_slice(&1u32.to_le_bytes());
+            bytes.push(ENTRY_KIND_ASSET);
+            bytes.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(path.as_bytes());
+            bytes.extend_from_slice(&0u64.to_le_bytes());
+            bytes.extend_from_slice(&3u64.to_le_bytes());
+            bytes.extend_from_slice(b"abc");
+            assert!(
+                matches!(
+                    AssetArchive::from_bytes(bytes),
+                    Err(AssetArchiveError::InvalidPath)
+                ),
+                "{path} was accepted"
+            );
+        }
+    }
+}
@@ -0,0 +1,598 @@
+//! Asset sources that stack several "layers" of other sources on top of each other.
+//!
+//! This is useful for games that support patches, DLC or user mods: each of these can be its own
+//! layer (for example, a directory or an [`AssetArchive`](crate::io::archive::AssetArchive)), and
+//! a path like `textures/foo.png` will resolve to the file in the highest-priority layer that
+//! contains it.
+//!
+//! ```no_run
+//! # use bevy_app::App;
+//! # use bevy_asset::{AssetApp, io::{AssetSourceBuilder, AssetSourceId, overlay::OverlaySourceBuilder}};
+//! # let mut app = App::new();
+//! app.register_asset_source(
+//!     AssetSourceId::Default,
+//!     OverlaySourceBuilder::new()
+//!         .with_layer("base", 0, AssetSourceBuilder::platform_default("assets", None))
+//!         .with_layer("mods", 10, AssetSourceBuilder::platform_default("mods", None))
+//!         .build(),
+//! );
+//! ```
+
+use crate::io::{
+    AssetReader, AssetReaderError, AssetSourceBuilder, AssetSourceEvent, AssetWatcher,
+    ErasedAssetReader, PathStream, Reader,
+};
+use alloc::{boxed::Box, collections::VecDeque, sync::Arc, vec::Vec};
+use atomicow::CowArc;
+use bevy_platform::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, PoisonError, RwLock},
+};
+use futures_lite::StreamExt;
+use std::path::{Path, PathBuf};
+
+/// A single layer of an [`OverlaySourceBuilder`].
+struct OverlayLayer {
+    name: CowArc<'static, str>,
+    priority: i32,
+    builder: AssetSourceBuilder,
+}
+
+/// Builds an [`AssetSourceBuilder`] whose readers resolve each path against a stack of layers,
+/// each of which is itself described by an [`AssetSourceBuilder`].
+///
+/// Reads are served by the highest-priority layer that contains the requested path. Directory
+/// listings are merged across all layers, and watching the overlay watches every layer that has
+/// a watcher configured.
+///
+/// Overlay sources are read-only: the resulting builder has no writers. Both the unprocessed and
+/// processed readers are overlaid, using whichever layers provide them.
+#[derive(Default)]
+pub struct OverlaySourceBuilder {
+    layers: Vec<OverlayLayer>,
+}
+
+impl OverlaySourceBuilder {
+    /// Creates a new overlay without any layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a layer with the given `name` and `priority`. Layers with a higher `priority` take
+    /// precedence over layers with a lower one. Layers with the same `priority` take precedence
+    /// in the order they were added.
+    pub fn with_layer(
+        mut self,
+        name: impl Into<CowArc<'static, str>>,
+        priority: i32,
+        builder: AssetSourceBuilder,
+    ) -> Self {
+        self.layers.push(OverlayLayer {
+            name: name.into(),
+            priority,
+            builder,
+        });
+        self
+    }
+
+    /// Builds the [`AssetSourceBuilder`] for the overlay.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no layers have been added.
+    pub fn build(mut self) -> AssetSourceBuilder {
+        assert!(
+            !self.layers.is_empty(),
+            "An overlay asset source needs at least one layer"
+        );
+        // This is a stable sort, so layers of equal priority keep their insertion order.
+        self.layers.sort_by_key(|layer| -i64::from(layer.priority));
+
+        let overlay_layers = OverlayLayers {
+            names: self.layers.iter().map(|layer| layer.name.clone()).collect(),
+            served: Default::default(),
+            processed_served: Default::default(),
+        };
+        let watch_warning = self.layers[0].builder.watch_warning;
+        let processed_watch_warning = self.layers[0].builder.processed_watch_warning;
+        let has_processed_reader = self
+            .layers
+            .iter()
+            .any(|layer| layer.builder.processed_reader.is_some());
+        let builders = Arc::new(Mutex::new(
+            self.layers
+                .into_iter()
+                .map(|layer| layer.builder)
+                .collect::<Vec<_>>(),
+        ));
+
+        let reader_builders = builders.clone();
+        let served = overlay_layers.served.clone();
+        let mut source = AssetSourceBuilder::new(move || {
+            let mut builders = reader_builders
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let layers = builders
+                .iter_mut()
+                .enumerate()
+                .map(|(index, builder)| (index, builder.reader.as_mut()()))
+                .collect();
+            Box::new(OverlayAssetReader {
+                layers,
+                served: served.clone(),
+            })
+        })
+        .with_watcher(overlay_watcher(builders.clone(), |builder| {
+            builder.watcher.as_mut()
+        }))
+        .with_processed_watcher(overlay_watcher(builders.clone(), |builder| {
+            builder.processed_watcher.as_mut()
+        }));
+
+        if has_processed_reader {
+            let processed_served = overlay_layers.processed_served.clone();
+            source = source.with_processed_reader(move || {
+                let mut builders = builders.lock().unwrap_or_else(PoisonError::into_inner);
+                let layers = builders
+                    .iter_mut()
+                    .enumerate()
+                    .filter_map(|(index, builder)| {
+                        Some((index, builder.processed_reader.as_mut()?()))
+                    })
+                    .collect();
+                Box::new(OverlayAssetReader {
+                    layers,
+                    served: processed_served.clone(),
+                })
+            });
+        }
+        if let Some(warning) = watch_warning {
+            source = source.with_watch_warning(warning);
+        }
+        if let Some(warning) = processed_watch_warning {
+            source = source.with_processed_watch_warning(warning);
+        }
+        source.overlay_layers = Some(overlay_layers);
+        source
+    }
+}
+
+type WatcherBuilder = dyn FnMut(async_channel::Sender<AssetSourceEvent>) -> Option<Box<dyn AssetWatcher>>
+    + Send
+    + Sync;
+
+/// Returns a watcher builder that starts the watcher of every layer (as selected by
+/// `get_watcher`), all sending their events to the same channel.
+fn overlay_watcher(
+    builders: Arc<Mutex<Vec<AssetSourceBuilder>>>,
+    get_watcher: fn(&mut AssetSourceBuilder) -> Option<&mut Box<WatcherBuilder>>,
+) -> impl FnMut(async_channel::Sender<AssetSourceEvent>) -> Option<Box<dyn AssetWatcher>> + Send + Sync
+{
+    move |sender| {
+        let mut builders = builders.lock().unwrap_or_else(PoisonError::into_inner);
+        let watchers = builders
+            .iter_mut()
+            .filter_map(|builder| get_watcher(builder)?(sender.clone()))
+            .collect::<Vec<_>>();
+        if watchers.is_empty() {
+            None
+        } else {
+            Some(Box::new(OverlayWatcher {
+                _watchers: watchers,
+            }))
+        }
+    }
+}
+
+/// An [`AssetWatcher`] that keeps the watchers of every layer of an overlay alive.
+struct OverlayWatcher {
+    _watchers: Vec<Box<dyn AssetWatcher>>,
+}
+
+impl AssetWatcher for OverlayWatcher {}
+
+/// The number of paths for which [`OverlayLayers`] remembers the layer that served them.
+const MAX_SERVED_PATHS: usize = 4096;
+
+/// The layer that served each of the most recently read paths of an overlay.
+#[derive(Default)]
+struct ServedLayers {
+    layers: HashMap<PathBuf, usize>,
+    /// The paths in `layers`, from the least to the most recently inserted.
+    order: VecDeque<PathBuf>,
+}
+
+impl ServedLayers {
+    fn insert(&mut self, path: &Path, layer: usize) {
+        if let Some(served) = self.layers.get_mut(path) {
+            *served = layer;
+            return;
+        }
+        if self.order.len() == MAX_SERVED_PATHS
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.layers.remove(&oldest);
+        }
+        self.layers.insert(path.to_path_buf(), layer);
+        self.order.push_back(path.to_path_buf());
+    }
+}
+
+/// Describes the layers of an overlay asset source, and which layer served each asset that has
+/// been read from it.
+///
+/// Only the layers of the most recently read paths are remembered here. The layer that served a
+/// loaded asset is also kept with the asset for as long as it is loaded.
+///
+/// This is accessible through [`AssetSource::overlay_layers`](crate::io::AssetSource::overlay_layers)
+/// and [`AssetServer::get_path_overlay_layer`](crate::AssetServer::get_path_overlay_layer).
+#[derive(Clone)]
+pub struct OverlayLayers {
+    names: Arc<[CowArc<'static, str>]>,
+    served: Arc<RwLock<ServedLayers>>,
+    processed_served: Arc<RwLock<ServedLayers>>,
+}
+
+impl OverlayLayers {
+    /// Iterates the names of all layers, from highest to lowest priority.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(AsRef::as_ref)
+    }
+
+    /// Returns the name of the layer that most recently served the unprocessed asset at `path`,
+    /// if it has been read.
+    pub fn served_layer(&self, path: &Path) -> Option<&str> {
+        let index = *self
+            .served
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .layers
+            .get(path)?;
+        Some(&self.names[index])
+    }
+
+    /// Returns the name of the layer that most recently served the processed asset at `path`,
+    /// if it has been read.
+    pub fn processed_served_layer(&self, path: &Path) -> Option<&str> {
+        let index = *self
+            .processed_served
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .layers
+            .get(path)?;
+        Some(&self.names[index])
+    }
+
+    /// Returns the layer that most recently served the processed or unprocessed asset at `path`,
+    /// to be kept with the asset once it is loaded.
+    pub(crate) fn served_layer_name(
+        &self,
+        path: &Path,
+        processed: bool,
+    ) -> Option<CowArc<'static, str>> {
+        let served = if processed {
+            &self.processed_served
+        } else {
+            &self.served
+        };
+        let index = *served
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .layers
+            .get(path)?;
+        Some(self.names[index].clone())
+    }
+}
+
+/// An [`AssetReader`] that reads from the highest-priority layer that contains a given path.
+///
+/// This is generally constructed through an [`OverlaySourceBuilder`].
+pub struct OverlayAssetReader {
+    /// The readers of each layer, ordered from highest to lowest priority, alongside the index of
+    /// their layer.
+    layers: Vec<(usize, Box<dyn ErasedAssetReader>)>,
+    served: Arc<RwLock<ServedLayers>>,
+}
+
+impl AssetReader for OverlayAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        for (index, layer) in &self.layers {
+            match layer.read(path).await {
+                Ok(reader) => {
+                    self.served
+                        .write()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .insert(path, *index);
+                    return Ok(reader);
+                }
+                Err(AssetReaderError::NotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        // A layer replacing an asset without a meta file keeps the settings of a lower layer, so
+        // patches don't need to repeat the meta files of the assets they replace.
+        for (_, layer) in &self.layers {
+            match layer.read_meta(path).await {
+                Ok(reader) => return Ok(reader),
+                Err(AssetReaderError::NotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Err(AssetReaderError::NotFound(path.to_path_buf()))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        let mut found = false;
+        let mut seen = <HashSet<PathBuf>>::default();
+        let mut paths = Vec::new();
+        for (_, layer) in &self.layers {
+            let mut stream = match layer.read_directory(path).await {
+                Ok(stream) => stream,
+                Err(AssetReaderError::NotFound(_)) => continue,
+                Err(err) => return Err(err),
+            };
+            found = true;
+            while let Some(path) = stream.next().await {
+                if seen.insert(path.clone()) {
+                    paths.push(path);
+                }
+            }
+        }
+        if !found {
+            return Err(AssetReaderError::NotFound(path.to_path_buf()));
+        }
+        let stream: Box<PathStream> = Box::new(futures_lite::stream::iter(paths));
+        Ok(stream)
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        // Some readers report paths they don't contain as not being directories, so a directory
+        // in any layer must not be hidden by a higher one.
+        let mut found = false;
+        for (_, layer) in &self.layers {
+            match layer.is_directory(path).await {
+                Ok(true) => return Ok(true),
+                Ok(false) => found = true,
+                Err(AssetReaderError::NotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        if found {
+            Ok(false)
+        } else {
+            Err(AssetReaderError::NotFound(path.to_path_buf()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OverlaySourceBuilder, ServedLayers, MAX_SERVED_PATHS};
+    use crate::io::{
+        memory::{Dir, MemoryAssetReader},
+        AssetReaderError, AssetSourceBuilder, AssetSourceId,
+    };
+    use crate::{
+        tests::{run_app_until, CoolText, CoolTextLoader, SubText},
+        AssetApp, AssetPlugin, AssetServer, Assets, Handle,
+    };
+    use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+    use bevy_app::{App, TaskPoolPlugin};
+    use bevy_tasks::block_on;
+    use futures_lite::StreamExt;
+    use std::path::{Path, PathBuf};
+
+    const COOL_TEXT: &str = r#"
+(
+    text: "a",
+    dependencies: [],
+    embedded_dependencies: [],
+    sub_texts: [],
+)"#;
+
+    fn memory_layer(dir: &Dir) -> AssetSourceBuilder {
+        let reader = MemoryAssetReader { root: dir.clone() };
+        AssetSourceBuilder::new(move || Box::new(reader.clone()))
+    }
+
+    fn read_to_string(reader: &dyn crate::io::ErasedAssetReader, path: &str) -> String {
+        block_on(async {
+            let mut reader = reader.read(Path::new(path)).await.unwrap();
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await.unwrap();
+            String::from_utf8(bytes).unwrap()
+        })
+    }
+
+    #[test]
+    fn highest_priority_layer_wins() {
+        let base = Dir::default();
+        base.insert_asset_text(Path::new("a.txt"), "base a");
+        base.insert_asset_text(Path::new("b.txt"), "base b");
+        let mods = Dir::default();
+        mods.insert_asset_text(Path::new("a.txt"), "mod a");
+
+        let source = OverlaySourceBuilder::new()
+            .with_layer("mods", 10, memory_layer(&mods))
+            .with_layer("base", 0, memory_layer(&base))
+            .build()
+            .build(AssetSourceId::Default, false, false);
+        let reader = source.reader();
+
+        assert_eq!(read_to_string(reader, "a.txt"), "mod a");
+        assert_eq!(read_to_string(reader, "b.txt"), "base b");
+        assert!(matches!(
+            block_on(reader.read(Path::new("c.txt"))),
+            Err(AssetReaderError::NotFound(_))
+        ));
+
+        let layers = source.overlay_layers().unwrap();
+        assert_eq!(layers.names().collect::<Vec<_>>(), vec!["mods", "base"]);
+        assert_eq!(layers.served_layer(Path::new("a.txt")), Some("mods"));
+        assert_eq!(layers.served_layer(Path::new("b.txt")), Some("base"));
+        assert_eq!(layers.served_layer(Path::new("c.txt")), None);
+    }
+
+    #[test]
+    fn meta_falls_through_to_lower_layers() {
+        let base = Dir::default();
+        base.insert_asset_text(Path::new("a.txt"), "base a");
+        base.insert_meta_text(Path::new("a.txt"), "base meta");
+        base.insert_asset_text(Path::new("b.txt"), "base b");
+        base.insert_meta_text(Path::new("b.txt"), "base meta");
+        let mods = Dir::default();
+        mods.insert_asset_text(Path::new("a.txt"), "mod a");
+
+        let source = OverlaySourceBuilder::new()
+            .with_layer("base", 0, memory_layer(&base))
+            .with_layer("mods", 10, memory_layer(&mods))
+            .build()
+            .build(AssetSourceId::Default, false, false);
+        let reader = source.reader();
+
+        assert_eq!(
+            block_on(reader.read_meta_bytes(Path::new("a.txt"))).unwrap(),
+            b"base meta"
+        );
+        assert_eq!(
+            block_on(reader.read_meta_bytes(Path::new("b.txt"))).unwrap(),
+            b"base meta"
+        );
+        assert!(matches!(
+            block_on(reader.read_meta_bytes(Path::new("c.txt"))),
+            Err(AssetReaderError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn directories_are_found_in_lower_layers() {
+        let base = Dir::default();
+        base.insert_asset_text(Path::new("x/a.txt"), "base a");
+        let mods = Dir::default();
+        mods.insert_asset_text(Path::new("b.txt"), "mod b");
+
+        let source = OverlaySourceBuilder::new()
+            .with_layer("mods", 10, memory_layer(&mods))
+            .with_layer("base", 0, memory_layer(&base))
+            .build()
+            .build(AssetSourceId::Default, false, false);
+        let reader = source.reader();
+
+        assert!(block_on(reader.is_directory(Path::new("x"))).unwrap());
+        assert!(!block_on(reader.is_directory(Path::new("b.txt"))).unwrap());
+    }
+
+    #[test]
+    fn served_layers_are_bounded() {
+        let mut served = ServedLayers::default();
+        for i in 0..MAX_SERVED_PATHS + 1 {
+            served.insert(&PathBuf::from(format!("{i}.txt")), 0);
+        }
+        served.insert(Path::new("1.txt"), 1);
+
+        assert_eq!(served.layers.len(), MAX_SERVED_PATHS);
+        assert_eq!(served.order.len(), MAX_SERVED_PATHS);
+        assert_eq!(served.layers.get(Path::new("0.txt")), None);
+        assert_eq!(served.layers.get(Path::new("1.txt")), Some(&1));
+    }
+
+    #[test]
+    fn loaded_assets_remember_their_layer() {
+        let base = Dir::default();
+        base.insert_asset_text(Path::new("a.cool.ron"), COOL_TEXT);
+        for i in 0..MAX_SERVED_PATHS {
+            base.insert_asset_text(&PathBuf::from(format!("{i}.txt")), "filler");
+        }
+        let mods = Dir::default();
+        mods.insert_asset_text(Path::new("a.cool.ron"), COOL_TEXT);
+
+        let mut app = App::new();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            OverlaySourceBuilder::new()
+                .with_layer("mods", 10, memory_layer(&mods))
+                .with_layer("base", 0, memory_layer(&base))
+                .build(),
+        )
+        .add_plugins((
+            TaskPoolPlugin::default(),
+            AssetPlugin {
+                watch_for_changes_override: Some(false),
+                use_asset_processor_override: Some(false),
+                ..Default::default()
+            },
+        ))
+        .init_asset::<CoolText>()
+        .init_asset::<SubText>()
+        .register_asset_loader(CoolTextLoader);
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let handle: Handle<CoolText> = asset_server.load("a.cool.ron");
+        run_app_until(&mut app, |world| {
+            world
+                .resource::<Assets<CoolText>>()
+                .get(&handle)
+                .map(|_| ())
+        });
+
+        // Reading other paths pushes the asset out of the source's served layers.
+        let source = asset_server.get_source(AssetSourceId::Default).unwrap();
+        for i in 0..MAX_SERVED_PATHS {
+            read_to_string(source.reader(), &format!("{i}.txt"));
+        }
+        assert_eq!(
+            source
+                .overlay_layers()
+                .unwrap()
+                .served_layer(Path::new("a.cool.ron")),
+            None
+        );
+        assert_eq!(
+            asset_server.get_path_overlay_layer("a.cool.ron").as_deref(),
+            Some("mods")
+        );
+    }
+
+    #[test]
+    fn directories_are_merged() {
+        let base = Dir::default();
+        base.insert_asset_text(Path::new("x/a.txt"), "base a");
+        base.insert_asset_text(Path::new("x/b.txt"), "base b");
+        let mods = Dir::default();
+        mods.insert_asset_text(Path::new("x/a.txt"), "mod a");
+        mods.insert_asset_text(Path::new("x/c.txt"), "mod c");
+
+        let source = OverlaySourceBuilder::new()
+            .with_layer("mods", 10, memory_layer(&mods))
+            .with_layer("base", 0, memory_layer(&base))
+            .build()
+            .build(AssetSourceId::Default, false, false);
+        let reader = source.reader();
+
+        assert!(block_on(reader.is_directory(Path::new("x"))).unwrap());
+        let mut paths = block_on(async {
+            reader
+                .read_directory(Path::new("x"))
+                .await
+                .unwrap()
+                .collect::<Vec<_>>()
+                .await
+        });
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("x/a.txt"),
+                PathBuf::from("x/b.txt"),
+                PathBuf::from("x/c.txt")
+            ]
+        );
+    }
+}
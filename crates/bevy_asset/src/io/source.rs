@@ -1,5 +1,8 @@
 use crate::{
-    io::{processor_gated::ProcessorGatedReader, AssetSourceEvent, AssetWatcher},
+    io::{
+        overlay::OverlayLayers, processor_gated::ProcessorGatedReader, AssetSourceEvent,
+        AssetWatcher,
+    },
     processor::ProcessingState,
 };
 use alloc::{
@@ -146,6 +149,9 @@ pub struct AssetSourceBuilder {
     pub watch_warning: Option<&'static str>,
     /// The warning message to display when watching a processed asset fails.
     pub processed_watch_warning: Option<&'static str>,
+    /// The layers of this source, if it was built by an
+    /// [`OverlaySourceBuilder`](crate::io::overlay::OverlaySourceBuilder).
+    pub overlay_layers: Option<OverlayLayers>,
 }
 
 impl AssetSourceBuilder {
@@ -162,6 +168,7 @@ impl AssetSourceBuilder {
             processed_watcher: None,
             watch_warning: None,
             processed_watch_warning: None,
+            overlay_layers: None,
         }
     }
 
@@ -191,6 +198,7 @@ impl AssetSourceBuilder {
             watcher: None,
             processed_event_receiver: None,
             processed_watcher: None,
+            overlay_layers: self.overlay_layers.clone(),
         };
 
         if watch {
@@ -402,6 +410,7 @@ pub struct AssetSource {
     processed_watcher: Option<Box<dyn AssetWatcher>>,
     event_receiver: Option<async_channel::Receiver<AssetSourceEvent>>,
     processed_event_receiver: Option<async_channel::Receiver<AssetSourceEvent>>,
+    overlay_layers: Option<OverlayLayers>,
 }
 
 impl AssetSource {
@@ -464,6 +473,13 @@ impl AssetSource {
         self.processed_event_receiver.as_ref()
     }
 
+    /// Returns the layers of this source, if it is an overlay of several sources built by an
+    /// [`OverlaySourceBuilder`](crate::io::overlay::OverlaySourceBuilder).
+    #[inline]
+    pub fn overlay_layers(&self) -> Option<&OverlayLayers> {
+        self.overlay_layers.as_ref()
+    }
+
     /// Returns true if the assets in this source should be processed.
     #[inline]
     pub fn should_process(&self) -> bool {
@@ -0,0 +1,244 @@
+use crate::{
+    io::Writer,
+    meta::{AssetHash, META_FORMAT_VERSION},
+};
+use alloc::{boxed::Box, vec::Vec};
+use bevy_ecs::error::BevyError;
+use bevy_tasks::BoxedFuture;
+use core::{
+    fmt::Display,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_io::AsyncWrite;
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    alloc::{format, string::ToString},
+    std::path::PathBuf,
+};
+
+/// Identifies the output of processing an asset, independently of where that asset is stored.
+///
+/// The key is derived from the hash of the source asset and its meta (which includes the
+/// processor settings), the type path of the processor, and the processor's
+/// [`Process::VERSION`](crate::processor::Process::VERSION). Two assets with identical bytes
+/// and meta processed by the same processor will therefore share a key, even on different
+/// machines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ProcessorCacheKey(pub AssetHash);
+
+impl ProcessorCacheKey {
+    /// Computes the key for an asset with the given `asset_hash` (the hash of the asset bytes and
+    /// its meta), processed by the processor with the given type path and version.
+    pub fn new(asset_hash: AssetHash, processor_type_path: &str, processor_version: u32) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(META_FORMAT_VERSION.as_bytes());
+        hasher.update(&asset_hash);
+        hasher.update(processor_type_path.as_bytes());
+        hasher.update(&processor_version.to_le_bytes());
+        Self(*hasher.finalize().as_bytes())
+    }
+}
+
+impl Display for ProcessorCacheKey {
+    /// Formats the key as lowercase hex.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The output of processing an asset, as stored in a [`ProcessorCache`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedProcessedAsset {
+    /// The processed asset bytes.
+    pub asset: Vec<u8>,
+    /// The processed asset's meta bytes, including its
+    /// [`ProcessedInfo`](crate::meta::ProcessedInfo).
+    pub meta: Vec<u8>,
+}
+
+/// A content-addressed store of processed assets, which can be shared between machines (for
+/// example, between team members and CI) so an asset only needs to be processed once.
+///
+/// Set it with [`AssetProcessorData::set_cache`](crate::processor::AssetProcessorData::set_cache).
+/// Errors returned by a cache are logged, and the [`AssetProcessor`](crate::processor::AssetProcessor)
+/// falls back to processing the asset itself.
+pub trait ProcessorCache: Send + Sync + 'static {
+    /// Returns the processed asset stored for `key`, or [`None`] if there is no such entry.
+    fn get<'a>(
+        &'a self,
+        key: &'a ProcessorCacheKey,
+    ) -> BoxedFuture<'a, Result<Option<CachedProcessedAsset>, BevyError>>;
+
+    /// Stores `asset` as the processed output for `key`.
+    fn put<'a>(
+        &'a self,
+        key: &'a ProcessorCacheKey,
+        asset: &'a CachedProcessedAsset,
+    ) -> BoxedFuture<'a, Result<(), BevyError>>;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// A [`ProcessorCache`] that stores entries as files in a local (or network mounted) directory.
+///
+/// Each entry is stored as a `<key>.asset` and `<key>.meta` file pair, in a subdirectory named
+/// after the first two characters of the key. Files are written to a temporary path first and
+/// then renamed, so several processors can safely share the same directory.
+pub struct FileProcessorCache {
+    /// The directory entries are stored in.
+    pub root: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileProcessorCache {
+    /// Creates a new [`FileProcessorCache`] storing its entries in `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn entry_path(&self, key: &ProcessorCacheKey, extension: &str) -> PathBuf {
+        let key = key.to_string();
+        self.root.join(&key[..2]).join(format!("{key}.{extension}"))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_if_exists(path: &PathBuf) -> Result<Option<Vec<u8>>, std::io::Error> {
+    match async_fs::read(path).await {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn write_atomic(path: &PathBuf, bytes: &[u8]) -> Result<(), std::io::Error> {
+    if let Some(parent) = path.parent() {
+        async_fs::create_dir_all(parent).await?;
+    }
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let temp_path = path.with_file_name(temp_name);
+    async_fs::write(&temp_path, bytes).await?;
+    if let Err(err) = async_fs::rename(&temp_path, path).await {
+        let _ = async_fs::remove_file(&temp_path).await;
+        return Err(err);
+    }
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ProcessorCache for FileProcessorCache {
+    fn get<'a>(
+        &'a self,
+        key: &'a ProcessorCacheKey,
+    ) -> BoxedFuture<'a, Result<Option<CachedProcessedAsset>, BevyError>> {
+        Box::pin(async move {
+            // The meta is written last, so if it exists the asset is complete.
+            let Some(meta) = read_if_exists(&self.entry_path(key, "meta")).await? else {
+                return Ok(None);
+            };
+            let Some(asset) = read_if_exists(&self.entry_path(key, "asset")).await? else {
+                return Ok(None);
+            };
+            Ok(Some(CachedProcessedAsset { asset, meta }))
+        })
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a ProcessorCacheKey,
+        asset: &'a CachedProcessedAsset,
+    ) -> BoxedFuture<'a, Result<(), BevyError>> {
+        Box::pin(async move {
+            write_atomic(&self.entry_path(key, "asset"), &asset.asset).await?;
+            write_atomic(&self.entry_path(key, "meta"), &asset.meta).await?;
+            Ok(())
+        })
+    }
+}
+
+/// A [`Writer`] that forwards all writes to another writer, optionally keeping a copy of the
+/// written bytes so they can be stored in a [`ProcessorCache`].
+pub(crate) struct CachingWriter {
+    writer: Box<Writer>,
+    bytes: Option<Vec<u8>>,
+}
+
+impl CachingWriter {
+    pub(crate) fn new(writer: Box<Writer>, keep_bytes: bool) -> Self {
+        Self {
+            writer,
+            bytes: keep_bytes.then(Vec::new),
+        }
+    }
+
+    /// Returns the bytes written so far, if they were kept.
+    pub(crate) fn into_bytes(self) -> Option<Vec<u8>> {
+        self.bytes
+    }
+}
+
+impl AsyncWrite for CachingWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        let result = Pin::new(&mut *this.writer).poll_write(cx, buf);
+        if let (Poll::Ready(Ok(written)), Some(bytes)) = (&result, &mut this.bytes) {
+            bytes.extend_from_slice(&buf[..*written]);
+        }
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut *self.get_mut().writer).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut *self.get_mut().writer).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CachedProcessedAsset, FileProcessorCache, ProcessorCache, ProcessorCacheKey};
+    use alloc::{format, string::ToString};
+    use bevy_tasks::block_on;
+
+    #[test]
+    fn key_depends_on_processor_and_version() {
+        let hash = [7; 32];
+        let key = ProcessorCacheKey::new(hash, "my::Processor", 0);
+        assert_eq!(key, ProcessorCacheKey::new(hash, "my::Processor", 0));
+        assert_ne!(key, ProcessorCacheKey::new(hash, "my::Processor", 1));
+        assert_ne!(key, ProcessorCacheKey::new(hash, "my::OtherProcessor", 0));
+        assert_ne!(key, ProcessorCacheKey::new([8; 32], "my::Processor", 0));
+        assert_eq!(key.to_string().len(), 64);
+    }
+
+    #[test]
+    fn file_cache_round_trip() {
+        let root = std::env::temp_dir().join(format!(
+            "bevy_asset_processor_cache_{}",
+            uuid::Uuid::new_v4()
+        ));
+        let cache = FileProcessorCache::new(&root);
+        let key = ProcessorCacheKey::new([1; 32], "my::Processor", 0);
+        let entry = CachedProcessedAsset {
+            asset: b"asset".to_vec(),
+            meta: b"meta".to_vec(),
+        };
+
+        assert_eq!(block_on(cache.get(&key)).unwrap(), None);
+        block_on(cache.put(&key, &entry)).unwrap();
+        assert_eq!(block_on(cache.get(&key)).unwrap(), Some(entry));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -37,17 +37,20 @@
 //!
 //! In most cases, [`LoadTransformAndSave`] should be sufficient.
 
+mod cache;
 mod log;
 mod process;
 
 use async_lock::RwLockReadGuardArc;
+pub use cache::*;
 pub use log::*;
 pub use process::*;
 
 use crate::{
     io::{
-        AssetReaderError, AssetSource, AssetSourceBuilders, AssetSourceEvent, AssetSourceId,
-        AssetSources, AssetWriterError, ErasedAssetReader, MissingAssetSourceError,
+        archive::AssetArchiveBuilder, AssetReaderError, AssetSource, AssetSourceBuilders,
+        AssetSourceEvent, AssetSourceId, AssetSources, AssetWriterError, ErasedAssetReader,
+        MissingAssetSourceError, MissingProcessedAssetReaderError,
     },
     meta::{
         get_asset_hash, get_full_asset_hash, AssetAction, AssetActionMinimal, AssetHash, AssetMeta,
@@ -113,6 +116,8 @@ pub struct AssetProcessorData {
     log: async_lock::RwLock<Option<Box<dyn ProcessorTransactionLog>>>,
     /// The processors that will be used to process assets.
     processors: RwLock<Processors>,
+    /// The cache that processed outputs are shared through, if any.
+    cache: RwLock<Option<Arc<dyn ProcessorCache>>>,
     sources: Arc<AssetSources>,
 }
 
@@ -208,6 +213,23 @@ impl AssetProcessor {
         &self.data.sources
     }
 
+    /// Waits until processing has finished, then packs every processed asset (and its meta file) in
+    /// the given source into an [`AssetArchive`](crate::io::archive::AssetArchive), returning the
+    /// archive bytes.
+    ///
+    /// The result can be shipped in place of the processed directory and read back with
+    /// [`AssetSourceBuilder::archive`](crate::io::AssetSourceBuilder::archive).
+    pub async fn pack_processed_source<'a>(
+        &self,
+        source: impl Into<AssetSourceId<'a>>,
+    ) -> Result<Vec<u8>, PackProcessedSourceError> {
+        self.data.wait_until_finished().await;
+        let reader = self.get_source(source)?.processed_reader()?;
+        let mut builder = AssetArchiveBuilder::new();
+        builder.add_directory(reader, Path::new("")).await?;
+        Ok(builder.build())
+    }
+
     /// Logs an unrecoverable error. On the next run of the processor, all assets will be regenerated. This should only be used as a last resort.
     /// Every call to this should be considered with scrutiny and ideally replaced with something more granular.
     async fn log_unrecoverable(&self) {
@@ -1142,6 +1164,15 @@ impl AssetProcessor {
             }
         }
 
+        let cache = processor.as_ref().and_then(|_| self.data.cache());
+        let cache_key = processor.as_ref().map(|processor| {
+            ProcessorCacheKey::new(new_hash, processor.type_path(), processor.version())
+        });
+        let cached = match (&cache, &cache_key) {
+            (Some(cache), Some(key)) => self.get_cached(cache.as_ref(), key, new_hash).await,
+            _ => None,
+        };
+
         // Note: this lock must remain alive until all processed asset and meta writes have finished (or failed)
         // See ProcessedAssetInfo::file_transaction_lock docs for more info
         let _transaction_lock = {
@@ -1160,7 +1191,17 @@ impl AssetProcessor {
         // Directly writing to the asset destination in the processor necessitates this behavior
         // TODO: this class of failure can be recovered via re-processing + smarter log validation that allows for duplicate transactions in the event of failures
         self.log_begin_processing(asset_path).await;
-        if let Some(processor) = processor {
+        if let Some((cached, processed_info)) = cached {
+            processed_writer
+                .write_bytes(path, &cached.asset)
+                .await
+                .map_err(writer_err)?;
+            processed_writer
+                .write_meta_bytes(path, &cached.meta)
+                .await
+                .map_err(writer_err)?;
+            new_processed_info = processed_info;
+        } else if let Some(processor) = processor {
             // Unwrap is ok since we have a processor, so the `AssetAction` must have been
             // `AssetAction::Process` (which includes its settings).
             let settings = source_meta.process_settings().unwrap();
@@ -1176,7 +1217,8 @@ impl AssetProcessor {
             // reads or not.
             let reader_for_process = reader.read(path).await.map_err(reader_err)?;
 
-            let mut writer = processed_writer.write(path).await.map_err(writer_err)?;
+            let writer = processed_writer.write(path).await.map_err(writer_err)?;
+            let mut writer = CachingWriter::new(writer, cache.is_some());
             let mut processed_meta = {
                 let mut context = ProcessContext::new(
                     self,
@@ -1184,7 +1226,7 @@ impl AssetProcessor {
                     reader_for_process,
                     &mut new_processed_info,
                 );
-                let process = processor.process(&mut context, settings, &mut *writer);
+                let process = processor.process(&mut context, settings, &mut writer);
                 #[cfg(feature = "trace")]
                 let process = {
                     let span = info_span!(
@@ -1220,6 +1262,16 @@ impl AssetProcessor {
                 .write_meta_bytes(path, &meta_bytes)
                 .await
                 .map_err(writer_err)?;
+
+            if let (Some(cache), Some(key), Some(asset)) = (cache, cache_key, writer.into_bytes()) {
+                let entry = CachedProcessedAsset {
+                    asset,
+                    meta: meta_bytes,
+                };
+                if let Err(err) = cache.put(&key, &entry).await {
+                    warn!("Failed to store processed asset {asset_path} in the processor cache: {err}");
+                }
+            }
         } else {
             // See the reasoning for processing why it's ok to do a second read here.
             let mut reader_for_copy = reader.read(path).await.map_err(reader_err)?;
@@ -1242,6 +1294,55 @@ impl AssetProcessor {
         Ok(ProcessResult::Processed(new_processed_info))
     }
 
+    /// Looks up the processed output for `key` in `cache`, returning it along with its
+    /// [`ProcessedInfo`] if it can be used in place of processing the asset.
+    ///
+    /// An entry is only used if all of its process dependencies still have the same full hash as
+    /// when it was stored. Cache errors are logged and treated as a miss.
+    async fn get_cached(
+        &self,
+        cache: &dyn ProcessorCache,
+        key: &ProcessorCacheKey,
+        hash: AssetHash,
+    ) -> Option<(CachedProcessedAsset, ProcessedInfo)> {
+        let cached = match cache.get(key).await {
+            Ok(cached) => cached?,
+            Err(err) => {
+                warn!("Failed to read from the processor cache: {err}");
+                return None;
+            }
+        };
+        let processed_info = match ron::de::from_bytes::<ProcessedInfoMinimal>(&cached.meta) {
+            Ok(ProcessedInfoMinimal {
+                processed_info: Some(processed_info),
+            }) if processed_info.hash == hash => processed_info,
+            Ok(_) => return None,
+            Err(err) => {
+                warn!("Processor cache entry {key} has invalid meta: {err}");
+                return None;
+            }
+        };
+        for dependency in &processed_info.process_dependencies {
+            if self
+                .data
+                .wait_until_processed(dependency.path.clone())
+                .await
+                != ProcessStatus::Processed
+            {
+                return None;
+            }
+            let infos = self.data.processing_state.asset_infos.read().await;
+            let live_hash = infos
+                .get(&dependency.path)
+                .and_then(|info| info.processed_info.as_ref())
+                .map(|info| info.full_hash);
+            if live_hash != Some(dependency.full_hash) {
+                return None;
+            }
+        }
+        Some((cached, processed_info))
+    }
+
     async fn validate_transaction_log_and_recover(&self) {
         let log_factory = self
             .data
@@ -1346,9 +1447,27 @@ impl AssetProcessorData {
             log_factory: Mutex::new(Some(Box::new(FileTransactionLogFactory::default()))),
             log: Default::default(),
             processors: Default::default(),
+            cache: Default::default(),
         }
     }
 
+    /// Sets the [`ProcessorCache`] that processed assets are looked up in before processing, and
+    /// stored in after processing.
+    ///
+    /// This should be called before asset processing has begun (in the `Startup` schedule), as
+    /// assets processed before then will not use the cache.
+    pub fn set_cache(&self, cache: impl ProcessorCache) {
+        *self.cache.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(cache));
+    }
+
+    /// Returns the [`ProcessorCache`] set by [`Self::set_cache`], if any.
+    pub fn cache(&self) -> Option<Arc<dyn ProcessorCache>> {
+        self.cache
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
     /// Sets the transaction log factory for the processor.
     ///
     /// If this is called after asset processing has begun (in the `Startup` schedule), it will
@@ -1826,6 +1945,17 @@ pub enum InitializeError {
     ValidateLogError(#[from] ValidateLogError),
 }
 
+/// An error that occurs when packing a processed asset source into an archive.
+#[derive(Error, Debug)]
+pub enum PackProcessedSourceError {
+    #[error(transparent)]
+    MissingAssetSource(#[from] MissingAssetSourceError),
+    #[error(transparent)]
+    MissingProcessedAssetReader(#[from] MissingProcessedAssetReaderError),
+    #[error("Failed to read processed assets: {0}")]
+    AssetReaderError(#[from] AssetReaderError),
+}
+
 /// An error when attempting to set the transaction log factory.
 #[derive(Error, Debug)]
 pub enum SetTransactionLogFactoryError {
@@ -29,6 +29,10 @@ use thiserror::Error;
 /// This is a "low level", maximally flexible interface. Most use cases are better served by the [`LoadTransformAndSave`] implementation
 /// of [`Process`].
 pub trait Process: TypePath + Send + Sync + Sized + 'static {
+    /// The version of this processor's output format. Bump this whenever the processor starts
+    /// producing different output for the same input, so that outputs stored in a
+    /// [`ProcessorCache`](crate::processor::ProcessorCache) by older versions are not reused.
+    const VERSION: u32 = 0;
     /// The configuration / settings used to process the asset. This will be stored in the [`AssetMeta`] and is user-configurable per-asset.
     type Settings: Settings + Default + Serialize + for<'a> Deserialize<'a>;
     /// The [`AssetLoader`] that will be used to load the final processed asset.
@@ -232,6 +236,8 @@ pub trait ErasedProcessor: Send + Sync {
     fn type_path(&self) -> &'static str;
     /// Returns the short type path of this processor.
     fn short_type_path(&self) -> &'static str;
+    /// Returns the [`Process::VERSION`] of the original [`Process`].
+    fn version(&self) -> u32;
     /// Returns the default type-erased [`AssetMeta`] for the underlying [`Process`] impl.
     fn default_meta(&self, processor_path_kind: MetaTypePathKind) -> Box<dyn AssetMetaDyn>;
 }
@@ -276,6 +282,10 @@ impl<P: Process> ErasedProcessor for P {
         P::short_type_path()
     }
 
+    fn version(&self) -> u32 {
+        P::VERSION
+    }
+
     fn default_meta(&self, processor_path_kind: MetaTypePathKind) -> Box<dyn AssetMetaDyn> {
         let type_path = match processor_path_kind {
             MetaTypePathKind::Short => P::short_type_path(),
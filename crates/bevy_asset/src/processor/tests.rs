@@ -24,13 +24,15 @@ use bevy_tasks::BoxedFuture;
 
 use crate::{
     io::{
+        archive::AssetArchive,
         memory::{Dir, MemoryAssetReader, MemoryAssetWriter},
         AssetReader, AssetReaderError, AssetSourceBuilder, AssetSourceBuilders, AssetSourceEvent,
         AssetSourceId, AssetWatcher, PathStream, Reader,
     },
     processor::{
-        AssetProcessor, GetProcessorError, LoadTransformAndSave, LogEntry, Process, ProcessContext,
-        ProcessError, ProcessorState, ProcessorTransactionLog, ProcessorTransactionLogFactory,
+        AssetProcessor, CachedProcessedAsset, GetProcessorError, LoadTransformAndSave, LogEntry,
+        Process, ProcessContext, ProcessError, ProcessorCache, ProcessorCacheKey, ProcessorState,
+        ProcessorTransactionLog, ProcessorTransactionLogFactory,
     },
     saver::{tests::CoolTextSaver, AssetSaver},
     tests::{
@@ -560,6 +562,156 @@ fn asset_processor_transforms_asset_default_processor() {
     );
 }
 
+#[test]
+fn pack_processed_source_contains_processed_assets_and_meta() {
+    let AppWithProcessor {
+        mut app,
+        source_gate,
+        default_source_dirs:
+            ProcessingDirs {
+                source: source_dir,
+                processed: processed_dir,
+                ..
+            },
+        ..
+    } = create_app_with_asset_processor(&[]);
+
+    type CoolTextProcessor = LoadTransformAndSave<
+        CoolTextLoader,
+        RootAssetTransformer<AddText, CoolText>,
+        CoolTextSaver,
+    >;
+    app.register_asset_loader(CoolTextLoader)
+        .register_asset_processor(CoolTextProcessor::new(
+            RootAssetTransformer::new(AddText("_def".into())),
+            CoolTextSaver,
+        ))
+        .set_default_asset_processor::<CoolTextProcessor>("cool.ron");
+
+    let guard = source_gate.write_blocking();
+
+    let path = Path::new("dir/abc.cool.ron");
+    source_dir.insert_asset_text(
+        path,
+        r#"(
+    text: "abc",
+    dependencies: [],
+    embedded_dependencies: [],
+    sub_texts: [],
+)"#,
+    );
+
+    run_app_until_finished_processing(&mut app, guard);
+
+    let processor = app.world().resource::<AssetProcessor>().clone();
+    let bytes =
+        bevy_tasks::block_on(processor.pack_processed_source(AssetSourceId::Default)).unwrap();
+    let archive = AssetArchive::from_bytes(bytes).unwrap();
+
+    assert_eq!(
+        archive.get_asset(path).unwrap(),
+        processed_dir.get_asset(path).unwrap().value()
+    );
+    assert_eq!(
+        archive.get_meta(path).unwrap(),
+        processed_dir.get_metadata(path).unwrap().value()
+    );
+}
+
+/// A [`ProcessorCache`] that keeps its entries in memory, and can be shared between apps.
+#[derive(Clone, Default)]
+struct FakeProcessorCache(Arc<Mutex<HashMap<ProcessorCacheKey, CachedProcessedAsset>>>);
+
+impl ProcessorCache for FakeProcessorCache {
+    fn get<'a>(
+        &'a self,
+        key: &'a ProcessorCacheKey,
+    ) -> BoxedFuture<'a, Result<Option<CachedProcessedAsset>, BevyError>> {
+        Box::pin(async move {
+            Ok(self
+                .0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get(key)
+                .cloned())
+        })
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a ProcessorCacheKey,
+        asset: &'a CachedProcessedAsset,
+    ) -> BoxedFuture<'a, Result<(), BevyError>> {
+        Box::pin(async move {
+            self.0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(*key, asset.clone());
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn processor_cache_reuses_processed_output() {
+    /// Processes `abc.cool.ron` in a new app using `cache`, appending `suffix` to its text, and
+    /// returns the processed asset.
+    fn process_with_cache(cache: &FakeProcessorCache, suffix: &str) -> String {
+        let AppWithProcessor {
+            mut app,
+            source_gate,
+            default_source_dirs:
+                ProcessingDirs {
+                    source: source_dir,
+                    processed: processed_dir,
+                    ..
+                },
+            ..
+        } = create_app_with_asset_processor(&[]);
+
+        type CoolTextProcessor = LoadTransformAndSave<
+            CoolTextLoader,
+            RootAssetTransformer<AddText, CoolText>,
+            CoolTextSaver,
+        >;
+        app.register_asset_loader(CoolTextLoader)
+            .register_asset_processor(CoolTextProcessor::new(
+                RootAssetTransformer::new(AddText(suffix.into())),
+                CoolTextSaver,
+            ))
+            .set_default_asset_processor::<CoolTextProcessor>("cool.ron");
+        app.world()
+            .resource::<AssetProcessor>()
+            .data()
+            .set_cache(cache.clone());
+
+        let guard = source_gate.write_blocking();
+
+        let path = Path::new("abc.cool.ron");
+        source_dir.insert_asset_text(path, &serialize_as_cool_text("abc"));
+
+        run_app_until_finished_processing(&mut app, guard);
+
+        let processed_asset = processed_dir.get_asset(path).unwrap();
+        str::from_utf8(processed_asset.value()).unwrap().to_string()
+    }
+
+    let cache = FakeProcessorCache::default();
+    assert_eq!(
+        process_with_cache(&cache, "_def"),
+        serialize_as_cool_text("abc_def")
+    );
+    assert_eq!(cache.0.lock().unwrap().len(), 1);
+
+    // The processor type, version, and settings are the same, so the second app is served from
+    // the cache instead of running its (different) transformer.
+    assert_eq!(
+        process_with_cache(&cache, "_other"),
+        serialize_as_cool_text("abc_def")
+    );
+    assert_eq!(cache.0.lock().unwrap().len(), 1);
+}
+
 #[test]
 fn asset_processor_transforms_asset_with_meta() {
     let AppWithProcessor {
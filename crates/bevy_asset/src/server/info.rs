@@ -10,6 +10,7 @@ use alloc::{
     sync::{Arc, Weak},
     vec::Vec,
 };
+use atomicow::CowArc;
 use bevy_ecs::world::World;
 use bevy_platform::collections::{hash_map::Entry, HashMap, HashSet};
 use bevy_tasks::Task;
@@ -47,6 +48,8 @@ pub(crate) struct AssetInfo {
     handle_drops_to_skip: usize,
     /// List of tasks waiting for this asset to complete loading
     pub(crate) waiting_tasks: Vec<Waker>,
+    /// The name of the overlay layer this asset was read from, if its source is an overlay.
+    pub(crate) overlay_layer: Option<CowArc<'static, str>>,
 }
 
 impl AssetInfo {
@@ -66,6 +69,7 @@ impl AssetInfo {
             dependents_waiting_on_recursive_dep_load: HashSet::default(),
             handle_drops_to_skip: 0,
             waiting_tasks: Vec::new(),
+            overlay_layer: None,
         }
     }
 }
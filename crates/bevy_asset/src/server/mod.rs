@@ -785,6 +785,7 @@ impl AssetServer {
         if let Some(meta_transform) = input_handle.as_ref().and_then(|h| h.meta_transform()) {
             (*meta_transform)(&mut *meta);
         }
+        let overlay_layer = self.served_overlay_layer(&path);
 
         let asset_id: Option<ErasedAssetIndex>; // The asset ID of the asset we are trying to load.
         let fetched_handle; // The handle if one was looked up/created.
@@ -947,6 +948,9 @@ impl AssetServer {
                     fetched_handle
                 };
 
+                if let Some(info) = self.write_infos().get_mut(base_asset_id) {
+                    info.overlay_layer = overlay_layer;
+                }
                 self.send_asset_event(InternalAssetEvent::Loaded {
                     index: base_asset_id,
                     loaded_asset,
@@ -1513,6 +1517,33 @@ impl AssetServer {
         Some(info.path.as_ref()?.clone())
     }
 
+    /// Returns the name of the overlay layer that the asset at `path` was read from, if its source
+    /// was built by an [`OverlaySourceBuilder`](crate::io::overlay::OverlaySourceBuilder).
+    ///
+    /// The layer is known for as long as the asset is loaded. For other paths, this returns the
+    /// layer that served the most recent read, if it is still remembered by the source's
+    /// [`OverlayLayers`](crate::io::overlay::OverlayLayers).
+    ///
+    /// Returns [`None`] if the source is not an overlay, or the asset has not been read.
+    pub fn get_path_overlay_layer<'a>(
+        &self,
+        path: impl Into<AssetPath<'a>>,
+    ) -> Option<CowArc<'static, str>> {
+        // Labeled assets are read from the file of their base asset.
+        let path = path.into().without_label().into_owned();
+        let infos = self.read_infos();
+        infos
+            .get_path_indices(&path)
+            .find_map(|index| infos.get(index)?.overlay_layer.clone())
+            .or_else(|| self.served_overlay_layer(&path))
+    }
+
+    /// Returns the overlay layer that most recently served `path`, if its source is an overlay.
+    fn served_overlay_layer(&self, path: &AssetPath) -> Option<CowArc<'static, str>> {
+        let layers = self.get_source(path.source()).ok()?.overlay_layers()?;
+        layers.served_layer_name(path.path(), self.data.mode == AssetServerMode::Processed)
+    }
+
     /// Returns the [`AssetServerMode`] this server is currently in.
     pub fn mode(&self) -> AssetServerMode {
         self.data.mode
@@ -0,0 +1,246 @@
+//! Checking of [`CiTestingCondition`]s, using reflection.
+
+use super::config::CiTestingCondition;
+use bevy_ecs::{
+    name::Name,
+    prelude::*,
+    reflect::{AppTypeRegistry, ReflectComponent, ReflectResource},
+};
+use bevy_reflect::{
+    serde::TypedReflectDeserializer, PartialReflect, ReflectPath, TypeRegistration, TypeRegistry,
+};
+use bevy_state::reflect::ReflectState;
+use serde::de::DeserializeSeed;
+
+/// The result of checking a [`CiTestingCondition`].
+#[derive(Debug, PartialEq)]
+pub(crate) enum ConditionCheck {
+    /// The condition is met.
+    Met,
+    /// The condition isn't met, for the given reason.
+    NotMet(String),
+    /// The condition can't be checked, for the given reason.
+    Invalid(String),
+}
+
+/// Checks whether the condition is met in the world.
+pub(crate) fn check_condition(world: &World, condition: &CiTestingCondition) -> ConditionCheck {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    check(world, &registry, condition).unwrap_or_else(ConditionCheck::Invalid)
+}
+
+fn check(
+    world: &World,
+    registry: &TypeRegistry,
+    condition: &CiTestingCondition,
+) -> Result<ConditionCheck, String> {
+    match condition {
+        CiTestingCondition::State { state, value } => {
+            let reflect_state = registration(registry, state)?
+                .data::<ReflectState>()
+                .ok_or_else(|| format!("`{state}` isn't a registered state"))?;
+            let Some(current) = reflect_state.reflect(world) else {
+                return Ok(ConditionCheck::NotMet(format!(
+                    "state `{state}` doesn't exist"
+                )));
+            };
+            compare(registry, current.as_partial_reflect(), "", value)
+        }
+        CiTestingCondition::EntityExists(name) => Ok(match find_named(world, name) {
+            Some(_) => ConditionCheck::Met,
+            None => ConditionCheck::NotMet(format!("no entity is named `{name}`")),
+        }),
+        CiTestingCondition::Resource {
+            resource,
+            path,
+            value,
+        } => {
+            let registration = registration(registry, resource)?;
+            if registration.data::<ReflectResource>().is_none() {
+                return Err(format!("`{resource}` isn't a reflected resource"));
+            }
+            let reflect_component = registration
+                .data::<ReflectComponent>()
+                .ok_or_else(|| format!("`{resource}` has no `ReflectComponent` type data"))?;
+            let current = world
+                .components()
+                .get_id(registration.type_id())
+                .and_then(|id| world.resource_entities().get(id))
+                .and_then(|entity| reflect_component.reflect(world.entity(entity)));
+            let Some(current) = current else {
+                return Ok(ConditionCheck::NotMet(format!(
+                    "resource `{resource}` isn't in the world"
+                )));
+            };
+            compare(registry, current.as_partial_reflect(), path, value)
+        }
+        CiTestingCondition::Component {
+            entity,
+            component,
+            path,
+            value,
+        } => {
+            let reflect_component = registration(registry, component)?
+                .data::<ReflectComponent>()
+                .ok_or_else(|| format!("`{component}` isn't a reflected component"))?;
+            let Some(named) = find_named(world, entity) else {
+                return Ok(ConditionCheck::NotMet(format!(
+                    "no entity is named `{entity}`"
+                )));
+            };
+            let Some(current) = reflect_component.reflect(world.entity(named)) else {
+                return Ok(ConditionCheck::NotMet(format!(
+                    "entity `{entity}` has no `{component}` component"
+                )));
+            };
+            compare(registry, current.as_partial_reflect(), path, value)
+        }
+    }
+}
+
+/// Looks up a type by its type path, or by its short type path if it is unambiguous.
+fn registration<'r>(
+    registry: &'r TypeRegistry,
+    type_path: &str,
+) -> Result<&'r TypeRegistration, String> {
+    registry
+        .get_with_type_path(type_path)
+        .or_else(|| registry.get_with_short_type_path(type_path))
+        .ok_or_else(|| format!("unknown type `{type_path}`"))
+}
+
+fn find_named(world: &World, name: &str) -> Option<Entity> {
+    world
+        .try_query::<(Entity, &Name)>()?
+        .iter(world)
+        .find(|(_, entity_name)| entity_name.as_str() == name)
+        .map(|(entity, _)| entity)
+}
+
+/// Compares the field at `path` with the value written in `expected`.
+fn compare(
+    registry: &TypeRegistry,
+    root: &dyn PartialReflect,
+    path: &str,
+    expected: &str,
+) -> Result<ConditionCheck, String> {
+    let field = if path.is_empty() {
+        root
+    } else {
+        path.reflect_element(root).map_err(|err| err.to_string())?
+    };
+    let registration = field
+        .get_represented_type_info()
+        .and_then(|info| registry.get(info.type_id()))
+        .ok_or_else(|| format!("the type of `{path}` isn't registered"))?;
+    let mut deserializer = ron::Deserializer::from_str(expected).map_err(|err| err.to_string())?;
+    let expected_value = TypedReflectDeserializer::new(registration, registry)
+        .deserialize(&mut deserializer)
+        .map_err(|err| format!("invalid value `{expected}`: {err}"))?;
+
+    Ok(match field.reflect_partial_eq(expected_value.as_ref()) {
+        Some(true) => ConditionCheck::Met,
+        _ => ConditionCheck::NotMet(format!("expected {expected}, found {field:?}")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_reflect::Reflect;
+
+    #[derive(Resource, Reflect)]
+    #[reflect(Resource)]
+    struct Score(u32);
+
+    #[derive(Resource, Reflect)]
+    #[reflect(Resource)]
+    struct Lives(u32);
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct Health {
+        current: f32,
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<Score>();
+            registry.register::<Lives>();
+            registry.register::<Health>();
+            registry.register::<Name>();
+        }
+        world.insert_resource(registry);
+        world.insert_resource(Score(10));
+        world.spawn((Name::new("Player"), Health { current: 5.0 }));
+        world
+    }
+
+    #[test]
+    fn resource_and_component_conditions() {
+        let world = world();
+
+        let resource = |path: &str, value: &str| CiTestingCondition::Resource {
+            resource: "Score".into(),
+            path: path.into(),
+            value: value.into(),
+        };
+        assert_eq!(
+            check_condition(&world, &resource(".0", "10")),
+            ConditionCheck::Met
+        );
+        assert_eq!(
+            check_condition(&world, &resource("", "(10)")),
+            ConditionCheck::Met
+        );
+        assert!(matches!(
+            check_condition(&world, &resource(".0", "11")),
+            ConditionCheck::NotMet(_)
+        ));
+        assert!(matches!(
+            check_condition(&world, &resource(".1", "10")),
+            ConditionCheck::Invalid(_)
+        ));
+
+        let other_resource = |resource: &str| CiTestingCondition::Resource {
+            resource: resource.into(),
+            path: ".0".into(),
+            value: "3".into(),
+        };
+        assert_eq!(
+            check_condition(&world, &other_resource("Lives")),
+            ConditionCheck::NotMet("resource `Lives` isn't in the world".into())
+        );
+        assert_eq!(
+            check_condition(&world, &other_resource("Coins")),
+            ConditionCheck::Invalid("unknown type `Coins`".into())
+        );
+        assert_eq!(
+            check_condition(&world, &other_resource("Health")),
+            ConditionCheck::Invalid("`Health` isn't a reflected resource".into())
+        );
+
+        let component = |entity: &str| CiTestingCondition::Component {
+            entity: entity.into(),
+            component: "Health".into(),
+            path: "current".into(),
+            value: "5.0".into(),
+        };
+        assert_eq!(
+            check_condition(&world, &component("Player")),
+            ConditionCheck::Met
+        );
+        assert!(matches!(
+            check_condition(&world, &component("Enemy")),
+            ConditionCheck::NotMet(_)
+        ));
+
+        assert_eq!(
+            check_condition(&world, &CiTestingCondition::EntityExists("Player".into())),
+            ConditionCheck::Met
+        );
+    }
+}
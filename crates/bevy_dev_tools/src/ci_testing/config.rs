@@ -1,6 +1,9 @@
 use bevy_ecs::prelude::*;
-use bevy_math::{Quat, Vec3};
-use serde::Deserialize;
+#[cfg(feature = "gamepad")]
+use bevy_input::gamepad::{GamepadAxis, GamepadButton};
+use bevy_input::{keyboard::KeyCode, mouse::MouseButton};
+use bevy_math::{Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
 
 /// A configuration struct for automated CI testing.
 ///
@@ -15,6 +18,12 @@ pub struct CiTestingConfig {
     /// Events to send, with their associated frame.
     #[serde(default)]
     pub events: Vec<CiTestingEventOnFrame>,
+    /// Steps to run one after the other, starting on the first frame.
+    ///
+    /// Unlike [`events`](Self::events), steps can wait on conditions and make assertions. The
+    /// outcome of every step is collected in the [`CiTestingReport`].
+    #[serde(default)]
+    pub scenario: Vec<CiTestingStep>,
 }
 
 /// Setup for a test.
@@ -26,6 +35,11 @@ pub struct CiTestingSetup {
     ///
     /// [`TimeUpdateStrategy::ManualDuration`]: bevy_time::TimeUpdateStrategy::ManualDuration
     pub fixed_frame_time: Option<f32>,
+    /// The file to write the [`CiTestingReport`] to once the [`scenario`] is finished.
+    ///
+    /// [`scenario`]: CiTestingConfig::scenario
+    #[serde(default)]
+    pub report_path: Option<String>,
 }
 
 /// An event to send at a given frame, used for CI testing.
@@ -61,12 +75,162 @@ pub enum CiTestingEvent {
     },
     /// Sends a [`CiTestingCustomEvent`] using the given [`String`].
     Custom(String),
+    /// Presses a key.
+    KeyPress(KeyCode),
+    /// Releases a key.
+    KeyRelease(KeyCode),
+    /// Presses a mouse button.
+    MouseButtonPress(MouseButton),
+    /// Releases a mouse button.
+    MouseButtonRelease(MouseButton),
+    /// Moves the mouse by the given delta.
+    MouseMotion(Vec2),
+    /// Moves the cursor to the given position in logical pixels in the primary window.
+    CursorMoved(Vec2),
+    /// Scrolls the mouse wheel by the given number of lines.
+    MouseWheel(Vec2),
+    /// Sets the value of a button of a simulated gamepad, which is connected when first used.
+    ///
+    /// Requires the `gamepad` feature.
+    #[cfg(feature = "gamepad")]
+    GamepadButton(GamepadButton, f32),
+    /// Sets the value of an axis of a simulated gamepad, which is connected when first used.
+    ///
+    /// Requires the `gamepad` feature.
+    #[cfg(feature = "gamepad")]
+    GamepadAxis(GamepadAxis, f32),
+}
+
+/// A step of a [scenario](CiTestingConfig::scenario), used for CI testing.
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub enum CiTestingStep {
+    /// Sends an event, then moves on to the next step in the same frame.
+    ///
+    /// Simulated input is processed in `PreUpdate` of the next frame, so conditions depending on
+    /// it should be checked after a [`WaitFrames`](Self::WaitFrames) or a
+    /// [`WaitUntil`](Self::WaitUntil).
+    ///
+    /// If a previous step failed, [`CiTestingEvent::AppExit`] exits with an error.
+    Event(CiTestingEvent),
+    /// Waits for the given number of frames.
+    WaitFrames(u32),
+    /// Waits until the condition is met.
+    ///
+    /// If the condition isn't met within `timeout` frames, the step fails and the scenario is
+    /// aborted.
+    WaitUntil {
+        /// The condition to wait on.
+        condition: CiTestingCondition,
+        /// The maximum number of frames to wait.
+        #[serde(default = "default_timeout")]
+        timeout: u32,
+    },
+    /// Checks that the condition is met. The scenario goes on if it isn't, but the test fails.
+    Assert(CiTestingCondition),
+}
+
+fn default_timeout() -> u32 {
+    600
+}
+
+/// A condition on the world, used for CI testing.
+///
+/// Types are looked up by their type path in the [`AppTypeRegistry`], and must be registered.
+/// Expected values are written in [`ron`], and deserialized with reflection using the type of
+/// the value they are compared to.
+///
+/// [`AppTypeRegistry`]: bevy_ecs::reflect::AppTypeRegistry
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+pub enum CiTestingCondition {
+    /// The state of the given [`States`] type is equal to `value`.
+    ///
+    /// The state type must be registered with `register_type_state`.
+    ///
+    /// [`States`]: bevy_state::state::States
+    State {
+        /// The type path of the [`States`](bevy_state::state::States) type.
+        state: String,
+        /// The expected state.
+        value: String,
+    },
+    /// An entity with the given [`Name`](bevy_ecs::name::Name) exists.
+    EntityExists(String),
+    /// The field at `path` of the given resource is equal to `value`.
+    Resource {
+        /// The type path of the resource.
+        resource: String,
+        /// The [reflect path](bevy_reflect::GetPath) of the field, or an empty string to compare
+        /// the whole resource.
+        #[serde(default)]
+        path: String,
+        /// The expected value.
+        value: String,
+    },
+    /// The field at `path` of the given component, on the first entity with the given
+    /// [`Name`](bevy_ecs::name::Name), is equal to `value`.
+    Component {
+        /// The name of the entity.
+        entity: String,
+        /// The type path of the component.
+        component: String,
+        /// The [reflect path](bevy_reflect::GetPath) of the field, or an empty string to compare
+        /// the whole component.
+        #[serde(default)]
+        path: String,
+        /// The expected value.
+        value: String,
+    },
 }
 
 /// A custom event that can be configured from a configuration file for CI testing.
 #[derive(Message)]
 pub struct CiTestingCustomEvent(pub String);
 
+/// The outcome of the [scenario](CiTestingConfig::scenario) of a CI test.
+///
+/// It is written to [`CiTestingSetup::report_path`] once the scenario is finished.
+#[derive(Serialize, Resource, PartialEq, Debug, Clone)]
+pub struct CiTestingReport {
+    /// Whether every step of the scenario passed.
+    pub passed: bool,
+    /// Whether the scenario is finished, or was aborted.
+    pub finished: bool,
+    /// The outcome of every step of the scenario, in order.
+    pub steps: Vec<CiTestingStepReport>,
+}
+
+impl Default for CiTestingReport {
+    fn default() -> Self {
+        Self {
+            passed: true,
+            finished: false,
+            steps: Vec::new(),
+        }
+    }
+}
+
+/// The report of a [`CiTestingStep`].
+#[derive(Serialize, PartialEq, Debug, Clone)]
+pub struct CiTestingStepReport {
+    /// The index of the step in the scenario.
+    pub step: usize,
+    /// The frame the step completed on.
+    pub frame: u32,
+    /// The outcome of the step.
+    pub outcome: CiTestingOutcome,
+}
+
+/// The outcome of a [`CiTestingStep`].
+#[derive(Serialize, PartialEq, Debug, Clone)]
+pub enum CiTestingOutcome {
+    /// The step passed.
+    Passed,
+    /// The step failed for the given reason.
+    Failed(String),
+    /// The step wasn't run because the scenario was aborted.
+    Skipped,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,16 +252,66 @@ mod tests {
         let expected = CiTestingConfig {
             setup: CiTestingSetup {
                 fixed_frame_time: Some(0.03),
+                report_path: None,
             },
             events: vec![
                 CiTestingEventOnFrame(100, CiTestingEvent::Custom("Hello, world!".into())),
                 CiTestingEventOnFrame(200, CiTestingEvent::Screenshot),
                 CiTestingEventOnFrame(300, CiTestingEvent::AppExit),
             ],
+            scenario: Vec::new(),
         };
 
         let config: CiTestingConfig = ron::from_str(INPUT).unwrap();
 
         assert_eq!(config, expected);
     }
+
+    #[test]
+    fn deserialize_scenario() {
+        const INPUT: &str = r#"
+(
+    setup: (
+        report_path: Some("report.ron"),
+    ),
+    scenario: [
+        Event(KeyPress(Space)),
+        WaitFrames(2),
+        Event(KeyRelease(Space)),
+        WaitUntil(
+            condition: State(state: "game::GameState", value: "Playing"),
+            timeout: 100,
+        ),
+        Assert(Resource(resource: "game::Score", path: ".0", value: "10")),
+        Assert(EntityExists("Player")),
+        Event(AppExit),
+    ],
+)"#;
+
+        let config: CiTestingConfig = ron::from_str(INPUT).unwrap();
+
+        assert_eq!(config.setup.report_path.as_deref(), Some("report.ron"));
+        assert_eq!(
+            config.scenario,
+            vec![
+                CiTestingStep::Event(CiTestingEvent::KeyPress(KeyCode::Space)),
+                CiTestingStep::WaitFrames(2),
+                CiTestingStep::Event(CiTestingEvent::KeyRelease(KeyCode::Space)),
+                CiTestingStep::WaitUntil {
+                    condition: CiTestingCondition::State {
+                        state: "game::GameState".into(),
+                        value: "Playing".into(),
+                    },
+                    timeout: 100,
+                },
+                CiTestingStep::Assert(CiTestingCondition::Resource {
+                    resource: "game::Score".into(),
+                    path: ".0".into(),
+                    value: "10".into(),
+                }),
+                CiTestingStep::Assert(CiTestingCondition::EntityExists("Player".into())),
+                CiTestingStep::Event(CiTestingEvent::AppExit),
+            ]
+        );
+    }
 }
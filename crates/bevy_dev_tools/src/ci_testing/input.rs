@@ -0,0 +1,120 @@
+//! Simulated input for CI testing.
+//!
+//! Input is written as the messages a windowing or gamepad backend would write, so that it is
+//! processed in `PreUpdate` of the next frame like real input.
+
+use bevy_ecs::prelude::*;
+#[cfg(feature = "gamepad")]
+use bevy_input::gamepad::{
+    GamepadAxis, GamepadButton, GamepadConnection, GamepadConnectionEvent,
+    RawGamepadAxisChangedEvent, RawGamepadButtonChangedEvent, RawGamepadEvent,
+};
+use bevy_input::{
+    keyboard::{Key, KeyCode, KeyboardInput, NativeKey},
+    mouse::{MouseButton, MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+    touch::TouchPhase,
+    ButtonState,
+};
+use bevy_math::Vec2;
+use bevy_window::{CursorMoved, PrimaryWindow, Window};
+
+/// The simulated gamepad, spawned the first time gamepad input is sent.
+#[cfg(feature = "gamepad")]
+#[derive(Resource)]
+struct CiTestingGamepad(Entity);
+
+/// Returns the primary window, or [`Entity::PLACEHOLDER`] for headless apps.
+fn primary_window(world: &mut World) -> Entity {
+    world
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .single(world)
+        .unwrap_or(Entity::PLACEHOLDER)
+}
+
+pub(crate) fn send_keyboard_input(world: &mut World, key_code: KeyCode, state: ButtonState) {
+    let window = primary_window(world);
+    world.write_message(KeyboardInput {
+        key_code,
+        logical_key: Key::Unidentified(NativeKey::Unidentified),
+        state,
+        text: None,
+        repeat: false,
+        window,
+    });
+}
+
+pub(crate) fn send_mouse_button_input(world: &mut World, button: MouseButton, state: ButtonState) {
+    let window = primary_window(world);
+    world.write_message(MouseButtonInput {
+        button,
+        state,
+        window,
+    });
+}
+
+pub(crate) fn send_mouse_motion(world: &mut World, delta: Vec2) {
+    world.write_message(MouseMotion { delta });
+}
+
+pub(crate) fn send_cursor_moved(world: &mut World, position: Vec2) {
+    let window = primary_window(world);
+    let delta = world.get_mut::<Window>(window).and_then(|mut window| {
+        let previous = window.cursor_position();
+        window.set_cursor_position(Some(position));
+        previous.map(|previous| position - previous)
+    });
+    world.write_message(CursorMoved {
+        window,
+        position,
+        delta,
+    });
+}
+
+pub(crate) fn send_mouse_wheel(world: &mut World, delta: Vec2) {
+    let window = primary_window(world);
+    world.write_message(MouseWheel {
+        unit: MouseScrollUnit::Line,
+        x: delta.x,
+        y: delta.y,
+        window,
+        phase: TouchPhase::Moved,
+    });
+}
+
+#[cfg(feature = "gamepad")]
+pub(crate) fn send_gamepad_button(world: &mut World, button: GamepadButton, value: f32) {
+    let gamepad = simulated_gamepad(world);
+    let event = RawGamepadButtonChangedEvent::new(gamepad, button, value);
+    world.write_message(event);
+    world.write_message(RawGamepadEvent::Button(event));
+}
+
+#[cfg(feature = "gamepad")]
+pub(crate) fn send_gamepad_axis(world: &mut World, axis: GamepadAxis, value: f32) {
+    let gamepad = simulated_gamepad(world);
+    let event = RawGamepadAxisChangedEvent::new(gamepad, axis, value);
+    world.write_message(event);
+    world.write_message(RawGamepadEvent::Axis(event));
+}
+
+/// Returns the simulated gamepad, connecting it if needed.
+#[cfg(feature = "gamepad")]
+fn simulated_gamepad(world: &mut World) -> Entity {
+    if let Some(gamepad) = world.get_resource::<CiTestingGamepad>() {
+        return gamepad.0;
+    }
+
+    let gamepad = world.spawn_empty().id();
+    let event = GamepadConnectionEvent::new(
+        gamepad,
+        GamepadConnection::Connected {
+            name: "CI Testing Gamepad".into(),
+            vendor_id: None,
+            product_id: None,
+        },
+    );
+    world.write_message(event.clone());
+    world.write_message(RawGamepadEvent::Connection(event));
+    world.insert_resource(CiTestingGamepad(gamepad));
+    gamepad
+}
@@ -1,6 +1,8 @@
 //! Utilities for testing in CI environments.
 
+mod conditions;
 mod config;
+mod input;
 mod systems;
 
 use crate::EasyCameraMovementPlugin;
@@ -80,9 +82,11 @@ impl Plugin for CiTestingPlugin {
         }
         app.add_message::<CiTestingCustomEvent>()
             .insert_resource(config)
+            .init_resource::<CiTestingReport>()
             .add_systems(
                 Update,
-                systems::send_events
+                (systems::send_events, systems::run_scenario)
+                    .chain()
                     .before(trigger_screenshots)
                     .before(bevy_window::close_when_requested)
                     .in_set(EventSenderSystems)
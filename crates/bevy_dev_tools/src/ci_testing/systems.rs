@@ -1,11 +1,16 @@
 use crate::CameraMovement;
 
-use super::config::*;
+use super::{
+    conditions::{check_condition, ConditionCheck},
+    config::*,
+    input,
+};
 use bevy_app::AppExit;
 use bevy_camera::Camera;
 use bevy_ecs::prelude::*;
+use bevy_input::ButtonState;
 use bevy_render::view::screenshot::{save_to_disk, Screenshot};
-use tracing::{debug, info};
+use tracing::{debug, error, info};
 
 pub(crate) fn send_events(world: &mut World, mut current_frame: Local<u32>) {
     let mut config = world.resource_mut::<CiTestingConfig>();
@@ -18,70 +23,339 @@ pub(crate) fn send_events(world: &mut World, mut current_frame: Local<u32>) {
     config.events = remaining;
 
     for CiTestingEventOnFrame(_, event) in to_run {
-        debug!("Handling event: {:?}", event);
-        match event {
-            CiTestingEvent::AppExit => {
-                world.write_message(AppExit::Success);
-                info!("Exiting after {} frames. Test successful!", *current_frame);
-            }
-            CiTestingEvent::ScreenshotAndExit => {
-                let this_frame = *current_frame;
-                world.spawn(Screenshot::primary_window()).observe(
-                    move |captured: On<bevy_render::view::screenshot::ScreenshotCaptured>,
-                          mut app_exit_writer: MessageWriter<AppExit>| {
-                        let path = format!("./screenshot-{this_frame}.png");
-                        save_to_disk(path)(captured);
-                        info!("Exiting. Test successful!");
-                        app_exit_writer.write(AppExit::Success);
-                    },
-                );
-                info!("Took a screenshot at frame {}.", *current_frame);
-            }
-            CiTestingEvent::Screenshot => {
-                let path = format!("./screenshot-{}.png", *current_frame);
-                world
-                    .spawn(Screenshot::primary_window())
-                    .observe(save_to_disk(path));
-                info!("Took a screenshot at frame {}.", *current_frame);
-            }
-            CiTestingEvent::NamedScreenshot(name) => {
-                let path = format!("./screenshot-{name}.png");
-                world
-                    .spawn(Screenshot::primary_window())
-                    .observe(save_to_disk(path));
-                info!(
-                    "Took a screenshot at frame {} for {}.",
-                    *current_frame, name
-                );
-            }
-            CiTestingEvent::StartScreenRecording => {
-                info!("Started recording screen at frame {}.", *current_frame);
-                #[cfg(feature = "screenrecording")]
-                world.write_message(crate::RecordScreen::Start);
+        send_event(world, event, *current_frame);
+    }
+
+    *current_frame += 1;
+}
+
+/// The progress of the scenario of the [`CiTestingConfig`].
+#[derive(Default)]
+pub(crate) struct ScenarioProgress {
+    /// The index of the current step.
+    step: usize,
+    /// The frame the current step started on.
+    started_on: Option<u32>,
+    /// Whether an [`AppExit::error`] has already been written by a step.
+    exited_with_error: bool,
+}
+
+pub(crate) fn run_scenario(
+    world: &mut World,
+    mut progress: Local<ScenarioProgress>,
+    mut current_frame: Local<u32>,
+) {
+    let frame = *current_frame;
+    *current_frame += 1;
+    if world.resource::<CiTestingConfig>().scenario.is_empty()
+        || world.resource::<CiTestingReport>().finished
+    {
+        return;
+    }
+
+    loop {
+        let config = world.resource::<CiTestingConfig>();
+        let Some(step) = config.scenario.get(progress.step).cloned() else {
+            finish_scenario(world, frame, progress.exited_with_error);
+            return;
+        };
+        let started_on = *progress.started_on.get_or_insert(frame);
+        let waited = frame - started_on;
+
+        let (outcome, abort) = match step {
+            CiTestingStep::Event(CiTestingEvent::AppExit)
+                if !world.resource::<CiTestingReport>().passed =>
+            {
+                world.write_message(AppExit::error());
+                error!("Exiting after {} frames. Test failed!", frame);
+                progress.exited_with_error = true;
+                (CiTestingOutcome::Passed, false)
             }
-            CiTestingEvent::StopScreenRecording => {
-                info!("Stopped recording screen at frame {}.", *current_frame);
-                #[cfg(feature = "screenrecording")]
-                world.write_message(crate::RecordScreen::Stop);
+            CiTestingStep::Event(event) => {
+                send_event(world, event, frame);
+                (CiTestingOutcome::Passed, false)
             }
-            CiTestingEvent::MoveCamera {
-                translation,
-                rotation,
-            } => {
-                info!("Moved camera at frame {}.", *current_frame);
-                if let Ok(camera) = world.query_filtered::<Entity, With<Camera>>().single(world) {
-                    world.entity_mut(camera).insert(CameraMovement {
-                        translation,
-                        rotation,
-                    });
+            CiTestingStep::WaitFrames(frames) if waited < frames => return,
+            CiTestingStep::WaitFrames(_) => (CiTestingOutcome::Passed, false),
+            CiTestingStep::WaitUntil { condition, timeout } => {
+                match check_condition(world, &condition) {
+                    ConditionCheck::Met => (CiTestingOutcome::Passed, false),
+                    ConditionCheck::NotMet(_) if waited < timeout => return,
+                    ConditionCheck::NotMet(reason) => (
+                        CiTestingOutcome::Failed(format!(
+                            "timed out after {timeout} frames: {reason}"
+                        )),
+                        true,
+                    ),
+                    ConditionCheck::Invalid(reason) => (CiTestingOutcome::Failed(reason), true),
                 }
             }
-            // Custom events are forwarded to the world.
-            CiTestingEvent::Custom(event_string) => {
-                world.write_message(CiTestingCustomEvent(event_string));
+            CiTestingStep::Assert(condition) => match check_condition(world, &condition) {
+                ConditionCheck::Met => (CiTestingOutcome::Passed, false),
+                ConditionCheck::NotMet(reason) | ConditionCheck::Invalid(reason) => {
+                    (CiTestingOutcome::Failed(reason), false)
+                }
+            },
+        };
+
+        if let CiTestingOutcome::Failed(reason) = &outcome {
+            error!(
+                "Step {} failed at frame {}: {}",
+                progress.step, frame, reason
+            );
+        }
+        let mut report = world.resource_mut::<CiTestingReport>();
+        report.passed &= outcome == CiTestingOutcome::Passed;
+        report.steps.push(CiTestingStepReport {
+            step: progress.step,
+            frame,
+            outcome,
+        });
+        progress.step += 1;
+        progress.started_on = None;
+
+        if abort {
+            let remaining = world.resource::<CiTestingConfig>().scenario.len();
+            let mut report = world.resource_mut::<CiTestingReport>();
+            report
+                .steps
+                .extend((progress.step..remaining).map(|step| CiTestingStepReport {
+                    step,
+                    frame,
+                    outcome: CiTestingOutcome::Skipped,
+                }));
+            finish_scenario(world, frame, progress.exited_with_error);
+            return;
+        }
+    }
+}
+
+/// Writes the report of the scenario, and exits with an error if it failed, unless a step
+/// already did.
+fn finish_scenario(world: &mut World, frame: u32, exited_with_error: bool) {
+    let mut report = world.resource_mut::<CiTestingReport>();
+    report.finished = true;
+    let report = report.clone();
+
+    if let Some(path) = &world.resource::<CiTestingConfig>().setup.report_path {
+        let written = ron::ser::to_string_pretty(&report, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|report| std::fs::write(path, report).map_err(|err| err.to_string()));
+        match written {
+            Ok(()) => info!("Wrote CI testing report to {}.", path),
+            Err(err) => error!("Failed to write CI testing report to {}: {}", path, err),
+        }
+    }
+
+    if report.passed {
+        info!("Scenario finished after {} frames.", frame);
+    } else if !exited_with_error {
+        world.write_message(AppExit::error());
+        error!("Scenario failed after {} frames. Test failed!", frame);
+    }
+}
+
+fn send_event(world: &mut World, event: CiTestingEvent, current_frame: u32) {
+    debug!("Handling event: {:?}", event);
+    match event {
+        CiTestingEvent::AppExit => {
+            world.write_message(AppExit::Success);
+            info!("Exiting after {} frames. Test successful!", current_frame);
+        }
+        CiTestingEvent::ScreenshotAndExit => {
+            world.spawn(Screenshot::primary_window()).observe(
+                move |captured: On<bevy_render::view::screenshot::ScreenshotCaptured>,
+                      mut app_exit_writer: MessageWriter<AppExit>| {
+                    let path = format!("./screenshot-{current_frame}.png");
+                    save_to_disk(path)(captured);
+                    info!("Exiting. Test successful!");
+                    app_exit_writer.write(AppExit::Success);
+                },
+            );
+            info!("Took a screenshot at frame {}.", current_frame);
+        }
+        CiTestingEvent::Screenshot => {
+            let path = format!("./screenshot-{}.png", current_frame);
+            world
+                .spawn(Screenshot::primary_window())
+                .observe(save_to_disk(path));
+            info!("Took a screenshot at frame {}.", current_frame);
+        }
+        CiTestingEvent::NamedScreenshot(name) => {
+            let path = format!("./screenshot-{name}.png");
+            world
+                .spawn(Screenshot::primary_window())
+                .observe(save_to_disk(path));
+            info!("Took a screenshot at frame {} for {}.", current_frame, name);
+        }
+        CiTestingEvent::StartScreenRecording => {
+            info!("Started recording screen at frame {}.", current_frame);
+            #[cfg(feature = "screenrecording")]
+            world.write_message(crate::RecordScreen::Start);
+        }
+        CiTestingEvent::StopScreenRecording => {
+            info!("Stopped recording screen at frame {}.", current_frame);
+            #[cfg(feature = "screenrecording")]
+            world.write_message(crate::RecordScreen::Stop);
+        }
+        CiTestingEvent::MoveCamera {
+            translation,
+            rotation,
+        } => {
+            info!("Moved camera at frame {}.", current_frame);
+            if let Ok(camera) = world.query_filtered::<Entity, With<Camera>>().single(world) {
+                world.entity_mut(camera).insert(CameraMovement {
+                    translation,
+                    rotation,
+                });
             }
         }
+        // Custom events are forwarded to the world.
+        CiTestingEvent::Custom(event_string) => {
+            world.write_message(CiTestingCustomEvent(event_string));
+        }
+        CiTestingEvent::KeyPress(key_code) => {
+            input::send_keyboard_input(world, key_code, ButtonState::Pressed);
+        }
+        CiTestingEvent::KeyRelease(key_code) => {
+            input::send_keyboard_input(world, key_code, ButtonState::Released);
+        }
+        CiTestingEvent::MouseButtonPress(button) => {
+            input::send_mouse_button_input(world, button, ButtonState::Pressed);
+        }
+        CiTestingEvent::MouseButtonRelease(button) => {
+            input::send_mouse_button_input(world, button, ButtonState::Released);
+        }
+        CiTestingEvent::MouseMotion(delta) => input::send_mouse_motion(world, delta),
+        CiTestingEvent::CursorMoved(position) => input::send_cursor_moved(world, position),
+        CiTestingEvent::MouseWheel(delta) => input::send_mouse_wheel(world, delta),
+        #[cfg(feature = "gamepad")]
+        CiTestingEvent::GamepadButton(button, value) => {
+            input::send_gamepad_button(world, button, value);
+        }
+        #[cfg(feature = "gamepad")]
+        CiTestingEvent::GamepadAxis(axis, value) => input::send_gamepad_axis(world, axis, value),
     }
+}
 
-    *current_frame += 1;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{name::Name, reflect::AppTypeRegistry, system::SystemId};
+
+    fn world(scenario: Vec<CiTestingStep>) -> (World, SystemId) {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<Messages<AppExit>>();
+        world.init_resource::<CiTestingReport>();
+        world.insert_resource(CiTestingConfig {
+            scenario,
+            ..Default::default()
+        });
+        let run_scenario = world.register_system(run_scenario);
+        (world, run_scenario)
+    }
+
+    fn run_frames(world: &mut World, run_scenario: SystemId, frames: u32) {
+        for _ in 0..frames {
+            world.run_system(run_scenario).unwrap();
+        }
+    }
+
+    fn exits(world: &mut World) -> Vec<AppExit> {
+        world.resource_mut::<Messages<AppExit>>().drain().collect()
+    }
+
+    fn step(step: usize, frame: u32, outcome: CiTestingOutcome) -> CiTestingStepReport {
+        CiTestingStepReport {
+            step,
+            frame,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn wait_frames() {
+        let (mut world, run_scenario) = world(vec![
+            CiTestingStep::WaitFrames(2),
+            CiTestingStep::Assert(CiTestingCondition::EntityExists("Player".into())),
+        ]);
+        world.spawn(Name::new("Player"));
+
+        run_frames(&mut world, run_scenario, 2);
+        assert!(world.resource::<CiTestingReport>().steps.is_empty());
+
+        run_frames(&mut world, run_scenario, 1);
+        let report = world.resource::<CiTestingReport>();
+        assert!(report.passed);
+        assert!(report.finished);
+        assert_eq!(
+            report.steps,
+            [
+                step(0, 2, CiTestingOutcome::Passed),
+                step(1, 2, CiTestingOutcome::Passed),
+            ]
+        );
+        assert!(exits(&mut world).is_empty());
+    }
+
+    #[test]
+    fn wait_until_timeout_aborts_scenario() {
+        let (mut world, run_scenario) = world(vec![
+            CiTestingStep::WaitUntil {
+                condition: CiTestingCondition::EntityExists("Player".into()),
+                timeout: 2,
+            },
+            CiTestingStep::WaitFrames(1),
+            CiTestingStep::Event(CiTestingEvent::AppExit),
+        ]);
+
+        run_frames(&mut world, run_scenario, 2);
+        assert!(!world.resource::<CiTestingReport>().finished);
+
+        run_frames(&mut world, run_scenario, 1);
+        let report = world.resource::<CiTestingReport>();
+        assert!(!report.passed);
+        assert!(report.finished);
+        assert!(matches!(
+            &report.steps[0],
+            CiTestingStepReport {
+                step: 0,
+                frame: 2,
+                outcome: CiTestingOutcome::Failed(reason),
+            } if reason.starts_with("timed out after 2 frames")
+        ));
+        assert_eq!(
+            report.steps[1..],
+            [
+                step(1, 2, CiTestingOutcome::Skipped),
+                step(2, 2, CiTestingOutcome::Skipped),
+            ]
+        );
+        assert_eq!(exits(&mut world), [AppExit::error()]);
+
+        // The scenario doesn't go on once aborted.
+        run_frames(&mut world, run_scenario, 1);
+        assert_eq!(world.resource::<CiTestingReport>().steps.len(), 3);
+        assert!(exits(&mut world).is_empty());
+    }
+
+    #[test]
+    fn exit_with_error_after_failed_assert() {
+        let (mut world, run_scenario) = world(vec![
+            CiTestingStep::Assert(CiTestingCondition::EntityExists("Player".into())),
+            CiTestingStep::Event(CiTestingEvent::AppExit),
+        ]);
+
+        run_frames(&mut world, run_scenario, 1);
+        let report = world.resource::<CiTestingReport>();
+        assert!(!report.passed);
+        assert!(report.finished);
+        assert!(matches!(
+            report.steps[0].outcome,
+            CiTestingOutcome::Failed(_)
+        ));
+        assert_eq!(report.steps[1], step(1, 0, CiTestingOutcome::Passed));
+        assert_eq!(exits(&mut world), [AppExit::error()]);
+    }
 }
@@ -0,0 +1,766 @@
+//! Deterministic recording and replaying of input and time, to reproduce bug reports.
+//!
+//! The [`InputRecordPlugin`] captures, for every frame, the [`WindowEvent`]s (which include all
+//! keyboard, mouse, touch and window messages), the [`RawGamepadEvent`]s, and the delta of
+//! [`Time<Real>`]. The [`InputReplayPlugin`] feeds them back to the app, controlling time with
+//! [`TimeUpdateStrategy::ManualDuration`] so that `Update` and `FixedUpdate` run exactly as they
+//! did during the recording. Live keyboard, mouse, touch and gamepad input is ignored while
+//! replaying.
+//!
+//! Both plugins accept a checksum of the state of the world, computed at the end of every frame.
+//! The replayer compares it against the recorded checksum, and reports the first frame where
+//! they diverge.
+//!
+//! ```no_run
+//! # use bevy_app::App;
+//! # use bevy_dev_tools::input_replay::{InputRecordPlugin, InputReplayPlugin};
+//! # use bevy_ecs::prelude::*;
+//! # #[derive(Resource)]
+//! # struct Score(u64);
+//! fn checksum(world: &mut World) -> u64 {
+//!     world.resource::<Score>().0
+//! }
+//!
+//! let mut app = App::new();
+//! if let Ok(path) = std::env::var("REPLAY") {
+//!     let plugin = InputReplayPlugin::from_file(path).expect("invalid input recording");
+//!     app.add_plugins(plugin.with_checksum(checksum));
+//! } else {
+//!     app.add_plugins(
+//!         InputRecordPlugin::default()
+//!             .save_on_exit("session.ron")
+//!             .with_checksum(checksum),
+//!     );
+//! }
+//! ```
+//!
+//! Replays rely on the app being deterministic apart from its input and time. Windows are
+//! expected to be spawned identically during the recording and the replay, since the window
+//! entities of recorded events are replayed as is. Gamepad entities are spawned by the replayer.
+
+use alloc::sync::Arc;
+use bevy_app::prelude::*;
+use bevy_ecs::{entity::EntityHashMap, prelude::*};
+use bevy_input::gamepad::{
+    GamepadConnectionEvent, RawGamepadAxisChangedEvent, RawGamepadButtonChangedEvent,
+    RawGamepadEvent,
+};
+use bevy_input::{
+    gestures::{DoubleTapGesture, PanGesture, PinchGesture, RotationGesture},
+    keyboard::{KeyboardFocusLost, KeyboardInput},
+    mouse::{MouseButtonInput, MouseMotion, MouseWheel},
+    touch::TouchInput,
+};
+use bevy_time::{Real, Time, TimeSystems, TimeUpdateStrategy};
+use bevy_window::{CursorEntered, CursorLeft, CursorMoved, FileDragAndDrop, Ime, WindowEvent};
+use core::{marker::PhantomData, time::Duration};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::{error, info, warn};
+
+/// The version of the [`InputRecording`] format written by this version of Bevy.
+pub const INPUT_RECORDING_VERSION: u32 = 1;
+
+/// A function computing a checksum of the state of the world at the end of a frame.
+type ChecksumFn = Arc<dyn Fn(&mut World) -> u64 + Send + Sync>;
+
+/// A recording of the input and time of every frame of a session.
+///
+/// Recordings are stored as [`ron`] files. Use [`InputRecording::load`] and
+/// [`InputRecording::save`] to read and write them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InputRecording {
+    /// The version of the format of this recording.
+    pub version: u32,
+    /// The recorded frames, in order.
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Default for InputRecording {
+    fn default() -> Self {
+        Self {
+            version: INPUT_RECORDING_VERSION,
+            frames: Vec::new(),
+        }
+    }
+}
+
+/// The input and time of a single frame of an [`InputRecording`].
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct RecordedFrame {
+    /// The delta of [`Time<Real>`] during this frame.
+    pub delta: Duration,
+    /// The window events, including keyboard, mouse and touch input, received during this
+    /// frame.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub window_events: Vec<WindowEvent>,
+    /// The gamepad events received during this frame.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gamepad_events: Vec<RawGamepadEvent>,
+    /// The checksum of the world at the end of this frame, if one was computed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<u64>,
+}
+
+/// An error that occurs when loading or saving an [`InputRecording`].
+#[derive(Error, Debug)]
+pub enum InputRecordingError {
+    /// An I/O error occurred.
+    #[error("could not read or write the input recording: {0}")]
+    Io(#[from] std::io::Error),
+    /// The recording could not be parsed.
+    #[error("could not parse the input recording: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    /// The recording could not be serialized.
+    #[error("could not serialize the input recording: {0}")]
+    Serialize(#[from] ron::Error),
+    /// The recording was written in a format this version of Bevy doesn't support.
+    #[error("unsupported input recording version {0}, expected version {INPUT_RECORDING_VERSION}")]
+    UnsupportedVersion(u32),
+}
+
+impl InputRecording {
+    /// Parses a recording from the contents of a [`ron`] file.
+    pub fn from_ron(text: &str) -> Result<Self, InputRecordingError> {
+        // The version is checked first, so that recordings in an unsupported format are reported
+        // as such rather than as parse errors.
+        #[derive(Deserialize)]
+        struct Header {
+            version: u32,
+        }
+        let header: Header = ron::from_str(text)?;
+        if header.version != INPUT_RECORDING_VERSION {
+            return Err(InputRecordingError::UnsupportedVersion(header.version));
+        }
+        Ok(ron::from_str(text)?)
+    }
+
+    /// Serializes the recording as a [`ron`] string.
+    pub fn to_ron(&self) -> Result<String, InputRecordingError> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    /// Loads a recording from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputRecordingError> {
+        Self::from_ron(&std::fs::read_to_string(path)?)
+    }
+
+    /// Saves the recording to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputRecordingError> {
+        std::fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+}
+
+/// A plugin that records the input and time of every frame into the [`InputRecorder`]
+/// resource.
+///
+/// See the [module docs](self) for more details.
+#[derive(Default)]
+pub struct InputRecordPlugin {
+    path: Option<PathBuf>,
+    checksum: Option<ChecksumFn>,
+}
+
+impl InputRecordPlugin {
+    /// Saves the recording to the given file when the app exits.
+    #[must_use]
+    pub fn save_on_exit(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Records a checksum of the world at the end of every frame, to be compared when replaying.
+    #[must_use]
+    pub fn with_checksum(
+        mut self,
+        checksum: impl Fn(&mut World) -> u64 + Send + Sync + 'static,
+    ) -> Self {
+        self.checksum = Some(Arc::new(checksum));
+        self
+    }
+}
+
+impl Plugin for InputRecordPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputRecorder {
+            recording: InputRecording::default(),
+            path: self.path.clone(),
+            checksum: self.checksum.clone(),
+        })
+        .add_systems(Last, (record_frame, record_checksum).chain());
+    }
+}
+
+/// The recording made by the [`InputRecordPlugin`].
+#[derive(Resource)]
+pub struct InputRecorder {
+    recording: InputRecording,
+    path: Option<PathBuf>,
+    checksum: Option<ChecksumFn>,
+}
+
+impl InputRecorder {
+    /// Returns the frames recorded so far.
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    /// Takes the frames recorded so far, leaving an empty recording.
+    pub fn take(&mut self) -> InputRecording {
+        core::mem::take(&mut self.recording)
+    }
+}
+
+/// Records the input and time of the current frame, and saves the recording if the app is
+/// exiting.
+fn record_frame(
+    mut recorder: ResMut<InputRecorder>,
+    real_time: Res<Time<Real>>,
+    mut window_events: MessageReader<WindowEvent>,
+    mut gamepad_events: MessageReader<RawGamepadEvent>,
+    mut app_exit: MessageReader<AppExit>,
+) {
+    recorder.recording.frames.push(RecordedFrame {
+        delta: real_time.delta(),
+        window_events: window_events.read().cloned().collect(),
+        gamepad_events: gamepad_events.read().cloned().collect(),
+        checksum: None,
+    });
+
+    if app_exit.read().next().is_some()
+        && let Some(path) = &recorder.path
+    {
+        match recorder.recording.save(path) {
+            Ok(()) => info!("Saved input recording to {}.", path.display()),
+            Err(err) => error!("Failed to save input recording: {err}"),
+        }
+    }
+}
+
+fn record_checksum(world: &mut World) {
+    let Some(checksum) = world.resource::<InputRecorder>().checksum.clone() else {
+        return;
+    };
+    let checksum = checksum(world);
+    if let Some(frame) = world
+        .resource_mut::<InputRecorder>()
+        .recording
+        .frames
+        .last_mut()
+    {
+        frame.checksum = Some(checksum);
+    }
+}
+
+/// A plugin that replays an [`InputRecording`], and reports the first frame where the
+/// checksum of the world diverges from the recorded one.
+///
+/// Once the replay is finished, the app exits, with an error if the checksums diverged.
+/// Use [`InputReplayPlugin::exit_when_finished`] to keep the app running instead, in which case
+/// time goes back to being updated automatically.
+///
+/// See the [module docs](self) for more details.
+pub struct InputReplayPlugin {
+    recording: InputRecording,
+    checksum: Option<ChecksumFn>,
+    exit_when_finished: bool,
+}
+
+impl InputReplayPlugin {
+    /// Creates a plugin replaying the given recording.
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            checksum: None,
+            exit_when_finished: true,
+        }
+    }
+
+    /// Creates a plugin replaying the recording stored in the given file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, InputRecordingError> {
+        Ok(Self::new(InputRecording::load(path)?))
+    }
+
+    /// Compares a checksum of the world at the end of every frame with the recorded one.
+    #[must_use]
+    pub fn with_checksum(
+        mut self,
+        checksum: impl Fn(&mut World) -> u64 + Send + Sync + 'static,
+    ) -> Self {
+        self.checksum = Some(Arc::new(checksum));
+        self
+    }
+
+    /// Sets whether the app exits once the replay is finished. Defaults to `true`.
+    #[must_use]
+    pub fn exit_when_finished(mut self, exit_when_finished: bool) -> Self {
+        self.exit_when_finished = exit_when_finished;
+        self
+    }
+}
+
+impl Plugin for InputReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputReplayer {
+            recording: self.recording.clone(),
+            checksum: self.checksum.clone(),
+            exit_when_finished: self.exit_when_finished,
+            frame: 0,
+            gamepads: EntityHashMap::default(),
+            divergence: None,
+        })
+        .add_systems(First, replay_frame.before(TimeSystems))
+        .add_systems(Last, check_frame);
+    }
+}
+
+/// The first frame of a replay where the checksum of the world differed from the recorded one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayDivergence {
+    /// The index of the frame, starting from zero.
+    pub frame: usize,
+    /// The recorded checksum.
+    pub expected: u64,
+    /// The checksum computed during the replay.
+    pub found: u64,
+}
+
+/// The state of the replay of the [`InputReplayPlugin`].
+#[derive(Resource)]
+pub struct InputReplayer {
+    recording: InputRecording,
+    checksum: Option<ChecksumFn>,
+    exit_when_finished: bool,
+    frame: usize,
+    /// Maps the recorded gamepad entities to the gamepads spawned during the replay.
+    gamepads: EntityHashMap<Entity>,
+    divergence: Option<ReplayDivergence>,
+}
+
+impl InputReplayer {
+    /// Returns the index of the frame being replayed.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Returns `true` if every recorded frame has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.recording.frames.len()
+    }
+
+    /// Returns the first frame where the checksum of the world diverged, if any.
+    pub fn divergence(&self) -> Option<ReplayDivergence> {
+        self.divergence
+    }
+}
+
+/// Sets the time and writes the input of the current frame.
+fn replay_frame(world: &mut World) {
+    world.resource_scope(|world, mut replayer: Mut<InputReplayer>| {
+        let Some(frame) = replayer.recording.frames.get(replayer.frame).cloned() else {
+            return;
+        };
+        world.insert_resource(TimeUpdateStrategy::ManualDuration(frame.delta));
+
+        clear_live_input(world);
+        for event in frame.window_events {
+            write_window_event(world, event);
+        }
+        for mut event in frame.gamepad_events {
+            let gamepad = match &mut event {
+                RawGamepadEvent::Connection(event) => &mut event.gamepad,
+                RawGamepadEvent::Button(event) => &mut event.gamepad,
+                RawGamepadEvent::Axis(event) => &mut event.gamepad,
+            };
+            *gamepad = *replayer
+                .gamepads
+                .entry(*gamepad)
+                .or_insert_with(|| world.spawn_empty().id());
+
+            // Gamepad backends write both the raw events and their individual messages.
+            match event.clone() {
+                RawGamepadEvent::Connection(event) => {
+                    world.write_message(event);
+                }
+                RawGamepadEvent::Button(event) => {
+                    world.write_message(event);
+                }
+                RawGamepadEvent::Axis(event) => {
+                    world.write_message(event);
+                }
+            }
+            world.write_message(event);
+        }
+        mark_replayed_input(world);
+    });
+}
+
+/// Compares the checksum of the current frame, and finishes the replay after the last frame.
+fn check_frame(world: &mut World) {
+    let replayer = world.resource::<InputReplayer>();
+    if replayer.is_finished() {
+        return;
+    }
+    let expected = replayer.recording.frames[replayer.frame].checksum;
+    let found = replayer.checksum.clone().map(|checksum| checksum(world));
+
+    let mut replayer = world.resource_mut::<InputReplayer>();
+    if let (Some(expected), Some(found)) = (expected, found)
+        && expected != found
+        && replayer.divergence.is_none()
+    {
+        error!(
+            "Replay diverged at frame {}: expected checksum {expected:#x}, found {found:#x}.",
+            replayer.frame
+        );
+        replayer.divergence = Some(ReplayDivergence {
+            frame: replayer.frame,
+            expected,
+            found,
+        });
+    }
+    replayer.frame += 1;
+    if !replayer.is_finished() {
+        return;
+    }
+
+    let exit = match replayer.divergence {
+        None => {
+            info!("Replayed {} frames without diverging.", replayer.frame);
+            AppExit::Success
+        }
+        Some(divergence) => {
+            warn!(
+                "Replayed {} frames, diverging at frame {}.",
+                replayer.frame, divergence.frame
+            );
+            AppExit::error()
+        }
+    };
+    if replayer.exit_when_finished {
+        world.write_message(exit);
+    } else {
+        world.insert_resource(TimeUpdateStrategy::Automatic);
+    }
+}
+
+/// Returns `true` if the window event is user input, which is replaced by the recorded input
+/// while replaying.
+fn is_input(event: &WindowEvent) -> bool {
+    matches!(
+        event,
+        WindowEvent::CursorEntered(_)
+            | WindowEvent::CursorLeft(_)
+            | WindowEvent::CursorMoved(_)
+            | WindowEvent::FileDragAndDrop(_)
+            | WindowEvent::Ime(_)
+            | WindowEvent::MouseButtonInput(_)
+            | WindowEvent::MouseMotion(_)
+            | WindowEvent::MouseWheel(_)
+            | WindowEvent::PinchGesture(_)
+            | WindowEvent::RotationGesture(_)
+            | WindowEvent::DoubleTapGesture(_)
+            | WindowEvent::PanGesture(_)
+            | WindowEvent::TouchInput(_)
+            | WindowEvent::KeyboardInput(_)
+            | WindowEvent::KeyboardFocusLost(_)
+    )
+}
+
+/// Calls `$f::<M>($world)` for each input message written by the windowing and gamepad backends,
+/// except [`WindowEvent`].
+macro_rules! for_each_input_message {
+    ($f:ident($world:expr)) => {
+        $f::<CursorEntered>($world);
+        $f::<CursorLeft>($world);
+        $f::<CursorMoved>($world);
+        $f::<FileDragAndDrop>($world);
+        $f::<Ime>($world);
+        $f::<MouseButtonInput>($world);
+        $f::<MouseMotion>($world);
+        $f::<MouseWheel>($world);
+        $f::<PinchGesture>($world);
+        $f::<RotationGesture>($world);
+        $f::<DoubleTapGesture>($world);
+        $f::<PanGesture>($world);
+        $f::<TouchInput>($world);
+        $f::<KeyboardInput>($world);
+        $f::<KeyboardFocusLost>($world);
+        $f::<RawGamepadEvent>($world);
+        $f::<GamepadConnectionEvent>($world);
+        $f::<RawGamepadButtonChangedEvent>($world);
+        $f::<RawGamepadAxisChangedEvent>($world);
+    };
+}
+
+/// The number of `M` messages written up to the input replayed in the last frame.
+///
+/// Messages written after it are live input.
+#[derive(Resource)]
+struct ReplayedMessageCount<M: Message> {
+    count: usize,
+    marker: PhantomData<M>,
+}
+
+/// Returns the number of messages written up to the input replayed in the last frame, or `0`
+/// before the first frame.
+fn replayed_message_count<M: Message>(world: &World) -> usize {
+    world
+        .get_resource::<ReplayedMessageCount<M>>()
+        .map_or(0, |replayed| replayed.count)
+}
+
+/// Drops the input received from the windowing and gamepad backends since the input of the last
+/// frame was replayed.
+///
+/// The replayed input stays readable until the messages are updated, like live input would.
+fn clear_live_input(world: &mut World) {
+    fn clear<M: Message>(world: &mut World) {
+        let count = replayed_message_count::<M>(world);
+        if let Some(mut messages) = world.get_resource_mut::<Messages<M>>() {
+            messages.truncate(count);
+        }
+    }
+
+    let count = replayed_message_count::<WindowEvent>(world);
+    if let Some(mut messages) = world.get_resource_mut::<Messages<WindowEvent>>() {
+        let end = messages.oldest_message_count() + messages.len();
+        let window_events: Vec<_> = (count..end)
+            .filter_map(|id| messages.get_message(id))
+            .map(|(event, _)| event)
+            .filter(|event| !is_input(event))
+            .cloned()
+            .collect();
+        messages.truncate(count);
+        messages.write_batch(window_events);
+    }
+    for_each_input_message!(clear(world));
+}
+
+/// Remembers which messages were replayed, so that [`clear_live_input`] only drops the messages
+/// written after them.
+fn mark_replayed_input(world: &mut World) {
+    fn mark<M: Message>(world: &mut World) {
+        let Some(messages) = world.get_resource::<Messages<M>>() else {
+            return;
+        };
+        let count = messages.oldest_message_count() + messages.len();
+        world.insert_resource(ReplayedMessageCount::<M> {
+            count,
+            marker: PhantomData,
+        });
+    }
+
+    mark::<WindowEvent>(world);
+    for_each_input_message!(mark(world));
+}
+
+/// Writes a window event and its individual message, like the windowing backend does.
+fn write_window_event(world: &mut World, event: WindowEvent) {
+    macro_rules! write_individual_message {
+        ($($variant:ident),* $(,)?) => {
+            match event.clone() {
+                $(WindowEvent::$variant(event) => {
+                    world.write_message(event);
+                })*
+            }
+        };
+    }
+    write_individual_message!(
+        AppLifecycle,
+        CursorEntered,
+        CursorLeft,
+        CursorMoved,
+        FileDragAndDrop,
+        Ime,
+        RequestRedraw,
+        WindowBackendScaleFactorChanged,
+        WindowCloseRequested,
+        WindowCreated,
+        WindowDestroyed,
+        WindowFocused,
+        WindowMoved,
+        WindowOccluded,
+        WindowResized,
+        WindowScaleFactorChanged,
+        WindowThemeChanged,
+        MouseButtonInput,
+        MouseMotion,
+        MouseWheel,
+        PinchGesture,
+        RotationGesture,
+        DoubleTapGesture,
+        PanGesture,
+        TouchInput,
+        KeyboardInput,
+        KeyboardFocusLost,
+    );
+    world.write_message(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_input::{
+        keyboard::{Key, KeyCode},
+        ButtonInput, ButtonState, InputPlugin,
+    };
+    use bevy_time::TimePlugin;
+
+    #[derive(Resource, Default)]
+    struct Counter(u64);
+
+    fn count(mut counter: ResMut<Counter>, keys: Res<ButtonInput<KeyCode>>) {
+        if keys.just_pressed(KeyCode::Space) {
+            counter.0 += 100;
+        }
+    }
+
+    fn count_fixed(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    fn checksum(world: &mut World) -> u64 {
+        world.resource::<Counter>().0
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, InputPlugin))
+            .add_message::<WindowEvent>()
+            .init_resource::<Counter>()
+            .add_systems(Update, count)
+            .add_systems(FixedUpdate, count_fixed);
+        app
+    }
+
+    fn record() -> (InputRecording, u64) {
+        let mut app = app();
+        app.add_plugins(InputRecordPlugin::default().with_checksum(checksum));
+        for frame in 0..20_u64 {
+            app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                7 + frame % 5,
+            )));
+            if frame == 10 {
+                let input = KeyboardInput {
+                    key_code: KeyCode::Space,
+                    logical_key: Key::Space,
+                    state: ButtonState::Pressed,
+                    text: None,
+                    repeat: false,
+                    window: Entity::PLACEHOLDER,
+                };
+                app.world_mut()
+                    .write_message(WindowEvent::KeyboardInput(input.clone()));
+                app.world_mut().write_message(input);
+            }
+            app.update();
+        }
+        let counter = app.world().resource::<Counter>().0;
+        let recording = app.world_mut().resource_mut::<InputRecorder>().take();
+        (recording, counter)
+    }
+
+    fn replay(recording: InputRecording) -> App {
+        let mut app = app();
+        app.add_plugins(InputReplayPlugin::new(recording).with_checksum(checksum));
+        for _ in 0..20 {
+            app.update();
+        }
+        app
+    }
+
+    #[test]
+    fn replay_matches_recording() {
+        let (recording, counter) = record();
+        assert!(counter > 100);
+        assert_eq!(recording.frames.len(), 20);
+        assert_eq!(recording.frames[10].window_events.len(), 1);
+
+        let recording = InputRecording::from_ron(&recording.to_ron().unwrap()).unwrap();
+        let mut app = replay(recording);
+        assert_eq!(app.world().resource::<Counter>().0, counter);
+        let replayer = app.world().resource::<InputReplayer>();
+        assert!(replayer.is_finished());
+        assert_eq!(replayer.divergence(), None);
+        let exit = app
+            .world_mut()
+            .resource_mut::<Messages<AppExit>>()
+            .drain()
+            .next();
+        assert_eq!(exit, Some(AppExit::Success));
+    }
+
+    #[test]
+    fn replay_reports_first_divergence() {
+        let (mut recording, _) = record();
+        // Dropping the key press changes the checksum of every following frame.
+        recording.frames[10].window_events.clear();
+        let mut app = replay(recording.clone());
+        let replayer = app.world().resource::<InputReplayer>();
+        assert_eq!(
+            replayer.divergence(),
+            Some(ReplayDivergence {
+                frame: 10,
+                expected: recording.frames[10].checksum.unwrap(),
+                found: recording.frames[10].checksum.unwrap() - 100,
+            })
+        );
+        let exit = app
+            .world_mut()
+            .resource_mut::<Messages<AppExit>>()
+            .drain()
+            .next();
+        assert_eq!(exit, Some(AppExit::error()));
+    }
+
+    #[test]
+    fn clear_keeps_replayed_input() {
+        let mut world = World::new();
+        world.init_resource::<Messages<KeyboardInput>>();
+        world.init_resource::<Messages<WindowEvent>>();
+        let input = |key_code| KeyboardInput {
+            key_code,
+            logical_key: Key::Space,
+            state: ButtonState::Pressed,
+            text: None,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        };
+
+        // Input replayed in the previous frame, which is still buffered.
+        write_window_event(&mut world, WindowEvent::KeyboardInput(input(KeyCode::KeyA)));
+        mark_replayed_input(&mut world);
+        world.resource_mut::<Messages<KeyboardInput>>().update();
+        world.resource_mut::<Messages<WindowEvent>>().update();
+        // Live input received since then.
+        write_window_event(&mut world, WindowEvent::KeyboardInput(input(KeyCode::KeyB)));
+
+        clear_live_input(&mut world);
+        let messages = world.resource::<Messages<KeyboardInput>>();
+        let read: Vec<_> = messages
+            .get_cursor()
+            .read(messages)
+            .map(|input| input.key_code)
+            .collect();
+        assert_eq!(read, [KeyCode::KeyA]);
+        assert_eq!(world.resource::<Messages<WindowEvent>>().len(), 1);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let recording = InputRecording {
+            version: INPUT_RECORDING_VERSION + 1,
+            frames: Vec::new(),
+        };
+        assert!(matches!(
+            InputRecording::from_ron(&recording.to_ron().unwrap()),
+            Err(InputRecordingError::UnsupportedVersion(_))
+        ));
+    }
+}
@@ -18,6 +18,11 @@ mod easy_screenshot;
 pub mod fps_overlay;
 pub mod frame_time_graph;
 
+#[cfg(feature = "input_replay")]
+pub mod input_replay;
+
+pub mod log_overlay;
+
 pub mod picking_debug;
 
 #[cfg(feature = "schedule_data")]
@@ -0,0 +1,336 @@
+//! Module containing logic for the log overlay.
+//!
+//! The overlay lists the most recent records of the [`LogBuffer`], which is filled by the
+//! [`LogPlugin`](bevy_log::LogPlugin) when [`capture_capacity`](bevy_log::LogPlugin::capture_capacity)
+//! isn't `0`. Capturing logs is disabled by default. The listed records can be filtered by level and target, and exported to a file
+//! to be attached to bug reports.
+
+use core::time::Duration;
+use std::{fs, io, path::PathBuf};
+
+use bevy_app::{Plugin, Startup, Update};
+use bevy_color::{palettes::basic, Alpha, Color};
+use bevy_ecs::{
+    change_detection::DetectChanges,
+    component::Component,
+    entity::Entity,
+    query::With,
+    resource::Resource,
+    schedule::{
+        common_conditions::{resource_changed, resource_exists},
+        IntoScheduleConfigs, SystemSet,
+    },
+    system::{Commands, Res, ResMut, Single},
+};
+use bevy_input::{keyboard::KeyCode, ButtonInput};
+use bevy_log::{Level, LogBuffer, LogRecord};
+use bevy_picking::Pickable;
+use bevy_text::{TextColor, TextFont};
+use bevy_time::common_conditions::on_timer;
+use bevy_ui::{
+    widget::Text, BackgroundColor, FlexDirection, GlobalZIndex, Node, PositionType, UiRect, Val,
+};
+use tracing::{info, warn};
+
+/// [`GlobalZIndex`] used to render the log overlay.
+///
+/// This is below the [`FPS_OVERLAY_ZINDEX`](crate::fps_overlay::FPS_OVERLAY_ZINDEX),
+/// so that both overlays can be used at the same time.
+pub const LOG_OVERLAY_ZINDEX: i32 = i32::MAX - 64;
+
+/// A plugin that adds an overlay listing the most recent log records to the Bevy application.
+///
+/// The records are read from the [`LogBuffer`], so the [`LogPlugin`](bevy_log::LogPlugin) must
+/// be added before this plugin with a non-zero [`capture_capacity`](bevy_log::LogPlugin::capture_capacity).
+///
+/// ```no_run
+/// # use bevy_app::{App, NoopPluginGroup as DefaultPlugins, PluginGroup};
+/// # use bevy_dev_tools::log_overlay::LogOverlayPlugin;
+/// # use bevy_log::{LogPlugin, DEFAULT_CAPTURE_CAPACITY};
+/// App::new()
+///     .add_plugins(DefaultPlugins.set(LogPlugin {
+///         capture_capacity: DEFAULT_CAPTURE_CAPACITY,
+///         ..Default::default()
+///     }))
+///     .add_plugins(LogOverlayPlugin::default())
+///     .run();
+/// ```
+#[derive(Default)]
+pub struct LogOverlayPlugin {
+    /// Starting configuration of overlay, this can be later be changed through [`LogOverlayConfig`] resource.
+    pub config: LogOverlayConfig,
+}
+
+/// System sets for log overlay updates.
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub enum LogOverlaySystems {
+    /// Handles the keyboard shortcuts of the overlay.
+    Input,
+    /// Updates the overlay contents.
+    UpdateText,
+}
+
+impl Plugin for LogOverlayPlugin {
+    fn build(&self, app: &mut bevy_app::App) {
+        if !app.world().contains_resource::<LogBuffer>() {
+            warn!(
+                "The log overlay needs the `LogPlugin` to capture logs. \
+                Add it before the `LogOverlayPlugin` with a non-zero `capture_capacity`, \
+                such as `DEFAULT_CAPTURE_CAPACITY`."
+            );
+        }
+
+        app.insert_resource(self.config.clone())
+            .configure_sets(
+                Update,
+                LogOverlaySystems::Input.before(LogOverlaySystems::UpdateText),
+            )
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    (toggle_overlay, export_logs)
+                        .run_if(resource_exists::<ButtonInput<KeyCode>>)
+                        .in_set(LogOverlaySystems::Input),
+                    toggle_display
+                        .run_if(resource_changed::<LogOverlayConfig>)
+                        .in_set(LogOverlaySystems::UpdateText),
+                    update_text
+                        .run_if(resource_exists::<LogBuffer>)
+                        .run_if(on_timer(self.config.refresh_interval))
+                        .after(toggle_display)
+                        .in_set(LogOverlaySystems::UpdateText),
+                ),
+            );
+    }
+}
+
+/// Configuration options for the log overlay.
+#[derive(Resource, Clone)]
+pub struct LogOverlayConfig {
+    /// Configuration of text in the overlay.
+    pub text_config: TextFont,
+    /// Displays the log overlay if true.
+    pub enabled: bool,
+    /// The most verbose level of the listed records.
+    ///
+    /// Defaults to [`Level::INFO`].
+    pub level: Level,
+    /// Only records whose target starts with one of these are listed.
+    ///
+    /// All targets are listed if this is empty, which is the default.
+    pub targets: Vec<String>,
+    /// The maximum number of records listed at once.
+    ///
+    /// Defaults to 20.
+    pub max_lines: usize,
+    /// The period after which the log overlay re-renders.
+    ///
+    /// Defaults to once every 100 ms.
+    pub refresh_interval: Duration,
+    /// Key toggling [`enabled`](Self::enabled).
+    ///
+    /// Defaults to [`KeyCode::F9`].
+    pub toggle_key: Option<KeyCode>,
+    /// Key writing the records matching the filters of the overlay to [`export_path`](Self::export_path).
+    ///
+    /// Defaults to [`KeyCode::F10`].
+    pub export_key: Option<KeyCode>,
+    /// The file records are exported to, overwriting any previous export.
+    ///
+    /// Defaults to `logs.txt`.
+    pub export_path: PathBuf,
+}
+
+impl Default for LogOverlayConfig {
+    fn default() -> Self {
+        LogOverlayConfig {
+            text_config: TextFont::from_font_size(14.),
+            enabled: true,
+            level: Level::INFO,
+            targets: Vec::new(),
+            max_lines: 20,
+            refresh_interval: Duration::from_millis(100),
+            toggle_key: Some(KeyCode::F9),
+            export_key: Some(KeyCode::F10),
+            export_path: PathBuf::from("logs.txt"),
+        }
+    }
+}
+
+impl LogOverlayConfig {
+    /// Returns `true` if the `record` passes the level and target filters of the overlay.
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        record.level <= self.level
+            && (self.targets.is_empty()
+                || self
+                    .targets
+                    .iter()
+                    .any(|target| record.target.starts_with(target.as_str())))
+    }
+
+    /// Writes the records of `buffer` matching the filters of the overlay to `writer`,
+    /// one record per line, oldest first.
+    pub fn export(&self, buffer: &LogBuffer, mut writer: impl io::Write) -> io::Result<()> {
+        for record in buffer.iter().filter(|record| self.matches(record)) {
+            writeln!(writer, "{record}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Component)]
+struct LogOverlay;
+
+fn setup(mut commands: Commands, overlay_config: Res<LogOverlayConfig>) {
+    commands.spawn((
+        Node {
+            // We need to make sure the overlay doesn't affect the position of other UI nodes
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(0.),
+            left: Val::Px(0.),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(4.)),
+            display: if overlay_config.enabled {
+                bevy_ui::Display::DEFAULT
+            } else {
+                bevy_ui::Display::None
+            },
+            ..Default::default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.75)),
+        // Render overlay on top of everything
+        GlobalZIndex(LOG_OVERLAY_ZINDEX),
+        Pickable::IGNORE,
+        LogOverlay,
+    ));
+}
+
+fn level_color(level: Level) -> Color {
+    match level {
+        Level::ERROR => basic::RED.into(),
+        Level::WARN => basic::YELLOW.into(),
+        Level::INFO => Color::WHITE,
+        Level::DEBUG => basic::AQUA.into(),
+        _ => basic::SILVER.into(),
+    }
+}
+
+fn update_text(
+    mut commands: Commands,
+    overlay_config: Res<LogOverlayConfig>,
+    buffer: Res<LogBuffer>,
+    overlay: Single<Entity, With<LogOverlay>>,
+) {
+    if !overlay_config.enabled || !(buffer.is_changed() || overlay_config.is_changed()) {
+        return;
+    }
+
+    let mut records: Vec<_> = buffer
+        .iter()
+        .rev()
+        .filter(|record| overlay_config.matches(record))
+        .take(overlay_config.max_lines)
+        .collect();
+    records.reverse();
+
+    commands
+        .entity(*overlay)
+        .despawn_children()
+        .with_children(|p| {
+            for record in records {
+                p.spawn((
+                    Text::new(record.to_string()),
+                    overlay_config.text_config.clone(),
+                    TextColor(level_color(record.level)),
+                    Pickable::IGNORE,
+                ));
+            }
+        });
+}
+
+fn toggle_display(
+    overlay_config: Res<LogOverlayConfig>,
+    mut node: Single<&mut Node, With<LogOverlay>>,
+) {
+    if overlay_config.enabled {
+        node.display = bevy_ui::Display::DEFAULT;
+    } else {
+        node.display = bevy_ui::Display::None;
+    }
+}
+
+fn toggle_overlay(mut overlay_config: ResMut<LogOverlayConfig>, keys: Res<ButtonInput<KeyCode>>) {
+    if let Some(key) = overlay_config.toggle_key
+        && keys.just_pressed(key)
+    {
+        overlay_config.enabled = !overlay_config.enabled;
+    }
+}
+
+fn export_logs(
+    overlay_config: Res<LogOverlayConfig>,
+    buffer: Option<Res<LogBuffer>>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let (Some(key), Some(buffer)) = (overlay_config.export_key, buffer) else {
+        return;
+    };
+    if !keys.just_pressed(key) {
+        return;
+    }
+
+    let path = &overlay_config.export_path;
+    let result = fs::File::create(path).and_then(|file| {
+        let mut writer = io::BufWriter::new(file);
+        overlay_config.export(&buffer, &mut writer)?;
+        // Flush explicitly, as dropping the writer ignores errors.
+        io::Write::flush(&mut writer)
+    });
+    match result {
+        Ok(()) => info!("Exported logs to {}", path.display()),
+        Err(err) => warn!("Failed to export logs to {}: {err}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_log::LogPlugin;
+
+    #[test]
+    fn export_filters_records() {
+        let mut app = App::new();
+        app.add_plugins(LogPlugin {
+            level: Level::DEBUG,
+            capture_capacity: bevy_log::DEFAULT_CAPTURE_CAPACITY,
+            ..Default::default()
+        });
+        app.add_systems(Update, || {
+            bevy_log::info!(target: "game::combat", "hit");
+            bevy_log::warn!(target: "game::ai", "stuck");
+            bevy_log::debug!(target: "game::combat", "too verbose");
+            bevy_log::error!(target: "engine", "wrong target");
+        });
+        app.update();
+        app.update();
+
+        let config = LogOverlayConfig {
+            level: Level::DEBUG,
+            targets: vec!["game".into()],
+            ..Default::default()
+        };
+        let mut exported = Vec::new();
+        config
+            .export(app.world().resource::<LogBuffer>(), &mut exported)
+            .unwrap();
+
+        let exported = String::from_utf8(exported).unwrap();
+        let lines: Vec<_> = exported.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("game::combat: hit"));
+        assert!(lines[1].ends_with("game::ai: stuck"));
+        assert!(lines[2].ends_with("game::combat: too verbose"));
+    }
+}
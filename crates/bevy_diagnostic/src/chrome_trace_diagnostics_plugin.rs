@@ -0,0 +1,187 @@
+use super::{DiagnosticsStore, MeasurementCursor};
+
+use alloc::string::String;
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_platform::time::Instant;
+use bevy_time::{Real, Time};
+use core::fmt::Write as _;
+use log::error;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+/// An App Plugin that writes the measurements of all enabled diagnostics as counters of a
+/// [Chrome trace event](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU)
+/// file.
+///
+/// The file can be opened in `chrome://tracing` or [Perfetto](https://ui.perfetto.dev), and is
+/// created when the plugin is built, overwriting the file of a previous run.
+///
+/// Diagnostics are collected by plugins such as
+/// [`FrameTimeDiagnosticsPlugin`](crate::FrameTimeDiagnosticsPlugin)
+/// or can be provided by the user.
+pub struct ChromeTraceDiagnosticsPlugin {
+    /// The path of the trace file.
+    ///
+    /// Defaults to `diagnostics.json`.
+    pub path: PathBuf,
+}
+
+impl Default for ChromeTraceDiagnosticsPlugin {
+    fn default() -> Self {
+        ChromeTraceDiagnosticsPlugin {
+            path: PathBuf::from("diagnostics.json"),
+        }
+    }
+}
+
+impl ChromeTraceDiagnosticsPlugin {
+    /// Writes the diagnostics to the trace file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        ChromeTraceDiagnosticsPlugin { path: path.into() }
+    }
+
+    fn write_diagnostics_system(
+        mut state: ResMut<ChromeTraceDiagnosticsState>,
+        time: Res<Time<Real>>,
+        diagnostics: Res<DiagnosticsStore>,
+    ) {
+        let ChromeTraceDiagnosticsState { writer, cursor } = &mut *state;
+        let result = write_measurements(writer, cursor, &diagnostics, time.startup());
+        if let Err(err) = result.and_then(|()| writer.flush()) {
+            error!("Failed to write diagnostics to the Chrome trace: {err}");
+        }
+    }
+}
+
+impl Plugin for ChromeTraceDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut writer = match File::create(&self.path) {
+            Ok(file) => BufWriter::new(file),
+            Err(err) => {
+                error!(
+                    "Failed to create the diagnostics trace file {}: {err}",
+                    self.path.display()
+                );
+                return;
+            }
+        };
+        // Every event is written with a leading comma, so start with metadata naming the process.
+        if let Err(err) = writeln!(
+            writer,
+            r#"[{{"name":"process_name","ph":"M","pid":0,"tid":0,"args":{{"name":"bevy diagnostics"}}}}"#
+        ) {
+            error!("Failed to write diagnostics to the Chrome trace: {err}");
+        }
+
+        app.insert_resource(ChromeTraceDiagnosticsState {
+            writer,
+            cursor: MeasurementCursor::default(),
+        })
+        .add_systems(Last, Self::write_diagnostics_system);
+    }
+}
+
+/// State used by the [`ChromeTraceDiagnosticsPlugin`]
+#[derive(Resource)]
+struct ChromeTraceDiagnosticsState {
+    writer: BufWriter<File>,
+    cursor: MeasurementCursor,
+}
+
+impl Drop for ChromeTraceDiagnosticsState {
+    fn drop(&mut self) {
+        // Trace viewers accept unterminated files, so there is nothing to do if this fails.
+        let _ = writeln!(self.writer, "]");
+    }
+}
+
+/// Writes a counter event for each measurement read by `cursor`.
+fn write_measurements(
+    writer: &mut impl Write,
+    cursor: &mut MeasurementCursor,
+    diagnostics: &DiagnosticsStore,
+    startup: Instant,
+) -> io::Result<()> {
+    let mut result = Ok(());
+    cursor.read(diagnostics, |diagnostic, measurement| {
+        // JSON has no representation for NaN or infinity.
+        if result.is_err() || !measurement.value.is_finite() {
+            return;
+        }
+        let timestamp = measurement
+            .time
+            .saturating_duration_since(startup)
+            .as_secs_f64()
+            * 1_000_000.0;
+        let mut name = String::new();
+        escape_json(diagnostic.path().as_str(), &mut name);
+        result = writeln!(
+            writer,
+            r#",{{"name":"{name}","ph":"C","ts":{timestamp:.3},"pid":0,"tid":0,"args":{{"value":{}}}}}"#,
+            measurement.value
+        );
+    });
+    result
+}
+
+fn escape_json(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Diagnostic, DiagnosticMeasurement, DiagnosticPath};
+    use alloc::vec::Vec;
+    use core::time::Duration;
+
+    #[test]
+    fn write_counter_events() {
+        let startup = Instant::now();
+        let mut diagnostic = Diagnostic::new(DiagnosticPath::new("frame\"time"));
+        for (millis, value) in [(1, 16.5), (2, f64::NAN)] {
+            diagnostic.add_measurement(DiagnosticMeasurement {
+                time: startup + Duration::from_millis(millis),
+                value,
+            });
+        }
+        let mut diagnostics = DiagnosticsStore::default();
+        diagnostics.add(diagnostic);
+
+        let mut output = Vec::new();
+        write_measurements(
+            &mut output,
+            &mut MeasurementCursor::default(),
+            &diagnostics,
+            startup,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                r#",{"name":"frame\"time","ph":"C","ts":1000.000,"pid":0,"tid":0,"args":{"value":16.5}}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn escape_control_characters() {
+        let mut escaped = String::new();
+        escape_json("a\\b\n", &mut escaped);
+        assert_eq!(escaped, "a\\\\b\\u000a");
+    }
+}
@@ -0,0 +1,154 @@
+use super::{DiagnosticsStore, MeasurementCursor};
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_platform::time::Instant;
+use bevy_time::{Real, Time};
+use log::error;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+/// An App Plugin that writes the measurements of all enabled diagnostics to a CSV file.
+///
+/// The file is created when the plugin is built, overwriting the file of a previous run. Each row
+/// holds one measurement, as `time,path,value`, where `time` is the number of seconds since the
+/// app started. Paths containing commas, quotes or line breaks are quoted as described in
+/// [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180).
+///
+/// Diagnostics are collected by plugins such as
+/// [`FrameTimeDiagnosticsPlugin`](crate::FrameTimeDiagnosticsPlugin)
+/// or can be provided by the user.
+pub struct CsvDiagnosticsPlugin {
+    /// The path of the CSV file.
+    ///
+    /// Defaults to `diagnostics.csv`.
+    pub path: PathBuf,
+}
+
+impl Default for CsvDiagnosticsPlugin {
+    fn default() -> Self {
+        CsvDiagnosticsPlugin {
+            path: PathBuf::from("diagnostics.csv"),
+        }
+    }
+}
+
+impl CsvDiagnosticsPlugin {
+    /// Writes the diagnostics to the CSV file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        CsvDiagnosticsPlugin { path: path.into() }
+    }
+
+    fn write_diagnostics_system(
+        mut state: ResMut<CsvDiagnosticsState>,
+        time: Res<Time<Real>>,
+        diagnostics: Res<DiagnosticsStore>,
+    ) {
+        let CsvDiagnosticsState { writer, cursor } = &mut *state;
+        let result = write_measurements(writer, cursor, &diagnostics, time.startup());
+        if let Err(err) = result.and_then(|()| writer.flush()) {
+            error!("Failed to write diagnostics to CSV: {err}");
+        }
+    }
+}
+
+impl Plugin for CsvDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut writer = match File::create(&self.path) {
+            Ok(file) => BufWriter::new(file),
+            Err(err) => {
+                error!(
+                    "Failed to create the diagnostics CSV file {}: {err}",
+                    self.path.display()
+                );
+                return;
+            }
+        };
+        if let Err(err) = writeln!(writer, "time,path,value") {
+            error!("Failed to write diagnostics to CSV: {err}");
+        }
+
+        app.insert_resource(CsvDiagnosticsState {
+            writer,
+            cursor: MeasurementCursor::default(),
+        })
+        .add_systems(Last, Self::write_diagnostics_system);
+    }
+}
+
+/// State used by the [`CsvDiagnosticsPlugin`]
+#[derive(Resource)]
+struct CsvDiagnosticsState {
+    writer: BufWriter<File>,
+    cursor: MeasurementCursor,
+}
+
+/// Writes a row for each measurement read by `cursor`.
+fn write_measurements(
+    writer: &mut impl Write,
+    cursor: &mut MeasurementCursor,
+    diagnostics: &DiagnosticsStore,
+    startup: Instant,
+) -> io::Result<()> {
+    let mut result = Ok(());
+    cursor.read(diagnostics, |diagnostic, measurement| {
+        if result.is_err() {
+            return;
+        }
+        let time = measurement
+            .time
+            .saturating_duration_since(startup)
+            .as_secs_f64();
+        result = write!(writer, "{time:.6},")
+            .and_then(|()| write_field(writer, diagnostic.path().as_str()))
+            .and_then(|()| writeln!(writer, ",{}", measurement.value));
+    });
+    result
+}
+
+/// Writes `field`, quoting it if it contains a separator, a quote or a line break.
+fn write_field(writer: &mut impl Write, field: &str) -> io::Result<()> {
+    if field.contains([',', '"', '\r', '\n']) {
+        write!(writer, "\"{}\"", field.replace('"', "\"\""))
+    } else {
+        writer.write_all(field.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Diagnostic, DiagnosticMeasurement, DiagnosticPath};
+    use alloc::{string::String, vec::Vec};
+    use core::time::Duration;
+
+    #[test]
+    fn write_quoted_rows() {
+        let startup = Instant::now();
+        let mut diagnostics = DiagnosticsStore::default();
+        for (path, value) in [("fps", 60.0), ("a,b/\"c\"", 1.5)] {
+            let mut diagnostic = Diagnostic::new(DiagnosticPath::new(path));
+            diagnostic.add_measurement(DiagnosticMeasurement {
+                time: startup + Duration::from_millis(250),
+                value,
+            });
+            diagnostics.add(diagnostic);
+        }
+
+        let mut cursor = MeasurementCursor::default();
+        let mut output = Vec::new();
+        write_measurements(&mut output, &mut cursor, &diagnostics, startup).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let mut rows: Vec<_> = output.lines().collect();
+        rows.sort_unstable();
+        assert_eq!(rows, ["0.250000,\"a,b/\"\"c\"\"\",1.5", "0.250000,fps,60"]);
+
+        // Measurements are only written once.
+        let mut output = Vec::new();
+        write_measurements(&mut output, &mut cursor, &diagnostics, startup).unwrap();
+        assert!(output.is_empty());
+    }
+}
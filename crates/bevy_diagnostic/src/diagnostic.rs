@@ -39,19 +39,10 @@ impl DiagnosticPath {
     pub fn new(path: impl Into<Cow<'static, str>>) -> DiagnosticPath {
         let path = path.into();
 
-        debug_assert!(!path.is_empty(), "diagnostic path should not be empty");
-        debug_assert!(
-            !path.starts_with('/'),
-            "diagnostic path should not start with `/`"
-        );
-        debug_assert!(
-            !path.ends_with('/'),
-            "diagnostic path should not end with `/`"
-        );
-        debug_assert!(
-            !path.contains("//"),
-            "diagnostic path should not contain empty components"
-        );
+        #[cfg(debug_assertions)]
+        if let Err(error) = Self::validate(&path) {
+            panic!("{error}");
+        }
 
         DiagnosticPath {
             hash: fnv1a_hash_str_64(&path),
@@ -59,6 +50,21 @@ impl DiagnosticPath {
         }
     }
 
+    /// Checks that `path` follows all the requirements of a `DiagnosticPath`.
+    fn validate(path: &str) -> Result<(), &'static str> {
+        if path.is_empty() {
+            Err("diagnostic path should not be empty")
+        } else if path.starts_with('/') {
+            Err("diagnostic path should not start with `/`")
+        } else if path.ends_with('/') {
+            Err("diagnostic path should not end with `/`")
+        } else if path.contains("//") {
+            Err("diagnostic path should not contain empty components")
+        } else {
+            Ok(())
+        }
+    }
+
     /// Create a new `DiagnosticPath` from an iterator over components.
     pub fn from_components<'a>(components: impl IntoIterator<Item = &'a str>) -> DiagnosticPath {
         let mut buf = String::new();
@@ -110,6 +116,22 @@ impl core::fmt::Display for DiagnosticPath {
     }
 }
 
+#[cfg(feature = "serialize")]
+impl serde::Serialize for DiagnosticPath {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.path)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de> serde::Deserialize<'de> for DiagnosticPath {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = <String as serde::Deserialize>::deserialize(deserializer)?;
+        DiagnosticPath::validate(&path).map_err(serde::de::Error::custom)?;
+        Ok(DiagnosticPath::new(path))
+    }
+}
+
 /// A single measurement of a [`Diagnostic`].
 #[derive(Debug)]
 pub struct DiagnosticMeasurement {
@@ -131,6 +153,8 @@ pub struct Diagnostic {
     ema: f64,
     ema_smoothing_factor: f64,
     max_history_length: usize,
+    /// The number of measurements added since the diagnostic was created.
+    measurement_count: u64,
     /// Disabled [`Diagnostic`]s are not measured or logged.
     pub is_enabled: bool,
 }
@@ -169,6 +193,7 @@ impl Diagnostic {
         }
 
         self.history.push_back(measurement);
+        self.measurement_count += 1;
     }
 
     /// Create a new diagnostic with the given path.
@@ -181,6 +206,7 @@ impl Diagnostic {
             sum: 0.0,
             ema: 0.0,
             ema_smoothing_factor: 2.0 / 21.0,
+            measurement_count: 0,
             is_enabled: true,
         }
     }
@@ -343,6 +369,47 @@ impl DiagnosticsStore {
     }
 }
 
+/// Tracks which [`DiagnosticMeasurement`]s were already read, to only read new measurements.
+///
+/// Measurements are counted rather than compared by time, as several of them can share the same
+/// [`Instant`].
+#[derive(Default)]
+pub(crate) struct MeasurementCursor {
+    /// The number of measurements of each diagnostic that were added when it was last read.
+    last_read: HashMap<DiagnosticPath, u64, PassHash>,
+}
+
+impl MeasurementCursor {
+    /// Calls `f` with each measurement of an enabled [`Diagnostic`] added since the last call.
+    pub(crate) fn read(
+        &mut self,
+        diagnostics: &DiagnosticsStore,
+        mut f: impl FnMut(&Diagnostic, &DiagnosticMeasurement),
+    ) {
+        for diagnostic in diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.is_enabled)
+        {
+            let count = diagnostic.measurement_count;
+            let last_read = self.last_read.get(diagnostic.path()).copied();
+            if last_read == Some(count) {
+                continue;
+            }
+            // A diagnostic added again with the same path starts counting from zero.
+            let added = last_read
+                .and_then(|last_read| count.checked_sub(last_read))
+                .unwrap_or(count);
+            let history = &diagnostic.history;
+            let new =
+                usize::try_from(added).map_or(history.len(), |added| added.min(history.len()));
+            for measurement in history.iter().skip(history.len() - new) {
+                f(diagnostic, measurement);
+            }
+            self.last_read.insert(diagnostic.path().clone(), count);
+        }
+    }
+}
+
 /// Record new [`DiagnosticMeasurement`]'s.
 #[derive(SystemParam)]
 pub struct Diagnostics<'w, 's> {
@@ -463,4 +530,79 @@ mod tests {
             diagnostic.clear_history();
         }
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn measurement_cursor_reads_new_measurements() {
+        let mut diagnostics = DiagnosticsStore::default();
+        let now = Instant::now();
+        let mut enabled = Diagnostic::new(DiagnosticPath::new("enabled"));
+        let mut disabled = Diagnostic::new(DiagnosticPath::new("disabled"));
+        disabled.is_enabled = false;
+        for (diagnostic, value) in [(&mut enabled, 1.0), (&mut disabled, 2.0)] {
+            diagnostic.add_measurement(DiagnosticMeasurement { time: now, value });
+        }
+        diagnostics.add(enabled);
+        diagnostics.add(disabled);
+
+        let mut cursor = MeasurementCursor::default();
+        let read_values = |cursor: &mut MeasurementCursor, diagnostics: &DiagnosticsStore| {
+            let mut read = alloc::vec::Vec::new();
+            cursor.read(diagnostics, |diagnostic, measurement| {
+                read.push((diagnostic.path().clone(), measurement.value));
+            });
+            read
+        };
+        let path = DiagnosticPath::new("enabled");
+        assert_eq!(
+            read_values(&mut cursor, &diagnostics),
+            [(path.clone(), 1.0)]
+        );
+
+        let diagnostic = diagnostics.get_mut(&path).unwrap();
+        for (offset, value) in [(1, 3.0), (2, 4.0)] {
+            diagnostic.add_measurement(DiagnosticMeasurement {
+                time: now + Duration::from_secs(offset),
+                value,
+            });
+        }
+        assert_eq!(
+            read_values(&mut cursor, &diagnostics),
+            [(path.clone(), 3.0), (path.clone(), 4.0)]
+        );
+        assert!(read_values(&mut cursor, &diagnostics).is_empty());
+
+        // Measurements sharing the time of the last read one are still new.
+        diagnostics
+            .get_mut(&path)
+            .unwrap()
+            .add_measurement(DiagnosticMeasurement {
+                time: now + Duration::from_secs(2),
+                value: 5.0,
+            });
+        assert_eq!(read_values(&mut cursor, &diagnostics), [(path, 5.0)]);
+    }
+}
+
+#[cfg(all(test, feature = "serialize"))]
+mod serde_tests {
+    use super::*;
+
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    #[test]
+    fn test_serde_diagnostic_path() {
+        assert_tokens(
+            &DiagnosticPath::const_new("render/frame_time"),
+            &[Token::Str("render/frame_time")],
+        );
+        assert_de_tokens_error::<DiagnosticPath>(
+            &[Token::Str("render//frame_time")],
+            "diagnostic path should not contain empty components",
+        );
+        assert_de_tokens_error::<DiagnosticPath>(
+            &[Token::Str("")],
+            "diagnostic path should not be empty",
+        );
+    }
 }
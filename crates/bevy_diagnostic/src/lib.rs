@@ -15,22 +15,43 @@ extern crate std;
 
 extern crate alloc;
 
+#[cfg(feature = "std")]
+mod chrome_trace_diagnostics_plugin;
+#[cfg(feature = "std")]
+mod csv_diagnostics_plugin;
 mod diagnostic;
 mod entity_count_diagnostics_plugin;
 mod frame_count;
 mod frame_time_diagnostics_plugin;
 mod log_diagnostics_plugin;
+#[cfg(feature = "std")]
+mod open_metrics_diagnostics_plugin;
+mod performance_budget_plugin;
 #[cfg(feature = "sysinfo_plugin")]
 mod system_information_diagnostics_plugin;
+mod system_timing_diagnostics_plugin;
 
+#[cfg(feature = "std")]
+pub use chrome_trace_diagnostics_plugin::ChromeTraceDiagnosticsPlugin;
+#[cfg(feature = "std")]
+pub use csv_diagnostics_plugin::CsvDiagnosticsPlugin;
 pub use diagnostic::*;
 
 pub use entity_count_diagnostics_plugin::EntityCountDiagnosticsPlugin;
 pub use frame_count::{update_frame_count, FrameCount, FrameCountPlugin};
 pub use frame_time_diagnostics_plugin::FrameTimeDiagnosticsPlugin;
 pub use log_diagnostics_plugin::{LogDiagnosticsPlugin, LogDiagnosticsState};
+#[cfg(feature = "std")]
+pub use open_metrics_diagnostics_plugin::OpenMetricsDiagnosticsPlugin;
+pub use performance_budget_plugin::{
+    BudgetStatistic, DiagnosticReport, PerformanceBudget, PerformanceBudgetExceeded,
+    PerformanceBudgetPlugin, PerformanceBudgetState, PerformanceReport,
+};
 #[cfg(feature = "sysinfo_plugin")]
 pub use system_information_diagnostics_plugin::{SystemInfo, SystemInformationDiagnosticsPlugin};
+pub use system_timing_diagnostics_plugin::{
+    SystemTimingDiagnosticsPlugin, SystemTimingDiagnosticsState,
+};
 
 use bevy_app::prelude::*;
 
@@ -0,0 +1,325 @@
+use super::DiagnosticsStore;
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_platform::collections::HashMap;
+use const_fnv1a_hash::fnv1a_hash_str_64;
+use core::{
+    fmt::Write as _,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use log::{debug, error};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Mutex, PoisonError},
+    thread::{self, JoinHandle},
+};
+
+/// An App Plugin that serves the latest values of all enabled diagnostics as an
+/// [OpenMetrics](https://openmetrics.io) text endpoint, which can be scraped by Prometheus and
+/// compatible monitoring tools.
+///
+/// The endpoint is served over HTTP by a background thread listening on [`address`](Self::address),
+/// and is refreshed once per frame. Every diagnostic is exposed as a gauge named after its
+/// [`DiagnosticPath`](crate::DiagnosticPath), prefixed with `bevy_` and with all characters that
+/// are not ASCII alphanumerics replaced by `_`: `fps` becomes `bevy_fps`. If several paths map to
+/// the same name, the paths that had characters replaced are suffixed with a hash of the path,
+/// which doesn't depend on the other diagnostics: `a_b` and `a/b` are exposed as `bevy_a_b` and
+/// `bevy_a_b_` followed by 8 hexadecimal digits.
+///
+/// Each connection is answered by its own thread, so slow clients don't delay the others. The
+/// background thread is stopped when the app is dropped.
+///
+/// Diagnostics are collected by plugins such as
+/// [`FrameTimeDiagnosticsPlugin`](crate::FrameTimeDiagnosticsPlugin)
+/// or can be provided by the user.
+pub struct OpenMetricsDiagnosticsPlugin {
+    /// The address to listen on.
+    ///
+    /// Defaults to `127.0.0.1:9464`.
+    pub address: SocketAddr,
+}
+
+impl Default for OpenMetricsDiagnosticsPlugin {
+    fn default() -> Self {
+        OpenMetricsDiagnosticsPlugin {
+            address: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 9464),
+        }
+    }
+}
+
+impl OpenMetricsDiagnosticsPlugin {
+    /// Serves the diagnostics on `address`.
+    pub fn new(address: impl Into<SocketAddr>) -> Self {
+        OpenMetricsDiagnosticsPlugin {
+            address: address.into(),
+        }
+    }
+
+    fn update_metrics_system(
+        state: Res<OpenMetricsDiagnosticsState>,
+        diagnostics: Res<DiagnosticsStore>,
+    ) {
+        let metrics = render_metrics(&diagnostics);
+        *state.metrics.lock().unwrap_or_else(PoisonError::into_inner) = metrics;
+    }
+}
+
+impl Plugin for OpenMetricsDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let listener = match TcpListener::bind(self.address) {
+            Ok(listener) => listener,
+            Err(err) => {
+                error!(
+                    "Failed to serve OpenMetrics diagnostics on {}: {err}",
+                    self.address
+                );
+                return;
+            }
+        };
+
+        let address = match listener.local_addr() {
+            Ok(address) => address,
+            Err(err) => {
+                error!(
+                    "Failed to serve OpenMetrics diagnostics on {}: {err}",
+                    self.address
+                );
+                return;
+            }
+        };
+
+        let metrics = Arc::new(Mutex::new(String::from("# EOF\n")));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let served_metrics = Arc::clone(&metrics);
+        let thread_shutdown = Arc::clone(&shutdown);
+        let spawned = thread::Builder::new()
+            .name("OpenMetrics diagnostics".into())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if thread_shutdown.load(Ordering::Acquire) {
+                        break;
+                    }
+                    let metrics = Arc::clone(&served_metrics);
+                    let result = stream.and_then(|stream| {
+                        thread::Builder::new()
+                            .name("OpenMetrics connection".into())
+                            .spawn(move || {
+                                if let Err(err) = serve_metrics(stream, &metrics) {
+                                    debug!("Failed to serve OpenMetrics diagnostics: {err}");
+                                }
+                            })
+                    });
+                    if let Err(err) = result {
+                        debug!("Failed to serve OpenMetrics diagnostics: {err}");
+                    }
+                }
+            });
+        let thread = match spawned {
+            Ok(thread) => thread,
+            Err(err) => {
+                error!("Failed to spawn the OpenMetrics diagnostics thread: {err}");
+                return;
+            }
+        };
+
+        app.insert_resource(OpenMetricsDiagnosticsState {
+            metrics,
+            shutdown,
+            address,
+            thread: Some(thread),
+        })
+        .add_systems(Last, Self::update_metrics_system);
+    }
+}
+
+/// State used by the [`OpenMetricsDiagnosticsPlugin`]
+#[derive(Resource)]
+struct OpenMetricsDiagnosticsState {
+    metrics: Arc<Mutex<String>>,
+    /// Set to stop the listener thread.
+    shutdown: Arc<AtomicBool>,
+    /// The address the listener is bound to.
+    address: SocketAddr,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for OpenMetricsDiagnosticsState {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        // The listener blocks until the next connection, so connect to wake it up.
+        let mut address = self.address;
+        if address.ip().is_unspecified() {
+            address.set_ip(match address {
+                SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            });
+        }
+        if TcpStream::connect_timeout(&address, Duration::from_secs(1)).is_err() {
+            // Don't wait for a thread that may never wake up.
+            return;
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Answers a single HTTP request with the current metrics, whatever was requested.
+fn serve_metrics(stream: TcpStream, metrics: &Mutex<String>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    // Skip the request line and headers, up to the empty line ending them.
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let body = metrics
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: application/openmetrics-text; version=1.0.0; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Renders the latest value of every enabled diagnostic in the `OpenMetrics` text format.
+fn render_metrics(diagnostics: &DiagnosticsStore) -> String {
+    // Sort the diagnostics so they are rendered in the same order every frame.
+    let mut values: Vec<_> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_enabled)
+        .filter_map(|diagnostic| Some((diagnostic.path().as_str(), diagnostic.value()?)))
+        .collect();
+    values.sort_unstable_by_key(|&(path, _)| path);
+
+    let mut name_counts = <HashMap<String, usize>>::default();
+    for &(path, _) in &values {
+        *name_counts.entry(metric_name(path)).or_default() += 1;
+    }
+
+    let mut metrics = String::new();
+    for (path, value) in values {
+        let mut name = metric_name(path);
+        let renamed = !path.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if renamed && name_counts[&name] > 1 {
+            // Only use the lower bits of the hash to keep names readable.
+            let _ = write!(name, "_{:08x}", fnv1a_hash_str_64(path) as u32);
+        }
+        let _ = write!(
+            metrics,
+            "# TYPE {name} gauge\n# HELP {name} {}\n{name} {}\n",
+            path.replace('\\', "\\\\"),
+            format_value(value)
+        );
+    }
+    metrics.push_str("# EOF\n");
+    metrics
+}
+
+fn metric_name(path: &str) -> String {
+    let mut name = String::from("bevy_");
+    name.extend(
+        path.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }),
+    );
+    name
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        String::from("NaN")
+    } else if value.is_infinite() {
+        String::from(if value > 0.0 { "+Inf" } else { "-Inf" })
+    } else {
+        let mut formatted = String::new();
+        let _ = write!(formatted, "{value}");
+        formatted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Diagnostic, DiagnosticMeasurement, DiagnosticPath};
+    use bevy_platform::time::Instant;
+
+    #[test]
+    fn render_enabled_diagnostics() {
+        let mut diagnostics = DiagnosticsStore::default();
+        for (path, value, is_enabled) in [
+            ("frame_time/fps", Some(60.0), true),
+            ("disabled", Some(1.0), false),
+            ("empty", None, true),
+        ] {
+            let mut diagnostic = Diagnostic::new(DiagnosticPath::new(path));
+            diagnostic.is_enabled = is_enabled;
+            if let Some(value) = value {
+                diagnostic.add_measurement(DiagnosticMeasurement {
+                    time: Instant::now(),
+                    value,
+                });
+            }
+            diagnostics.add(diagnostic);
+        }
+
+        assert_eq!(
+            render_metrics(&diagnostics),
+            "# TYPE bevy_frame_time_fps gauge\n\
+             # HELP bevy_frame_time_fps frame_time/fps\n\
+             bevy_frame_time_fps 60\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn disambiguate_colliding_names() {
+        let mut diagnostics = DiagnosticsStore::default();
+        for (path, value) in [("a_b", 2.0), ("a/b", 1.0), ("a_b_2", 3.0)] {
+            let mut diagnostic = Diagnostic::new(DiagnosticPath::new(path));
+            diagnostic.add_measurement(DiagnosticMeasurement {
+                time: Instant::now(),
+                value,
+            });
+            diagnostics.add(diagnostic);
+        }
+
+        let metrics = render_metrics(&diagnostics);
+        let samples: Vec<_> = metrics
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        let renamed = alloc::format!("bevy_a_b_{:08x} 1", fnv1a_hash_str_64("a/b") as u32);
+        assert_eq!(samples, [renamed.as_str(), "bevy_a_b 2", "bevy_a_b_2 3"]);
+    }
+
+    #[test]
+    fn listener_thread_stops_when_app_is_dropped() {
+        let mut app = App::new();
+        app.add_plugins(OpenMetricsDiagnosticsPlugin::new((Ipv4Addr::LOCALHOST, 0)));
+        let address = app
+            .world()
+            .resource::<OpenMetricsDiagnosticsState>()
+            .address;
+        assert_ne!(address.port(), 0);
+
+        drop(app);
+        // The listener is dropped with the thread, so new connections are refused.
+        assert!(TcpStream::connect(address).is_err());
+    }
+}
@@ -0,0 +1,543 @@
+use super::{DiagnosticPath, DiagnosticsStore, MeasurementCursor, DEFAULT_MAX_HISTORY_LENGTH};
+
+use alloc::{collections::VecDeque, vec::Vec};
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_platform::collections::HashMap;
+use core::fmt;
+use log::{info, warn};
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// An App Plugin that checks diagnostics against [`PerformanceBudget`]s, to catch performance
+/// regressions in headless tests.
+///
+/// When a budget is exceeded, a [`PerformanceBudgetExceeded`] message is written, and if
+/// [`exit_on_violation`](Self::exit_on_violation) is set, the app exits with
+/// [`AppExit::error()`]. When the app exits, a [`PerformanceReport`] of the recent measurements
+/// of all budgeted diagnostics is logged.
+///
+/// ```
+/// use bevy_app::App;
+/// use bevy_diagnostic::{FrameTimeDiagnosticsPlugin, PerformanceBudget, PerformanceBudgetPlugin};
+///
+/// App::new()
+///     .add_plugins(FrameTimeDiagnosticsPlugin::default())
+///     .add_plugins(
+///         PerformanceBudgetPlugin::default()
+///             // The 95th percentile of the last 120 frame times must stay below 20ms.
+///             .with_budget(
+///                 PerformanceBudget::percentile(FrameTimeDiagnosticsPlugin::FRAME_TIME, 95.0, 20.0)
+///                     .with_window(120),
+///             )
+///             .with_exit_on_violation(true),
+///     );
+/// ```
+///
+/// Diagnostics are collected by plugins such as
+/// [`FrameTimeDiagnosticsPlugin`](crate::FrameTimeDiagnosticsPlugin)
+/// or can be provided by the user.
+#[derive(Debug, Clone)]
+pub struct PerformanceBudgetPlugin {
+    /// The budgets to check.
+    pub budgets: Vec<PerformanceBudget>,
+    /// If `true`, the app exits with [`AppExit::error()`] when a budget is exceeded.
+    ///
+    /// Defaults to `false`.
+    pub exit_on_violation: bool,
+    /// The number of most recent measurements of each diagnostic kept for the [`PerformanceReport`].
+    ///
+    /// Defaults to [`DEFAULT_REPORT_SAMPLES`](Self::DEFAULT_REPORT_SAMPLES).
+    pub report_samples: usize,
+}
+
+impl Default for PerformanceBudgetPlugin {
+    fn default() -> Self {
+        Self::new([])
+    }
+}
+
+impl PerformanceBudgetPlugin {
+    /// The default number of measurements of each diagnostic kept for the [`PerformanceReport`].
+    pub const DEFAULT_REPORT_SAMPLES: usize = 10_000;
+
+    /// Checks the given budgets.
+    pub fn new(budgets: impl IntoIterator<Item = PerformanceBudget>) -> Self {
+        Self {
+            budgets: budgets.into_iter().collect(),
+            exit_on_violation: false,
+            report_samples: Self::DEFAULT_REPORT_SAMPLES,
+        }
+    }
+
+    /// Parses the budgets to check from a list of [`PerformanceBudget`]s in the [`ron`] format.
+    ///
+    /// ```
+    /// # use bevy_diagnostic::PerformanceBudgetPlugin;
+    /// let plugin = PerformanceBudgetPlugin::from_ron(
+    ///     r#"[
+    ///         (path: "frame_time", statistic: Percentile(99.0), limit: 33.3, window: 300),
+    ///         (path: "entity_count", statistic: Max, limit: 10000.0),
+    ///     ]"#,
+    /// )
+    /// .unwrap();
+    /// ```
+    #[cfg(feature = "performance_budget_ron")]
+    pub fn from_ron(budgets: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str::<Vec<PerformanceBudget>>(budgets).map(Self::new)
+    }
+
+    /// Adds a budget to check.
+    #[must_use]
+    pub fn with_budget(mut self, budget: PerformanceBudget) -> Self {
+        self.budgets.push(budget);
+        self
+    }
+
+    /// Sets whether the app exits with [`AppExit::error()`] when a budget is exceeded.
+    #[must_use]
+    pub fn with_exit_on_violation(mut self, exit_on_violation: bool) -> Self {
+        self.exit_on_violation = exit_on_violation;
+        self
+    }
+
+    /// Sets the number of most recent measurements of each diagnostic kept for the
+    /// [`PerformanceReport`].
+    #[must_use]
+    pub fn with_report_samples(mut self, report_samples: usize) -> Self {
+        self.report_samples = report_samples;
+        self
+    }
+
+    fn check_budgets_system(
+        mut state: ResMut<PerformanceBudgetState>,
+        diagnostics: Res<DiagnosticsStore>,
+        mut exceeded: MessageWriter<PerformanceBudgetExceeded>,
+        mut exit: MessageWriter<AppExit>,
+    ) {
+        let PerformanceBudgetState {
+            budgets,
+            samples,
+            report_samples,
+            cursor,
+            exit_on_violation,
+        } = &mut *state;
+
+        cursor.read(&diagnostics, |diagnostic, measurement| {
+            let Some(samples) = samples.get_mut(diagnostic.path()) else {
+                return;
+            };
+            if measurement.value.is_nan() {
+                return;
+            }
+            if samples.len() >= *report_samples {
+                samples.pop_front();
+            }
+            samples.push_back(measurement.value);
+            for budget in budgets
+                .iter_mut()
+                .filter(|budget| budget.budget.path == *diagnostic.path())
+            {
+                if budget.window.len() >= budget.budget.window {
+                    budget.window.pop_front();
+                }
+                budget.window.push_back(measurement.value);
+            }
+        });
+
+        for budget in budgets.iter_mut() {
+            let Some(value) = budget.budget.statistic.evaluate(&budget.window) else {
+                continue;
+            };
+            let violated = value > budget.budget.limit;
+            // Only report the budget when it starts being exceeded, not on every frame.
+            if violated && !budget.violated {
+                warn!(
+                    "Performance budget exceeded: {} of `{}` is {value}, over the limit of {}",
+                    budget.budget.statistic, budget.budget.path, budget.budget.limit
+                );
+                exceeded.write(PerformanceBudgetExceeded {
+                    budget: budget.budget.clone(),
+                    value,
+                });
+                if *exit_on_violation {
+                    exit.write(AppExit::error());
+                }
+            }
+            budget.violated = violated;
+        }
+    }
+
+    fn report_system(state: Res<PerformanceBudgetState>, mut exit: MessageReader<AppExit>) {
+        if exit.read().last().is_some() {
+            info!("{}", state.report());
+        }
+    }
+}
+
+impl Plugin for PerformanceBudgetPlugin {
+    fn build(&self, app: &mut App) {
+        let samples = self
+            .budgets
+            .iter()
+            .map(|budget| (budget.path.clone(), VecDeque::new()))
+            .collect();
+        let budgets = self
+            .budgets
+            .iter()
+            .map(|budget| BudgetState {
+                budget: budget.clone(),
+                window: VecDeque::with_capacity(budget.window),
+                violated: false,
+            })
+            .collect();
+
+        app.init_resource::<DiagnosticsStore>()
+            .add_message::<PerformanceBudgetExceeded>()
+            .insert_resource(PerformanceBudgetState {
+                budgets,
+                samples,
+                report_samples: self.report_samples,
+                cursor: MeasurementCursor::default(),
+                exit_on_violation: self.exit_on_violation,
+            })
+            .add_systems(
+                Last,
+                (Self::check_budgets_system, Self::report_system).chain(),
+            );
+    }
+}
+
+/// A limit on a statistic of the recent measurements of a [`Diagnostic`](crate::Diagnostic),
+/// checked by the [`PerformanceBudgetPlugin`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct PerformanceBudget {
+    /// The path of the budgeted diagnostic.
+    pub path: DiagnosticPath,
+    /// The statistic of the measurements compared to the [`limit`](Self::limit).
+    pub statistic: BudgetStatistic,
+    /// The budget is exceeded when the [`statistic`](Self::statistic) is greater than this value.
+    pub limit: f64,
+    /// The number of most recent measurements the statistic is computed over.
+    ///
+    /// Until this many measurements were taken, the statistic is computed over all measurements.
+    ///
+    /// Defaults to [`DEFAULT_MAX_HISTORY_LENGTH`].
+    #[cfg_attr(feature = "serialize", serde(default = "default_window"))]
+    pub window: usize,
+}
+
+#[cfg(feature = "serialize")]
+fn default_window() -> usize {
+    DEFAULT_MAX_HISTORY_LENGTH
+}
+
+impl PerformanceBudget {
+    /// Creates a budget on the given statistic of the diagnostic at `path`.
+    pub fn new(path: DiagnosticPath, statistic: BudgetStatistic, limit: f64) -> Self {
+        Self {
+            path,
+            statistic,
+            limit,
+            window: DEFAULT_MAX_HISTORY_LENGTH,
+        }
+    }
+
+    /// Creates a budget on the mean of the diagnostic at `path`.
+    pub fn mean(path: DiagnosticPath, limit: f64) -> Self {
+        Self::new(path, BudgetStatistic::Mean, limit)
+    }
+
+    /// Creates a budget on the given percentile, between `0.0` and `100.0`, of the diagnostic at
+    /// `path`.
+    pub fn percentile(path: DiagnosticPath, percentile: f64, limit: f64) -> Self {
+        Self::new(path, BudgetStatistic::Percentile(percentile), limit)
+    }
+
+    /// Creates a budget on the maximum of the diagnostic at `path`.
+    pub fn max(path: DiagnosticPath, limit: f64) -> Self {
+        Self::new(path, BudgetStatistic::Max, limit)
+    }
+
+    /// Sets the number of most recent measurements the statistic is computed over.
+    #[must_use]
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+}
+
+/// The statistic of a window of measurements checked by a [`PerformanceBudget`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum BudgetStatistic {
+    /// The arithmetic mean of the measurements.
+    Mean,
+    /// The given percentile of the measurements, between `0.0` and `100.0`.
+    Percentile(f64),
+    /// The largest measurement.
+    Max,
+}
+
+impl BudgetStatistic {
+    /// Computes the statistic of `values`, or `None` if `values` is empty.
+    pub fn evaluate<'a>(&self, values: impl IntoIterator<Item = &'a f64>) -> Option<f64> {
+        let mut values: Vec<f64> = values.into_iter().copied().collect();
+        if values.is_empty() {
+            return None;
+        }
+        match *self {
+            BudgetStatistic::Mean => Some(values.iter().sum::<f64>() / values.len() as f64),
+            BudgetStatistic::Percentile(percentile) => {
+                values.sort_unstable_by(f64::total_cmp);
+                Some(nearest_rank(&values, percentile))
+            }
+            BudgetStatistic::Max => values.into_iter().reduce(f64::max),
+        }
+    }
+}
+
+impl fmt::Display for BudgetStatistic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetStatistic::Mean => write!(f, "mean"),
+            BudgetStatistic::Percentile(percentile) => write!(f, "p{percentile}"),
+            BudgetStatistic::Max => write!(f, "max"),
+        }
+    }
+}
+
+/// Returns the `percentile` of `sorted` with the nearest-rank method.
+fn nearest_rank(sorted: &[f64], percentile: f64) -> f64 {
+    let exact_rank = percentile / 100.0 * sorted.len() as f64;
+    // Round up, without `f64::ceil` which isn't available in `no_std`.
+    let mut rank = exact_rank as usize;
+    if (rank as f64) < exact_rank {
+        rank += 1;
+    }
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// A message written by the [`PerformanceBudgetPlugin`] when a [`PerformanceBudget`] starts
+/// being exceeded.
+#[derive(Message, Debug, Clone, PartialEq)]
+pub struct PerformanceBudgetExceeded {
+    /// The exceeded budget.
+    pub budget: PerformanceBudget,
+    /// The value of the budget's statistic.
+    pub value: f64,
+}
+
+/// State used by the [`PerformanceBudgetPlugin`]
+#[derive(Resource)]
+pub struct PerformanceBudgetState {
+    budgets: Vec<BudgetState>,
+    samples: HashMap<DiagnosticPath, VecDeque<f64>>,
+    report_samples: usize,
+    cursor: MeasurementCursor,
+    exit_on_violation: bool,
+}
+
+struct BudgetState {
+    budget: PerformanceBudget,
+    window: VecDeque<f64>,
+    violated: bool,
+}
+
+impl PerformanceBudgetState {
+    /// Returns the checked budgets.
+    pub fn budgets(&self) -> impl Iterator<Item = &PerformanceBudget> {
+        self.budgets.iter().map(|budget| &budget.budget)
+    }
+
+    /// Returns whether the budget is currently exceeded, for each checked budget.
+    pub fn violations(&self) -> impl Iterator<Item = (&PerformanceBudget, bool)> {
+        self.budgets
+            .iter()
+            .map(|budget| (&budget.budget, budget.violated))
+    }
+
+    /// Summarizes the most recent measurements of the budgeted diagnostics, up to
+    /// [`PerformanceBudgetPlugin::report_samples`] for each diagnostic.
+    pub fn report(&self) -> PerformanceReport {
+        let mut diagnostics: Vec<DiagnosticReport> = self
+            .samples
+            .iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|(path, samples)| {
+                let mut sorted = samples.iter().copied().collect::<Vec<_>>();
+                sorted.sort_unstable_by(f64::total_cmp);
+                DiagnosticReport {
+                    path: path.clone(),
+                    samples: sorted.len(),
+                    mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+                    p50: nearest_rank(&sorted, 50.0),
+                    p95: nearest_rank(&sorted, 95.0),
+                    p99: nearest_rank(&sorted, 99.0),
+                    max: sorted[sorted.len() - 1],
+                }
+            })
+            .collect();
+        diagnostics.sort_by(|a, b| a.path.as_str().cmp(b.path.as_str()));
+        PerformanceReport { diagnostics }
+    }
+}
+
+/// A summary of the recent measurements of the diagnostics budgeted by the [`PerformanceBudgetPlugin`].
+///
+/// Its [`Display`](fmt::Display) implementation formats it as a table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerformanceReport {
+    /// The summary of each budgeted diagnostic with measurements, sorted by path.
+    pub diagnostics: Vec<DiagnosticReport>,
+}
+
+/// A summary of the measurements of a single diagnostic in a [`PerformanceReport`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticReport {
+    /// The path of the diagnostic.
+    pub path: DiagnosticPath,
+    /// The number of measurements.
+    pub samples: usize,
+    /// The mean of the measurements.
+    pub mean: f64,
+    /// The median of the measurements.
+    pub p50: f64,
+    /// The 95th percentile of the measurements.
+    pub p95: f64,
+    /// The 99th percentile of the measurements.
+    pub p99: f64,
+    /// The largest measurement.
+    pub max: f64,
+}
+
+impl fmt::Display for PerformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.path.as_str().len())
+            .max()
+            .unwrap_or(0)
+            .max("diagnostic".len());
+        write!(
+            f,
+            "Performance report\n{:<width$} {:>8} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            "diagnostic", "samples", "mean", "p50", "p95", "p99", "max"
+        )?;
+        for diagnostic in &self.diagnostics {
+            write!(
+                f,
+                "\n{:<width$} {:>8} {:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>10.3}",
+                diagnostic.path.as_str(),
+                diagnostic.samples,
+                diagnostic.mean,
+                diagnostic.p50,
+                diagnostic.p95,
+                diagnostic.p99,
+                diagnostic.max
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Diagnostic, DiagnosticMeasurement, RegisterDiagnostic};
+    use bevy_ecs::message::Messages;
+    use bevy_platform::time::Instant;
+
+    const BUDGETED: DiagnosticPath = DiagnosticPath::const_new("budgeted");
+
+    fn measure(app: &mut App, value: f64) {
+        app.world_mut()
+            .resource_mut::<DiagnosticsStore>()
+            .get_mut(&BUDGETED)
+            .unwrap()
+            .add_measurement(DiagnosticMeasurement {
+                time: Instant::now(),
+                value,
+            });
+        app.update();
+    }
+
+    #[test]
+    fn exceeding_a_budget_exits_with_an_error() {
+        let mut app = App::new();
+        app.register_diagnostic(Diagnostic::new(BUDGETED))
+            .add_plugins(
+                PerformanceBudgetPlugin::default()
+                    .with_budget(PerformanceBudget::mean(BUDGETED, 10.0).with_window(2))
+                    .with_exit_on_violation(true),
+            );
+
+        measure(&mut app, 5.0);
+        measure(&mut app, 12.0);
+        assert_eq!(app.should_exit(), None);
+
+        measure(&mut app, 9.0);
+        let exceeded = app
+            .world()
+            .resource::<Messages<PerformanceBudgetExceeded>>()
+            .iter_current_update_messages()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            exceeded,
+            [PerformanceBudgetExceeded {
+                budget: PerformanceBudget::mean(BUDGETED, 10.0).with_window(2),
+                value: 10.5,
+            }]
+        );
+        assert_eq!(app.should_exit(), Some(AppExit::error()));
+
+        let report = app.world().resource::<PerformanceBudgetState>().report();
+        assert_eq!(
+            report.diagnostics,
+            [DiagnosticReport {
+                path: BUDGETED,
+                samples: 3,
+                mean: 26.0 / 3.0,
+                p50: 9.0,
+                p95: 12.0,
+                p99: 12.0,
+                max: 12.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn report_keeps_recent_samples() {
+        let mut app = App::new();
+        app.register_diagnostic(Diagnostic::new(BUDGETED))
+            .add_plugins(
+                PerformanceBudgetPlugin::default()
+                    .with_budget(PerformanceBudget::max(BUDGETED, 100.0))
+                    .with_report_samples(2),
+            );
+
+        measure(&mut app, 50.0);
+        measure(&mut app, 1.0);
+        measure(&mut app, 2.0);
+
+        let report = app.world().resource::<PerformanceBudgetState>().report();
+        assert_eq!(report.diagnostics[0].samples, 2);
+        assert_eq!(report.diagnostics[0].max, 2.0);
+    }
+
+    #[cfg(feature = "performance_budget_ron")]
+    #[test]
+    fn from_ron_rejects_invalid_paths() {
+        use alloc::string::ToString;
+
+        let error =
+            PerformanceBudgetPlugin::from_ron(r#"[(path: "render/", statistic: Max, limit: 1.0)]"#)
+                .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("diagnostic path should not end with `/`"));
+    }
+}
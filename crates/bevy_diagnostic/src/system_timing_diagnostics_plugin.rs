@@ -0,0 +1,145 @@
+use crate::{Diagnostic, DiagnosticMeasurement, DiagnosticPath, DiagnosticsStore};
+
+use alloc::format;
+use bevy_app::prelude::*;
+use bevy_ecs::{
+    prelude::*,
+    schedule::{InternedScheduleLabel, SystemKey, SystemTimings},
+};
+use bevy_platform::{collections::HashMap, time::Instant};
+use core::time::Duration;
+
+/// Adds a diagnostic for the execution time of each schedule and system to an App.
+///
+/// This inserts the [`SystemTimings`] resource, which enables recording the execution time of
+/// schedules and systems without an external profiler, including in release builds. Each frame,
+/// the time spent running each schedule and system is measured in milliseconds as:
+/// - `schedule/<schedule label>` for each schedule,
+/// - `system/<schedule label>/<system name>` for each system.
+///
+/// A schedule that runs several times in a frame, such as `FixedUpdate`, is measured once with
+/// the time of all runs. Diagnostics are registered the first time a schedule or system runs.
+/// System names are only available with the `debug` feature.
+///
+/// The recorded timings can also be read using the `schedule.timings` method of the Bevy
+/// Remote Protocol.
+///
+/// # See also
+///
+/// [`LogDiagnosticsPlugin`](crate::LogDiagnosticsPlugin) to output diagnostics to the console.
+pub struct SystemTimingDiagnosticsPlugin {
+    /// The total number of values to keep for averaging.
+    pub max_history_length: usize,
+}
+
+impl Default for SystemTimingDiagnosticsPlugin {
+    fn default() -> Self {
+        Self {
+            max_history_length: crate::DEFAULT_MAX_HISTORY_LENGTH,
+        }
+    }
+}
+
+impl Plugin for SystemTimingDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SystemTimings>()
+            .init_resource::<DiagnosticsStore>()
+            .insert_resource(SystemTimingDiagnosticsState {
+                max_history_length: self.max_history_length,
+                previous: HashMap::default(),
+            })
+            .add_systems(Last, Self::diagnostic_system);
+    }
+}
+
+impl SystemTimingDiagnosticsPlugin {
+    /// Updates the schedule and system timing diagnostics.
+    pub fn diagnostic_system(
+        mut state: ResMut<SystemTimingDiagnosticsState>,
+        timings: Res<SystemTimings>,
+        mut diagnostics: ResMut<DiagnosticsStore>,
+    ) {
+        let SystemTimingDiagnosticsState {
+            max_history_length,
+            previous,
+        } = &mut *state;
+
+        // Systems sharing a name are measured together.
+        let mut frame_times = HashMap::<DiagnosticPath, Duration>::default();
+        let mut record = |key: (InternedScheduleLabel, Option<SystemKey>),
+                          total: Duration,
+                          path: &dyn Fn() -> DiagnosticPath| {
+            let previous = previous
+                .entry(key)
+                .or_insert_with(|| (path(), Duration::ZERO));
+            *frame_times.entry(previous.0.clone()).or_default() += total.saturating_sub(previous.1);
+            previous.1 = total;
+        };
+        for (label, schedule) in timings.iter() {
+            record((label, None), schedule.total, &|| {
+                DiagnosticPath::new(format!("schedule/{label:?}"))
+            });
+            for (key, system) in schedule.systems() {
+                record((label, Some(key)), system.total, &|| {
+                    DiagnosticPath::new(format!("system/{label:?}/{}", system.name.shortname()))
+                });
+            }
+        }
+
+        let time = Instant::now();
+        for (path, duration) in frame_times {
+            if diagnostics.get(&path).is_none() {
+                diagnostics.add(
+                    Diagnostic::new(path.clone())
+                        .with_suffix("ms")
+                        .with_max_history_length(*max_history_length),
+                );
+            }
+            let Some(diagnostic) = diagnostics.get_mut(&path) else {
+                continue;
+            };
+            if diagnostic.is_enabled {
+                diagnostic.add_measurement(DiagnosticMeasurement {
+                    time,
+                    value: duration.as_secs_f64() * 1000.0,
+                });
+            }
+        }
+    }
+}
+
+/// State used by the [`SystemTimingDiagnosticsPlugin`]
+#[derive(Resource)]
+pub struct SystemTimingDiagnosticsState {
+    max_history_length: usize,
+    /// The diagnostic path and the total recorded time of each schedule and system.
+    previous: HashMap<(InternedScheduleLabel, Option<SystemKey>), (DiagnosticPath, Duration)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_systems_and_schedules() {
+        fn my_system() {}
+
+        let mut app = App::new();
+        app.add_plugins(SystemTimingDiagnosticsPlugin::default())
+            .add_systems(Update, my_system);
+        app.update();
+        app.update();
+
+        let diagnostics = app.world().resource::<DiagnosticsStore>();
+        let schedule = diagnostics
+            .get(&DiagnosticPath::const_new("schedule/Update"))
+            .unwrap();
+        assert_eq!(schedule.history_len(), 2);
+        // System names depend on the `debug` feature.
+        let system = diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.path().as_str().starts_with("system/Update/"))
+            .unwrap();
+        assert_eq!(system.history_len(), 2);
+    }
+}
@@ -231,6 +231,30 @@ impl<M: Message> Messages<M> {
         self.messages_b.clear();
     }
 
+    /// Removes the messages with an id of at least `message_count`, as if they were never written.
+    ///
+    /// The ids of the removed messages are given to the next messages written, so any reader that
+    /// already read one of them would skip the messages reusing its id. This is only meant for input
+    /// replay, which removes live input before any system has had a chance to read it, and is not
+    /// part of the public API.
+    #[doc(hidden)]
+    pub fn truncate(&mut self, message_count: usize) {
+        let message_count = message_count.max(self.oldest_message_count());
+        if message_count >= self.message_count {
+            return;
+        }
+        if message_count < self.messages_b.start_message_count {
+            let len = message_count - self.messages_a.start_message_count;
+            self.messages_a.truncate(len);
+            self.messages_b.clear();
+            self.messages_b.start_message_count = message_count;
+        } else {
+            let len = message_count - self.messages_b.start_message_count;
+            self.messages_b.truncate(len);
+        }
+        self.message_count = message_count;
+    }
+
     /// Returns the number of messages currently stored in the message buffer.
     #[inline]
     pub fn len(&self) -> usize {
@@ -398,6 +422,7 @@ impl<M: Message> ExactSizeIterator for WriteBatchIds<M> {
 #[cfg(test)]
 mod tests {
     use crate::message::{Message, Messages};
+    use alloc::vec::Vec;
 
     #[test]
     fn iter_current_update_messages_iterates_over_current_messages() {
@@ -445,4 +470,32 @@ mod tests {
             (expected_len, Some(expected_len))
         );
     }
+
+    #[test]
+    fn truncate_removes_newest_messages() {
+        #[derive(Message, Clone, Copy, Debug, PartialEq)]
+        struct TestMessage(usize);
+
+        let mut test_messages = Messages::<TestMessage>::default();
+        test_messages.write_batch([TestMessage(0), TestMessage(1)]);
+        test_messages.update();
+        test_messages.write_batch([TestMessage(2), TestMessage(3)]);
+
+        // Removing messages from both buffers.
+        test_messages.truncate(1);
+        assert_eq!(test_messages.len(), 1);
+        assert_eq!(test_messages.write(TestMessage(4)).id, 1);
+        let mut cursor = test_messages.get_cursor();
+        assert_eq!(
+            cursor.read(&test_messages).copied().collect::<Vec<_>>(),
+            [TestMessage(0), TestMessage(4)]
+        );
+
+        // Messages removed by updates stay removed.
+        test_messages.update();
+        test_messages.update();
+        test_messages.truncate(0);
+        assert_eq!(test_messages.oldest_message_count(), 2);
+        assert_eq!(test_messages.write(TestMessage(5)).id, 2);
+    }
 }
@@ -104,6 +104,8 @@ pub struct SystemSchedule {
     ///
     /// If a set doesn't run because of its conditions, this is used to skip all systems in it.
     pub(super) systems_in_sets_with_conditions: Vec<FixedBitSet>,
+    /// Whether the executor should measure how long each system takes to run.
+    pub(super) record_timings: bool,
 }
 
 impl SystemSchedule {
@@ -119,6 +121,7 @@ impl SystemSchedule {
             system_dependents: Vec::new(),
             sets_with_conditions_of_systems: Vec::new(),
             systems_in_sets_with_conditions: Vec::new(),
+            record_timings: false,
         }
     }
 
@@ -1,3 +1,4 @@
+use bevy_platform::time::Instant;
 use core::panic::AssertUnwindSafe;
 use fixedbitset::FixedBitSet;
 
@@ -131,6 +132,7 @@ impl SystemExecutor for SingleThreadedExecutor {
                 continue;
             }
 
+            let start = schedule.record_timings.then(Instant::now);
             let f = AssertUnwindSafe(|| {
                 if let Err(RunSystemError::Failed(err)) =
                     __rust_begin_short_backtrace::run_without_applying_deferred(system, world)
@@ -159,6 +161,7 @@ impl SystemExecutor for SingleThreadedExecutor {
                 (f)();
             }
 
+            schedule.systems[system_index].run_duration = start.map(|start| start.elapsed());
             self.unapplied_systems.insert(system_index);
         }
 
@@ -3,6 +3,7 @@ use core::{
     any::TypeId,
     fmt::{self, Debug},
     ops::{Deref, Index, IndexMut, Range},
+    time::Duration,
 };
 
 use bevy_platform::collections::{HashMap, HashSet};
@@ -39,6 +40,9 @@ pub struct SystemWithAccess {
     /// The access returned by [`System::initialize`].
     /// This will be empty if the system has not been initialized yet.
     pub(crate) access: FilteredAccessSet,
+    /// How long the system took to run, if it ran since its schedule last recorded
+    /// [`SystemTimings`](crate::schedule::SystemTimings).
+    pub(crate) run_duration: Option<Duration>,
 }
 
 impl SystemWithAccess {
@@ -48,6 +52,7 @@ impl SystemWithAccess {
         Self {
             system,
             access: FilteredAccessSet::new(),
+            run_duration: None,
         }
     }
 }
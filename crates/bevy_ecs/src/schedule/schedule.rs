@@ -14,6 +14,7 @@ use bevy_ecs_macros::Event;
 use bevy_platform::{
     collections::{HashMap, HashSet},
     hash::FixedHasher,
+    time::Instant,
 };
 use bevy_utils::{default, TypeIdMap};
 use core::{
@@ -571,6 +572,9 @@ impl Schedule {
         });
 
         let error_handler = world.fallback_error_handler();
+        let record_timings = world.contains_resource::<SystemTimings>();
+        self.executable.record_timings = record_timings;
+        let start = record_timings.then(Instant::now);
 
         #[cfg(not(feature = "bevy_debug_stepping"))]
         self.executor
@@ -590,6 +594,22 @@ impl Schedule {
                 error_handler,
             );
         }
+
+        if let Some(start) = start {
+            let duration = start.elapsed();
+            let systems = self
+                .executable
+                .system_ids
+                .iter()
+                .copied()
+                .zip(&mut self.executable.systems);
+            // The resource may have been removed by one of the systems.
+            if let Some(mut timings) = world.get_resource_mut::<SystemTimings>() {
+                timings.record(self.label, duration, systems);
+            } else {
+                systems.for_each(|(_, system)| system.run_duration = None);
+            }
+        }
     }
 
     /// Initializes any newly-added systems and conditions, rebuilds the executable schedule,
@@ -1362,6 +1382,7 @@ impl ScheduleGraph {
             system_dependents,
             sets_with_conditions_of_systems,
             systems_in_sets_with_conditions,
+            record_timings: false,
         }
     }
 
@@ -0,0 +1,199 @@
+use bevy_platform::collections::HashMap;
+use bevy_utils::prelude::DebugName;
+use core::time::Duration;
+
+use crate::{
+    resource::Resource,
+    schedule::{InternedScheduleLabel, ScheduleLabel, SystemKey, SystemWithAccess},
+};
+
+/// Records how long schedules and their systems take to run.
+///
+/// Recording is opt-in: while this resource exists in the [`World`](crate::world::World), every
+/// [`Schedule`](crate::schedule::Schedule) that runs on it measures its own execution time and the
+/// execution time of each of its systems, and adds them to this resource. This costs two clock
+/// reads per system, and doesn't rely on tracing spans or an external profiler.
+///
+/// Timings are accumulated since the resource was inserted, so that consumers can compute
+/// averages, or the time spent over a frame by comparing two readings.
+///
+/// ```
+/// # use bevy_ecs::{prelude::*, schedule::SystemTimings};
+/// # #[derive(bevy_ecs::schedule::ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+/// # struct Update;
+/// fn heavy_system() {}
+///
+/// let mut world = World::new();
+/// world.init_resource::<SystemTimings>();
+///
+/// let mut schedule = Schedule::new(Update);
+/// schedule.add_systems(heavy_system);
+/// schedule.run(&mut world);
+///
+/// let timings = world.resource::<SystemTimings>().get(Update).unwrap();
+/// assert_eq!(timings.runs, 1);
+/// for (_, system) in timings.systems() {
+///     println!("{}: {:?} on average", system.name, system.mean());
+/// }
+/// ```
+///
+/// Systems run by a custom [`SystemExecutor`](crate::schedule::SystemExecutor) are not timed,
+/// only their schedule is.
+#[derive(Resource, Debug, Default)]
+pub struct SystemTimings {
+    schedules: HashMap<InternedScheduleLabel, ScheduleTimings>,
+}
+
+impl SystemTimings {
+    /// Returns the timings of the schedule with the given `label`, if it ran since recording
+    /// started.
+    pub fn get(&self, label: impl ScheduleLabel) -> Option<&ScheduleTimings> {
+        self.schedules.get(&label.intern())
+    }
+
+    /// Returns an iterator over the timings of all schedules that ran since recording started.
+    pub fn iter(&self) -> impl Iterator<Item = (InternedScheduleLabel, &ScheduleTimings)> {
+        self.schedules
+            .iter()
+            .map(|(label, timings)| (*label, timings))
+    }
+
+    /// Discards all recorded timings.
+    pub fn clear(&mut self) {
+        self.schedules.clear();
+    }
+
+    /// Adds a run of the schedule `label`, taking the durations of the systems that ran.
+    pub(super) fn record<'a>(
+        &mut self,
+        label: InternedScheduleLabel,
+        duration: Duration,
+        systems: impl Iterator<Item = (SystemKey, &'a mut SystemWithAccess)>,
+    ) {
+        let timings = self.schedules.entry(label).or_default();
+        timings.runs += 1;
+        timings.total += duration;
+        timings.last = duration;
+        for (key, system) in systems {
+            let Some(duration) = system.run_duration.take() else {
+                continue;
+            };
+            let timing = timings.systems.entry(key).or_insert_with(|| SystemTiming {
+                name: system.system.name(),
+                runs: 0,
+                total: Duration::ZERO,
+                last: Duration::ZERO,
+            });
+            timing.runs += 1;
+            timing.total += duration;
+            timing.last = duration;
+        }
+    }
+}
+
+/// The execution time of a schedule and its systems, recorded in [`SystemTimings`].
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleTimings {
+    /// The number of times the schedule ran.
+    pub runs: u64,
+    /// The time spent running the schedule, including the time spent in schedules run by its
+    /// exclusive systems.
+    pub total: Duration,
+    /// The duration of the last run of the schedule.
+    pub last: Duration,
+    systems: HashMap<SystemKey, SystemTiming>,
+}
+
+impl ScheduleTimings {
+    /// Returns the average duration of a run of the schedule.
+    pub fn mean(&self) -> Duration {
+        mean(self.total, self.runs)
+    }
+
+    /// Returns the timings of the system with the given `key`, if it ran since recording started.
+    pub fn system(&self, key: SystemKey) -> Option<&SystemTiming> {
+        self.systems.get(&key)
+    }
+
+    /// Returns an iterator over the timings of all systems of the schedule that ran since
+    /// recording started.
+    pub fn systems(&self) -> impl Iterator<Item = (SystemKey, &SystemTiming)> {
+        self.systems.iter().map(|(key, timing)| (*key, timing))
+    }
+}
+
+/// The execution time of a system, recorded in [`SystemTimings`].
+///
+/// Systems skipped by their run conditions are not counted.
+#[derive(Debug, Clone)]
+pub struct SystemTiming {
+    /// The name of the system.
+    pub name: DebugName,
+    /// The number of times the system ran.
+    pub runs: u64,
+    /// The time spent running the system, excluding applying its deferred buffers.
+    pub total: Duration,
+    /// The duration of the last run of the system.
+    pub last: Duration,
+}
+
+impl SystemTiming {
+    /// Returns the average duration of a run of the system.
+    pub fn mean(&self) -> Duration {
+        mean(self.total, self.runs)
+    }
+}
+
+fn mean(total: Duration, runs: u64) -> Duration {
+    if runs == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_secs_f64(total.as_secs_f64() / runs as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        schedule::{Schedule, SystemExecutor},
+    };
+
+    #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+    struct TestSchedule;
+
+    fn record_timings(executor: impl SystemExecutor + 'static) {
+        fn system() {}
+        fn skipped() {}
+        fn exclusive(_world: &mut World) {}
+
+        let mut world = World::new();
+        let mut schedule = Schedule::new(TestSchedule);
+        schedule
+            .set_executor(executor)
+            .add_systems((system, skipped.run_if(|| false), exclusive));
+
+        // Nothing is recorded without the resource.
+        schedule.run(&mut world);
+        world.init_resource::<SystemTimings>();
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        let timings = world.resource::<SystemTimings>().get(TestSchedule).unwrap();
+        assert_eq!(timings.runs, 2);
+        // The skipped system isn't timed.
+        assert_eq!(timings.systems().count(), 2);
+        assert!(timings.systems().all(|(_, timing)| timing.runs == 2));
+    }
+
+    #[test]
+    fn single_threaded_timings() {
+        record_timings(crate::schedule::SingleThreadedExecutor::new());
+    }
+
+    #[cfg(feature = "multi_threaded")]
+    #[test]
+    fn multi_threaded_timings() {
+        record_timings(crate::schedule::MultiThreadedExecutor::new());
+    }
+}
@@ -0,0 +1,1168 @@
+//! Mapping of physical inputs to semantic actions.
+//!
+//! Instead of reading [`ButtonInput<KeyCode>`] or [`Gamepad`] directly, games can define the
+//! actions a player can perform as an enum, bind any number of inputs to each action in an
+//! [`InputMap`], and read the resulting [`ActionState`]:
+//!
+//! ```
+//! # use bevy_app::{App, Update};
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_input::action::{ActionPlugin, ActionState, Binding, DualAxisBinding, InputMap};
+//! # use bevy_input::prelude::*;
+//! #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+//! enum PlayerAction {
+//!     Jump,
+//!     Move,
+//!     Save,
+//! }
+//!
+//! let input_map = InputMap::new()
+//!     .with(PlayerAction::Jump, KeyCode::Space)
+//!     .with(PlayerAction::Jump, GamepadButton::South)
+//!     .with(PlayerAction::Move, DualAxisBinding::wasd())
+//!     .with(PlayerAction::Move, DualAxisBinding::left_stick())
+//!     .with(
+//!         PlayerAction::Save,
+//!         Binding::chord([KeyCode::ControlLeft, KeyCode::KeyS]),
+//!     );
+//!
+//! fn jump(actions: Res<ActionState<PlayerAction>>) {
+//!     if actions.just_pressed(PlayerAction::Jump) {
+//!         println!("Jump!");
+//!     }
+//!     println!("Moving {}", actions.axis_pair(PlayerAction::Move));
+//! }
+//!
+//! App::new()
+//!     .add_plugins(ActionPlugin::<PlayerAction>::default())
+//!     .insert_resource(input_map)
+//!     .add_systems(Update, jump);
+//! ```
+//!
+//! An [`InputMap`] inserted as a resource updates the [`ActionState`] resource, and reads from
+//! every connected gamepad. For local multiplayer, spawn an entity per player with an
+//! [`ActionContext`] instead, and restrict the map of each context to a single gamepad with
+//! [`InputMap::with_gamepad`].
+//!
+//! [`ButtonInput<KeyCode>`]: crate::ButtonInput
+//! [`Gamepad`]: crate::gamepad::Gamepad
+
+use crate::{ButtonInput, InputSystems};
+use alloc::vec::Vec;
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::prelude::*;
+use bevy_math::Vec2;
+use bevy_platform::collections::HashMap;
+use core::{hash::Hash, marker::PhantomData};
+#[cfg(feature = "bevy_reflect")]
+use {
+    bevy_ecs::reflect::{ReflectComponent, ReflectResource},
+    bevy_reflect::{std_traits::ReflectDefault, Reflect},
+};
+
+#[cfg(feature = "gamepad")]
+use crate::gamepad::{Gamepad, GamepadAxis, GamepadButton};
+#[cfg(feature = "keyboard")]
+use crate::keyboard::KeyCode;
+#[cfg(feature = "mouse")]
+use crate::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseButton};
+#[cfg(feature = "touch")]
+use crate::touch::Touches;
+
+/// A type which can be used as an action in an [`InputMap`], typically a user-defined enum.
+///
+/// This is implemented for every type which satisfies its bounds.
+pub trait Action: Clone + Eq + Hash + Send + Sync + 'static {}
+
+impl<T: Clone + Eq + Hash + Send + Sync + 'static> Action for T {}
+
+/// Adds the systems which update the [`ActionState`] of the action type `A` from its
+/// [`InputMap`], and initializes the [`ActionState`] resource.
+pub struct ActionPlugin<A: Action>(PhantomData<fn() -> A>);
+
+impl<A: Action> Default for ActionPlugin<A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: Action> Plugin for ActionPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState<A>>()
+            .add_systems(PreUpdate, update_action_states::<A>.after(InputSystems));
+    }
+}
+
+/// The kind of value an action produces, which is determined by its bindings.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, Hash, PartialEq, Clone)
+)]
+pub enum ActionKind {
+    /// The action is either pressed or released, like a button.
+    Button,
+    /// The action has a value between -1.0 and 1.0, like a gamepad trigger or the horizontal
+    /// axis of a stick.
+    Axis,
+    /// The action has a two-dimensional value, like a stick or the mouse motion.
+    DualAxis,
+}
+
+/// A modifier key, matching both its left and right variants.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, Hash, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg(feature = "keyboard")]
+pub enum ModifierKey {
+    /// [`KeyCode::ControlLeft`] or [`KeyCode::ControlRight`].
+    Control,
+    /// [`KeyCode::ShiftLeft`] or [`KeyCode::ShiftRight`].
+    Shift,
+    /// [`KeyCode::AltLeft`] or [`KeyCode::AltRight`].
+    Alt,
+    /// [`KeyCode::SuperLeft`] or [`KeyCode::SuperRight`].
+    Super,
+}
+
+#[cfg(feature = "keyboard")]
+impl ModifierKey {
+    /// Returns the left and right keys of this modifier.
+    pub const fn keys(self) -> [KeyCode; 2] {
+        match self {
+            ModifierKey::Control => [KeyCode::ControlLeft, KeyCode::ControlRight],
+            ModifierKey::Shift => [KeyCode::ShiftLeft, KeyCode::ShiftRight],
+            ModifierKey::Alt => [KeyCode::AltLeft, KeyCode::AltRight],
+            ModifierKey::Super => [KeyCode::SuperLeft, KeyCode::SuperRight],
+        }
+    }
+}
+
+/// A physical input which is either pressed or released.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, Hash, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ButtonBinding {
+    /// A key of the keyboard.
+    #[cfg(feature = "keyboard")]
+    Key(KeyCode),
+    /// Either the left or the right key of a modifier.
+    #[cfg(feature = "keyboard")]
+    Modifier(ModifierKey),
+    /// A mouse button.
+    #[cfg(feature = "mouse")]
+    Mouse(MouseButton),
+    /// A gamepad button.
+    #[cfg(feature = "gamepad")]
+    Gamepad(GamepadButton),
+    /// Any finger touching the screen.
+    #[cfg(feature = "touch")]
+    AnyTouch,
+}
+
+#[cfg(feature = "keyboard")]
+impl From<KeyCode> for ButtonBinding {
+    fn from(key: KeyCode) -> Self {
+        ButtonBinding::Key(key)
+    }
+}
+
+#[cfg(feature = "keyboard")]
+impl From<ModifierKey> for ButtonBinding {
+    fn from(modifier: ModifierKey) -> Self {
+        ButtonBinding::Modifier(modifier)
+    }
+}
+
+#[cfg(feature = "mouse")]
+impl From<MouseButton> for ButtonBinding {
+    fn from(button: MouseButton) -> Self {
+        ButtonBinding::Mouse(button)
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl From<GamepadButton> for ButtonBinding {
+    fn from(button: GamepadButton) -> Self {
+        ButtonBinding::Gamepad(button)
+    }
+}
+
+/// A physical input, or a combination of inputs, with a value between -1.0 and 1.0.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AxisBinding {
+    /// A gamepad axis, such as the horizontal axis of the left stick.
+    #[cfg(feature = "gamepad")]
+    Gamepad(GamepadAxis),
+    /// The analog value of a gamepad button, such as a trigger.
+    #[cfg(feature = "gamepad")]
+    GamepadButton(GamepadButton),
+    /// A virtual axis, which is -1.0 while the `negative` button is pressed and 1.0 while the
+    /// `positive` button is pressed.
+    Buttons {
+        /// The button which makes the axis negative.
+        negative: ButtonBinding,
+        /// The button which makes the axis positive.
+        positive: ButtonBinding,
+    },
+}
+
+impl AxisBinding {
+    /// Creates a virtual axis from two buttons.
+    pub fn buttons(negative: impl Into<ButtonBinding>, positive: impl Into<ButtonBinding>) -> Self {
+        AxisBinding::Buttons {
+            negative: negative.into(),
+            positive: positive.into(),
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl From<GamepadAxis> for AxisBinding {
+    fn from(axis: GamepadAxis) -> Self {
+        AxisBinding::Gamepad(axis)
+    }
+}
+
+/// A physical input, or a combination of inputs, with a two-dimensional value.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum DualAxisBinding {
+    /// A combination of two axes. The length of the value is clamped to 1.0, so that moving
+    /// diagonally with keys isn't faster than moving straight.
+    Axes {
+        /// The horizontal axis.
+        x: AxisBinding,
+        /// The vertical axis.
+        y: AxisBinding,
+    },
+    /// The motion of the mouse this frame, from [`AccumulatedMouseMotion`].
+    #[cfg(feature = "mouse")]
+    MouseMotion,
+    /// The scrolling of the mouse wheel this frame, from [`AccumulatedMouseScroll`].
+    #[cfg(feature = "mouse")]
+    MouseScroll,
+}
+
+impl DualAxisBinding {
+    /// Creates a dual-axis binding from two axes.
+    pub fn axes(x: impl Into<AxisBinding>, y: impl Into<AxisBinding>) -> Self {
+        DualAxisBinding::Axes {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+
+    /// Creates a dual-axis binding from four buttons.
+    pub fn buttons(
+        left: impl Into<ButtonBinding>,
+        right: impl Into<ButtonBinding>,
+        down: impl Into<ButtonBinding>,
+        up: impl Into<ButtonBinding>,
+    ) -> Self {
+        Self::axes(
+            AxisBinding::buttons(left, right),
+            AxisBinding::buttons(down, up),
+        )
+    }
+
+    /// The W, A, S and D keys.
+    #[cfg(feature = "keyboard")]
+    pub fn wasd() -> Self {
+        Self::buttons(KeyCode::KeyA, KeyCode::KeyD, KeyCode::KeyS, KeyCode::KeyW)
+    }
+
+    /// The arrow keys.
+    #[cfg(feature = "keyboard")]
+    pub fn arrow_keys() -> Self {
+        Self::buttons(
+            KeyCode::ArrowLeft,
+            KeyCode::ArrowRight,
+            KeyCode::ArrowDown,
+            KeyCode::ArrowUp,
+        )
+    }
+
+    /// The left stick of a gamepad.
+    #[cfg(feature = "gamepad")]
+    pub fn left_stick() -> Self {
+        Self::axes(GamepadAxis::LeftStickX, GamepadAxis::LeftStickY)
+    }
+
+    /// The right stick of a gamepad.
+    #[cfg(feature = "gamepad")]
+    pub fn right_stick() -> Self {
+        Self::axes(GamepadAxis::RightStickX, GamepadAxis::RightStickY)
+    }
+
+    /// The directional pad of a gamepad.
+    #[cfg(feature = "gamepad")]
+    pub fn dpad() -> Self {
+        Self::buttons(
+            GamepadButton::DPadLeft,
+            GamepadButton::DPadRight,
+            GamepadButton::DPadDown,
+            GamepadButton::DPadUp,
+        )
+    }
+}
+
+/// The input of a [`Binding`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum BindingInput {
+    /// An input which makes the action a [`ActionKind::Button`].
+    Button(ButtonBinding),
+    /// An input which makes the action an [`ActionKind::Axis`].
+    Axis(AxisBinding),
+    /// An input which makes the action an [`ActionKind::DualAxis`].
+    DualAxis(DualAxisBinding),
+}
+
+/// An input bound to an action in an [`InputMap`], along with the modifiers which must be held
+/// for it to apply.
+///
+/// Most inputs can be converted into a binding directly. Use [`Binding::with_modifier`] for
+/// bindings such as "Shift + mouse motion", and [`Binding::chord`] for combinations of
+/// buttons such as "Ctrl + S".
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, PartialEq, Clone)
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Binding {
+    /// The input which determines the value of the action.
+    pub input: BindingInput,
+    /// The buttons which must all be pressed for this binding to apply.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub modifiers: Vec<ButtonBinding>,
+}
+
+impl Binding {
+    /// Creates a binding without modifiers.
+    pub fn new(input: BindingInput) -> Self {
+        Self {
+            input,
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Creates a binding which is pressed while all of the given buttons are pressed.
+    ///
+    /// When several button bindings of the same [`InputMap`] are pressed, and the buttons of one
+    /// include all the buttons of another, only the longest one applies. For example, pressing
+    /// "Ctrl + S" doesn't also trigger an action bound to "S".
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buttons` is empty.
+    pub fn chord<B: Into<ButtonBinding>>(buttons: impl IntoIterator<Item = B>) -> Self {
+        let mut modifiers: Vec<ButtonBinding> = buttons.into_iter().map(Into::into).collect();
+        let input = modifiers.pop().expect("a chord needs at least one button");
+        Self {
+            input: BindingInput::Button(input),
+            modifiers,
+        }
+    }
+
+    /// Adds a button which must be pressed for this binding to apply.
+    #[must_use]
+    pub fn with_modifier(mut self, modifier: impl Into<ButtonBinding>) -> Self {
+        self.modifiers.push(modifier.into());
+        self
+    }
+
+    /// Returns the kind of action this binding can be used for.
+    pub fn kind(&self) -> ActionKind {
+        match self.input {
+            BindingInput::Button(_) => ActionKind::Button,
+            BindingInput::Axis(_) => ActionKind::Axis,
+            BindingInput::DualAxis(_) => ActionKind::DualAxis,
+        }
+    }
+
+    /// Returns `true` if all the buttons of this binding are also buttons of `other`.
+    fn is_contained_in(&self, other: &Binding) -> bool {
+        let (BindingInput::Button(input), BindingInput::Button(other_input)) =
+            (&self.input, &other.input)
+        else {
+            return false;
+        };
+        let other_buttons = || other.modifiers.iter().chain([other_input]);
+        self.modifiers
+            .iter()
+            .chain([input])
+            .all(|button| other_buttons().any(|other| other == button))
+    }
+
+    /// Returns the number of buttons of this binding.
+    fn len(&self) -> usize {
+        self.modifiers.len() + 1
+    }
+}
+
+impl From<BindingInput> for Binding {
+    fn from(input: BindingInput) -> Self {
+        Self::new(input)
+    }
+}
+
+impl From<ButtonBinding> for Binding {
+    fn from(button: ButtonBinding) -> Self {
+        Self::new(BindingInput::Button(button))
+    }
+}
+
+impl From<AxisBinding> for Binding {
+    fn from(axis: AxisBinding) -> Self {
+        Self::new(BindingInput::Axis(axis))
+    }
+}
+
+impl From<DualAxisBinding> for Binding {
+    fn from(dual_axis: DualAxisBinding) -> Self {
+        Self::new(BindingInput::DualAxis(dual_axis))
+    }
+}
+
+#[cfg(feature = "keyboard")]
+impl From<KeyCode> for Binding {
+    fn from(key: KeyCode) -> Self {
+        ButtonBinding::Key(key).into()
+    }
+}
+
+#[cfg(feature = "keyboard")]
+impl From<ModifierKey> for Binding {
+    fn from(modifier: ModifierKey) -> Self {
+        ButtonBinding::Modifier(modifier).into()
+    }
+}
+
+#[cfg(feature = "mouse")]
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Self {
+        ButtonBinding::Mouse(button).into()
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl From<GamepadButton> for Binding {
+    fn from(button: GamepadButton) -> Self {
+        ButtonBinding::Gamepad(button).into()
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl From<GamepadAxis> for Binding {
+    fn from(axis: GamepadAxis) -> Self {
+        AxisBinding::Gamepad(axis).into()
+    }
+}
+
+/// The bindings of the actions of type `A`.
+///
+/// When inserted as a resource, the [`ActionState`] resource is updated from it. Use an
+/// [`ActionContext`] to give an entity its own bindings instead. Each action can have any number
+/// of bindings, which must all be of the same [`ActionKind`].
+#[derive(Resource, Debug, Clone)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect), reflect(Resource, Clone))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputMap<A: Action> {
+    bindings: HashMap<A, Vec<Binding>>,
+    /// The gamepad read by the bindings, or `None` to read every gamepad.
+    #[cfg(feature = "gamepad")]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    gamepad: Option<Entity>,
+}
+
+impl<A: Action> Default for InputMap<A> {
+    fn default() -> Self {
+        Self {
+            bindings: Default::default(),
+            #[cfg(feature = "gamepad")]
+            gamepad: None,
+        }
+    }
+}
+
+impl<A: Action> InputMap<A> {
+    /// Creates an empty input map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds an input to an action.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the action already has bindings of another [`ActionKind`].
+    #[must_use]
+    pub fn with(mut self, action: A, binding: impl Into<Binding>) -> Self {
+        self.insert(action, binding);
+        self
+    }
+
+    /// Binds an input to an action.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the action already has bindings of another [`ActionKind`].
+    pub fn insert(&mut self, action: A, binding: impl Into<Binding>) -> &mut Self {
+        let binding = binding.into();
+        let bindings = self.bindings.entry(action).or_default();
+        if let Some(existing) = bindings.first() {
+            assert_eq!(
+                existing.kind(),
+                binding.kind(),
+                "all the bindings of an action must be of the same kind"
+            );
+        }
+        bindings.push(binding);
+        self
+    }
+
+    /// Removes all the bindings of an action, returning them.
+    pub fn remove(&mut self, action: &A) -> Option<Vec<Binding>> {
+        self.bindings.remove(action)
+    }
+
+    /// Returns the bindings of an action.
+    pub fn bindings(&self, action: &A) -> &[Binding] {
+        self.bindings.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the kind of an action, or `None` if it doesn't have any bindings.
+    pub fn kind(&self, action: &A) -> Option<ActionKind> {
+        self.bindings(action).first().map(Binding::kind)
+    }
+
+    /// An iterator visiting every action and its bindings in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&A, &[Binding])> {
+        self.bindings
+            .iter()
+            .map(|(action, bindings)| (action, bindings.as_slice()))
+    }
+
+    /// Restricts the gamepad bindings to the given gamepad.
+    #[cfg(feature = "gamepad")]
+    #[must_use]
+    pub fn with_gamepad(mut self, gamepad: Entity) -> Self {
+        self.gamepad = Some(gamepad);
+        self
+    }
+
+    /// Sets the gamepad read by the gamepad bindings, or `None` to read every gamepad.
+    #[cfg(feature = "gamepad")]
+    pub fn set_gamepad(&mut self, gamepad: Option<Entity>) {
+        self.gamepad = gamepad;
+    }
+
+    /// Returns the gamepad read by the gamepad bindings, or `None` if every gamepad is read.
+    #[cfg(feature = "gamepad")]
+    pub fn gamepad(&self) -> Option<Entity> {
+        self.gamepad
+    }
+}
+
+/// The value of an action.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Debug, Default, PartialEq, Clone)
+)]
+struct ActionValue {
+    value: f32,
+    axis_pair: Vec2,
+}
+
+/// The current state of the actions of type `A`, as updated from an [`InputMap`].
+///
+/// This mirrors [`ButtonInput`], with the addition of the analog value of each action:
+///
+/// * [`ActionState::pressed`] returns `true` while the value of the action isn't zero.
+/// * [`ActionState::just_pressed`] returns `true` for one frame after the action is pressed.
+/// * [`ActionState::just_released`] returns `true` for one frame after the action is released.
+/// * [`ActionState::value`] returns 1.0 for pressed buttons, the value of axes, and the length
+///   of dual axes.
+/// * [`ActionState::axis_pair`] returns the value of dual axes, or the value of other actions
+///   as its `x` coordinate.
+///
+/// When several bindings of an action are active, the one with the largest value is used.
+#[derive(Resource, Debug, Clone)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect), reflect(Default, Resource))]
+pub struct ActionState<A: Action> {
+    buttons: ButtonInput<A>,
+    values: HashMap<A, ActionValue>,
+}
+
+impl<A: Action> Default for ActionState<A> {
+    fn default() -> Self {
+        Self {
+            buttons: Default::default(),
+            values: Default::default(),
+        }
+    }
+}
+
+impl<A: Action> ActionState<A> {
+    /// Returns `true` if the `action` is pressed.
+    pub fn pressed(&self, action: A) -> bool {
+        self.buttons.pressed(action)
+    }
+
+    /// Returns `true` if any of the `actions` is pressed.
+    pub fn any_pressed(&self, actions: impl IntoIterator<Item = A>) -> bool {
+        self.buttons.any_pressed(actions)
+    }
+
+    /// Returns `true` if all of the `actions` are pressed.
+    pub fn all_pressed(&self, actions: impl IntoIterator<Item = A>) -> bool {
+        self.buttons.all_pressed(actions)
+    }
+
+    /// Returns `true` if the `action` has been pressed during the current frame.
+    pub fn just_pressed(&self, action: A) -> bool {
+        self.buttons.just_pressed(action)
+    }
+
+    /// Returns `true` if any of the `actions` has been pressed during the current frame.
+    pub fn any_just_pressed(&self, actions: impl IntoIterator<Item = A>) -> bool {
+        self.buttons.any_just_pressed(actions)
+    }
+
+    /// Clears the `just_pressed` state of the `action` and returns `true` if it has just been
+    /// pressed.
+    pub fn clear_just_pressed(&mut self, action: A) -> bool {
+        self.buttons.clear_just_pressed(action)
+    }
+
+    /// Returns `true` if the `action` has been released during the current frame.
+    pub fn just_released(&self, action: A) -> bool {
+        self.buttons.just_released(action)
+    }
+
+    /// Returns `true` if any of the `actions` has been released during the current frame.
+    pub fn any_just_released(&self, actions: impl IntoIterator<Item = A>) -> bool {
+        self.buttons.any_just_released(actions)
+    }
+
+    /// Clears the `just_released` state of the `action` and returns `true` if it has just been
+    /// released.
+    pub fn clear_just_released(&mut self, action: A) -> bool {
+        self.buttons.clear_just_released(action)
+    }
+
+    /// Returns the value of the `action`, or 0.0 if it isn't pressed.
+    pub fn value(&self, action: A) -> f32 {
+        self.values.get(&action).map_or(0.0, |value| value.value)
+    }
+
+    /// Returns the two-dimensional value of the `action`, or zero if it isn't pressed.
+    pub fn axis_pair(&self, action: A) -> Vec2 {
+        self.values
+            .get(&action)
+            .map_or(Vec2::ZERO, |value| value.axis_pair)
+    }
+
+    /// An iterator visiting every pressed action in arbitrary order.
+    pub fn get_pressed(&self) -> impl ExactSizeIterator<Item = &A> {
+        self.buttons.get_pressed()
+    }
+
+    /// An iterator visiting every just pressed action in arbitrary order.
+    pub fn get_just_pressed(&self) -> impl ExactSizeIterator<Item = &A> {
+        self.buttons.get_just_pressed()
+    }
+
+    /// An iterator visiting every just released action in arbitrary order.
+    pub fn get_just_released(&self) -> impl ExactSizeIterator<Item = &A> {
+        self.buttons.get_just_released()
+    }
+
+    /// Releases every action, and clears their values.
+    pub fn reset_all(&mut self) {
+        self.buttons.reset_all();
+        self.values.clear();
+    }
+
+    /// Updates the state of every action from the current inputs.
+    fn update(&mut self, input_map: &InputMap<A>, inputs: &ActionInputs) {
+        #[cfg(feature = "gamepad")]
+        let inputs = &ActionInputs {
+            gamepad: input_map.gamepad,
+            ..*inputs
+        };
+
+        // Bindings whose buttons are all part of another active binding are suppressed, so
+        // that chords take precedence over their individual buttons.
+        let active: Vec<&Binding> = input_map
+            .bindings
+            .values()
+            .flatten()
+            .filter(|binding| matches!(binding.input, BindingInput::Button(_)))
+            .filter(|binding| inputs.modifiers_pressed(binding) && inputs.value(binding) != 0.0)
+            .collect();
+        let is_suppressed = |binding: &Binding| {
+            active
+                .iter()
+                .any(|other| other.len() > binding.len() && binding.is_contained_in(other))
+        };
+
+        self.buttons.clear();
+        self.values.clear();
+        for (action, bindings) in &input_map.bindings {
+            let mut value = ActionValue::default();
+            for binding in bindings {
+                if !inputs.modifiers_pressed(binding) || is_suppressed(binding) {
+                    continue;
+                }
+                let axis_pair = inputs.axis_pair(binding);
+                if axis_pair.length_squared() > value.axis_pair.length_squared() {
+                    value = ActionValue {
+                        value: match binding.input {
+                            BindingInput::DualAxis(_) => axis_pair.length(),
+                            _ => axis_pair.x,
+                        },
+                        axis_pair,
+                    };
+                }
+            }
+
+            if value.value != 0.0 {
+                self.buttons.press(action.clone());
+                self.values.insert(action.clone(), value);
+            } else {
+                self.buttons.release(action.clone());
+            }
+        }
+
+        // Actions which have been unbound are released.
+        let unbound: Vec<A> = self
+            .buttons
+            .get_pressed()
+            .filter(|action| !input_map.bindings.contains_key(*action))
+            .cloned()
+            .collect();
+        for action in unbound {
+            self.buttons.release(action);
+        }
+    }
+}
+
+/// The physical inputs read by the bindings of an [`InputMap`].
+#[derive(Clone, Copy)]
+struct ActionInputs<'a> {
+    #[cfg(feature = "keyboard")]
+    keys: Option<&'a ButtonInput<KeyCode>>,
+    #[cfg(feature = "mouse")]
+    mouse_buttons: Option<&'a ButtonInput<MouseButton>>,
+    #[cfg(feature = "mouse")]
+    mouse_motion: Option<&'a AccumulatedMouseMotion>,
+    #[cfg(feature = "mouse")]
+    mouse_scroll: Option<&'a AccumulatedMouseScroll>,
+    #[cfg(feature = "gamepad")]
+    gamepads: &'a [(Entity, &'a Gamepad)],
+    /// The gamepad read by the bindings, or `None` to read every gamepad.
+    #[cfg(feature = "gamepad")]
+    gamepad: Option<Entity>,
+    #[cfg(feature = "touch")]
+    touches: Option<&'a Touches>,
+    marker: PhantomData<&'a ()>,
+}
+
+impl ActionInputs<'_> {
+    /// Returns the gamepads read by the bindings.
+    #[cfg(feature = "gamepad")]
+    fn gamepads(&self) -> impl Iterator<Item = &Gamepad> {
+        self.gamepads
+            .iter()
+            .filter(|(entity, _)| self.gamepad.is_none_or(|gamepad| gamepad == *entity))
+            .map(|(_, gamepad)| *gamepad)
+    }
+
+    fn button_pressed(&self, button: &ButtonBinding) -> bool {
+        match *button {
+            #[cfg(feature = "keyboard")]
+            ButtonBinding::Key(key) => self.keys.is_some_and(|keys| keys.pressed(key)),
+            #[cfg(feature = "keyboard")]
+            ButtonBinding::Modifier(modifier) => self
+                .keys
+                .is_some_and(|keys| keys.any_pressed(modifier.keys())),
+            #[cfg(feature = "mouse")]
+            ButtonBinding::Mouse(button) => self
+                .mouse_buttons
+                .is_some_and(|buttons| buttons.pressed(button)),
+            #[cfg(feature = "gamepad")]
+            ButtonBinding::Gamepad(button) => {
+                self.gamepads().any(|gamepad| gamepad.pressed(button))
+            }
+            #[cfg(feature = "touch")]
+            ButtonBinding::AnyTouch => self
+                .touches
+                .is_some_and(|touches| touches.iter().next().is_some()),
+        }
+    }
+
+    fn axis_value(&self, axis: &AxisBinding) -> f32 {
+        match *axis {
+            #[cfg(feature = "gamepad")]
+            AxisBinding::Gamepad(axis) => self.largest_gamepad_value(|gamepad| gamepad.get(axis)),
+            #[cfg(feature = "gamepad")]
+            AxisBinding::GamepadButton(button) => {
+                self.largest_gamepad_value(|gamepad| gamepad.get(button))
+            }
+            AxisBinding::Buttons {
+                ref negative,
+                ref positive,
+            } => {
+                let negative = if self.button_pressed(negative) {
+                    1.0
+                } else {
+                    0.0
+                };
+                let positive = if self.button_pressed(positive) {
+                    1.0
+                } else {
+                    0.0
+                };
+                positive - negative
+            }
+        }
+    }
+
+    /// Returns the value of a gamepad input with the largest magnitude.
+    #[cfg(feature = "gamepad")]
+    fn largest_gamepad_value(&self, get: impl Fn(&Gamepad) -> Option<f32>) -> f32 {
+        self.gamepads().filter_map(get).fold(0.0, |largest, value| {
+            if value.abs() > largest.abs() {
+                value
+            } else {
+                largest
+            }
+        })
+    }
+
+    fn dual_axis_value(&self, dual_axis: &DualAxisBinding) -> Vec2 {
+        match *dual_axis {
+            DualAxisBinding::Axes { ref x, ref y } => {
+                Vec2::new(self.axis_value(x), self.axis_value(y)).clamp_length_max(1.0)
+            }
+            #[cfg(feature = "mouse")]
+            DualAxisBinding::MouseMotion => self.mouse_motion.map_or(Vec2::ZERO, |m| m.delta),
+            #[cfg(feature = "mouse")]
+            DualAxisBinding::MouseScroll => self.mouse_scroll.map_or(Vec2::ZERO, |s| s.delta),
+        }
+    }
+
+    fn modifiers_pressed(&self, binding: &Binding) -> bool {
+        binding
+            .modifiers
+            .iter()
+            .all(|modifier| self.button_pressed(modifier))
+    }
+
+    /// Returns the value of a binding, ignoring its modifiers.
+    fn value(&self, binding: &Binding) -> f32 {
+        self.axis_pair(binding).x
+    }
+
+    /// Returns the two-dimensional value of a binding, ignoring its modifiers. The value of
+    /// buttons and axes is the `x` coordinate.
+    fn axis_pair(&self, binding: &Binding) -> Vec2 {
+        match binding.input {
+            BindingInput::Button(ref button) => Vec2::new(
+                if self.button_pressed(button) {
+                    1.0
+                } else {
+                    0.0
+                },
+                0.0,
+            ),
+            BindingInput::Axis(ref axis) => Vec2::new(self.axis_value(axis), 0.0),
+            BindingInput::DualAxis(ref dual_axis) => self.dual_axis_value(dual_axis),
+        }
+    }
+}
+
+/// A component which gives an entity its own [`InputMap`] and [`ActionState`], such as the
+/// actions of one player in local multiplayer.
+#[derive(Component, Debug, Clone)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Default, Clone)
+)]
+pub struct ActionContext<A: Action> {
+    /// The bindings of the actions.
+    pub input_map: InputMap<A>,
+    /// The state of the actions, updated from [`ActionContext::input_map`].
+    pub state: ActionState<A>,
+}
+
+impl<A: Action> Default for ActionContext<A> {
+    fn default() -> Self {
+        Self::new(InputMap::default())
+    }
+}
+
+impl<A: Action> ActionContext<A> {
+    /// Creates an action context, whose actions aren't pressed until it is updated.
+    pub fn new(input_map: InputMap<A>) -> Self {
+        Self {
+            input_map,
+            state: ActionState::default(),
+        }
+    }
+}
+
+/// Updates the [`ActionState`] resource from the [`InputMap`] resource, and the state of every
+/// [`ActionContext`].
+pub fn update_action_states<A: Action>(
+    #[cfg(feature = "keyboard")] keys: Option<Res<ButtonInput<KeyCode>>>,
+    #[cfg(feature = "mouse")] mouse_buttons: Option<Res<ButtonInput<MouseButton>>>,
+    #[cfg(feature = "mouse")] mouse_motion: Option<Res<AccumulatedMouseMotion>>,
+    #[cfg(feature = "mouse")] mouse_scroll: Option<Res<AccumulatedMouseScroll>>,
+    #[cfg(feature = "gamepad")] gamepads: Query<(Entity, &Gamepad)>,
+    #[cfg(feature = "touch")] touches: Option<Res<Touches>>,
+    input_map: Option<Res<InputMap<A>>>,
+    action_state: Option<ResMut<ActionState<A>>>,
+    mut contexts: Query<&mut ActionContext<A>>,
+) {
+    #[cfg(feature = "gamepad")]
+    let gamepads: Vec<_> = gamepads.iter().collect();
+    let inputs = ActionInputs {
+        #[cfg(feature = "keyboard")]
+        keys: keys.as_deref(),
+        #[cfg(feature = "mouse")]
+        mouse_buttons: mouse_buttons.as_deref(),
+        #[cfg(feature = "mouse")]
+        mouse_motion: mouse_motion.as_deref(),
+        #[cfg(feature = "mouse")]
+        mouse_scroll: mouse_scroll.as_deref(),
+        #[cfg(feature = "gamepad")]
+        gamepads: &gamepads,
+        #[cfg(feature = "gamepad")]
+        gamepad: None,
+        #[cfg(feature = "touch")]
+        touches: touches.as_deref(),
+        marker: PhantomData,
+    };
+
+    if let (Some(input_map), Some(mut action_state)) = (input_map, action_state) {
+        action_state.update(&input_map, &inputs);
+    }
+    for mut context in &mut contexts {
+        let ActionContext { input_map, state } = &mut *context;
+        state.update(input_map, &inputs);
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "keyboard", feature = "gamepad"))]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::RunSystemOnce;
+
+    #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+    enum TestAction {
+        Jump,
+        Throttle,
+        Move,
+        Save,
+        Back,
+    }
+
+    fn input_map() -> InputMap<TestAction> {
+        InputMap::new()
+            .with(TestAction::Jump, KeyCode::Space)
+            .with(TestAction::Jump, GamepadButton::South)
+            .with(
+                TestAction::Throttle,
+                AxisBinding::GamepadButton(GamepadButton::RightTrigger2),
+            )
+            .with(TestAction::Move, DualAxisBinding::wasd())
+            .with(TestAction::Move, DualAxisBinding::left_stick())
+            .with(
+                TestAction::Save,
+                Binding::chord([KeyCode::ControlLeft, KeyCode::KeyS]),
+            )
+            .with(TestAction::Back, KeyCode::KeyS)
+    }
+
+    fn update(world: &mut World) {
+        world
+            .run_system_once(update_action_states::<TestAction>)
+            .unwrap();
+        world.resource_mut::<ButtonInput<KeyCode>>().clear();
+    }
+
+    #[test]
+    fn buttons() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ActionState<TestAction>>();
+        world.insert_resource(input_map());
+        let gamepad = world.spawn(Gamepad::default()).id();
+
+        update(&mut world);
+        let actions = world.resource::<ActionState<TestAction>>();
+        assert!(!actions.pressed(TestAction::Jump));
+        assert_eq!(actions.value(TestAction::Jump), 0.0);
+
+        // Any of the bindings presses the action.
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Space);
+        update(&mut world);
+        let actions = world.resource::<ActionState<TestAction>>();
+        assert!(actions.pressed(TestAction::Jump));
+        assert!(actions.just_pressed(TestAction::Jump));
+        assert_eq!(actions.value(TestAction::Jump), 1.0);
+
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release(KeyCode::Space);
+        world
+            .get_mut::<Gamepad>(gamepad)
+            .unwrap()
+            .digital_mut()
+            .press(GamepadButton::South);
+        update(&mut world);
+        let actions = world.resource::<ActionState<TestAction>>();
+        assert!(actions.pressed(TestAction::Jump));
+        assert!(!actions.just_pressed(TestAction::Jump));
+
+        world
+            .get_mut::<Gamepad>(gamepad)
+            .unwrap()
+            .digital_mut()
+            .release(GamepadButton::South);
+        update(&mut world);
+        let actions = world.resource::<ActionState<TestAction>>();
+        assert!(!actions.pressed(TestAction::Jump));
+        assert!(actions.just_released(TestAction::Jump));
+    }
+
+    #[test]
+    fn chords_take_precedence() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ActionState<TestAction>>();
+        world.insert_resource(input_map());
+
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyS);
+        update(&mut world);
+        let actions = world.resource::<ActionState<TestAction>>();
+        assert!(actions.pressed(TestAction::Back));
+        assert!(!actions.pressed(TestAction::Save));
+
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::ControlLeft);
+        update(&mut world);
+        let actions = world.resource::<ActionState<TestAction>>();
+        assert!(actions.just_pressed(TestAction::Save));
+        assert!(actions.just_released(TestAction::Back));
+        // The `S` key of the `Move` action isn't a button binding, so it isn't suppressed.
+        assert_eq!(actions.axis_pair(TestAction::Move), Vec2::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn axes() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ActionState<TestAction>>();
+        world.insert_resource(input_map());
+        let gamepad = world.spawn(Gamepad::default()).id();
+
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyW);
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyD);
+        let mut gamepad_mut = world.get_mut::<Gamepad>(gamepad).unwrap();
+        gamepad_mut
+            .analog_mut()
+            .set(GamepadButton::RightTrigger2, 0.5);
+        gamepad_mut.analog_mut().set(GamepadAxis::LeftStickX, -0.25);
+        update(&mut world);
+
+        let actions = world.resource::<ActionState<TestAction>>();
+        assert_eq!(actions.value(TestAction::Throttle), 0.5);
+        assert!(actions.pressed(TestAction::Throttle));
+        // Diagonals are clamped, and the binding with the largest value is used.
+        let movement = actions.axis_pair(TestAction::Move);
+        assert!((movement - Vec2::splat(core::f32::consts::FRAC_1_SQRT_2)).length() < 1e-6);
+        assert!((actions.value(TestAction::Move) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn per_gamepad_contexts() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        let first_gamepad = world.spawn(Gamepad::default()).id();
+        let second_gamepad = world.spawn(Gamepad::default()).id();
+        let first_player = world
+            .spawn(ActionContext::new(input_map().with_gamepad(first_gamepad)))
+            .id();
+        let second_player = world
+            .spawn(ActionContext::new(input_map().with_gamepad(second_gamepad)))
+            .id();
+
+        world
+            .get_mut::<Gamepad>(second_gamepad)
+            .unwrap()
+            .digital_mut()
+            .press(GamepadButton::South);
+        update(&mut world);
+
+        let context = world
+            .get::<ActionContext<TestAction>>(first_player)
+            .unwrap();
+        assert!(!context.state.pressed(TestAction::Jump));
+        let context = world
+            .get::<ActionContext<TestAction>>(second_player)
+            .unwrap();
+        assert!(context.state.pressed(TestAction::Jump));
+    }
+
+    #[test]
+    #[should_panic]
+    fn bindings_of_different_kinds() {
+        let _ = InputMap::new()
+            .with(TestAction::Jump, KeyCode::Space)
+            .with(TestAction::Jump, DualAxisBinding::wasd());
+    }
+}
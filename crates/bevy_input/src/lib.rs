@@ -17,6 +17,13 @@ extern crate std;
 
 extern crate alloc;
 
+#[cfg(any(
+    feature = "keyboard",
+    feature = "mouse",
+    feature = "gamepad",
+    feature = "touch"
+))]
+pub mod action;
 mod axis;
 mod button_input;
 /// Common run conditions
@@ -47,6 +54,15 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate::{Axis, ButtonInput};
 
+    #[doc(hidden)]
+    #[cfg(any(
+        feature = "keyboard",
+        feature = "mouse",
+        feature = "gamepad",
+        feature = "touch"
+    ))]
+    pub use crate::action::{ActionContext, ActionPlugin, ActionState, InputMap};
+
     #[doc(hidden)]
     #[cfg(feature = "gamepad")]
     pub use crate::gamepad::{Gamepad, GamepadAxis, GamepadButton, GamepadSettings};
@@ -0,0 +1,331 @@
+use alloc::{collections::VecDeque, string::String, sync::Arc, vec::Vec};
+use core::{
+    fmt::{self, Write as _},
+    sync::atomic::{AtomicU32, Ordering},
+};
+use std::sync::{Mutex, PoisonError};
+
+use bevy_app::{App, First, Last};
+use bevy_ecs::{
+    message::{Message, MessageWriter},
+    resource::Resource,
+    system::{Res, ResMut},
+};
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_log::NormalizeEvent;
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// A suggested number of records kept in the [`LogBuffer`] when capturing logs.
+///
+/// Capturing is disabled by default, set [`LogPlugin::capture_capacity`](crate::LogPlugin::capture_capacity)
+/// to this value to enable it.
+pub const DEFAULT_CAPTURE_CAPACITY: usize = 1024;
+
+/// A log record captured by the [`LogPlugin`](crate::LogPlugin).
+///
+/// Captured records are kept in the [`LogBuffer`] resource, and written as messages at the start
+/// of the frame following the one they were logged in.
+#[derive(Message, Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    /// The level of the record.
+    pub level: Level,
+    /// The target of the record, which is usually the module it was logged from.
+    pub target: String,
+    /// The names of the spans the record was logged in, from the outermost to the innermost.
+    pub spans: Vec<&'static str>,
+    /// The message of the record, followed by its other fields.
+    pub message: String,
+    /// The number of the frame the record was logged in, counted like `FrameCount` from
+    /// `bevy_diagnostic`.
+    pub frame: u32,
+}
+
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[frame {}] {:>5} {}:",
+            self.frame, self.level, self.target
+        )?;
+        for span in &self.spans {
+            write!(f, " {span}:")?;
+        }
+        write!(f, " {}", self.message)
+    }
+}
+
+/// The most recent [`LogRecord`]s captured by the [`LogPlugin`](crate::LogPlugin), oldest first.
+///
+/// The capacity of the buffer is set by [`LogPlugin::capture_capacity`](crate::LogPlugin::capture_capacity).
+/// Only records enabled by the filter of the [`LogPlugin`](crate::LogPlugin) are captured.
+#[derive(Resource, Debug, Default)]
+pub struct LogBuffer {
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    /// Returns the maximum number of records kept in the buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of records in the buffer.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if the buffer contains no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns an iterator over the records in the buffer, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &LogRecord> + ExactSizeIterator {
+        self.records.iter()
+    }
+
+    /// Removes all records from the buffer.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    fn push(&mut self, record: LogRecord) {
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+}
+
+/// State shared between the [`LogCaptureLayer`] and the app.
+#[derive(Default)]
+struct CaptureState {
+    /// Records captured since they were last moved to the [`LogBuffer`].
+    pending: Mutex<VecDeque<LogRecord>>,
+    frame: AtomicU32,
+}
+
+#[derive(Resource)]
+struct LogCapture(Arc<CaptureState>);
+
+/// A [`Layer`] capturing log records into the [`LogBuffer`] of an app.
+pub(crate) struct LogCaptureLayer {
+    state: Arc<CaptureState>,
+    capacity: usize,
+}
+
+impl LogCaptureLayer {
+    /// Creates a layer capturing up to `capacity` records into the [`LogBuffer`] of `app`.
+    pub(crate) fn new(app: &mut App, capacity: usize) -> Self {
+        let state = Arc::new(CaptureState::default());
+        app.add_message::<LogRecord>()
+            .insert_resource(LogBuffer {
+                records: VecDeque::with_capacity(capacity),
+                capacity,
+            })
+            .insert_resource(LogCapture(state.clone()))
+            .add_systems(First, collect_log_records)
+            .add_systems(Last, count_log_frames);
+        Self { state, capacity }
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for LogCaptureLayer {
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        // Records from the `log` crate have their metadata in fields.
+        let normalized_metadata = event.normalized_metadata();
+        let metadata = normalized_metadata
+            .as_ref()
+            .unwrap_or_else(|| event.metadata());
+
+        let spans = ctx
+            .event_scope(event)
+            .map(|scope| scope.from_root().map(|span| span.name()).collect())
+            .unwrap_or_default();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let record = LogRecord {
+            level: *metadata.level(),
+            target: metadata.target().into(),
+            spans,
+            message: visitor.message,
+            frame: self.state.frame.load(Ordering::Relaxed),
+        };
+        let mut pending = self
+            .state
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // Don't grow without bound if the app doesn't update, for example before it runs.
+        if pending.len() >= self.capacity {
+            pending.pop_front();
+        }
+        pending.push_back(record);
+    }
+}
+
+/// Formats the fields of an event like the default formatter of `tracing_subscriber`.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.record_debug(field, &format_args!("{value}"));
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" if self.message.is_empty() => {
+                let _ = write!(self.message, "{value:?}");
+            }
+            "message" => {
+                self.message = alloc::format!("{value:?} {}", self.message);
+            }
+            // Metadata of records from the `log` crate.
+            name if name.starts_with("log.") => {}
+            name => {
+                if !self.message.is_empty() {
+                    self.message.push(' ');
+                }
+                let _ = write!(self.message, "{name}={value:?}");
+            }
+        }
+    }
+}
+
+fn collect_log_records(
+    capture: Res<LogCapture>,
+    mut buffer: ResMut<LogBuffer>,
+    mut messages: MessageWriter<LogRecord>,
+) {
+    let records = core::mem::take(
+        &mut *capture
+            .0
+            .pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner),
+    );
+    if records.is_empty() {
+        return;
+    }
+    for record in &records {
+        buffer.push(record.clone());
+    }
+    messages.write_batch(records);
+}
+
+fn count_log_frames(capture: Res<LogCapture>) {
+    capture.0.frame.fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use bevy_app::App;
+    use bevy_ecs::message::Messages;
+    use tracing::{info, info_span, subscriber::with_default, warn};
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    use super::*;
+
+    /// Runs `log` with a subscriber capturing up to `capacity` records into the returned app.
+    fn capture(capacity: usize, log: impl FnOnce(&mut App)) -> App {
+        let mut app = App::new();
+        let layer = LogCaptureLayer::new(&mut app, capacity);
+        with_default(Registry::default().with(layer), || log(&mut app));
+        app
+    }
+
+    fn messages(app: &App) -> Vec<String> {
+        app.world()
+            .resource::<LogBuffer>()
+            .iter()
+            .map(|record| record.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn records_are_formatted_like_the_fmt_layer() {
+        let app = capture(8, |app| {
+            let _outer = info_span!("outer").entered();
+            let _inner = info_span!("inner").entered();
+            warn!(answer = 42, name = "bevy", "hello {}", "world");
+            app.update();
+        });
+
+        let buffer = app.world().resource::<LogBuffer>();
+        let record = buffer.iter().next().unwrap();
+        assert_eq!(record.level, Level::WARN);
+        assert_eq!(record.target, module_path!());
+        assert_eq!(record.spans, vec!["outer", "inner"]);
+        assert_eq!(record.message, r#"hello world answer=42 name="bevy""#);
+        assert_eq!(
+            record.to_string(),
+            alloc::format!(
+                r#"[frame 0]  WARN {}: outer: inner: hello world answer=42 name="bevy""#,
+                module_path!()
+            )
+        );
+    }
+
+    #[test]
+    fn records_are_stamped_with_their_frame() {
+        let app = capture(8, |app| {
+            info!("first");
+            app.update();
+            info!("second");
+            app.update();
+            app.update();
+            info!("third");
+            app.update();
+        });
+
+        let frames: Vec<_> = app
+            .world()
+            .resource::<LogBuffer>()
+            .iter()
+            .map(|record| record.frame)
+            .collect();
+        assert_eq!(frames, vec![0, 1, 3]);
+
+        // Only the records collected during the last update are still readable as messages.
+        let messages = app.world().resource::<Messages<LogRecord>>();
+        let mut cursor = messages.get_cursor();
+        let read: Vec<_> = cursor
+            .read(messages)
+            .map(|record| record.message.as_str())
+            .collect();
+        assert!(read.ends_with(&["third"]));
+    }
+
+    #[test]
+    fn oldest_records_are_evicted() {
+        let app = capture(2, |app| {
+            // Records logged before the app updates are bounded too.
+            info!("1");
+            info!("2");
+            info!("3");
+            app.update();
+            assert_eq!(messages(app), vec!["2", "3"]);
+
+            info!("4");
+            app.update();
+        });
+
+        let buffer = app.world().resource::<LogBuffer>();
+        assert_eq!(buffer.capacity(), 2);
+        assert_eq!(messages(&app), vec!["3", "4"]);
+    }
+}
@@ -20,6 +20,7 @@ extern crate alloc;
 
 #[cfg(target_os = "android")]
 mod android_tracing;
+mod capture;
 mod once;
 
 #[cfg(feature = "trace_tracy_memory")]
@@ -44,6 +45,7 @@ pub mod prelude {
 }
 
 pub use bevy_utils::once;
+pub use capture::{LogBuffer, LogRecord, DEFAULT_CAPTURE_CAPACITY};
 pub use tracing::{
     self, debug, debug_span, error, error_span, event, info, info_span, trace, trace_span, warn,
     warn_span, Level,
@@ -91,6 +93,7 @@ pub(crate) struct FlushGuard(SyncCell<tracing_chrome::FlushGuard>);
 ///             filter: "wgpu=error,bevy_render=info,bevy_ecs=trace".to_string(),
 ///             custom_layer: |_| None,
 ///             fmt_layer: |_| None,
+///             capture_capacity: 0,
 ///         }))
 ///         .run();
 /// }
@@ -247,6 +250,16 @@ pub struct LogPlugin {
     ///
     /// Please see the `examples/app/log_layers.rs` for a complete example.
     pub fmt_layer: fn(app: &mut App) -> Option<BoxedFmtLayer>,
+
+    /// The number of most recent log records kept in the [`LogBuffer`] resource, which are also
+    /// written as [`LogRecord`] messages. This allows the app to read its own logs, for example
+    /// to display them or attach them to bug reports.
+    ///
+    /// Capturing logs adds some overhead to every log record, so it is disabled by default.
+    /// Set to a non-zero value, such as [`DEFAULT_CAPTURE_CAPACITY`], to enable it.
+    ///
+    /// Defaults to `0`.
+    pub capture_capacity: usize,
 }
 
 /// A boxed [`Layer`] that can be used with [`LogPlugin::custom_layer`].
@@ -288,6 +301,7 @@ impl Default for LogPlugin {
             level: Level::INFO,
             custom_layer: |_| None,
             fmt_layer: |_| None,
+            capture_capacity: 0,
         }
     }
 }
@@ -307,8 +321,16 @@ impl Plugin for LogPlugin {
         let finished_subscriber;
         let subscriber = Registry::default();
 
-        // add optional layer provided by user
-        let subscriber = subscriber.with((self.custom_layer)(app));
+        // add optional layer provided by user, and the layer capturing logs into `LogBuffer`
+        let mut layers: Vec<BoxedLayer> = (self.custom_layer)(app).into_iter().collect();
+        if self.capture_capacity > 0 {
+            layers.push(Box::new(capture::LogCaptureLayer::new(
+                app,
+                self.capture_capacity,
+            )));
+        }
+        let layer: Option<BoxedLayer> = (!layers.is_empty()).then(|| Box::new(layers) as _);
+        let subscriber = subscriber.with(layer);
 
         let subscriber = subscriber.with(self.build_filter_layer());
 
@@ -397,6 +419,10 @@ impl Plugin for LogPlugin {
             (false, true) => error!("Could not set global tracing subscriber as it is already set. Consider disabling LogPlugin."),
             (false, false) => (),
         }
+
+        if subscriber_already_set && self.capture_capacity > 0 {
+            warn!("Logs will not be captured into the `LogBuffer` as a global tracing subscriber is already set.");
+        }
     }
 }
 
@@ -15,7 +15,7 @@ use bevy_ecs::{
     query::QueryBuilder,
     reflect::{AppTypeRegistry, ReflectComponent, ReflectEvent, ReflectMessage, ReflectResource},
     resource::Resource,
-    schedule::Schedules,
+    schedule::{InternedScheduleLabel, NodeId, Schedules, Stepping, SystemKey, SystemTimings},
     system::{In, Local},
     world::{DeferredWorld, EntityRef, EntityWorldMut, FilteredEntityRef, Mut, World},
 };
@@ -28,6 +28,7 @@ use bevy_reflect::{
 };
 use serde::{de::DeserializeSeed as _, de::IntoDeserializer, Deserialize, Serialize};
 use serde_json::{Map, Value};
+use slotmap::{Key as _, KeyData};
 
 use crate::{
     error_codes,
@@ -107,6 +108,33 @@ pub const BRP_OBSERVE_METHOD: &str = "world.observe+watch";
 /// The method path for a `schedule.graph` request.
 pub const BRP_SCHEDULE_GRAPH: &str = "schedule.graph";
 
+/// The method path for a `schedule.timings` request.
+pub const BRP_SCHEDULE_TIMINGS: &str = "schedule.timings";
+
+/// The method path for a `stepping.enable` request.
+pub const BRP_STEPPING_ENABLE_METHOD: &str = "stepping.enable";
+
+/// The method path for a `stepping.disable` request.
+pub const BRP_STEPPING_DISABLE_METHOD: &str = "stepping.disable";
+
+/// The method path for a `stepping.list` request.
+pub const BRP_STEPPING_LIST_METHOD: &str = "stepping.list";
+
+/// The method path for a `stepping.set_breakpoint` request.
+pub const BRP_STEPPING_SET_BREAKPOINT_METHOD: &str = "stepping.set_breakpoint";
+
+/// The method path for a `stepping.clear_breakpoint` request.
+pub const BRP_STEPPING_CLEAR_BREAKPOINT_METHOD: &str = "stepping.clear_breakpoint";
+
+/// The method path for a `stepping.step_system` request.
+pub const BRP_STEPPING_STEP_SYSTEM_METHOD: &str = "stepping.step_system";
+
+/// The method path for a `stepping.step_frame` request.
+pub const BRP_STEPPING_STEP_FRAME_METHOD: &str = "stepping.step_frame";
+
+/// The method path for a `stepping.cursor` request.
+pub const BRP_STEPPING_CURSOR_METHOD: &str = "stepping.cursor";
+
 /// The method path for a `rpc.discover` request.
 pub const RPC_DISCOVER_METHOD: &str = "rpc.discover";
 
@@ -378,6 +406,29 @@ struct BrpScheduleGraphParams {
     pub schedule_label: String,
 }
 
+/// `stepping.enable`:
+///
+/// The server responds with a null.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct BrpSteppingEnableParams {
+    /// The schedules to add to stepping, in addition to those already added.
+    ///
+    /// A list of schedules can be fetched from the `schedule.list` endpoint.
+    #[serde(default)]
+    pub schedule_labels: Vec<String>,
+}
+
+/// `stepping.set_breakpoint` and `stepping.clear_breakpoint`:
+///
+/// The server responds with a null.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpSteppingBreakpointParams {
+    /// The schedule containing the system.
+    pub schedule_label: String,
+    /// The node id of the system, as returned by `stepping.list`.
+    pub node_id: u64,
+}
+
 /// Describes the data that is to be fetched in a query.
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 pub struct BrpQuery {
@@ -540,6 +591,85 @@ pub struct BrpScheduleListResponse {
     empty_schedule_labels: Vec<String>,
 }
 
+/// The response to a `schedule.timings` request.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct BrpScheduleTimingsResponse {
+    /// The timings of the schedules that ran since recording started, slowest first.
+    pub schedules: Vec<BrpScheduleTimings>,
+}
+
+/// The execution time of a schedule, in a [`BrpScheduleTimingsResponse`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpScheduleTimings {
+    /// The label of the schedule.
+    pub schedule_label: String,
+    /// The number of times the schedule ran.
+    pub runs: u64,
+    /// The time spent running the schedule, in seconds.
+    pub total_secs: f64,
+    /// The average duration of a run of the schedule, in seconds.
+    pub mean_secs: f64,
+    /// The duration of the last run of the schedule, in seconds.
+    pub last_secs: f64,
+    /// The timings of the systems of the schedule, slowest first.
+    pub systems: Vec<BrpSystemTimings>,
+}
+
+/// The execution time of a system, in a [`BrpScheduleTimings`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpSystemTimings {
+    /// The [`NodeId`] of the system.
+    pub node_id: u64,
+    /// The name of the system.
+    pub name: String,
+    /// The number of times the system ran.
+    pub runs: u64,
+    /// The time spent running the system, in seconds.
+    pub total_secs: f64,
+    /// The average duration of a run of the system, in seconds.
+    pub mean_secs: f64,
+    /// The duration of the last run of the system, in seconds.
+    pub last_secs: f64,
+}
+
+/// The response to a `stepping.list` request.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct BrpSteppingListResponse {
+    /// Whether stepping is enabled.
+    pub enabled: bool,
+    /// The schedules added to stepping, in the order they run.
+    pub schedules: Vec<BrpSteppingSchedule>,
+}
+
+/// A schedule added to stepping, in a [`BrpSteppingListResponse`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpSteppingSchedule {
+    /// The label of the schedule.
+    pub schedule_label: String,
+    /// The systems of the schedule, in the order they run.
+    ///
+    /// This is empty if the schedule is running, and so can't be inspected.
+    pub systems: Vec<BrpSteppingSystem>,
+}
+
+/// A system that can be stepped.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpSteppingSystem {
+    /// The [`NodeId`] of the system, used to set breakpoints.
+    pub node_id: u64,
+    /// The name of the system.
+    pub name: String,
+}
+
+/// The response to a `stepping.cursor` request, if stepping is paused on a system.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BrpSteppingCursorResponse {
+    /// The schedule containing the next system to run.
+    pub schedule_label: String,
+    /// The next system to run.
+    pub system: BrpSteppingSystem,
+}
+
 /// The response to a `schedule.graph` request.
 ///
 /// In Bevy, systems are ordered in a graph structure, [`ScheduleGraph`](`bevy_ecs::schedule::ScheduleGraph`).
@@ -1798,6 +1928,263 @@ pub fn schedule_graph(In(params): In<Option<Value>>, world: &mut World) -> BrpRe
     serde_json::to_value(BrpScheduleGraphResponse { schedule_data }).map_err(BrpError::internal)
 }
 
+/// Handles a `schedule.timings` request coming from a client.
+///
+/// Timings are only available while the [`SystemTimings`] resource exists.
+pub fn schedule_timings(In(_params): In<Option<Value>>, world: &World) -> BrpResult {
+    let Some(timings) = world.get_resource::<SystemTimings>() else {
+        return Err(BrpError::resource_error(
+            "System timings aren't being recorded. Insert the `SystemTimings` resource to record them",
+        ));
+    };
+
+    let mut schedules = timings
+        .iter()
+        .map(|(label, schedule)| {
+            let mut systems = schedule
+                .systems()
+                .map(|(key, system)| BrpSystemTimings {
+                    node_id: key.data().as_ffi(),
+                    name: system.name.to_string(),
+                    runs: system.runs,
+                    total_secs: system.total.as_secs_f64(),
+                    mean_secs: system.mean().as_secs_f64(),
+                    last_secs: system.last.as_secs_f64(),
+                })
+                .collect::<Vec<_>>();
+            systems.sort_by(|a, b| b.total_secs.total_cmp(&a.total_secs));
+            BrpScheduleTimings {
+                schedule_label: format!("{label:?}"),
+                runs: schedule.runs,
+                total_secs: schedule.total.as_secs_f64(),
+                mean_secs: schedule.mean().as_secs_f64(),
+                last_secs: schedule.last.as_secs_f64(),
+                systems,
+            }
+        })
+        .collect::<Vec<_>>();
+    schedules.sort_by(|a, b| b.total_secs.total_cmp(&a.total_secs));
+
+    serde_json::to_value(BrpScheduleTimingsResponse { schedules }).map_err(BrpError::internal)
+}
+
+/// Handles a `stepping.enable` request coming from a client.
+///
+/// This inserts the [`Stepping`] resource if needed. Changes to stepping are applied at the
+/// start of the next frame.
+pub fn process_remote_stepping_enable_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let BrpSteppingEnableParams { schedule_labels } = match params {
+        Some(params) => parse(params)?,
+        None => BrpSteppingEnableParams::default(),
+    };
+    let labels = schedule_labels
+        .iter()
+        .map(|schedule_label| find_schedule_label(world, schedule_label))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stepping = world.get_resource_or_init::<Stepping>();
+    for label in labels {
+        stepping.add_schedule(label);
+    }
+    stepping.enable();
+    Ok(Value::Null)
+}
+
+/// Handles a `stepping.disable` request coming from a client.
+pub fn process_remote_stepping_disable_request(
+    In(_params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    get_stepping_mut(world)?.disable();
+    Ok(Value::Null)
+}
+
+/// Handles a `stepping.list` request coming from a client.
+pub fn process_remote_stepping_list_request(
+    In(_params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let stepping = world
+        .get_resource::<Stepping>()
+        .ok_or_else(stepping_not_present)?;
+    let enabled = stepping.is_enabled();
+    let labels = stepping
+        .schedules()
+        .map_err(BrpError::resource_error)?
+        .clone();
+
+    let schedules = labels
+        .into_iter()
+        .map(|label| {
+            Ok(BrpSteppingSchedule {
+                schedule_label: format!("{label:?}"),
+                systems: stepping_systems(world, label)?
+                    .into_iter()
+                    .map(|(_, system)| system)
+                    .collect(),
+            })
+        })
+        .collect::<Result<Vec<_>, BrpError>>()?;
+
+    serde_json::to_value(BrpSteppingListResponse { enabled, schedules }).map_err(BrpError::internal)
+}
+
+/// Handles a `stepping.set_breakpoint` request coming from a client.
+pub fn process_remote_stepping_set_breakpoint_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let (label, node) = parse_stepping_node(world, params)?;
+    get_stepping_mut(world)?.set_breakpoint_node(label, node);
+    Ok(Value::Null)
+}
+
+/// Handles a `stepping.clear_breakpoint` request coming from a client.
+pub fn process_remote_stepping_clear_breakpoint_request(
+    In(params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let (label, node) = parse_stepping_node(world, params)?;
+    get_stepping_mut(world)?.clear_breakpoint_node(label, node);
+    Ok(Value::Null)
+}
+
+/// Handles a `stepping.step_system` request coming from a client.
+///
+/// This runs the next system during the next frame, see [`Stepping::step_frame`].
+pub fn process_remote_stepping_step_system_request(
+    In(_params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    get_stepping_mut(world)?.step_frame();
+    Ok(Value::Null)
+}
+
+/// Handles a `stepping.step_frame` request coming from a client.
+///
+/// This runs the remaining systems of the stepping frame during the next frame, stopping at
+/// breakpoints. See [`Stepping::continue_frame`].
+pub fn process_remote_stepping_step_frame_request(
+    In(_params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    get_stepping_mut(world)?.continue_frame();
+    Ok(Value::Null)
+}
+
+/// Handles a `stepping.cursor` request coming from a client.
+///
+/// The server responds with a [`BrpSteppingCursorResponse`], or a null if stepping isn't paused
+/// on a system.
+pub fn process_remote_stepping_cursor_request(
+    In(_params): In<Option<Value>>,
+    world: &mut World,
+) -> BrpResult {
+    let cursor = world
+        .get_resource::<Stepping>()
+        .ok_or_else(stepping_not_present)?
+        .cursor();
+    let Some((label, NodeId::System(key))) = cursor else {
+        return Ok(Value::Null);
+    };
+
+    let system = stepping_systems(world, label)?
+        .into_iter()
+        .find_map(|(system_key, system)| (system_key == key).then_some(system))
+        .unwrap_or_else(|| BrpSteppingSystem {
+            node_id: key.data().as_ffi(),
+            name: String::new(),
+        });
+    serde_json::to_value(BrpSteppingCursorResponse {
+        schedule_label: format!("{label:?}"),
+        system,
+    })
+    .map_err(BrpError::internal)
+}
+
+fn stepping_not_present() -> BrpError {
+    BrpError::resource_error("Stepping hasn't been enabled")
+}
+
+fn get_stepping_mut(world: &mut World) -> Result<Mut<'_, Stepping>, BrpError> {
+    world
+        .get_resource_mut::<Stepping>()
+        .ok_or_else(stepping_not_present)
+}
+
+/// Finds the label of a schedule from its debug representation, as returned by
+/// `schedule.list`.
+fn find_schedule_label(
+    world: &World,
+    schedule_label: &str,
+) -> Result<InternedScheduleLabel, BrpError> {
+    let schedules = world.resource::<Schedules>();
+    schedules
+        .iter()
+        .map(|(_, schedule)| schedule.label())
+        .chain(schedules.get_temporarily_removed())
+        .find(|label| format!("{label:?}") == schedule_label)
+        .ok_or_else(|| {
+            BrpError::resource_error(format!("Schedule with label={schedule_label} not found"))
+        })
+}
+
+/// Returns the systems of a schedule, initializing it if needed. Returns an empty list if the
+/// schedule is running.
+fn stepping_systems(
+    world: &mut World,
+    label: InternedScheduleLabel,
+) -> Result<Vec<(SystemKey, BrpSteppingSystem)>, BrpError> {
+    if !world.resource::<Schedules>().contains(label) {
+        return Ok(Vec::new());
+    }
+    world
+        .schedule_scope(label, |world, schedule| schedule.initialize(world))
+        .map_err(|err| {
+            BrpError::internal(format!(
+                "Failed to initialize schedule with label={label:?}: {err}"
+            ))
+        })?;
+    let schedule = world.resource::<Schedules>().get(label).unwrap();
+    let systems = schedule.systems().map_err(BrpError::internal)?;
+    Ok(systems
+        .map(|(key, system)| {
+            (
+                key,
+                BrpSteppingSystem {
+                    node_id: key.data().as_ffi(),
+                    name: system.name().to_string(),
+                },
+            )
+        })
+        .collect())
+}
+
+/// Parses [`BrpSteppingBreakpointParams`], checking that the system exists.
+fn parse_stepping_node(
+    world: &mut World,
+    params: Option<Value>,
+) -> Result<(InternedScheduleLabel, NodeId), BrpError> {
+    let BrpSteppingBreakpointParams {
+        schedule_label,
+        node_id,
+    } = parse_some(params)?;
+    let label = find_schedule_label(world, &schedule_label)?;
+    let key = SystemKey::from(KeyData::from_ffi(node_id));
+    if !stepping_systems(world, label)?
+        .iter()
+        .any(|(system_key, _)| *system_key == key)
+    {
+        return Err(BrpError::resource_error(format!(
+            "System with node_id={node_id} not found in schedule with label={schedule_label}"
+        )));
+    }
+    Ok((label, NodeId::System(key)))
+}
+
 /// Immutably retrieves an entity from the [`World`], returning an error if the
 /// entity isn't present.
 fn get_entity(world: &World, entity: Entity) -> Result<EntityRef<'_>, BrpError> {
@@ -2487,4 +2874,116 @@ mod tests {
             .dependency
             .contains(&(apply_deferred_index, f2_index)));
     }
+
+    #[test]
+    fn schedule_timings_over_brp() {
+        #[derive(ScheduleLabel, Hash, Clone, PartialEq, Eq, Debug)]
+        struct MySchedule;
+
+        fn my_system() {}
+
+        let mut world = World::default();
+        let mut schedule = Schedule::new(MySchedule);
+        schedule.add_systems(my_system);
+        world.add_schedule(schedule);
+
+        assert!(schedule_timings(In(None), &world).is_err());
+
+        world.init_resource::<SystemTimings>();
+        world.run_schedule(MySchedule);
+        world.run_schedule(MySchedule);
+
+        let response = schedule_timings(In(None), &world).unwrap();
+        let response = serde_json::from_value::<BrpScheduleTimingsResponse>(response).unwrap();
+        assert_eq!(response.schedules.len(), 1);
+        let schedule = &response.schedules[0];
+        assert_eq!(schedule.schedule_label, "MySchedule");
+        assert_eq!(schedule.runs, 2);
+        assert_eq!(schedule.systems.len(), 1);
+        assert!(schedule.systems[0].name.ends_with("my_system"));
+        assert_eq!(schedule.systems[0].runs, 2);
+    }
+
+    #[test]
+    fn stepping_over_brp() {
+        #[derive(ScheduleLabel, Hash, Clone, PartialEq, Eq, Debug)]
+        struct MySchedule;
+
+        #[derive(Resource, Default)]
+        struct Counter(u32);
+
+        fn first(mut counter: ResMut<Counter>) {
+            counter.0 += 1;
+        }
+        fn second(mut counter: ResMut<Counter>) {
+            counter.0 += 10;
+        }
+
+        let mut world = World::default();
+        world.init_resource::<Counter>();
+        let mut schedule = Schedule::new(MySchedule);
+        schedule.add_systems((first, second).chain());
+        world.add_schedule(schedule);
+
+        let run_frame = |world: &mut World| {
+            world.run_system_cached(Stepping::begin_frame).unwrap();
+            world.run_schedule(MySchedule);
+            world.resource::<Counter>().0
+        };
+        let cursor = |world: &mut World| {
+            let response = process_remote_stepping_cursor_request(In(None), world).unwrap();
+            serde_json::from_value::<Option<BrpSteppingCursorResponse>>(response)
+                .unwrap()
+                .map(|cursor| cursor.system.name)
+        };
+
+        let params = serde_json::to_value(BrpSteppingEnableParams {
+            schedule_labels: vec!["MySchedule".to_string()],
+        })
+        .unwrap();
+        process_remote_stepping_enable_request(In(Some(params)), &mut world).unwrap();
+        assert_eq!(run_frame(&mut world), 0);
+
+        let response = process_remote_stepping_list_request(In(None), &mut world).unwrap();
+        let list = serde_json::from_value::<BrpSteppingListResponse>(response).unwrap();
+        assert!(list.enabled);
+        assert_eq!(list.schedules.len(), 1);
+        assert_eq!(list.schedules[0].schedule_label, "MySchedule");
+        let systems = &list.schedules[0].systems;
+        assert_eq!(systems.len(), 2);
+        assert!(cursor(&mut world).unwrap().ends_with("first"));
+
+        // Step a single system.
+        process_remote_stepping_step_system_request(In(None), &mut world).unwrap();
+        assert_eq!(run_frame(&mut world), 1);
+        assert!(cursor(&mut world).unwrap().ends_with("second"));
+
+        // Continue the frame, and stop at the breakpoint on `second` in the next one.
+        let breakpoint = serde_json::to_value(BrpSteppingBreakpointParams {
+            schedule_label: "MySchedule".to_string(),
+            node_id: systems[1].node_id,
+        })
+        .unwrap();
+        process_remote_stepping_set_breakpoint_request(In(Some(breakpoint.clone())), &mut world)
+            .unwrap();
+        process_remote_stepping_step_frame_request(In(None), &mut world).unwrap();
+        assert_eq!(run_frame(&mut world), 11);
+        process_remote_stepping_step_frame_request(In(None), &mut world).unwrap();
+        assert_eq!(run_frame(&mut world), 12);
+        assert!(cursor(&mut world).unwrap().ends_with("second"));
+
+        process_remote_stepping_clear_breakpoint_request(In(Some(breakpoint)), &mut world).unwrap();
+        let invalid = serde_json::to_value(BrpSteppingBreakpointParams {
+            schedule_label: "MySchedule".to_string(),
+            node_id: 0,
+        })
+        .unwrap();
+        assert!(
+            process_remote_stepping_set_breakpoint_request(In(Some(invalid)), &mut world).is_err()
+        );
+
+        process_remote_stepping_disable_request(In(None), &mut world).unwrap();
+        assert_eq!(run_frame(&mut world), 23);
+        assert_eq!(cursor(&mut world), None);
+    }
 }
@@ -0,0 +1,329 @@
+//! Shared logic for BRP transports that exchange individual JSON-RPC messages over a single
+//! long-lived, bi-directional connection, like [`websocket`](crate::websocket) and
+//! [`stdio`](crate::stdio).
+//!
+//! Unlike HTTP, where each request gets its own response body, every response sent over such a
+//! connection is a standalone message, and responses to different requests may be interleaved.
+//! Clients match responses to their requests using the request `id`. Watching requests keep
+//! sending a response with the same `id` every time the watched data changes.
+
+#![cfg(not(target_family = "wasm"))]
+
+use crate::{error_codes, BrpBatch, BrpError, BrpMessage, BrpRequest, BrpResponse};
+use async_channel::Sender;
+use bevy_tasks::{IoTaskPool, Task};
+use serde_json::Value;
+
+/// The number of serialized responses that can be queued on a connection before the requests
+/// producing them wait for the client to catch up.
+pub(crate) const RESPONSE_CHANNEL_CAPACITY: usize = 64;
+
+/// A single connection of a message-based BRP transport.
+///
+/// Requests handled by the connection are processed concurrently. Dropping the connection
+/// cancels all of its ongoing requests, which also ends any watching requests it started.
+pub(crate) struct BrpConnection {
+    /// The channel requests are forwarded to, usually the [`BrpSender`](crate::BrpSender).
+    request_sender: Sender<BrpMessage>,
+    /// The channel serialized responses are sent to, to be written to the connection.
+    response_sender: Sender<String>,
+    /// The requests that are still being processed.
+    tasks: Vec<Task<()>>,
+}
+
+impl BrpConnection {
+    /// Creates a new connection forwarding requests to `request_sender`, and sending serialized
+    /// responses to `response_sender`.
+    ///
+    /// The response channel should be bounded, usually by [`RESPONSE_CHANNEL_CAPACITY`], so that
+    /// a slow client makes its requests wait instead of queuing responses without limit.
+    pub(crate) fn new(request_sender: Sender<BrpMessage>, response_sender: Sender<String>) -> Self {
+        Self {
+            request_sender,
+            response_sender,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Handles a single message received on the connection, which may contain a request or a
+    /// batch of requests.
+    pub(crate) fn handle_message(&mut self, message: &[u8]) {
+        self.tasks.retain(|task| !task.is_finished());
+
+        let request_sender = self.request_sender.clone();
+        let response_sender = self.response_sender.clone();
+        let task = match serde_json::from_slice::<BrpBatch>(message) {
+            Ok(BrpBatch::Single(request)) => IoTaskPool::get().spawn(async move {
+                process_single_request(request, &request_sender, &response_sender).await;
+            }),
+            Ok(BrpBatch::Batch(requests)) => IoTaskPool::get().spawn(async move {
+                process_request_batch(requests, &request_sender, &response_sender).await;
+            }),
+            Err(err) => {
+                let response = BrpResponse::new(
+                    None,
+                    Err(BrpError {
+                        code: error_codes::PARSE_ERROR,
+                        message: err.to_string(),
+                        data: None,
+                    }),
+                );
+                IoTaskPool::get().spawn(async move {
+                    send_response(&response, &response_sender).await;
+                })
+            }
+        };
+        self.tasks.push(task);
+    }
+}
+
+/// Processes a single request, sending one response, or a response for every update if it is a
+/// watching request.
+async fn process_single_request(
+    request: Value,
+    request_sender: &Sender<BrpMessage>,
+    response_sender: &Sender<String>,
+) {
+    let request = match parse_request(request) {
+        Ok(request) => request,
+        Err(response) => {
+            send_response(&response, response_sender).await;
+            return;
+        }
+    };
+
+    let watch = request.method.contains("+watch");
+    let size = if watch { 8 } else { 1 };
+    let (result_sender, result_receiver) = async_channel::bounded(size);
+
+    let id = request.id;
+    let _ = request_sender
+        .send(BrpMessage {
+            method: request.method,
+            params: request.params,
+            sender: result_sender,
+        })
+        .await;
+
+    if watch {
+        // Watching requests are only ended by the client disconnecting, which drops this task
+        // (and with it, the receiver).
+        while let Ok(result) = result_receiver.recv().await {
+            if !send_response(&BrpResponse::new(id.clone(), result), response_sender).await {
+                return;
+            }
+        }
+    } else if let Ok(result) = result_receiver.recv().await {
+        send_response(&BrpResponse::new(id, result), response_sender).await;
+    }
+}
+
+/// Processes a batch of requests, sending all of their responses as a single message.
+async fn process_request_batch(
+    requests: Vec<Value>,
+    request_sender: &Sender<BrpMessage>,
+    response_sender: &Sender<String>,
+) {
+    let mut responses = Vec::new();
+
+    for request in requests {
+        let request = match parse_request(request) {
+            Ok(request) => request,
+            Err(response) => {
+                responses.push(response);
+                continue;
+            }
+        };
+
+        if request.method.contains("+watch") {
+            responses.push(BrpResponse::new(
+                request.id,
+                Err(BrpError {
+                    code: error_codes::INVALID_REQUEST,
+                    message: "Streaming can not be used in batch requests".to_string(),
+                    data: None,
+                }),
+            ));
+            continue;
+        }
+
+        let (result_sender, result_receiver) = async_channel::bounded(1);
+        let _ = request_sender
+            .send(BrpMessage {
+                method: request.method,
+                params: request.params,
+                sender: result_sender,
+            })
+            .await;
+        let result = result_receiver.recv().await.unwrap_or_else(|err| {
+            Err(BrpError {
+                code: error_codes::INTERNAL_ERROR,
+                message: err.to_string(),
+                data: None,
+            })
+        });
+        responses.push(BrpResponse::new(request.id, result));
+    }
+
+    send_response(&responses, response_sender).await;
+}
+
+/// Parses a [`BrpRequest`], returning the error response to send if it's invalid.
+fn parse_request(request: Value) -> Result<BrpRequest, BrpResponse> {
+    // Reach in and get the request ID early so that we can report it even when parsing fails.
+    let id = request.as_object().and_then(|map| map.get("id")).cloned();
+
+    serde_json::from_value(request).map_err(|err| {
+        BrpResponse::new(
+            id,
+            Err(BrpError {
+                code: error_codes::INVALID_REQUEST,
+                message: err.to_string(),
+                data: None,
+            }),
+        )
+    })
+}
+
+/// Serializes `response` and queues it to be written to the connection, waiting for room in the
+/// queue if the client is reading slower than responses are produced.
+///
+/// Returns `false` if the connection has been closed.
+async fn send_response(response: &impl serde::Serialize, response_sender: &Sender<String>) -> bool {
+    let serialized = serde_json::to_string(response).expect("BRP responses are always valid JSON");
+    response_sender.send(serialized).await.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BrpConnection, RESPONSE_CHANNEL_CAPACITY};
+    use crate::{error_codes, BrpError, BrpMessage};
+    use async_channel::Receiver;
+    use bevy_tasks::{
+        tick_global_task_pools_on_main_thread, AsyncComputeTaskPool, ComputeTaskPool, IoTaskPool,
+        TaskPool,
+    };
+    use serde_json::{json, Value};
+
+    /// Creates a connection, returning it along with the receivers for its requests and
+    /// responses.
+    fn connect() -> (BrpConnection, Receiver<BrpMessage>, Receiver<String>) {
+        connect_with_capacity(RESPONSE_CHANNEL_CAPACITY)
+    }
+
+    fn connect_with_capacity(
+        capacity: usize,
+    ) -> (BrpConnection, Receiver<BrpMessage>, Receiver<String>) {
+        ComputeTaskPool::get_or_init(TaskPool::new);
+        AsyncComputeTaskPool::get_or_init(TaskPool::new);
+        IoTaskPool::get_or_init(TaskPool::new);
+
+        let (request_sender, request_receiver) = async_channel::unbounded();
+        let (response_sender, response_receiver) = async_channel::bounded(capacity);
+        (
+            BrpConnection::new(request_sender, response_sender),
+            request_receiver,
+            response_receiver,
+        )
+    }
+
+    /// Runs the task pools until `receiver` has a message.
+    fn recv<T>(receiver: &Receiver<T>) -> T {
+        loop {
+            if let Ok(message) = receiver.try_recv() {
+                return message;
+            }
+            tick_global_task_pools_on_main_thread();
+        }
+    }
+
+    fn recv_response(response_receiver: &Receiver<String>) -> Value {
+        serde_json::from_str(&recv(response_receiver)).unwrap()
+    }
+
+    #[test]
+    fn responses_are_matched_by_id() {
+        let (mut connection, request_receiver, response_receiver) = connect();
+
+        connection.handle_message(br#"{"jsonrpc": "2.0", "method": "first", "id": 1}"#);
+        let first = recv(&request_receiver);
+        connection.handle_message(br#"{"jsonrpc": "2.0", "method": "second", "id": 2}"#);
+        let second = recv(&request_receiver);
+        assert_eq!(first.method, "first");
+        assert_eq!(second.method, "second");
+
+        // Answer out of order: the second request shouldn't wait for the first.
+        second.sender.try_send(Ok(json!("b"))).unwrap();
+        assert_eq!(
+            recv_response(&response_receiver),
+            json!({ "jsonrpc": "2.0", "id": 2, "result": "b" })
+        );
+        first
+            .sender
+            .try_send(Err(BrpError::internal("failed")))
+            .unwrap();
+        assert_eq!(
+            recv_response(&response_receiver)["error"]["message"],
+            json!("failed")
+        );
+    }
+
+    #[test]
+    fn watching_requests_send_every_update_until_dropped() {
+        let (mut connection, request_receiver, response_receiver) = connect();
+
+        connection.handle_message(br#"{"jsonrpc": "2.0", "method": "watched+watch", "id": 7}"#);
+        let watch = recv(&request_receiver);
+        for update in 0..3 {
+            watch.sender.try_send(Ok(json!(update))).unwrap();
+            assert_eq!(
+                recv_response(&response_receiver),
+                json!({ "jsonrpc": "2.0", "id": 7, "result": update })
+            );
+        }
+
+        // Dropping the connection ends the watching request.
+        drop(connection);
+        while !watch.sender.is_closed() {
+            tick_global_task_pools_on_main_thread();
+        }
+    }
+
+    #[test]
+    fn invalid_messages_get_error_responses() {
+        let (mut connection, _request_receiver, response_receiver) = connect();
+
+        connection.handle_message(b"not json");
+        assert_eq!(
+            recv_response(&response_receiver)["error"]["code"],
+            json!(error_codes::PARSE_ERROR)
+        );
+
+        connection.handle_message(br#"[{"jsonrpc": "2.0", "method": "a+watch", "id": 3}]"#);
+        let response = recv_response(&response_receiver);
+        assert_eq!(response[0]["id"], json!(3));
+        assert_eq!(
+            response[0]["error"]["code"],
+            json!(error_codes::INVALID_REQUEST)
+        );
+    }
+
+    #[test]
+    fn responses_wait_for_room_in_a_full_channel() {
+        let (mut connection, request_receiver, response_receiver) = connect_with_capacity(1);
+
+        connection.handle_message(br#"{"jsonrpc": "2.0", "method": "watched+watch", "id": 1}"#);
+        let watch = recv(&request_receiver);
+        watch.sender.try_send(Ok(json!(0))).unwrap();
+        watch.sender.try_send(Ok(json!(1))).unwrap();
+        for _ in 0..100 {
+            tick_global_task_pools_on_main_thread();
+        }
+        // Only one response fits, the other one waits instead of being dropped.
+        assert_eq!(response_receiver.len(), 1);
+
+        for update in 0..2 {
+            assert_eq!(recv_response(&response_receiver)["result"], json!(update));
+        }
+    }
+}
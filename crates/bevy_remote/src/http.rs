@@ -14,6 +14,7 @@
 use crate::setup_mailbox_channel;
 use crate::{
     error_codes, BrpBatch, BrpError, BrpMessage, BrpRequest, BrpResponse, BrpResult, BrpSender,
+    RemoteAuth,
 };
 use anyhow::Result as AnyhowResult;
 use async_channel::{Receiver, Sender};
@@ -37,7 +38,7 @@ use hyper::{
     body::{Body, Bytes, Frame, Incoming},
     header::{HeaderName, HeaderValue},
     server::conn::http1,
-    service, Request, Response,
+    service, Request, Response, StatusCode,
 };
 use serde_json::Value;
 use smol_hyper::rt::{FuturesIo, SmolTimer};
@@ -109,6 +110,7 @@ impl Default for Headers {
 /// - [`DEFAULT_ADDR`] : 127.0.0.1.
 /// - [`DEFAULT_PORT`] : 15702.
 /// - [`DEFAULT_RENDER_PORT`] : 15703. (when `bevy_render` is enabled)
+/// - No authentication. See [`RemoteHttpPlugin::with_auth`].
 ///
 pub struct RemoteHttpPlugin {
     /// The address that Bevy will bind to.
@@ -119,6 +121,8 @@ pub struct RemoteHttpPlugin {
     render_port: u16,
     /// The headers that Bevy will include in its HTTP responses
     headers: Headers,
+    /// The credentials clients must present, if any.
+    auth: Option<RemoteAuth>,
 }
 
 impl Default for RemoteHttpPlugin {
@@ -128,6 +132,7 @@ impl Default for RemoteHttpPlugin {
             port: DEFAULT_PORT,
             render_port: DEFAULT_RENDER_PORT,
             headers: Headers::new(),
+            auth: None,
         }
     }
 }
@@ -137,6 +142,7 @@ impl Plugin for RemoteHttpPlugin {
         app.insert_resource(HostAddress(self.address))
             .insert_resource(HostPort(self.port))
             .insert_resource(HostHeaders(self.headers.clone()))
+            .insert_resource(HostAuth(self.auth.clone()))
             .add_systems(Startup, start_http_server);
 
         #[cfg(feature = "bevy_render")]
@@ -151,6 +157,7 @@ impl Plugin for RemoteHttpPlugin {
                 .insert_resource(HostAddress(self.address))
                 .insert_resource(HostPort(self.render_port))
                 .insert_resource(HostHeaders(self.headers.clone()))
+                .insert_resource(HostAuth(self.auth.clone()))
                 .add_systems(
                     RenderStartup,
                     start_http_server
@@ -210,6 +217,14 @@ impl RemoteHttpPlugin {
         self.headers = self.headers.insert(name, value);
         self
     }
+    /// Require clients to present the given credentials with every request.
+    ///
+    /// Requests without them are rejected with a `401 Unauthorized` status.
+    #[must_use]
+    pub fn with_auth(mut self, auth: RemoteAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
 }
 
 /// A resource containing the IP address that Bevy will host on.
@@ -231,12 +246,17 @@ pub struct HostPort(pub u16);
 #[derive(Debug, Resource)]
 struct HostHeaders(pub Headers);
 
+/// A resource containing the credentials clients must present, if any.
+#[derive(Debug, Resource)]
+struct HostAuth(Option<RemoteAuth>);
+
 /// A system that starts up the Bevy Remote Protocol HTTP server.
 fn start_http_server(
     request_sender: Res<BrpSender>,
     address: Res<HostAddress>,
     remote_port: Res<HostPort>,
     headers: Res<HostHeaders>,
+    auth: Res<HostAuth>,
 ) {
     IoTaskPool::get()
         .spawn(server_main(
@@ -244,6 +264,7 @@ fn start_http_server(
             remote_port.0,
             request_sender.clone(),
             headers.0.clone(),
+            auth.0.clone(),
         ))
         .detach();
 }
@@ -254,11 +275,13 @@ async fn server_main(
     port: u16,
     request_sender: Sender<BrpMessage>,
     headers: Headers,
+    auth: Option<RemoteAuth>,
 ) -> AnyhowResult<()> {
     listen(
         Async::<TcpListener>::bind((address, port))?,
         &request_sender,
         &headers,
+        &auth,
     )
     .await
 }
@@ -267,15 +290,17 @@ async fn listen(
     listener: Async<TcpListener>,
     request_sender: &Sender<BrpMessage>,
     headers: &Headers,
+    auth: &Option<RemoteAuth>,
 ) -> AnyhowResult<()> {
     loop {
         let (client, _) = listener.accept().await?;
 
         let request_sender = request_sender.clone();
         let headers = headers.clone();
+        let auth = auth.clone();
         IoTaskPool::get()
             .spawn(async move {
-                let _ = handle_client(client, request_sender, headers).await;
+                let _ = handle_client(client, request_sender, headers, auth).await;
             })
             .detach();
     }
@@ -285,13 +310,14 @@ async fn handle_client(
     client: Async<TcpStream>,
     request_sender: Sender<BrpMessage>,
     headers: Headers,
+    auth: Option<RemoteAuth>,
 ) -> AnyhowResult<()> {
     http1::Builder::new()
         .timer(SmolTimer::new())
         .serve_connection(
             FuturesIo::new(client),
             service::service_fn(|request| {
-                process_request_batch(request, &request_sender, &headers)
+                process_request_batch(request, &request_sender, &headers, auth.as_ref())
             }),
         )
         .await?;
@@ -305,7 +331,22 @@ async fn process_request_batch(
     request: Request<Incoming>,
     request_sender: &Sender<BrpMessage>,
     headers: &Headers,
+    auth: Option<&RemoteAuth>,
 ) -> AnyhowResult<Response<BrpHttpBody>> {
+    if let Some(auth) = auth
+        && !auth.verify(
+            request
+                .headers()
+                .get(auth.header_name())
+                .map(HeaderValue::as_bytes),
+        )
+    {
+        let err = BrpResponse::new(None, Err(BrpError::unauthorized()));
+        let mut response = complete_response(serde_json::to_string(&err)?, headers);
+        *response.status_mut() = StatusCode::UNAUTHORIZED;
+        return Ok(response);
+    }
+
     let batch_bytes = request.into_body().collect().await?.to_bytes();
     let batch: Result<BrpBatch, _> = serde_json::from_slice(&batch_bytes);
 
@@ -355,30 +396,34 @@ async fn process_request_batch(
         }
     };
 
-    let mut response = match result {
-        BrpHttpResponse::Complete(serialized) => {
-            let mut response = Response::new(BrpHttpBody::Complete(Full::new(Bytes::from(
-                serialized.as_bytes().to_owned(),
-            ))));
-            response.headers_mut().insert(
-                hyper::header::CONTENT_TYPE,
-                HeaderValue::from_static("application/json"),
-            );
-            response
-        }
+    let response = match result {
+        BrpHttpResponse::Complete(serialized) => complete_response(serialized, headers),
         BrpHttpResponse::Stream(stream) => {
             let mut response = Response::new(BrpHttpBody::Stream(stream));
             response.headers_mut().insert(
                 hyper::header::CONTENT_TYPE,
                 HeaderValue::from_static("text/event-stream"),
             );
+            for (key, value) in &headers.headers {
+                response.headers_mut().insert(key, value.clone());
+            }
             response
         }
     };
+    Ok(response)
+}
+
+/// Creates a JSON response with the given serialized body.
+fn complete_response(serialized: String, headers: &Headers) -> Response<BrpHttpBody> {
+    let mut response = Response::new(BrpHttpBody::Complete(Full::new(Bytes::from(serialized))));
+    response.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
     for (key, value) in &headers.headers {
         response.headers_mut().insert(key, value.clone());
     }
-    Ok(response)
+    response
 }
 
 /// A helper function for the Bevy Remote Protocol server that processes a single
@@ -6,6 +6,12 @@
 //! over HTTP. These *remote clients* can inspect and alter the state of the
 //! entity-component system.
 //!
+//! Other transports are also available:
+//! - `RemoteWebSocketPlugin` (with the `websocket` feature) accepts WebSocket connections, which
+//!   can carry many concurrent requests and receive watching updates as separate messages.
+//! - `RemoteStdioPlugin` (with the `stdio` feature) reads requests from `stdin` and writes
+//!   responses to `stdout`, so a tool can launch the app as a child process and control it.
+//!
 //! The Bevy Remote Protocol is based on the JSON-RPC 2.0 protocol.
 //!
 //! ## Request objects
@@ -478,6 +484,93 @@
 //! This contains schema information about that type, including field definitions, type information, reflect type information, and other metadata
 //! helpful for understanding the structure of the type.
 //!
+//! ### `schedule.timings`
+//!
+//! Get how long schedules and their systems took to run, as recorded in the
+//! [`SystemTimings`](bevy_ecs::schedule::SystemTimings) resource. Recording must have been enabled
+//! by inserting this resource, for example with the `SystemTimingDiagnosticsPlugin` of
+//! `bevy_diagnostic`. This method has no parameters.
+//!
+//! `result`:
+//! - `schedules`: An array of the schedules that ran since recording started, slowest first, each
+//!   with:
+//!   - `schedule_label`: The label of the schedule.
+//!   - `runs`: The number of times the schedule ran.
+//!   - `total_secs`, `mean_secs` and `last_secs`: The total, average and last duration of a run
+//!     of the schedule, in seconds.
+//!   - `systems`: An array of the systems of the schedule, slowest first, each with its `node_id`,
+//!     `name`, `runs`, `total_secs`, `mean_secs` and `last_secs`.
+//!
+//! ### `stepping.enable`
+//!
+//! Enable [system stepping](bevy_ecs::schedule::Stepping), pausing the schedules added to
+//! stepping at the start of the next frame. Bevy must be compiled with the
+//! `bevy_debug_stepping` feature.
+//!
+//! `params` (optional):
+//! - `schedule_labels`: An array of schedule labels, as returned by `schedule.list`, to add to
+//!   stepping.
+//!
+//! `result`: null.
+//!
+//! ### `stepping.disable`
+//!
+//! Disable system stepping, resuming normal execution. This method has no parameters.
+//!
+//! `result`: null.
+//!
+//! ### `stepping.list`
+//!
+//! List the schedules added to stepping and their systems. This method has no parameters.
+//!
+//! `result`:
+//! - `enabled`: Whether stepping is enabled.
+//! - `schedules`: An array of the schedules added to stepping, in the order they run, each with:
+//!   - `schedule_label`: The label of the schedule.
+//!   - `systems`: An array of the systems of the schedule, in the order they run, each with its
+//!     `node_id` and `name`.
+//!
+//! ### `stepping.set_breakpoint`
+//!
+//! Set a breakpoint on a system, pausing stepping before it runs.
+//!
+//! `params`:
+//! - `schedule_label`: The label of the schedule containing the system.
+//! - `node_id`: The node id of the system, as returned by `stepping.list`.
+//!
+//! `result`: null.
+//!
+//! ### `stepping.clear_breakpoint`
+//!
+//! Clear a breakpoint on a system.
+//!
+//! `params`:
+//! - `schedule_label`: The label of the schedule containing the system.
+//! - `node_id`: The node id of the system, as returned by `stepping.list`.
+//!
+//! `result`: null.
+//!
+//! ### `stepping.step_system`
+//!
+//! Run the next system while stepping. This method has no parameters.
+//!
+//! `result`: null.
+//!
+//! ### `stepping.step_frame`
+//!
+//! Run the remaining systems of the current stepping frame, stopping at breakpoints. This method
+//! has no parameters.
+//!
+//! `result`: null.
+//!
+//! ### `stepping.cursor`
+//!
+//! Get the next system to run while stepping. This method has no parameters.
+//!
+//! `result`: null if stepping isn't paused on a system, otherwise:
+//! - `schedule_label`: The label of the schedule containing the system.
+//! - `system`: The `node_id` and `name` of the system.
+//!
 //! ### `rpc.discover`
 //!
 //! Discover available remote methods and server information. This follows the [`OpenRPC` specification for service discovery](https://spec.open-rpc.org/#service-discovery-method).
@@ -548,7 +641,7 @@ use bevy_ecs::{
     system::{Commands, In, IntoSystem, ResMut, System, SystemId},
     world::World,
 };
-use bevy_platform::collections::HashMap;
+use bevy_platform::collections::{HashMap, HashSet};
 #[cfg(feature = "bevy_render")]
 use bevy_render::{Render, RenderApp, RenderScheduleOrder, RenderStartup};
 use bevy_utils::prelude::default;
@@ -557,9 +650,15 @@ use serde_json::Value;
 use std::sync::RwLock;
 
 pub mod builtin_methods;
+#[cfg(any(feature = "websocket", feature = "stdio"))]
+mod connection;
 #[cfg(feature = "http")]
 pub mod http;
 pub mod schemas;
+#[cfg(feature = "stdio")]
+pub mod stdio;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
 const CHANNEL_SIZE: usize = 16;
 
@@ -574,6 +673,8 @@ pub struct RemotePlugin {
     methods: RwLock<Vec<(String, RemoteMethodHandler)>>,
     /// The verbs that the server will recognize and respond to for the render subapp.
     render_methods: RwLock<Vec<(String, RemoteMethodHandler)>>,
+    /// Which of the methods clients are allowed to call.
+    access: RemoteMethodAccess,
 }
 
 impl RemotePlugin {
@@ -583,9 +684,21 @@ impl RemotePlugin {
         Self {
             methods: RwLock::new(vec![]),
             render_methods: RwLock::new(vec![]),
+            access: RemoteMethodAccess::default(),
         }
     }
 
+    /// Restrict which methods clients are allowed to call, in both the main app and the render
+    /// subapp.
+    ///
+    /// By default, all methods can be called. See [`RemoteMethodAccess::read_only`] for only
+    /// allowing the built-in methods that don't modify the world.
+    #[must_use]
+    pub fn with_method_access(mut self, access: RemoteMethodAccess) -> Self {
+        self.access = access;
+        self
+    }
+
     /// Add a remote method to the plugin using the given `name` and `handler` to main app.
     #[inline]
     pub fn with_method_main<M>(
@@ -785,6 +898,51 @@ impl RemotePlugin {
             builtin_methods::schedule_graph,
             to_main,
         )
+        .with_method(
+            builtin_methods::BRP_SCHEDULE_TIMINGS,
+            builtin_methods::schedule_timings,
+            to_main,
+        )
+        .with_method(
+            builtin_methods::BRP_STEPPING_ENABLE_METHOD,
+            builtin_methods::process_remote_stepping_enable_request,
+            to_main,
+        )
+        .with_method(
+            builtin_methods::BRP_STEPPING_DISABLE_METHOD,
+            builtin_methods::process_remote_stepping_disable_request,
+            to_main,
+        )
+        .with_method(
+            builtin_methods::BRP_STEPPING_LIST_METHOD,
+            builtin_methods::process_remote_stepping_list_request,
+            to_main,
+        )
+        .with_method(
+            builtin_methods::BRP_STEPPING_SET_BREAKPOINT_METHOD,
+            builtin_methods::process_remote_stepping_set_breakpoint_request,
+            to_main,
+        )
+        .with_method(
+            builtin_methods::BRP_STEPPING_CLEAR_BREAKPOINT_METHOD,
+            builtin_methods::process_remote_stepping_clear_breakpoint_request,
+            to_main,
+        )
+        .with_method(
+            builtin_methods::BRP_STEPPING_STEP_SYSTEM_METHOD,
+            builtin_methods::process_remote_stepping_step_system_request,
+            to_main,
+        )
+        .with_method(
+            builtin_methods::BRP_STEPPING_STEP_FRAME_METHOD,
+            builtin_methods::process_remote_stepping_step_frame_request,
+            to_main,
+        )
+        .with_method(
+            builtin_methods::BRP_STEPPING_CURSOR_METHOD,
+            builtin_methods::process_remote_stepping_cursor_request,
+            to_main,
+        )
     }
 }
 
@@ -805,6 +963,7 @@ impl Default for RemotePlugin {
 impl Plugin for RemotePlugin {
     fn build(&self, app: &mut App) {
         let mut remote_methods = RemoteMethods::new();
+        remote_methods.set_access(self.access.clone());
 
         let plugin_methods = &mut *self.methods.write().unwrap();
         for (name, handler) in plugin_methods.drain(..) {
@@ -822,7 +981,7 @@ impl Plugin for RemotePlugin {
         }
 
         if remote_methods
-            .0
+            .methods
             .contains_key(builtin_methods::BRP_SCHEDULE_GRAPH)
         {
             app.init_resource::<PreviousScheduleBuildMetadata>()
@@ -862,6 +1021,7 @@ impl Plugin for RemotePlugin {
             };
 
             let mut render_remote_methods = RemoteMethods::new();
+            render_remote_methods.set_access(self.access.clone());
 
             let render_plugin_methods = &mut *self.render_methods.write().unwrap();
             for (name, handler) in render_plugin_methods.drain(..) {
@@ -960,9 +1120,13 @@ pub enum RemoteMethodSystemId {
 
 /// Holds all implementations of methods known to the server.
 ///
-/// Custom methods can be added to this list using [`RemoteMethods::insert`].
+/// Custom methods can be added to this list using [`RemoteMethods::insert`]. Which of them
+/// clients are allowed to call is controlled by its [`RemoteMethodAccess`].
 #[derive(Debug, Resource, Default)]
-pub struct RemoteMethods(HashMap<String, RemoteMethodSystemId>);
+pub struct RemoteMethods {
+    methods: HashMap<String, RemoteMethodSystemId>,
+    access: RemoteMethodAccess,
+}
 
 impl RemoteMethods {
     /// Creates a new [`RemoteMethods`] resource with no methods registered in it.
@@ -978,20 +1142,210 @@ impl RemoteMethods {
         method_name: impl Into<String>,
         handler: RemoteMethodSystemId,
     ) -> Option<RemoteMethodSystemId> {
-        self.0.insert(method_name.into(), handler)
+        self.methods.insert(method_name.into(), handler)
     }
 
     /// Get a [`RemoteMethodSystemId`] with its method name.
+    ///
+    /// This returns the method even if clients aren't allowed to call it.
     pub fn get(&self, method: &str) -> Option<&RemoteMethodSystemId> {
-        self.0.get(method)
+        self.methods.get(method)
     }
 
     /// Get a [`Vec<String>`] with method names.
     pub fn methods(&self) -> Vec<String> {
-        self.0.keys().cloned().collect()
+        self.methods.keys().cloned().collect()
+    }
+
+    /// Returns which methods clients are allowed to call.
+    pub fn access(&self) -> &RemoteMethodAccess {
+        &self.access
+    }
+
+    /// Sets which methods clients are allowed to call.
+    pub fn set_access(&mut self, access: RemoteMethodAccess) {
+        self.access = access;
+    }
+
+    /// Returns whether clients are allowed to call the method with the given name.
+    pub fn is_allowed(&self, method: &str) -> bool {
+        self.access.is_allowed(method)
     }
 }
 
+/// Controls which [`RemoteMethods`] clients are allowed to call.
+///
+/// Calling a method that isn't allowed results in a [`BrpError`] with the
+/// [`METHOD_NOT_ALLOWED`](error_codes::METHOD_NOT_ALLOWED) code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RemoteMethodAccess {
+    /// All methods can be called.
+    #[default]
+    AllowAll,
+    /// Only the listed methods can be called.
+    Allow(HashSet<String>),
+    /// All methods except the listed ones can be called.
+    Deny(HashSet<String>),
+}
+
+impl RemoteMethodAccess {
+    /// Only allow calling the given methods.
+    pub fn allow(methods: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::Allow(methods.into_iter().map(Into::into).collect())
+    }
+
+    /// Allow calling all methods except the given ones.
+    pub fn deny(methods: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::Deny(methods.into_iter().map(Into::into).collect())
+    }
+
+    /// Only allow calling the built-in methods that don't modify the world, like `world.query`
+    /// and `world.get_components`.
+    ///
+    /// Custom methods can be added to the returned list with [`Self::with_allowed`].
+    pub fn read_only() -> Self {
+        Self::allow([
+            builtin_methods::BRP_GET_COMPONENTS_METHOD,
+            builtin_methods::BRP_QUERY_METHOD,
+            builtin_methods::BRP_LIST_COMPONENTS_METHOD,
+            builtin_methods::BRP_GET_COMPONENTS_AND_WATCH_METHOD,
+            builtin_methods::BRP_LIST_COMPONENTS_AND_WATCH_METHOD,
+            builtin_methods::BRP_GET_RESOURCE_METHOD,
+            builtin_methods::BRP_LIST_RESOURCES_METHOD,
+            builtin_methods::BRP_OBSERVE_METHOD,
+            builtin_methods::BRP_REGISTRY_SCHEMA_METHOD,
+            builtin_methods::BRP_SCHEDULE_LIST,
+            builtin_methods::BRP_SCHEDULE_GRAPH,
+            builtin_methods::BRP_SCHEDULE_TIMINGS,
+            builtin_methods::BRP_STEPPING_LIST_METHOD,
+            builtin_methods::BRP_STEPPING_CURSOR_METHOD,
+            builtin_methods::RPC_DISCOVER_METHOD,
+        ])
+    }
+
+    /// Allows calling `method`, whether it was previously allowed or denied.
+    #[must_use]
+    pub fn with_allowed(self, method: impl Into<String>) -> Self {
+        match self {
+            Self::AllowAll => Self::AllowAll,
+            Self::Allow(mut allowed) => {
+                allowed.insert(method.into());
+                Self::Allow(allowed)
+            }
+            Self::Deny(mut denied) => {
+                denied.remove(&method.into());
+                Self::Deny(denied)
+            }
+        }
+    }
+
+    /// Denies calling `method`, whether it was previously allowed or denied.
+    #[must_use]
+    pub fn with_denied(self, method: impl Into<String>) -> Self {
+        match self {
+            Self::AllowAll => Self::deny([method]),
+            Self::Allow(mut allowed) => {
+                allowed.remove(&method.into());
+                Self::Allow(allowed)
+            }
+            Self::Deny(mut denied) => {
+                denied.insert(method.into());
+                Self::Deny(denied)
+            }
+        }
+    }
+
+    /// Returns whether the method with the given name can be called.
+    pub fn is_allowed(&self, method: &str) -> bool {
+        match self {
+            Self::AllowAll => true,
+            Self::Allow(allowed) => allowed.contains(method),
+            Self::Deny(denied) => !denied.contains(method),
+        }
+    }
+}
+
+/// The credentials clients of a network transport must present before any of their requests are
+/// processed.
+///
+/// This is checked against the headers of each HTTP request, or of the WebSocket handshake.
+/// Requests with missing or invalid credentials are rejected with a [`BrpError`] with the
+/// [`UNAUTHORIZED`](error_codes::UNAUTHORIZED) code.
+///
+/// Note that credentials are sent in plain text, so this doesn't protect against anyone who can
+/// observe the traffic between the client and the app.
+#[derive(Clone, PartialEq, Eq)]
+pub enum RemoteAuth {
+    /// Clients must send an `Authorization: Bearer <token>` header.
+    BearerToken(String),
+    /// Clients must send the secret as the value of the header with the given name.
+    SharedSecret {
+        /// The name of the header holding the secret.
+        header: String,
+        /// The secret.
+        secret: String,
+    },
+}
+
+impl RemoteAuth {
+    /// The header [`RemoteAuth::shared_secret`] expects the secret in.
+    pub const DEFAULT_SECRET_HEADER: &str = "X-Bevy-Remote-Secret";
+
+    /// Require clients to send `token` as a bearer token.
+    pub fn bearer(token: impl Into<String>) -> Self {
+        Self::BearerToken(token.into())
+    }
+
+    /// Require clients to send `secret` in the [`Self::DEFAULT_SECRET_HEADER`] header.
+    pub fn shared_secret(secret: impl Into<String>) -> Self {
+        Self::SharedSecret {
+            header: Self::DEFAULT_SECRET_HEADER.to_string(),
+            secret: secret.into(),
+        }
+    }
+
+    /// The name of the header holding the credentials.
+    pub fn header_name(&self) -> &str {
+        match self {
+            Self::BearerToken(_) => "Authorization",
+            Self::SharedSecret { header, .. } => header,
+        }
+    }
+
+    /// Returns whether `value`, the value of the [`Self::header_name`] header sent by a client (if
+    /// any), holds the right credentials.
+    pub fn verify(&self, value: Option<&[u8]>) -> bool {
+        let Some(value) = value else {
+            return false;
+        };
+        match self {
+            Self::BearerToken(token) => value
+                .strip_prefix(b"Bearer ")
+                .is_some_and(|value| constant_time_eq(value, token.as_bytes())),
+            Self::SharedSecret { secret, .. } => constant_time_eq(value, secret.as_bytes()),
+        }
+    }
+}
+
+impl core::fmt::Debug for RemoteAuth {
+    // Don't leak the credentials into logs.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::BearerToken(_) => f.debug_tuple("BearerToken").finish_non_exhaustive(),
+            Self::SharedSecret { header, .. } => f
+                .debug_struct("SharedSecret")
+                .field("header", header)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Compares two byte strings in time that only depends on their lengths, so the credentials
+/// can't be guessed by timing the responses.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
 /// Holds the [`BrpMessage`]'s of all ongoing watching requests along with their handlers.
 #[derive(Debug, Resource, Default)]
 pub struct RemoteWatchingRequests(Vec<(BrpMessage, RemoteWatchingMethodSystemId)>);
@@ -1281,6 +1635,26 @@ impl BrpError {
             data: None,
         }
     }
+
+    /// The client didn't present valid credentials.
+    #[must_use]
+    pub fn unauthorized() -> Self {
+        Self {
+            code: error_codes::UNAUTHORIZED,
+            message: "Missing or invalid credentials".to_string(),
+            data: None,
+        }
+    }
+
+    /// The method exists, but clients aren't allowed to call it.
+    #[must_use]
+    pub fn method_not_allowed(method: &str) -> Self {
+        Self {
+            code: error_codes::METHOD_NOT_ALLOWED,
+            message: format!("Method `{method}` is not allowed"),
+            data: None,
+        }
+    }
 }
 
 /// Error codes used by BRP.
@@ -1305,6 +1679,12 @@ pub mod error_codes {
 
     // Bevy errors (i.e. application errors)
 
+    /// The client did not present valid credentials.
+    pub const UNAUTHORIZED: i16 = -23001;
+
+    /// The method exists, but clients are not allowed to call it.
+    pub const METHOD_NOT_ALLOWED: i16 = -23002;
+
     /// Entity not found.
     pub const ENTITY_NOT_FOUND: i16 = -23401;
 
@@ -1396,6 +1776,16 @@ fn process_remote_requests(world: &mut World) {
             return;
         };
 
+        if !world
+            .resource::<RemoteMethods>()
+            .is_allowed(&message.method)
+        {
+            let _ = message
+                .sender
+                .force_send(Err(BrpError::method_not_allowed(&message.method)));
+            continue;
+        }
+
         match handler {
             RemoteMethodSystemId::Instant(id) => {
                 let result = match world.run_system_with(id, message.params) {
@@ -1489,9 +1879,49 @@ fn cache_schedule_build_metadata(
 
 #[cfg(test)]
 mod tests {
-    use crate::BrpRequest;
+    use crate::{builtin_methods, BrpRequest, RemoteAuth, RemoteMethodAccess};
     use serde_json::json;
 
+    #[test]
+    fn read_only_access_rejects_mutating_methods() {
+        let access = RemoteMethodAccess::read_only();
+        assert!(access.is_allowed(builtin_methods::BRP_QUERY_METHOD));
+        assert!(access.is_allowed(builtin_methods::BRP_GET_COMPONENTS_AND_WATCH_METHOD));
+        assert!(!access.is_allowed(builtin_methods::BRP_DESPAWN_COMPONENTS_METHOD));
+        assert!(!access.is_allowed(builtin_methods::BRP_INSERT_RESOURCE_METHOD));
+        assert!(!access.is_allowed("custom.method"));
+
+        let access = access
+            .with_allowed("custom.method")
+            .with_denied(builtin_methods::BRP_QUERY_METHOD);
+        assert!(access.is_allowed("custom.method"));
+        assert!(!access.is_allowed(builtin_methods::BRP_QUERY_METHOD));
+    }
+
+    #[test]
+    fn deny_access_rejects_listed_methods() {
+        let access = RemoteMethodAccess::AllowAll.with_denied("world.despawn_entity");
+        assert_eq!(access, RemoteMethodAccess::deny(["world.despawn_entity"]));
+        assert!(!access.is_allowed("world.despawn_entity"));
+        assert!(access.is_allowed("world.query"));
+    }
+
+    #[test]
+    fn auth_verifies_header_value() {
+        let bearer = RemoteAuth::bearer("hunter2");
+        assert_eq!(bearer.header_name(), "Authorization");
+        assert!(bearer.verify(Some(b"Bearer hunter2")));
+        assert!(!bearer.verify(Some(b"Bearer hunter3")));
+        assert!(!bearer.verify(Some(b"hunter2")));
+        assert!(!bearer.verify(None));
+
+        let secret = RemoteAuth::shared_secret("hunter2");
+        assert_eq!(secret.header_name(), RemoteAuth::DEFAULT_SECRET_HEADER);
+        assert!(secret.verify(Some(b"hunter2")));
+        assert!(!secret.verify(Some(b"hunter")));
+        assert!(!format!("{secret:?}").contains("hunter2"));
+    }
+
     #[test]
     fn deserialize_brp_request_params_optional() {
         let request_json: &str = r#"{
@@ -113,6 +113,7 @@ impl From<&RemoteMethods> for Vec<MethodObject> {
         value
             .methods()
             .iter()
+            .filter(|e| value.is_allowed(e))
             .map(|e| MethodObject {
                 name: e.to_owned(),
                 ..default()
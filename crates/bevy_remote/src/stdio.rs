@@ -0,0 +1,96 @@
+//! The BRP transport using JSON-RPC over the standard input and output of the process.
+//!
+//! Adding the [`RemoteStdioPlugin`] to your [`App`] causes Bevy to read requests from `stdin`,
+//! and write responses to `stdout`. This allows tools like editors to launch the app as a child
+//! process and control it through its pipes, without opening any network ports.
+//!
+//! Messages are newline-delimited: each line written to `stdin` must contain a single JSON-RPC
+//! request, or a batch of requests, and each response is written to `stdout` as a single line.
+//! Any number of requests may be in flight at once, so clients should match responses to their
+//! requests using the request `id`. Watching requests (like `world.get_components+watch`) write a
+//! new response line with the request's `id` every time the watched data changes.
+//!
+//! Since `stdout` is used for responses, nothing else in the app should print to it. Bevy's own
+//! logging is written to `stderr`.
+//!
+//! This transport has no authentication, as only the process that launched the app can write to
+//! its `stdin`. [`RemoteMethodAccess`](crate::RemoteMethodAccess) still applies.
+
+#![cfg(not(target_family = "wasm"))]
+
+use crate::{
+    connection::{BrpConnection, RESPONSE_CHANNEL_CAPACITY},
+    BrpSender,
+};
+use bevy_app::{App, Plugin, Startup};
+use bevy_ecs::system::Res;
+use bevy_log::error;
+use bevy_tasks::IoTaskPool;
+use std::{
+    io::{BufRead, Write},
+    thread,
+};
+
+/// Add this plugin to your [`App`] to allow a parent process to inspect and modify entities over
+/// the app's standard input and output. It requires the [`RemotePlugin`](super::RemotePlugin).
+///
+/// This BRP transport cannot be used when targeting WASM.
+#[derive(Default)]
+pub struct RemoteStdioPlugin;
+
+impl Plugin for RemoteStdioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, start_stdio_transport);
+    }
+}
+
+/// A system that starts reading requests from `stdin` and writing responses to `stdout`.
+fn start_stdio_transport(request_sender: Res<BrpSender>) {
+    let (message_sender, message_receiver) = async_channel::unbounded::<String>();
+    let (response_sender, response_receiver) =
+        async_channel::bounded::<String>(RESPONSE_CHANNEL_CAPACITY);
+
+    // Reading from and writing to the standard streams blocks, so each gets its own thread.
+    let spawned = thread::Builder::new()
+        .name("BRP stdin".to_string())
+        .spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    return;
+                };
+                if !line.trim().is_empty() && message_sender.send_blocking(line).is_err() {
+                    return;
+                }
+            }
+        })
+        .and_then(|_| {
+            thread::Builder::new()
+                .name("BRP stdout".to_string())
+                .spawn(move || {
+                    let mut stdout = std::io::stdout().lock();
+                    while let Ok(response) = response_receiver.recv_blocking() {
+                        if writeln!(stdout, "{response}")
+                            .and_then(|()| stdout.flush())
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                })
+        });
+    if let Err(err) = spawned {
+        error!("Failed to start the BRP stdio transport: {err}");
+        return;
+    }
+
+    let request_sender = request_sender.clone();
+    IoTaskPool::get()
+        .spawn(async move {
+            // Once `stdin` is closed, the connection is dropped, ending all of its requests.
+            let mut connection = BrpConnection::new(request_sender, response_sender);
+            while let Ok(message) = message_receiver.recv().await {
+                connection.handle_message(message.as_bytes());
+            }
+        })
+        .detach();
+}
@@ -0,0 +1,223 @@
+//! The BRP transport using JSON-RPC over WebSocket.
+//!
+//! Adding the [`RemoteWebSocketPlugin`] to your [`App`] causes Bevy to accept WebSocket
+//! connections (by default, on port 15704) while your app is running.
+//!
+//! Each text or binary WebSocket message sent by a client must contain a single JSON-RPC request,
+//! or a batch of requests. Any number of requests may be in flight on the same connection at
+//! once: responses are sent back as individual messages as soon as they are ready, and clients
+//! should match them to their requests using the request `id`.
+//!
+//! Watching requests (like `world.get_components+watch`) send a new response message with the
+//! request's `id` every time the watched data changes, until the connection is closed.
+//!
+//! When [`RemoteWebSocketPlugin::with_auth`] is used, the credentials must be sent as a header of
+//! the handshake request, and connections without them are refused.
+
+#![cfg(not(target_family = "wasm"))]
+
+use crate::{
+    connection::{BrpConnection, RESPONSE_CHANNEL_CAPACITY},
+    BrpError, BrpMessage, BrpResponse, BrpSender, RemoteAuth,
+};
+use anyhow::Result as AnyhowResult;
+use async_channel::{Receiver, Sender};
+use async_io::Async;
+use async_tungstenite::{
+    tungstenite::{
+        handshake::server::{Callback, ErrorResponse, Request, Response},
+        http::{HeaderValue, StatusCode},
+        Message,
+    },
+    WebSocketReceiver, WebSocketSender,
+};
+use bevy_app::{App, Plugin, Startup};
+use bevy_ecs::{resource::Resource, system::Res};
+use bevy_tasks::{
+    futures_lite::{future, StreamExt},
+    IoTaskPool,
+};
+use core::net::{IpAddr, Ipv4Addr};
+use std::net::{TcpListener, TcpStream};
+
+/// The default port that Bevy will listen on for WebSocket connections.
+///
+/// This is one past the HTTP transport's render port, so both transports can be used together.
+pub const DEFAULT_WEBSOCKET_PORT: u16 = 15704;
+
+/// The default host address that Bevy will use for its WebSocket server.
+pub const DEFAULT_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+
+/// Add this plugin to your [`App`] to allow remote connections over WebSocket to inspect and
+/// modify entities. It requires the [`RemotePlugin`](super::RemotePlugin).
+///
+/// This BRP transport cannot be used when targeting WASM.
+///
+/// The defaults are:
+/// - [`DEFAULT_ADDR`] : 127.0.0.1.
+/// - [`DEFAULT_WEBSOCKET_PORT`] : 15704.
+/// - No authentication. See [`RemoteWebSocketPlugin::with_auth`].
+pub struct RemoteWebSocketPlugin {
+    /// The address that Bevy will bind to.
+    address: IpAddr,
+    /// The port that Bevy will listen on.
+    port: u16,
+    /// The credentials clients must present, if any.
+    auth: Option<RemoteAuth>,
+}
+
+impl Default for RemoteWebSocketPlugin {
+    fn default() -> Self {
+        Self {
+            address: DEFAULT_ADDR,
+            port: DEFAULT_WEBSOCKET_PORT,
+            auth: None,
+        }
+    }
+}
+
+impl Plugin for RemoteWebSocketPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(WebSocketServerConfig {
+            address: self.address,
+            port: self.port,
+            auth: self.auth.clone(),
+        })
+        .add_systems(Startup, start_websocket_server);
+    }
+}
+
+impl RemoteWebSocketPlugin {
+    /// Set the IP address that the server will use.
+    #[must_use]
+    pub fn with_address(mut self, address: impl Into<IpAddr>) -> Self {
+        self.address = address.into();
+        self
+    }
+
+    /// Set the remote port that the server will listen on.
+    #[must_use]
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Require clients to present the given credentials when connecting.
+    #[must_use]
+    pub fn with_auth(mut self, auth: RemoteAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+}
+
+/// The configuration set during the setup of the [`RemoteWebSocketPlugin`].
+#[derive(Debug, Resource)]
+struct WebSocketServerConfig {
+    address: IpAddr,
+    port: u16,
+    auth: Option<RemoteAuth>,
+}
+
+/// A system that starts up the Bevy Remote Protocol WebSocket server.
+fn start_websocket_server(request_sender: Res<BrpSender>, config: Res<WebSocketServerConfig>) {
+    IoTaskPool::get()
+        .spawn(server_main(
+            config.address,
+            config.port,
+            request_sender.clone(),
+            config.auth.clone(),
+        ))
+        .detach();
+}
+
+/// The Bevy Remote Protocol WebSocket server main loop.
+async fn server_main(
+    address: IpAddr,
+    port: u16,
+    request_sender: Sender<BrpMessage>,
+    auth: Option<RemoteAuth>,
+) -> AnyhowResult<()> {
+    let listener = Async::<TcpListener>::bind((address, port))?;
+    loop {
+        let (client, _) = listener.accept().await?;
+
+        let request_sender = request_sender.clone();
+        let auth = auth.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                let _ = handle_client(client, request_sender, auth).await;
+            })
+            .detach();
+    }
+}
+
+async fn handle_client(
+    client: Async<TcpStream>,
+    request_sender: Sender<BrpMessage>,
+    auth: Option<RemoteAuth>,
+) -> AnyhowResult<()> {
+    let (sender, receiver) = async_tungstenite::accept_hdr_async(client, CheckAuth(auth))
+        .await?
+        .split();
+    let (response_sender, response_receiver) = async_channel::bounded(RESPONSE_CHANNEL_CAPACITY);
+    let mut connection = BrpConnection::new(request_sender, response_sender);
+
+    // Stop as soon as either direction fails, which drops the connection and ends its requests.
+    future::or(
+        read_requests(receiver, &mut connection),
+        write_responses(sender, response_receiver),
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Refuses the WebSocket handshake if the client didn't present the right credentials.
+struct CheckAuth(Option<RemoteAuth>);
+
+impl Callback for CheckAuth {
+    fn on_request(self, request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+        let Some(auth) = self.0 else {
+            return Ok(response);
+        };
+        let value = request
+            .headers()
+            .get(auth.header_name())
+            .map(HeaderValue::as_bytes);
+        if auth.verify(value) {
+            return Ok(response);
+        }
+        let err = BrpResponse::new(None, Err(BrpError::unauthorized()));
+        let mut response = ErrorResponse::new(serde_json::to_string(&err).ok());
+        *response.status_mut() = StatusCode::UNAUTHORIZED;
+        Err(response)
+    }
+}
+
+/// Reads requests from the client until it closes the connection.
+async fn read_requests(
+    mut receiver: WebSocketReceiver<Async<TcpStream>>,
+    connection: &mut BrpConnection,
+) {
+    while let Some(message) = receiver.next().await {
+        match message {
+            Ok(Message::Text(text)) => connection.handle_message(text.as_bytes()),
+            Ok(Message::Binary(bytes)) => connection.handle_message(&bytes),
+            Ok(Message::Close(_)) | Err(_) => return,
+            // Pings are answered automatically.
+            Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_)) => {}
+        }
+    }
+}
+
+/// Writes responses to the client until the connection fails.
+async fn write_responses(
+    mut sender: WebSocketSender<Async<TcpStream>>,
+    response_receiver: Receiver<String>,
+) {
+    while let Ok(response) = response_receiver.recv().await {
+        if sender.send(Message::text(response)).await.is_err() {
+            return;
+        }
+    }
+}
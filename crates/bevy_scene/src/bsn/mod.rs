@@ -0,0 +1,231 @@
+//! Runtime support for the `.bsn` text format.
+//!
+//! `.bsn` assets use the same syntax as the [`bsn!`](crate::bsn) macro, minus Rust expressions. They are parsed
+//! into a [`BsnScene`], which resolves component types by name using the [`TypeRegistry`](bevy_reflect::TypeRegistry) and builds them
+//! using reflection. A [`BsnWriter`] does the opposite, and writes live entities back as `.bsn` text.
+
+mod parse;
+mod scene;
+mod template;
+mod types;
+mod write;
+
+pub use scene::BsnScene;
+pub use template::ReflectRelatedScenes;
+pub use write::{BsnWriteError, BsnWriter};
+
+pub(crate) use template::typed_template;
+
+use crate::ScenePatch;
+use alloc::{string::String, vec::Vec};
+use bevy_asset::{io::Reader, AssetLoader, LoadContext};
+use bevy_ecs::{
+    reflect::AppTypeRegistry,
+    world::{FromWorld, World},
+};
+use bevy_reflect::{TypePath, TypeRegistryArc};
+use thiserror::Error;
+use types::Location;
+
+/// An error produced when parsing a `.bsn` source, with the location it occurred at.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{kind} at line {line}, column {column}")]
+pub struct BsnError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    /// The column (in characters) of the error, starting at 1.
+    pub column: usize,
+    /// The kind of error.
+    pub kind: BsnErrorKind,
+}
+
+impl BsnError {
+    pub(crate) fn new(location: Location, kind: BsnErrorKind) -> Self {
+        Self {
+            line: location.line,
+            column: location.column,
+            kind,
+        }
+    }
+}
+
+/// The kind of a [`BsnError`].
+#[non_exhaustive]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BsnErrorKind {
+    /// A character that isn't valid `.bsn` syntax.
+    #[error("unexpected character `{0}`")]
+    UnexpectedChar(char),
+    /// A string that is not closed before the end of the source.
+    #[error("unterminated string")]
+    UnterminatedString,
+    /// A block comment that is not closed before the end of the source.
+    #[error("unterminated block comment")]
+    UnterminatedComment,
+    /// A character literal that doesn't contain exactly one character.
+    #[error("invalid character literal")]
+    InvalidChar,
+    /// An unknown escape sequence in a string or character literal.
+    #[error("invalid escape sequence")]
+    InvalidEscape,
+    /// A token that isn't valid at this position.
+    #[error("expected {expected}, found {found}")]
+    UnexpectedToken {
+        /// A description of what was expected.
+        expected: &'static str,
+        /// A description of the token that was found.
+        found: String,
+    },
+    /// A Rust expression or code block, which is only supported by the [`bsn!`](crate::bsn) macro.
+    #[error("expressions are not supported in .bsn assets")]
+    UnsupportedExpression,
+    /// Scenes or values nested more deeply than the parser supports.
+    #[error("scenes and values can't be nested more than {0} levels deep")]
+    TooDeeplyNested(usize),
+    /// An inherited scene (`:"path.bsn"`) that isn't the first entry of an entity.
+    #[error("inherited scenes must come first")]
+    LateInheritance,
+    /// An asset path that could not be parsed.
+    #[error("invalid asset path: {0}")]
+    InvalidAssetPath(String),
+    /// A type that isn't in the type registry.
+    #[error("unknown type `{0}`")]
+    UnknownType(String),
+    /// A short type path that matches several registered types.
+    #[error("`{0}` is ambiguous, use its full type path")]
+    AmbiguousType(String),
+    /// A type that is used by a field, but isn't in the type registry.
+    #[error("type `{0}` is not registered")]
+    UnregisteredType(&'static str),
+    /// A type used as a component that doesn't reflect [`Component`](bevy_ecs::component::Component).
+    #[error("`{0}` does not reflect `Component`")]
+    NotAComponent(&'static str),
+    /// A type used as a relationship target that doesn't register [`ReflectRelatedScenes`].
+    #[error("`{0}` does not register `ReflectRelatedScenes`")]
+    NotARelationship(String),
+    /// A type that is missing type data required to build it.
+    #[error("`{ty}` does not register `{data}`")]
+    MissingTypeData {
+        /// The type path of the type.
+        ty: &'static str,
+        /// The name of the missing type data.
+        data: &'static str,
+    },
+    /// A field that doesn't exist on the type.
+    #[error("`{ty}` has no field `{field}`")]
+    UnknownField {
+        /// The type path of the type.
+        ty: &'static str,
+        /// The name of the field.
+        field: String,
+    },
+    /// A field that is set more than once.
+    #[error("field `{0}` is set more than once")]
+    DuplicateField(String),
+    /// A field that must be set, because neither it nor its type reflect [`Default`].
+    #[error("`{ty}` does not reflect `Default`, so field `{field}` must be set")]
+    MissingField {
+        /// The type path of the type.
+        ty: &'static str,
+        /// The name or index of the field.
+        field: String,
+    },
+    /// A tuple, tuple struct or array with the wrong number of values.
+    #[error("`{ty}` expects {expected} values, found {found}")]
+    FieldCount {
+        /// The type path of the type.
+        ty: &'static str,
+        /// The expected number of values.
+        expected: usize,
+        /// The number of values that were found.
+        found: usize,
+    },
+    /// Fields given to a type (or variant) that doesn't have fields of that kind.
+    #[error("unexpected fields for `{0}`")]
+    UnexpectedFields(&'static str),
+    /// A variant that doesn't exist on the enum.
+    #[error("`{ty}` has no variant `{variant}`")]
+    UnknownVariant {
+        /// The type path of the enum.
+        ty: &'static str,
+        /// The name of the variant.
+        variant: String,
+    },
+    /// A type that doesn't match the type of the field it is assigned to.
+    #[error("expected `{expected}`, found `{found}`")]
+    MismatchedType {
+        /// The type path of the expected type.
+        expected: &'static str,
+        /// The path of the type that was found.
+        found: String,
+    },
+    /// A value that can't be converted to the type of the field it is assigned to.
+    #[error("expected a value of type `{expected}`, found {found}")]
+    InvalidValue {
+        /// The type path of the expected type.
+        expected: &'static str,
+        /// A description of the value that was found.
+        found: &'static str,
+    },
+    /// A number that doesn't fit in the type of the field it is assigned to.
+    #[error("`{number}` is not a valid `{ty}`")]
+    InvalidNumber {
+        /// The number, as written in the source.
+        number: String,
+        /// The type path of the number type.
+        ty: &'static str,
+    },
+}
+
+/// Asset loader for `.bsn` scenes, which are loaded as [`ScenePatch`] assets. See [`BsnScene`] for the supported syntax.
+#[derive(Debug, TypePath)]
+pub struct BsnLoader {
+    type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for BsnLoader {
+    fn from_world(world: &mut World) -> Self {
+        let type_registry = world.resource::<AppTypeRegistry>();
+        BsnLoader {
+            type_registry: type_registry.0.clone(),
+        }
+    }
+}
+
+/// Possible errors that can be produced by [`BsnLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum BsnLoaderError {
+    /// An [IO Error](std::io::Error)
+    #[error("Error while trying to read the scene file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The scene file is not valid UTF-8.
+    #[error("The scene file is not valid UTF-8: {0}")]
+    Utf8(#[from] core::str::Utf8Error),
+    /// A [`BsnError`]
+    #[error("Could not parse BSN: {0}")]
+    Bsn(#[from] BsnError),
+}
+
+impl AssetLoader for BsnLoader {
+    type Asset = ScenePatch;
+    type Settings = ();
+    type Error = BsnLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let source = core::str::from_utf8(&bytes)?;
+        let scene = BsnScene::parse(source, &self.type_registry.read(), load_context)?;
+        Ok(ScenePatch::load_with(load_context, scene))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bsn"]
+    }
+}
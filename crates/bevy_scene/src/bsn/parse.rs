@@ -0,0 +1,508 @@
+use super::{
+    types::{
+        BsnEntityAst, BsnEntry, BsnField, BsnFields, BsnPath, BsnTypeAst, BsnValue, BsnValueKind,
+        Location,
+    },
+    BsnError, BsnErrorKind,
+};
+use alloc::{format, string::String, vec::Vec};
+use core::{iter::Peekable, str::Chars};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    Char(char),
+    Number(String),
+    /// One of `# : , ( ) { } [ ]`
+    Punct(char),
+    /// `::`
+    PathSep,
+    Eof,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(ident) => format!("`{ident}`"),
+            Token::String(_) => "a string".into(),
+            Token::Char(_) => "a character".into(),
+            Token::Number(number) => format!("`{number}`"),
+            Token::Punct(punct) => format!("`{punct}`"),
+            Token::PathSep => "`::`".into(),
+            Token::Eof => "the end of the file".into(),
+        }
+    }
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn location(&self) -> Location {
+        Location {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        let mut chars = self.chars.clone();
+        chars.next();
+        chars.next()
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), BsnError> {
+        loop {
+            match self.chars.peek().copied() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') if self.peek_second() == Some('/') => {
+                    while self.chars.peek().is_some_and(|c| *c != '\n') {
+                        self.bump();
+                    }
+                }
+                Some('/') if self.peek_second() == Some('*') => {
+                    let start = self.location();
+                    self.bump();
+                    self.bump();
+                    loop {
+                        match self.bump() {
+                            Some('*') if self.chars.peek() == Some(&'/') => {
+                                self.bump();
+                                break;
+                            }
+                            Some(_) => {}
+                            None => {
+                                return Err(BsnError::new(start, BsnErrorKind::UnterminatedComment))
+                            }
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<(Token, Location), BsnError> {
+        self.skip_trivia()?;
+        let location = self.location();
+        let Some(c) = self.chars.peek().copied() else {
+            return Ok((Token::Eof, location));
+        };
+        let token = match c {
+            ':' if self.peek_second() == Some(':') => {
+                self.bump();
+                self.bump();
+                Token::PathSep
+            }
+            '#' | ':' | ',' | '(' | ')' | '{' | '}' | '[' | ']' => {
+                self.bump();
+                Token::Punct(c)
+            }
+            '"' => {
+                self.bump();
+                let mut value = String::new();
+                loop {
+                    match self.bump() {
+                        Some('"') => break,
+                        Some('\\') => value.push(self.escape()?),
+                        Some(c) => value.push(c),
+                        None => {
+                            return Err(BsnError::new(location, BsnErrorKind::UnterminatedString))
+                        }
+                    }
+                }
+                Token::String(value)
+            }
+            '\'' => {
+                self.bump();
+                let value = match self.bump() {
+                    Some('\\') => self.escape()?,
+                    Some(c) if c != '\'' => c,
+                    _ => return Err(BsnError::new(location, BsnErrorKind::InvalidChar)),
+                };
+                if self.bump() != Some('\'') {
+                    return Err(BsnError::new(location, BsnErrorKind::InvalidChar));
+                }
+                Token::Char(value)
+            }
+            c if c.is_ascii_digit()
+                || (c == '-' && self.peek_second().is_some_and(|c| c.is_ascii_digit())) =>
+            {
+                let mut value = String::new();
+                value.extend(self.bump());
+                while let Some(c) = self.chars.peek().copied() {
+                    let is_exponent_sign = (c == '-' || c == '+') && value.ends_with(['e', 'E']);
+                    if c.is_ascii_alphanumeric() || c == '_' || c == '.' || is_exponent_sign {
+                        value.extend(self.bump());
+                    } else {
+                        break;
+                    }
+                }
+                Token::Number(value)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut value = String::new();
+                while let Some(c) = self.chars.peek().copied() {
+                    if c.is_alphanumeric() || c == '_' {
+                        value.extend(self.bump());
+                    } else {
+                        break;
+                    }
+                }
+                Token::Ident(value)
+            }
+            // Other punctuation is only valid in expressions. Leave it to the parser, so that it
+            // reports the start of the expression instead.
+            c if c.is_ascii_punctuation() => {
+                self.bump();
+                Token::Punct(c)
+            }
+            c => return Err(BsnError::new(location, BsnErrorKind::UnexpectedChar(c))),
+        };
+        Ok((token, location))
+    }
+
+    fn escape(&mut self) -> Result<char, BsnError> {
+        let location = self.location();
+        let escaped = match self.bump() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some('\\') => '\\',
+            Some('"') => '"',
+            Some('\'') => '\'',
+            Some('u') if self.chars.peek() == Some(&'{') => {
+                self.bump();
+                let mut digits = String::new();
+                while let Some(c) = self.bump() {
+                    if c == '}' {
+                        break;
+                    }
+                    digits.push(c);
+                }
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| BsnError::new(location, BsnErrorKind::InvalidEscape))?
+            }
+            _ => return Err(BsnError::new(location, BsnErrorKind::InvalidEscape)),
+        };
+        Ok(escaped)
+    }
+}
+
+/// The maximum nesting depth of scenes and values, which keeps deeply nested input from overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Parses `.bsn` source text into its syntax tree.
+pub(crate) fn parse(source: &str) -> Result<BsnEntityAst, BsnError> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+    loop {
+        let (token, location) = lexer.next_token()?;
+        let is_eof = token == Token::Eof;
+        tokens.push((token, location));
+        if is_eof {
+            break;
+        }
+    }
+
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        depth: 0,
+    };
+    let scene = if parser.peek() == &Token::Punct('(') {
+        parser.parse_parenthesized_entity()?
+    } else {
+        parser.parse_entity()?
+    };
+    parser.expect_eof()?;
+    Ok(scene)
+}
+
+struct Parser {
+    tokens: Vec<(Token, Location)>,
+    position: usize,
+    /// The number of scenes and values currently being parsed.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn location(&self) -> Location {
+        self.tokens[self.position].1
+    }
+
+    fn next(&mut self) -> (Token, Location) {
+        let token = self.tokens[self.position].clone();
+        // The last token is always `Eof`, which is never consumed.
+        if self.position + 1 < self.tokens.len() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn unexpected(&self, expected: &'static str) -> BsnError {
+        BsnError::new(
+            self.location(),
+            BsnErrorKind::UnexpectedToken {
+                expected,
+                found: self.peek().describe(),
+            },
+        )
+    }
+
+    fn eat_punct(&mut self, punct: char) -> bool {
+        if self.peek() == &Token::Punct(punct) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: char, expected: &'static str) -> Result<(), BsnError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    /// Runs `parse` one level deeper, or fails if that is deeper than [`MAX_DEPTH`].
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, BsnError>,
+    ) -> Result<T, BsnError> {
+        if self.depth == MAX_DEPTH {
+            return Err(BsnError::new(
+                self.location(),
+                BsnErrorKind::TooDeeplyNested(MAX_DEPTH),
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn expect_eof(&mut self) -> Result<(), BsnError> {
+        if self.peek() == &Token::Eof {
+            Ok(())
+        } else {
+            Err(self.unexpected("a scene entry or the end of the file"))
+        }
+    }
+
+    /// Parses entries until a token that can't start an entry is found.
+    fn parse_entity(&mut self) -> Result<BsnEntityAst, BsnError> {
+        self.nested(Self::parse_entries)
+    }
+
+    fn parse_entries(&mut self) -> Result<BsnEntityAst, BsnError> {
+        let mut entries = Vec::new();
+        loop {
+            let entry = match self.peek() {
+                Token::Punct('#') => {
+                    self.next();
+                    let (name, _) = self.parse_ident("an entity name")?;
+                    BsnEntry::Name { name }
+                }
+                Token::Punct(':') => {
+                    self.next();
+                    let location = self.location();
+                    let Token::String(path) = self.peek().clone() else {
+                        return Err(self.unexpected("the asset path of the inherited scene"));
+                    };
+                    self.next();
+                    BsnEntry::Inherit { path, location }
+                }
+                Token::Ident(_) => {
+                    let path = self.parse_path()?;
+                    if self.peek() == &Token::Punct('[') {
+                        BsnEntry::Related {
+                            path,
+                            scenes: self.parse_scene_list()?,
+                        }
+                    } else {
+                        BsnEntry::Component(self.parse_type(path)?)
+                    }
+                }
+                Token::Punct('{') => {
+                    return Err(BsnError::new(
+                        self.location(),
+                        BsnErrorKind::UnsupportedExpression,
+                    ))
+                }
+                _ => break,
+            };
+            entries.push(entry);
+        }
+        Ok(BsnEntityAst { entries })
+    }
+
+    fn parse_parenthesized_entity(&mut self) -> Result<BsnEntityAst, BsnError> {
+        self.expect_punct('(', "`(`")?;
+        let entity = self.parse_entity()?;
+        self.expect_punct(')', "a scene entry or `)`")?;
+        Ok(entity)
+    }
+
+    fn parse_scene_list(&mut self) -> Result<Vec<BsnEntityAst>, BsnError> {
+        self.expect_punct('[', "`[`")?;
+        let mut scenes = Vec::new();
+        while !self.eat_punct(']') {
+            let scene = if self.peek() == &Token::Punct('(') {
+                self.parse_parenthesized_entity()?
+            } else {
+                let location = self.location();
+                let scene = self.parse_entity()?;
+                if scene.entries.is_empty() {
+                    return Err(BsnError::new(
+                        location,
+                        BsnErrorKind::UnexpectedToken {
+                            expected: "a scene or `]`",
+                            found: self.peek().describe(),
+                        },
+                    ));
+                }
+                scene
+            };
+            scenes.push(scene);
+            if !self.eat_punct(',') && self.peek() != &Token::Punct(']') {
+                return Err(self.unexpected("`,` or `]`"));
+            }
+        }
+        Ok(scenes)
+    }
+
+    fn parse_ident(&mut self, expected: &'static str) -> Result<(String, Location), BsnError> {
+        if let Token::Ident(ident) = self.peek().clone() {
+            let location = self.location();
+            self.next();
+            Ok((ident, location))
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn parse_path(&mut self) -> Result<BsnPath, BsnError> {
+        let (first, location) = self.parse_ident("a type")?;
+        let mut segments = Vec::from([first]);
+        while self.peek() == &Token::PathSep {
+            self.next();
+            segments.push(self.parse_ident("a path segment")?.0);
+        }
+        Ok(BsnPath { segments, location })
+    }
+
+    fn parse_type(&mut self, path: BsnPath) -> Result<BsnTypeAst, BsnError> {
+        let fields = match self.peek() {
+            Token::Punct('{') => {
+                self.next();
+                let mut fields = Vec::new();
+                while !self.eat_punct('}') {
+                    let (name, location) = self.parse_ident("a field name or `}`")?;
+                    self.expect_punct(':', "`:`")?;
+                    let value = self.parse_value()?;
+                    fields.push(BsnField {
+                        name,
+                        location,
+                        value,
+                    });
+                    if !self.eat_punct(',') && self.peek() != &Token::Punct('}') {
+                        return Err(self.unexpected("`,` or `}`"));
+                    }
+                }
+                BsnFields::Named(fields)
+            }
+            Token::Punct('(') => BsnFields::Tuple(self.parse_values(')')?),
+            _ => BsnFields::Tuple(Vec::new()),
+        };
+        Ok(BsnTypeAst { path, fields })
+    }
+
+    /// Parses comma separated values, starting at the opening delimiter.
+    fn parse_values(&mut self, close: char) -> Result<Vec<BsnValue>, BsnError> {
+        self.next();
+        let mut values = Vec::new();
+        while !self.eat_punct(close) {
+            values.push(self.parse_value()?);
+            if !self.eat_punct(',') && self.peek() != &Token::Punct(close) {
+                return Err(self.unexpected(if close == ')' {
+                    "`,` or `)`"
+                } else {
+                    "`,` or `]`"
+                }));
+            }
+        }
+        Ok(values)
+    }
+
+    fn parse_value(&mut self) -> Result<BsnValue, BsnError> {
+        self.nested(Self::parse_value_kind)
+    }
+
+    fn parse_value_kind(&mut self) -> Result<BsnValue, BsnError> {
+        let location = self.location();
+        let kind = match self.peek().clone() {
+            Token::String(value) => {
+                self.next();
+                BsnValueKind::String(value)
+            }
+            Token::Char(value) => {
+                self.next();
+                BsnValueKind::Char(value)
+            }
+            Token::Number(value) => {
+                self.next();
+                BsnValueKind::Number(value)
+            }
+            Token::Punct('#') => {
+                self.next();
+                BsnValueKind::Name(self.parse_ident("an entity name")?.0)
+            }
+            Token::Punct('(') => BsnValueKind::Tuple(self.parse_values(')')?),
+            Token::Punct('[') => BsnValueKind::List(self.parse_values(']')?),
+            Token::Ident(_) => {
+                let path = self.parse_path()?;
+                BsnValueKind::Type(self.parse_type(path)?)
+            }
+            Token::Punct('{') => {
+                return Err(BsnError::new(location, BsnErrorKind::UnsupportedExpression))
+            }
+            _ => return Err(self.unexpected("a value")),
+        };
+        Ok(BsnValue { kind, location })
+    }
+}
@@ -0,0 +1,861 @@
+use super::{
+    parse::parse,
+    template::{
+        ComponentPatch, DynamicTemplate, FieldPatch, FieldStep, PatchOp, ReflectRelatedScenes,
+        ValuePatch, ValueTemplate, VariantTemplate,
+    },
+    types::{
+        BsnEntityAst, BsnEntry, BsnField, BsnFields, BsnPath, BsnTypeAst, BsnValue, BsnValueKind,
+        Location,
+    },
+    BsnError, BsnErrorKind,
+};
+use crate::{
+    InheritSceneAsset, NameEntityReference, ResolveContext, ResolveSceneError, ResolvedScene,
+    Scene, SceneDependencies,
+};
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use bevy_asset::{AssetPath, LoadFromPath, ReflectHandle};
+use bevy_ecs::{
+    entity::Entity, name::Name, reflect::ReflectComponent, template::ScopedEntityIndex,
+};
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_reflect::{
+    enums::VariantInfo, std_traits::ReflectDefault, NamedField, Reflect, ReflectFromPtr,
+    ReflectFromReflect, Type, TypeInfo, TypeRegistration, TypeRegistry, UnnamedField,
+};
+use core::any::TypeId;
+
+/// A [`Scene`] parsed from `.bsn` text at runtime.
+///
+/// Unlike the [`bsn!`](crate::bsn) macro, component types are resolved by name through the
+/// [`TypeRegistry`], and the components are built using reflection. This requires the components to be
+/// registered with `#[reflect(Component)]`. Components that don't reflect [`Default`] must list all of
+/// the fields that don't implement [`Default`] themselves.
+///
+/// Compared to the macro, the format supports:
+/// - Components, optionally with field patches: `Transform { translation: Vec3 { x: 10.0 } }`
+/// - Enum components and values: `Visibility::Hidden`, `Some(10)`, `None`
+/// - Inheritance from a [`ScenePatch`](crate::ScenePatch) asset, as the first entry: `:"enemy.bsn"`
+/// - Names and entity references: `#Player`, `Target { entity: #Player }`
+/// - Related entities, for relationship targets registering [`ReflectRelatedScenes`]: `Children [ A, (B C) ]`
+/// - Literals, tuples `(1, 2)` and lists `[1, 2]`
+/// - Asset paths in place of [`Handle`](bevy_asset::Handle) fields, which are loaded along with the scene.
+///
+/// Rust expressions, function calls and constants are not supported.
+///
+/// `.bsn` files are loaded as [`ScenePatch`](crate::ScenePatch) assets by the [`BsnLoader`](super::BsnLoader).
+pub struct BsnScene {
+    root: BsnEntity,
+}
+
+impl BsnScene {
+    /// Parses `.bsn` `source` text into a [`BsnScene`], resolving types with `registry`.
+    ///
+    /// Asset paths used in place of [`Handle`](bevy_asset::Handle) fields start loading with `load_from_path`.
+    pub fn parse(
+        source: &str,
+        registry: &TypeRegistry,
+        load_from_path: &mut dyn LoadFromPath,
+    ) -> Result<Self, BsnError> {
+        let ast = parse(source)?;
+        let mut lowerer = Lowerer {
+            registry,
+            load_from_path,
+            names: HashMap::default(),
+        };
+        Ok(BsnScene {
+            root: lowerer.lower_entity(&ast)?,
+        })
+    }
+}
+
+impl Scene for BsnScene {
+    fn resolve(
+        self,
+        context: &mut ResolveContext,
+        scene: &mut ResolvedScene,
+    ) -> Result<(), ResolveSceneError> {
+        context.new_entity_scope(|context| self.root.resolve(context, scene))
+    }
+
+    fn register_dependencies(&self, dependencies: &mut SceneDependencies) {
+        self.root.register_dependencies(dependencies);
+    }
+}
+
+#[derive(Default)]
+struct BsnEntity {
+    inherit: Option<AssetPath<'static>>,
+    names: Vec<(String, usize)>,
+    components: Vec<ComponentPatch>,
+    related: Vec<BsnRelated>,
+}
+
+struct BsnRelated {
+    related: ReflectRelatedScenes,
+    entities: Vec<BsnEntity>,
+}
+
+impl BsnEntity {
+    fn resolve(
+        self,
+        context: &mut ResolveContext,
+        scene: &mut ResolvedScene,
+    ) -> Result<(), ResolveSceneError> {
+        if let Some(path) = self.inherit {
+            InheritSceneAsset(path).resolve(context, scene)?;
+        }
+        for (name, index) in self.names {
+            NameEntityReference {
+                name: Name::new(name),
+                index,
+            }
+            .resolve(context, scene)?;
+        }
+        let scope = context.current_entity_scope();
+        for mut component in self.components {
+            component.set_entity_scope(scope);
+            component.resolve(context, scene);
+        }
+        for related in self.related {
+            let related_scenes = scene.get_or_insert_related_resolved_scenes_erased(
+                related.related.relationship_type_id(),
+                || related.related.new_related_scenes(),
+            );
+            // Related entities don't inherit from the scene inherited by this entity.
+            let inherited = context.inherited.take();
+            for entity in related.entities {
+                let mut resolved_scene = ResolvedScene::default();
+                let result = entity.resolve(context, &mut resolved_scene);
+                context.inherited = None;
+                result?;
+                related_scenes.scenes.push(resolved_scene);
+            }
+            context.inherited = inherited;
+        }
+        Ok(())
+    }
+
+    fn register_dependencies(&self, dependencies: &mut SceneDependencies) {
+        if let Some(path) = &self.inherit {
+            InheritSceneAsset(path.clone()).register_dependencies(dependencies);
+        }
+        for related in &self.related {
+            for entity in &related.entities {
+                entity.register_dependencies(dependencies);
+            }
+        }
+    }
+}
+
+/// Converts the syntax tree of a `.bsn` source into [`BsnEntity`]s, using the [`TypeRegistry`].
+struct Lowerer<'a> {
+    registry: &'a TypeRegistry,
+    load_from_path: &'a mut dyn LoadFromPath,
+    /// Entity names, mapped to their index in the entity scope of the scene.
+    names: HashMap<String, usize>,
+}
+
+impl<'a> Lowerer<'a> {
+    fn name_index(&mut self, name: &str) -> usize {
+        let len = self.names.len();
+        *self.names.entry(name.to_string()).or_insert(len)
+    }
+
+    fn lower_entity(&mut self, ast: &BsnEntityAst) -> Result<BsnEntity, BsnError> {
+        let mut entity = BsnEntity::default();
+        for (index, entry) in ast.entries.iter().enumerate() {
+            match entry {
+                BsnEntry::Inherit { path, location } => {
+                    if index != 0 {
+                        return Err(BsnError::new(*location, BsnErrorKind::LateInheritance));
+                    }
+                    entity.inherit = Some(asset_path(path, *location)?);
+                }
+                BsnEntry::Name { name } => {
+                    entity.names.push((name.clone(), self.name_index(name)));
+                }
+                BsnEntry::Component(ty) => entity.components.push(self.lower_component(ty)?),
+                BsnEntry::Related { path, scenes } => {
+                    let (registration, variant) = self.resolve_type(path)?;
+                    let related = registration
+                        .data::<ReflectRelatedScenes>()
+                        .filter(|_| variant.is_none())
+                        .ok_or_else(|| {
+                            BsnError::new(
+                                path.location,
+                                BsnErrorKind::NotARelationship(path.joined()),
+                            )
+                        })?
+                        .clone();
+                    let entities = scenes
+                        .iter()
+                        .map(|scene| self.lower_entity(scene))
+                        .collect::<Result<_, _>>()?;
+                    entity.related.push(BsnRelated { related, entities });
+                }
+            }
+        }
+        Ok(entity)
+    }
+
+    fn lower_component(&mut self, ty: &BsnTypeAst) -> Result<ComponentPatch, BsnError> {
+        let (registration, variant) = self.resolve_type(&ty.path)?;
+        let type_path = registration.type_info().type_path();
+        let location = ty.path.location;
+        let reflect_component = registration
+            .data::<ReflectComponent>()
+            .ok_or_else(|| BsnError::new(location, BsnErrorKind::NotAComponent(type_path)))?
+            .clone();
+        let from_ptr = type_data::<ReflectFromPtr>(registration, "ReflectFromPtr", location)?;
+        let patch = if let BsnFields::Tuple(values) = &ty.fields
+            && let [name] = values.as_slice()
+            && registration.type_id() == TypeId::of::<Name>()
+        {
+            // The field of `Name` can't be built with reflection, so `Name("text")` is handled like a `Name` value.
+            ValuePatch::Replace(self.lower_value(name, registration.type_info().ty())?)
+        } else if let Some(default) = registration.data::<ReflectDefault>() {
+            let mut patches = Vec::new();
+            self.lower_patches(ty, registration.type_info(), variant, &[], &mut patches)?;
+            ValuePatch::Patch {
+                default: default.clone(),
+                patches,
+            }
+        } else {
+            ValuePatch::Replace(self.lower_type_value(ty, registration, variant)?)
+        };
+        Ok(ComponentPatch {
+            type_id: registration.type_id(),
+            type_path,
+            reflect_component,
+            from_ptr,
+            patch,
+        })
+    }
+
+    /// Resolves `path` to a type, or to the variant of an enum type.
+    fn resolve_type(
+        &self,
+        path: &BsnPath,
+    ) -> Result<(&'a TypeRegistration, Option<&'static VariantInfo>), BsnError> {
+        let joined = path.joined();
+        if let Some(registration) = self.lookup(&joined, path.location)? {
+            return Ok((registration, None));
+        }
+        if let Some((variant, enum_path)) = path.segments.split_last()
+            && !enum_path.is_empty()
+            && let Some(registration) = self.lookup(&enum_path.join("::"), path.location)?
+            && let TypeInfo::Enum(info) = registration.type_info()
+        {
+            let variant = info.variant(variant).ok_or_else(|| {
+                BsnError::new(
+                    path.location,
+                    BsnErrorKind::UnknownVariant {
+                        ty: info.type_path(),
+                        variant: variant.clone(),
+                    },
+                )
+            })?;
+            return Ok((registration, Some(variant)));
+        }
+        Err(BsnError::new(
+            path.location,
+            BsnErrorKind::UnknownType(joined),
+        ))
+    }
+
+    fn lookup(
+        &self,
+        path: &str,
+        location: Location,
+    ) -> Result<Option<&'a TypeRegistration>, BsnError> {
+        if let Some(registration) = self.registry.get_with_type_path(path) {
+            return Ok(Some(registration));
+        }
+        if self.registry.is_ambiguous(path) {
+            return Err(BsnError::new(
+                location,
+                BsnErrorKind::AmbiguousType(path.to_string()),
+            ));
+        }
+        Ok(self.registry.get_with_short_type_path(path))
+    }
+
+    fn registration(
+        &self,
+        ty: &Type,
+        location: Location,
+    ) -> Result<&'a TypeRegistration, BsnError> {
+        self.registry
+            .get(ty.id())
+            .ok_or_else(|| BsnError::new(location, BsnErrorKind::UnregisteredType(ty.path())))
+    }
+
+    /// Lowers the fields of `ty` into patches of the value at `path`, which has the type `info`.
+    fn lower_patches(
+        &mut self,
+        ty: &BsnTypeAst,
+        info: &'static TypeInfo,
+        variant: Option<&'static VariantInfo>,
+        path: &[FieldStep],
+        patches: &mut Vec<FieldPatch>,
+    ) -> Result<(), BsnError> {
+        let location = ty.path.location;
+        let field_path = |step: FieldStep| {
+            let mut path = path.to_vec();
+            path.push(step);
+            path
+        };
+        if let Some(variant) = variant {
+            patches.push(FieldPatch {
+                path: path.to_vec(),
+                op: PatchOp::Variant {
+                    name: variant.name().to_string(),
+                    value: self.lower_variant(info, variant, None, location)?,
+                },
+            });
+        }
+
+        match (&ty.fields, variant, info) {
+            (fields, _, _) if fields.is_empty() => {}
+            (BsnFields::Named(fields), Some(VariantInfo::Struct(variant)), _) => {
+                check_duplicate_fields(fields)?;
+                for field in fields {
+                    let info = variant
+                        .field(&field.name)
+                        .ok_or_else(|| unknown_field(info, field))?;
+                    self.lower_field_patch(
+                        &field.value,
+                        info.ty(),
+                        field_path(FieldStep::Named(field.name.clone())),
+                        patches,
+                    )?;
+                }
+            }
+            (BsnFields::Named(fields), None, TypeInfo::Struct(struct_info)) => {
+                check_duplicate_fields(fields)?;
+                for field in fields {
+                    let info = struct_info
+                        .field(&field.name)
+                        .ok_or_else(|| unknown_field(info, field))?;
+                    self.lower_field_patch(
+                        &field.value,
+                        info.ty(),
+                        field_path(FieldStep::Named(field.name.clone())),
+                        patches,
+                    )?;
+                }
+            }
+            (BsnFields::Tuple(values), Some(VariantInfo::Tuple(variant)), _) => {
+                check_field_count(info, variant.field_len(), values.len(), location)?;
+                for (index, (value, info)) in values.iter().zip(variant.iter()).enumerate() {
+                    self.lower_field_patch(
+                        value,
+                        info.ty(),
+                        field_path(FieldStep::Index(index)),
+                        patches,
+                    )?;
+                }
+            }
+            (BsnFields::Tuple(values), None, TypeInfo::TupleStruct(tuple_struct_info)) => {
+                check_field_count(info, tuple_struct_info.field_len(), values.len(), location)?;
+                for (index, (value, info)) in
+                    values.iter().zip(tuple_struct_info.iter()).enumerate()
+                {
+                    self.lower_field_patch(
+                        value,
+                        info.ty(),
+                        field_path(FieldStep::Index(index)),
+                        patches,
+                    )?;
+                }
+            }
+            _ => {
+                return Err(BsnError::new(
+                    location,
+                    BsnErrorKind::UnexpectedFields(info.type_path()),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    fn lower_field_patch(
+        &mut self,
+        value: &BsnValue,
+        ty: &Type,
+        path: Vec<FieldStep>,
+        patches: &mut Vec<FieldPatch>,
+    ) -> Result<(), BsnError> {
+        // Like in `bsn!`, nested structs patch their fields individually.
+        if let BsnValueKind::Type(value_ty) = &value.kind {
+            let registration = self.registration(ty, value.location)?;
+            let info = registration.type_info();
+            if matches!(info, TypeInfo::Struct(_) | TypeInfo::TupleStruct(_)) {
+                self.expect_type(value_ty, registration)?;
+                return self.lower_patches(value_ty, info, None, &path, patches);
+            }
+        }
+        patches.push(FieldPatch {
+            path,
+            op: PatchOp::Set(self.lower_value(value, ty)?),
+        });
+        Ok(())
+    }
+
+    /// Checks that `ty` refers to the type of `registration`.
+    fn expect_type(
+        &self,
+        ty: &BsnTypeAst,
+        registration: &TypeRegistration,
+    ) -> Result<(), BsnError> {
+        let (found, variant) = self.resolve_type(&ty.path)?;
+        if found.type_id() == registration.type_id() && variant.is_none() {
+            Ok(())
+        } else {
+            Err(BsnError::new(
+                ty.path.location,
+                BsnErrorKind::MismatchedType {
+                    expected: registration.type_info().type_path(),
+                    found: ty.path.joined(),
+                },
+            ))
+        }
+    }
+
+    /// Lowers `value` into a complete value of type `ty`.
+    fn lower_value(&mut self, value: &BsnValue, ty: &Type) -> Result<ValueTemplate, BsnError> {
+        let location = value.location;
+        let registration = self.registration(ty, location)?;
+        let invalid = || {
+            BsnError::new(
+                location,
+                BsnErrorKind::InvalidValue {
+                    expected: ty.path(),
+                    found: value.kind.describe(),
+                },
+            )
+        };
+        match &value.kind {
+            BsnValueKind::Name(name) => {
+                if ty.id() != TypeId::of::<Entity>() {
+                    return Err(invalid());
+                }
+                Ok(ValueTemplate::Entity(ScopedEntityIndex {
+                    scope: 0,
+                    index: self.name_index(name),
+                }))
+            }
+            BsnValueKind::String(text) => {
+                if let Some(reflect_handle) = registration.data::<ReflectHandle>() {
+                    let handle = self.load_from_path.load_from_path_erased(
+                        reflect_handle.asset_type_id(),
+                        asset_path(text, location)?,
+                    );
+                    return Ok(constant(reflect_handle.typed(handle)));
+                }
+                string_value(ty.id(), text)
+                    .map(constant)
+                    .ok_or_else(invalid)
+            }
+            BsnValueKind::Char(c) => {
+                if ty.id() != TypeId::of::<char>() {
+                    return Err(invalid());
+                }
+                Ok(constant(Box::new(*c)))
+            }
+            BsnValueKind::Number(number) => match number_value(ty.id(), number) {
+                Some(Some(value)) => Ok(constant(value)),
+                Some(None) => Err(BsnError::new(
+                    location,
+                    BsnErrorKind::InvalidNumber {
+                        number: number.clone(),
+                        ty: ty.path(),
+                    },
+                )),
+                None => Err(invalid()),
+            },
+            BsnValueKind::Tuple(values) => {
+                let TypeInfo::Tuple(info) = registration.type_info() else {
+                    return Err(invalid());
+                };
+                check_field_count(
+                    registration.type_info(),
+                    info.field_len(),
+                    values.len(),
+                    location,
+                )?;
+                let fields = values
+                    .iter()
+                    .zip(info.iter())
+                    .map(|(value, field)| self.lower_value(value, field.ty()))
+                    .collect::<Result<_, _>>()?;
+                Ok(dynamic(registration, DynamicTemplate::Tuple(fields)))
+            }
+            BsnValueKind::List(values) => match registration.type_info() {
+                TypeInfo::List(info) => {
+                    let item = info.item_ty();
+                    let items = values
+                        .iter()
+                        .map(|value| self.lower_value(value, &item))
+                        .collect::<Result<_, _>>()?;
+                    Ok(dynamic(registration, DynamicTemplate::List(items)))
+                }
+                TypeInfo::Array(info) => {
+                    check_field_count(
+                        registration.type_info(),
+                        info.capacity(),
+                        values.len(),
+                        location,
+                    )?;
+                    let item = info.item_ty();
+                    let items = values
+                        .iter()
+                        .map(|value| self.lower_value(value, &item))
+                        .collect::<Result<_, _>>()?;
+                    Ok(dynamic(registration, DynamicTemplate::Array(items)))
+                }
+                _ => Err(invalid()),
+            },
+            BsnValueKind::Type(value_ty) => match registration.type_info() {
+                TypeInfo::Enum(info) => {
+                    // Variants can be written as `Variant` or `Enum::Variant`.
+                    let (variant_name, enum_path) = value_ty.path.segments.split_last().unwrap();
+                    if !enum_path.is_empty() {
+                        let (found, variant) = self.resolve_type(&value_ty.path)?;
+                        if found.type_id() != registration.type_id() || variant.is_none() {
+                            return Err(BsnError::new(
+                                location,
+                                BsnErrorKind::MismatchedType {
+                                    expected: info.type_path(),
+                                    found: value_ty.path.joined(),
+                                },
+                            ));
+                        }
+                    }
+                    let variant = info.variant(variant_name).ok_or_else(|| {
+                        BsnError::new(
+                            location,
+                            BsnErrorKind::UnknownVariant {
+                                ty: info.type_path(),
+                                variant: variant_name.clone(),
+                            },
+                        )
+                    })?;
+                    self.lower_variant(
+                        registration.type_info(),
+                        variant,
+                        Some(&value_ty.fields),
+                        location,
+                    )
+                }
+                _ if ty.id() == TypeId::of::<bool>() => {
+                    match (
+                        value_ty.path.segments.as_slice(),
+                        value_ty.fields.is_empty(),
+                    ) {
+                        ([value], true) if value == "true" => Ok(constant(Box::new(true))),
+                        ([value], true) if value == "false" => Ok(constant(Box::new(false))),
+                        _ => Err(invalid()),
+                    }
+                }
+                _ => {
+                    self.expect_type(value_ty, registration)?;
+                    self.lower_type_value(value_ty, registration, None)
+                }
+            },
+        }
+    }
+
+    /// Lowers `ty` into a complete value of the type of `registration`.
+    fn lower_type_value(
+        &mut self,
+        ty: &BsnTypeAst,
+        registration: &TypeRegistration,
+        variant: Option<&'static VariantInfo>,
+    ) -> Result<ValueTemplate, BsnError> {
+        let info = registration.type_info();
+        let location = ty.path.location;
+        if let Some(variant) = variant {
+            return self.lower_variant(info, variant, Some(&ty.fields), location);
+        }
+        if let Some(default) = registration.data::<ReflectDefault>() {
+            let mut patches = Vec::new();
+            self.lower_patches(ty, info, None, &[], &mut patches)?;
+            let base = ValueTemplate::Default(default.clone());
+            return Ok(if patches.is_empty() {
+                base
+            } else {
+                ValueTemplate::Patched {
+                    base: Box::new(base),
+                    patches,
+                }
+            });
+        }
+        let value = match info {
+            TypeInfo::Struct(struct_info) => DynamicTemplate::Struct(self.lower_named_fields(
+                info,
+                &ty.fields,
+                &struct_info.iter().collect::<Vec<_>>(),
+                location,
+            )?),
+            TypeInfo::TupleStruct(tuple_struct_info) => {
+                DynamicTemplate::TupleStruct(self.lower_unnamed_fields(
+                    info,
+                    &ty.fields,
+                    &tuple_struct_info.iter().collect::<Vec<_>>(),
+                    location,
+                )?)
+            }
+            _ => {
+                return Err(BsnError::new(
+                    location,
+                    BsnErrorKind::UnexpectedFields(info.type_path()),
+                ))
+            }
+        };
+        Ok(dynamic(registration, value))
+    }
+
+    /// Lowers a complete value of the enum `info`, set to `variant`.
+    fn lower_variant(
+        &mut self,
+        info: &'static TypeInfo,
+        variant: &'static VariantInfo,
+        fields: Option<&BsnFields>,
+        location: Location,
+    ) -> Result<ValueTemplate, BsnError> {
+        let empty = BsnFields::Tuple(Vec::new());
+        let fields = fields.unwrap_or(&empty);
+        let variant_fields = match variant {
+            VariantInfo::Unit(_) if fields.is_empty() => VariantTemplate::Unit,
+            VariantInfo::Unit(_) => {
+                return Err(BsnError::new(
+                    location,
+                    BsnErrorKind::UnexpectedFields(info.type_path()),
+                ))
+            }
+            VariantInfo::Struct(variant) => VariantTemplate::Struct(self.lower_named_fields(
+                info,
+                fields,
+                &variant.iter().collect::<Vec<_>>(),
+                location,
+            )?),
+            VariantInfo::Tuple(variant) => VariantTemplate::Tuple(self.lower_unnamed_fields(
+                info,
+                fields,
+                &variant.iter().collect::<Vec<_>>(),
+                location,
+            )?),
+        };
+        let registration = self.registry.get(info.type_id()).ok_or_else(|| {
+            BsnError::new(location, BsnErrorKind::UnregisteredType(info.type_path()))
+        })?;
+        Ok(dynamic(
+            registration,
+            DynamicTemplate::Enum {
+                variant: variant.name().to_string(),
+                fields: variant_fields,
+            },
+        ))
+    }
+
+    /// Lowers all `expected` fields, using their [`Default`] value if they are not in `fields`.
+    fn lower_named_fields(
+        &mut self,
+        info: &'static TypeInfo,
+        fields: &BsnFields,
+        expected: &[&'static NamedField],
+        location: Location,
+    ) -> Result<Vec<(String, ValueTemplate)>, BsnError> {
+        let fields: &[BsnField] = match fields {
+            BsnFields::Named(fields) => fields,
+            BsnFields::Tuple(values) if values.is_empty() => &[],
+            BsnFields::Tuple(_) => {
+                return Err(BsnError::new(
+                    location,
+                    BsnErrorKind::UnexpectedFields(info.type_path()),
+                ))
+            }
+        };
+        check_duplicate_fields(fields)?;
+        if let Some(field) = fields
+            .iter()
+            .find(|field| !expected.iter().any(|named| named.name() == field.name))
+        {
+            return Err(unknown_field(info, field));
+        }
+        expected
+            .iter()
+            .map(|named| {
+                let value = match fields.iter().find(|field| field.name == named.name()) {
+                    Some(field) => self.lower_value(&field.value, named.ty())?,
+                    None => self.field_default(info, named.ty(), named.name(), location)?,
+                };
+                Ok((named.name().to_string(), value))
+            })
+            .collect()
+    }
+
+    /// Lowers all `expected` fields, using their [`Default`] value if they are not in `fields`.
+    fn lower_unnamed_fields(
+        &mut self,
+        info: &'static TypeInfo,
+        fields: &BsnFields,
+        expected: &[&'static UnnamedField],
+        location: Location,
+    ) -> Result<Vec<ValueTemplate>, BsnError> {
+        let BsnFields::Tuple(values) = fields else {
+            return Err(BsnError::new(
+                location,
+                BsnErrorKind::UnexpectedFields(info.type_path()),
+            ));
+        };
+        if values.len() > expected.len() {
+            check_field_count(info, expected.len(), values.len(), location)?;
+        }
+        expected
+            .iter()
+            .enumerate()
+            .map(|(index, unnamed)| match values.get(index) {
+                Some(value) => self.lower_value(value, unnamed.ty()),
+                None => self.field_default(info, unnamed.ty(), &index.to_string(), location),
+            })
+            .collect()
+    }
+
+    fn field_default(
+        &self,
+        info: &'static TypeInfo,
+        ty: &Type,
+        field: &str,
+        location: Location,
+    ) -> Result<ValueTemplate, BsnError> {
+        self.registry
+            .get_type_data::<ReflectDefault>(ty.id())
+            .map(|default| ValueTemplate::Default(default.clone()))
+            .ok_or_else(|| {
+                BsnError::new(
+                    location,
+                    BsnErrorKind::MissingField {
+                        ty: info.type_path(),
+                        field: field.to_string(),
+                    },
+                )
+            })
+    }
+}
+
+fn asset_path(path: &str, location: Location) -> Result<AssetPath<'static>, BsnError> {
+    AssetPath::try_parse(path)
+        .map(AssetPath::into_owned)
+        .map_err(|err| BsnError::new(location, BsnErrorKind::InvalidAssetPath(err.to_string())))
+}
+
+fn type_data<T: Clone + bevy_reflect::TypeData>(
+    registration: &TypeRegistration,
+    name: &'static str,
+    location: Location,
+) -> Result<T, BsnError> {
+    registration.data::<T>().cloned().ok_or_else(|| {
+        BsnError::new(
+            location,
+            BsnErrorKind::MissingTypeData {
+                ty: registration.type_info().type_path(),
+                data: name,
+            },
+        )
+    })
+}
+
+fn dynamic(registration: &TypeRegistration, value: DynamicTemplate) -> ValueTemplate {
+    ValueTemplate::Dynamic {
+        info: registration.type_info(),
+        from_reflect: registration.data::<ReflectFromReflect>().cloned(),
+        value,
+    }
+}
+
+fn constant(value: Box<dyn Reflect>) -> ValueTemplate {
+    ValueTemplate::Constant(value.into())
+}
+
+fn unknown_field(info: &'static TypeInfo, field: &BsnField) -> BsnError {
+    BsnError::new(
+        field.location,
+        BsnErrorKind::UnknownField {
+            ty: info.type_path(),
+            field: field.name.clone(),
+        },
+    )
+}
+
+fn check_duplicate_fields(fields: &[BsnField]) -> Result<(), BsnError> {
+    let mut seen = HashSet::new();
+    for field in fields {
+        if !seen.insert(field.name.as_str()) {
+            return Err(BsnError::new(
+                field.location,
+                BsnErrorKind::DuplicateField(field.name.clone()),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_field_count(
+    info: &'static TypeInfo,
+    expected: usize,
+    found: usize,
+    location: Location,
+) -> Result<(), BsnError> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(BsnError::new(
+            location,
+            BsnErrorKind::FieldCount {
+                ty: info.type_path(),
+                expected,
+                found,
+            },
+        ))
+    }
+}
+
+/// Converts a string literal to a value of the type `type_id`.
+fn string_value(type_id: TypeId, text: &str) -> Option<Box<dyn Reflect>> {
+    if type_id == TypeId::of::<String>() {
+        Some(Box::new(text.to_string()))
+    } else if type_id == TypeId::of::<Cow<'static, str>>() {
+        Some(Box::new(Cow::<'static, str>::Owned(text.to_string())))
+    } else if type_id == TypeId::of::<Name>() {
+        Some(Box::new(Name::new(text.to_string())))
+    } else {
+        None
+    }
+}
+
+/// Converts a number literal to a value of the type `type_id`.
+///
+/// Returns `None` if `type_id` is not a number type, and `Some(None)` if `number` is not a valid value of that type.
+fn number_value(type_id: TypeId, number: &str) -> Option<Option<Box<dyn Reflect>>> {
+    let number = number.replace('_', "");
+    macro_rules! parse_number {
+        ($($ty:ty),*) => {
+            $(
+                if type_id == TypeId::of::<$ty>() {
+                    return Some(number.parse::<$ty>().ok().map(|value| Box::new(value) as Box<dyn Reflect>));
+                }
+            )*
+        };
+    }
+    parse_number!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+    None
+}
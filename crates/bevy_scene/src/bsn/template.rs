@@ -0,0 +1,580 @@
+use crate::{ErasedComponentTemplate, RelatedResolvedScenes, ResolveContext, ResolvedScene};
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use bevy_ecs::{
+    bundle::BundleWriter,
+    entity::Entity,
+    error::BevyError,
+    ptr::{OwningPtr, Ptr},
+    reflect::ReflectComponent,
+    relationship::{Relationship, RelationshipTarget},
+    template::{ScopedEntityIndex, TemplateContext},
+    world::{EntityRef, EntityWorldMut},
+};
+use bevy_reflect::{
+    array::DynamicArray,
+    enums::{DynamicEnum, DynamicVariant},
+    list::DynamicList,
+    std_traits::ReflectDefault,
+    structs::DynamicStruct,
+    tuple::DynamicTuple,
+    tuple_struct::DynamicTupleStruct,
+    FromType, PartialReflect, Reflect, ReflectCloneError, ReflectFromPtr, ReflectFromReflect,
+    ReflectMut, ReflectRef, TypeInfo,
+};
+use core::{
+    alloc::Layout,
+    any::{Any, TypeId},
+    fmt,
+    ptr::NonNull,
+};
+use thiserror::Error;
+use tracing::warn;
+
+/// Type data that allows a [`RelationshipTarget`] to spawn related entities from `.bsn` assets,
+/// such as `Children [ ... ]`, and to write them with a [`BsnWriter`](super::BsnWriter).
+///
+/// The [`ScenePlugin`](crate::ScenePlugin) registers this for [`Children`]. Other relationship
+/// targets can opt in with `#[reflect(RelatedScenes)]`.
+///
+/// [`Children`]: bevy_ecs::hierarchy::Children
+#[derive(Clone)]
+pub struct ReflectRelatedScenes {
+    relationship: TypeId,
+    new_related_scenes: fn() -> RelatedResolvedScenes,
+    related_entities: fn(EntityRef) -> Vec<Entity>,
+    relate: fn(&mut EntityWorldMut, Entity),
+}
+
+impl ReflectRelatedScenes {
+    /// The [`TypeId`] of the [`Relationship`] added to the related entities.
+    pub fn relationship_type_id(&self) -> TypeId {
+        self.relationship
+    }
+
+    /// Creates an empty [`RelatedResolvedScenes`] for this relationship.
+    pub fn new_related_scenes(&self) -> RelatedResolvedScenes {
+        (self.new_related_scenes)()
+    }
+
+    /// Returns the entities related to `entity`, in order.
+    pub fn related_entities(&self, entity: EntityRef) -> Vec<Entity> {
+        (self.related_entities)(entity)
+    }
+
+    /// Relates `entity` to `target`, by inserting the [`Relationship`] on `entity`.
+    pub fn relate(&self, entity: &mut EntityWorldMut, target: Entity) {
+        (self.relate)(entity, target);
+    }
+}
+
+impl<T: RelationshipTarget> FromType<T> for ReflectRelatedScenes {
+    fn from_type() -> Self {
+        Self {
+            relationship: TypeId::of::<T::Relationship>(),
+            new_related_scenes: RelatedResolvedScenes::new::<T::Relationship>,
+            related_entities: |entity| {
+                entity
+                    .get::<T>()
+                    .map(|target| target.iter().collect())
+                    .unwrap_or_default()
+            },
+            relate: |entity, target| {
+                entity.insert(<T::Relationship as Relationship>::from(target));
+            },
+        }
+    }
+}
+
+/// An error produced when building a value described by a `.bsn` asset.
+#[derive(Error, Debug)]
+pub(crate) enum ReflectTemplateError {
+    #[error("The field `{0}` does not exist on the patched value")]
+    MissingField(FieldStep),
+    #[error("Failed to build a `{0}` from its fields")]
+    FromReflect(&'static str),
+    #[error("Failed to set a value of type `{0}`")]
+    TypeMismatch(String),
+    #[error("Entity references can only be built when spawning a scene")]
+    UnresolvedEntity,
+    #[error(transparent)]
+    Clone(#[from] ReflectCloneError),
+}
+
+/// Describes how to build a reflected value.
+#[derive(Clone)]
+pub(crate) enum ValueTemplate {
+    /// A value that is cloned each time the template is built.
+    Constant(Arc<dyn Reflect>),
+    /// The [`Default`] value of a type.
+    Default(ReflectDefault),
+    /// A reference to a named entity of the scene.
+    Entity(ScopedEntityIndex),
+    /// A value built from all of its fields with [`FromReflect`](bevy_reflect::FromReflect).
+    ///
+    /// Types that don't register [`ReflectFromReflect`] (such as tuples) are left as dynamic values, which
+    /// are converted along with the value that contains them.
+    Dynamic {
+        info: &'static TypeInfo,
+        from_reflect: Option<ReflectFromReflect>,
+        value: DynamicTemplate,
+    },
+    /// A `base` value with `patches` applied on top of it.
+    Patched {
+        base: Box<ValueTemplate>,
+        patches: Vec<FieldPatch>,
+    },
+}
+
+#[derive(Clone)]
+pub(crate) enum DynamicTemplate {
+    Struct(Vec<(String, ValueTemplate)>),
+    TupleStruct(Vec<ValueTemplate>),
+    Tuple(Vec<ValueTemplate>),
+    List(Vec<ValueTemplate>),
+    Array(Vec<ValueTemplate>),
+    Enum {
+        variant: String,
+        fields: VariantTemplate,
+    },
+}
+
+#[derive(Clone)]
+pub(crate) enum VariantTemplate {
+    Unit,
+    Tuple(Vec<ValueTemplate>),
+    Struct(Vec<(String, ValueTemplate)>),
+}
+
+/// Sets the value found at `path`.
+#[derive(Clone)]
+pub(crate) struct FieldPatch {
+    pub path: Vec<FieldStep>,
+    pub op: PatchOp,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum FieldStep {
+    Named(String),
+    Index(usize),
+}
+
+impl fmt::Display for FieldStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldStep::Named(name) => f.write_str(name),
+            FieldStep::Index(index) => write!(f, "{index}"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum PatchOp {
+    /// Overwrites the value.
+    Set(ValueTemplate),
+    /// Overwrites the value if it isn't already the enum variant `name`.
+    /// This keeps the fields of the current variant, so that they can be patched individually.
+    Variant { name: String, value: ValueTemplate },
+}
+
+impl ValueTemplate {
+    /// Builds the value. `context` is required to build entity references.
+    pub fn build(
+        &self,
+        mut context: Option<&mut TemplateContext>,
+    ) -> Result<Box<dyn PartialReflect>, ReflectTemplateError> {
+        match self {
+            ValueTemplate::Constant(value) => Ok((**value).reflect_clone()?.into_partial_reflect()),
+            ValueTemplate::Default(default) => Ok(default.default().into_partial_reflect()),
+            ValueTemplate::Entity(index) => {
+                let context = context.ok_or(ReflectTemplateError::UnresolvedEntity)?;
+                Ok(Box::new(context.get_scoped_entity(*index)))
+            }
+            ValueTemplate::Dynamic {
+                info,
+                from_reflect,
+                value,
+            } => {
+                let dynamic = value.build(info, context)?;
+                let Some(from_reflect) = from_reflect else {
+                    return Ok(dynamic);
+                };
+                from_reflect
+                    .from_reflect(&*dynamic)
+                    .map(<dyn Reflect>::into_partial_reflect)
+                    .ok_or(ReflectTemplateError::FromReflect(info.type_path()))
+            }
+            ValueTemplate::Patched { base, patches } => {
+                let mut value = base.build(context.as_deref_mut())?;
+                for patch in patches {
+                    patch.apply(&mut *value, context.as_deref_mut())?;
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    /// Applies `patches` on top of this value.
+    fn push_patches(&mut self, mut new_patches: Vec<FieldPatch>) {
+        if let ValueTemplate::Patched { patches, .. } = self {
+            patches.append(&mut new_patches);
+        } else {
+            *self = ValueTemplate::Patched {
+                base: Box::new(self.clone()),
+                patches: new_patches,
+            };
+        }
+    }
+
+    /// Sets the scope of all entity references, which is only known when the scene is resolved.
+    pub fn set_entity_scope(&mut self, scope: usize) {
+        match self {
+            ValueTemplate::Constant(_) | ValueTemplate::Default(_) => {}
+            ValueTemplate::Entity(index) => index.scope = scope,
+            ValueTemplate::Dynamic { value, .. } => match value {
+                DynamicTemplate::Struct(fields)
+                | DynamicTemplate::Enum {
+                    fields: VariantTemplate::Struct(fields),
+                    ..
+                } => fields
+                    .iter_mut()
+                    .for_each(|(_, field)| field.set_entity_scope(scope)),
+                DynamicTemplate::TupleStruct(fields)
+                | DynamicTemplate::Tuple(fields)
+                | DynamicTemplate::List(fields)
+                | DynamicTemplate::Array(fields)
+                | DynamicTemplate::Enum {
+                    fields: VariantTemplate::Tuple(fields),
+                    ..
+                } => fields
+                    .iter_mut()
+                    .for_each(|field| field.set_entity_scope(scope)),
+                DynamicTemplate::Enum {
+                    fields: VariantTemplate::Unit,
+                    ..
+                } => {}
+            },
+            ValueTemplate::Patched { base, patches } => {
+                base.set_entity_scope(scope);
+                for patch in patches {
+                    match &mut patch.op {
+                        PatchOp::Set(value) | PatchOp::Variant { value, .. } => {
+                            value.set_entity_scope(scope);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl DynamicTemplate {
+    fn build(
+        &self,
+        info: &'static TypeInfo,
+        mut context: Option<&mut TemplateContext>,
+    ) -> Result<Box<dyn PartialReflect>, ReflectTemplateError> {
+        let mut build = |value: &ValueTemplate| value.build(context.as_deref_mut());
+        let mut build_struct = |fields: &[(String, ValueTemplate)]| {
+            let mut dynamic = DynamicStruct::default();
+            for (name, value) in fields {
+                dynamic.insert_boxed(name.as_str(), build(value)?);
+            }
+            Ok::<_, ReflectTemplateError>(dynamic)
+        };
+        Ok(match self {
+            DynamicTemplate::Struct(fields) => {
+                let mut dynamic = build_struct(fields)?;
+                dynamic.set_represented_type(Some(info));
+                Box::new(dynamic)
+            }
+            DynamicTemplate::Enum { variant, fields } => {
+                let variant_value = match fields {
+                    VariantTemplate::Unit => DynamicVariant::Unit,
+                    VariantTemplate::Struct(fields) => {
+                        DynamicVariant::Struct(build_struct(fields)?)
+                    }
+                    VariantTemplate::Tuple(fields) => {
+                        let mut dynamic = DynamicTuple::default();
+                        for value in fields {
+                            dynamic.insert_boxed(build(value)?);
+                        }
+                        DynamicVariant::Tuple(dynamic)
+                    }
+                };
+                let mut dynamic = DynamicEnum::new(variant.as_str(), variant_value);
+                dynamic.set_represented_type(Some(info));
+                Box::new(dynamic)
+            }
+            DynamicTemplate::TupleStruct(fields) => {
+                let mut dynamic = DynamicTupleStruct::default();
+                dynamic.set_represented_type(Some(info));
+                for value in fields {
+                    dynamic.insert_boxed(build(value)?);
+                }
+                Box::new(dynamic)
+            }
+            DynamicTemplate::Tuple(fields) => {
+                let mut dynamic = DynamicTuple::default();
+                dynamic.set_represented_type(Some(info));
+                for value in fields {
+                    dynamic.insert_boxed(build(value)?);
+                }
+                Box::new(dynamic)
+            }
+            DynamicTemplate::List(items) => {
+                let mut dynamic = DynamicList::default();
+                dynamic.set_represented_type(Some(info));
+                for value in items {
+                    dynamic.push_box(build(value)?);
+                }
+                Box::new(dynamic)
+            }
+            DynamicTemplate::Array(items) => {
+                let items = items.iter().map(build).collect::<Result<Vec<_>, _>>()?;
+                let mut dynamic = DynamicArray::new(items.into_boxed_slice());
+                dynamic.set_represented_type(Some(info));
+                Box::new(dynamic)
+            }
+        })
+    }
+}
+
+impl FieldPatch {
+    fn apply(
+        &self,
+        value: &mut dyn PartialReflect,
+        context: Option<&mut TemplateContext>,
+    ) -> Result<(), ReflectTemplateError> {
+        let mut target = value;
+        for step in &self.path {
+            target = field_mut(target, step)
+                .ok_or_else(|| ReflectTemplateError::MissingField(step.clone()))?;
+        }
+        let value = match &self.op {
+            PatchOp::Set(value) => value,
+            PatchOp::Variant { name, value } => {
+                if let ReflectRef::Enum(current) = target.reflect_ref()
+                    && current.variant_name() == name
+                {
+                    return Ok(());
+                }
+                value
+            }
+        };
+        let value = value.build(context)?;
+        let type_path = target.reflect_type_path().to_string();
+        match (target.try_as_reflect_mut(), value.try_into_reflect()) {
+            (Some(target), Ok(value)) => target
+                .set(value)
+                .map_err(|_| ReflectTemplateError::TypeMismatch(type_path)),
+            // Dynamic values are always complete, so applying them overwrites the target.
+            (_, Err(value)) => target
+                .try_apply(&*value)
+                .map_err(|_| ReflectTemplateError::TypeMismatch(type_path)),
+            (None, Ok(_)) => Err(ReflectTemplateError::TypeMismatch(type_path)),
+        }
+    }
+}
+
+fn field_mut<'a>(
+    value: &'a mut dyn PartialReflect,
+    step: &FieldStep,
+) -> Option<&'a mut dyn PartialReflect> {
+    match (value.reflect_mut(), step) {
+        (ReflectMut::Struct(value), FieldStep::Named(name)) => value.field_mut(name),
+        (ReflectMut::TupleStruct(value), FieldStep::Index(index)) => value.field_mut(*index),
+        (ReflectMut::Tuple(value), FieldStep::Index(index)) => value.field_mut(*index),
+        (ReflectMut::Enum(value), FieldStep::Named(name)) => value.field_mut(name),
+        (ReflectMut::Enum(value), FieldStep::Index(index)) => value.field_at_mut(*index),
+        _ => None,
+    }
+}
+
+/// A component [`Template`](bevy_ecs::template::Template) described by a `.bsn` asset, which builds the
+/// component using reflection.
+///
+/// It is stored in a [`ResolvedScene`] under the [`TypeId`] of the component, which is also the key
+/// used by components that are their own template (`Clone + Default`). This lets `.bsn` assets and
+/// the [`bsn!`](crate::bsn) macro patch the same components.
+#[derive(Clone)]
+pub(crate) struct ReflectComponentTemplate {
+    component: TypeId,
+    type_path: &'static str,
+    reflect_component: ReflectComponent,
+    value: ValueTemplate,
+}
+
+impl ErasedComponentTemplate for ReflectComponentTemplate {
+    unsafe fn apply(
+        &self,
+        context: &mut TemplateContext,
+        bundle_writer: &mut BundleWriter,
+    ) -> Result<(), BevyError> {
+        let component = self
+            .value
+            .build(Some(context))?
+            .try_into_reflect()
+            .ok()
+            .filter(|component| component.as_any().type_id() == self.component)
+            .ok_or_else(|| ReflectTemplateError::TypeMismatch(self.type_path.to_string()))?;
+        // SAFETY: world_mut is only used to register components, which does not affect entity location
+        let id = self
+            .reflect_component
+            .register_component(unsafe { context.entity.world_mut() });
+        let layout = Layout::for_value::<dyn Reflect>(&*component);
+        let ptr = NonNull::from(Box::leak(component)).cast::<u8>();
+        // SAFETY:
+        // - `ptr` points to an owned value of the component type registered as `id`, with a matching layout.
+        // - The caller verifies that `bundle_writer` is always used with the same World.
+        // - The value has been moved into `bundle_writer`, so its allocation is freed without dropping it.
+        unsafe {
+            bundle_writer.push_component_by_id(id, OwningPtr::new(ptr), layout);
+            if layout.size() != 0 {
+                alloc::alloc::dealloc(ptr.as_ptr(), layout);
+            }
+        }
+        Ok(())
+    }
+
+    fn clone_template(&self) -> Box<dyn ErasedComponentTemplate> {
+        Box::new(self.clone())
+    }
+
+    fn template_type_id(&self) -> TypeId {
+        self.component
+    }
+}
+
+/// The placeholder template inserted before a [`ReflectComponentTemplate`] replaces it.
+struct EmptyTemplate;
+
+impl ErasedComponentTemplate for EmptyTemplate {
+    unsafe fn apply(
+        &self,
+        _context: &mut TemplateContext,
+        _bundle_writer: &mut BundleWriter,
+    ) -> Result<(), BevyError> {
+        Ok(())
+    }
+
+    fn clone_template(&self) -> Box<dyn ErasedComponentTemplate> {
+        Box::new(EmptyTemplate)
+    }
+}
+
+/// How a `.bsn` entry changes a component.
+pub(crate) enum ValuePatch {
+    /// Replaces the whole component. Used for components that don't implement [`Default`].
+    Replace(ValueTemplate),
+    /// Patches the current value of the component, or its `default` value if it isn't in the scene yet.
+    Patch {
+        default: ReflectDefault,
+        patches: Vec<FieldPatch>,
+    },
+}
+
+/// A component entry of a `.bsn` asset.
+pub(crate) struct ComponentPatch {
+    pub type_id: TypeId,
+    pub type_path: &'static str,
+    pub reflect_component: ReflectComponent,
+    pub from_ptr: ReflectFromPtr,
+    pub patch: ValuePatch,
+}
+
+impl ComponentPatch {
+    pub fn set_entity_scope(&mut self, scope: usize) {
+        match &mut self.patch {
+            ValuePatch::Replace(value) => value.set_entity_scope(scope),
+            ValuePatch::Patch { patches, .. } => {
+                for patch in patches {
+                    match &mut patch.op {
+                        PatchOp::Set(value) | PatchOp::Variant { value, .. } => {
+                            value.set_entity_scope(scope);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn resolve(self, context: &mut ResolveContext, scene: &mut ResolvedScene) {
+        let template = scene
+            .get_or_insert_erased_template_box(context, self.type_id, || Box::new(EmptyTemplate));
+        let template_any: &mut dyn Any = &mut **template;
+        if let Some(existing) = template_any.downcast_mut::<ReflectComponentTemplate>() {
+            match self.patch {
+                ValuePatch::Replace(value) => existing.value = value,
+                ValuePatch::Patch { patches, .. } => existing.value.push_patches(patches),
+            }
+            return;
+        }
+
+        let base = if template_any.is::<EmptyTemplate>() {
+            None
+        } else if (**template).type_id() == self.type_id {
+            // This component is its own template, which was added by a `bsn!` scene.
+            // SAFETY: The template is a value of the component type, which `from_ptr` was created for.
+            let value = unsafe { self.from_ptr.as_reflect(Ptr::from(&**template)) };
+            match value.reflect_clone() {
+                Ok(value) => Some(ValueTemplate::Constant(value.into())),
+                Err(err) => {
+                    warn!("Failed to clone `{}` to patch it: {err}", self.type_path);
+                    None
+                }
+            }
+        } else {
+            warn!(
+                "`{}` is patched by a `.bsn` scene, which replaces its existing template",
+                self.type_path
+            );
+            None
+        };
+
+        let value = match (base, self.patch) {
+            (_, ValuePatch::Replace(value)) => value,
+            (Some(base), ValuePatch::Patch { patches, .. }) => ValueTemplate::Patched {
+                base: Box::new(base),
+                patches,
+            },
+            (None, ValuePatch::Patch { default, patches }) => ValueTemplate::Patched {
+                base: Box::new(ValueTemplate::Default(default)),
+                patches,
+            },
+        };
+        *template = Box::new(ReflectComponentTemplate {
+            component: self.type_id,
+            type_path: self.type_path,
+            reflect_component: self.reflect_component,
+            value,
+        });
+    }
+}
+
+/// Converts `template` to `T` if it is a [`ReflectComponentTemplate`] of the component `T`.
+///
+/// This lets typed scenes patch components added by `.bsn` assets.
+pub(crate) fn typed_template<T: Default + 'static>(template: &dyn ErasedComponentTemplate) -> T {
+    let template_any: &dyn Any = template;
+    if let Some(template) = template_any.downcast_ref::<ReflectComponentTemplate>() {
+        match template.value.build(None) {
+            Ok(value) => {
+                if let Ok(value) = value.try_into_reflect()
+                    && let Ok(value) = value.into_any().downcast::<T>()
+                {
+                    return *value;
+                }
+            }
+            Err(err) => warn!(
+                "Failed to build `{}` from a `.bsn` scene to patch it, using its default value instead: {err}",
+                core::any::type_name::<T>()
+            ),
+        }
+    }
+    T::default()
+}
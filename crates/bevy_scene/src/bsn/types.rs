@@ -0,0 +1,112 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A location in a `.bsn` source, used to report errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Location {
+    /// The line, starting at 1.
+    pub line: usize,
+    /// The column (in characters), starting at 1.
+    pub column: usize,
+}
+
+/// A single entity in a `.bsn` source, made of a list of entries.
+#[derive(Debug)]
+pub(crate) struct BsnEntityAst {
+    pub entries: Vec<BsnEntry>,
+}
+
+#[derive(Debug)]
+pub(crate) enum BsnEntry {
+    /// `:"path.bsn"`
+    Inherit { path: String, location: Location },
+    /// `#Name`
+    Name { name: String },
+    /// `Type`, `Type { field: value }`, `Type(value)` or `Enum::Variant`
+    Component(BsnTypeAst),
+    /// `RelationshipTarget [ scene, scene ]`
+    Related {
+        path: BsnPath,
+        scenes: Vec<BsnEntityAst>,
+    },
+}
+
+/// A `::` separated path, such as `Transform` or `bevy_transform::components::Transform`.
+#[derive(Debug, Clone)]
+pub(crate) struct BsnPath {
+    pub segments: Vec<String>,
+    pub location: Location,
+}
+
+impl BsnPath {
+    pub fn joined(&self) -> String {
+        self.segments.join("::")
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct BsnTypeAst {
+    pub path: BsnPath,
+    pub fields: BsnFields,
+}
+
+#[derive(Debug)]
+pub(crate) enum BsnFields {
+    Named(Vec<BsnField>),
+    Tuple(Vec<BsnValue>),
+}
+
+impl BsnFields {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            BsnFields::Named(fields) => fields.is_empty(),
+            BsnFields::Tuple(fields) => fields.is_empty(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct BsnField {
+    pub name: String,
+    pub location: Location,
+    pub value: BsnValue,
+}
+
+#[derive(Debug)]
+pub(crate) struct BsnValue {
+    pub kind: BsnValueKind,
+    pub location: Location,
+}
+
+#[derive(Debug)]
+pub(crate) enum BsnValueKind {
+    /// `"text"`
+    String(String),
+    /// `'c'`
+    Char(char),
+    /// `10`, `-1.5`
+    Number(String),
+    /// `#Name`
+    Name(String),
+    /// A type, enum variant or plain identifier (such as `true` or `None`), with optional fields.
+    Type(BsnTypeAst),
+    /// `(a, b)`
+    Tuple(Vec<BsnValue>),
+    /// `[a, b]`
+    List(Vec<BsnValue>),
+}
+
+impl BsnValueKind {
+    /// Describes this value in error messages.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            BsnValueKind::String(_) => "a string",
+            BsnValueKind::Char(_) => "a character",
+            BsnValueKind::Number(_) => "a number",
+            BsnValueKind::Name(_) => "an entity name",
+            BsnValueKind::Type(_) => "a type",
+            BsnValueKind::Tuple(_) => "a tuple",
+            BsnValueKind::List(_) => "a list",
+        }
+    }
+}
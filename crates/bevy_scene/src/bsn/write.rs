@@ -0,0 +1,666 @@
+use super::ReflectRelatedScenes;
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use bevy_asset::{AssetPath, ReflectHandle};
+use bevy_ecs::{
+    component::{Component, ComponentInfo},
+    entity::Entity,
+    name::Name,
+    reflect::ReflectComponent,
+    world::{EntityRef, World},
+};
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_reflect::{
+    std_traits::ReflectDefault, structs::Struct, tuple_struct::TupleStruct, PartialReflect,
+    Reflect, ReflectRef, TypeRegistry,
+};
+use core::any::TypeId;
+use thiserror::Error;
+use tracing::warn;
+
+/// Writes a live entity hierarchy as `.bsn` text, which can be loaded back with the [`BsnLoader`](super::BsnLoader).
+///
+/// Components are found using the [`TypeRegistry`], and must be registered with `#[reflect(Component)]` to be written.
+/// Entities related by a [`RelationshipTarget`] that registers [`ReflectRelatedScenes`] (such as [`Children`]) are written
+/// as related scene lists.
+///
+/// The output is a minimal patch: only fields that differ from the [`Default`] value of their component are written.
+/// If the hierarchy was spawned from a [`ScenePatch`](crate::ScenePatch) that it should keep inheriting from, use
+/// [`BsnWriter::inherit`] to only write what differs from that scene instead.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_reflect::TypeRegistry;
+/// # use bevy_scene::BsnWriter;
+/// # let world = World::new();
+/// # let root = Entity::PLACEHOLDER;
+/// # let registry = TypeRegistry::new();
+/// # let _ = || -> Result<(), bevy_scene::BsnWriteError> {
+/// let bsn = BsnWriter::new(&world, &registry).write(root)?;
+/// # Ok(())
+/// # };
+/// ```
+///
+/// [`RelationshipTarget`]: bevy_ecs::relationship::RelationshipTarget
+/// [`Children`]: bevy_ecs::hierarchy::Children
+pub struct BsnWriter<'w> {
+    world: &'w World,
+    registry: &'w TypeRegistry,
+    inherit: Option<(AssetPath<'static>, Entity)>,
+    denied_components: HashSet<TypeId>,
+}
+
+impl<'w> BsnWriter<'w> {
+    /// Creates a new [`BsnWriter`] for entities of `world`.
+    pub fn new(world: &'w World, registry: &'w TypeRegistry) -> Self {
+        Self {
+            world,
+            registry,
+            inherit: None,
+            denied_components: HashSet::default(),
+        }
+    }
+
+    /// Makes the written scene inherit from the [`ScenePatch`](crate::ScenePatch) at `path`.
+    ///
+    /// `baseline` must be an entity of the world that was spawned from that scene, and left unmodified. Only the values
+    /// that differ from `baseline` are written. Related entities that come from the inherited scene can't be patched,
+    /// so changes to them are not written.
+    pub fn inherit(mut self, path: impl Into<AssetPath<'static>>, baseline: Entity) -> Self {
+        self.inherit = Some((path.into(), baseline));
+        self
+    }
+
+    /// Excludes the component `T` from the written scene.
+    pub fn deny_component<T: Component>(mut self) -> Self {
+        self.denied_components.insert(TypeId::of::<T>());
+        self
+    }
+
+    /// Writes `root` and its related entities as `.bsn` text.
+    pub fn write(&self, root: Entity) -> Result<String, BsnWriteError> {
+        let mut names = HashMap::default();
+        let mut used_names = HashSet::new();
+        let baseline = self.inherit.as_ref().map(|(_, baseline)| *baseline);
+        self.collect_names(root, baseline, &mut names, &mut used_names)?;
+        let writer = EntityWriter {
+            writer: self,
+            names,
+            relationships: self
+                .registry
+                .iter_with_data::<ReflectRelatedScenes>()
+                .map(|(_, related)| related.relationship_type_id())
+                .collect(),
+        };
+        let mut out = String::new();
+        if let Some((path, _)) = &self.inherit {
+            out.push_str(&format!(":{}\n", quote(&path.to_string())));
+        }
+        writer.write_entity(&mut out, root, baseline, 0)?;
+        Ok(out)
+    }
+
+    fn entity(&self, entity: Entity) -> Result<EntityRef<'w>, BsnWriteError> {
+        self.world
+            .get_entity(entity)
+            .map_err(|_| BsnWriteError::MissingEntity(entity))
+    }
+
+    /// Returns the related entities of `entity`, grouped by the [`RelationshipTarget`](bevy_ecs::relationship::RelationshipTarget)
+    /// that holds them.
+    fn related(&self, entity: EntityRef) -> Vec<(&'w str, &'w ReflectRelatedScenes, Vec<Entity>)> {
+        entity
+            .archetype()
+            .components()
+            .iter()
+            .filter_map(|id| {
+                let type_id = self.world.components().get_info(*id)?.type_id()?;
+                let registration = self.registry.get(type_id)?;
+                let related = registration.data::<ReflectRelatedScenes>()?;
+                Some((
+                    registration.type_info().type_path_table().short_path(),
+                    related,
+                    related.related_entities(entity),
+                ))
+            })
+            .collect()
+    }
+
+    /// Names the entities written with `#Name`, so that they can be referenced.
+    fn collect_names(
+        &self,
+        entity: Entity,
+        baseline: Option<Entity>,
+        names: &mut HashMap<Entity, String>,
+        used_names: &mut HashSet<String>,
+    ) -> Result<(), BsnWriteError> {
+        let entity_ref = self.entity(entity)?;
+        if let Some(name) = entity_ref.get::<Name>()
+            && is_ident(name.as_str())
+            && used_names.insert(name.as_str().to_string())
+        {
+            names.insert(entity, name.as_str().to_string());
+        }
+        let baseline = baseline.map(|baseline| self.entity(baseline)).transpose()?;
+        for (_, related, entities) in self.related(entity_ref) {
+            let inherited = inherited_count(baseline, related);
+            for related_entity in entities.into_iter().skip(inherited) {
+                self.collect_names(related_entity, None, names, used_names)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn inherited_count(baseline: Option<EntityRef>, related: &ReflectRelatedScenes) -> usize {
+    baseline.map_or(0, |baseline| related.related_entities(baseline).len())
+}
+
+/// An error produced by [`BsnWriter::write`].
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum BsnWriteError {
+    /// An entity of the hierarchy does not exist.
+    #[error("Entity {0} does not exist")]
+    MissingEntity(Entity),
+    /// A value that can't be represented in `.bsn` text, such as maps or non-finite floats.
+    #[error("`{0}` can't be written as .bsn")]
+    UnsupportedValue(String),
+    /// An entity reference to an entity that is not named with a unique `#Name` in the written hierarchy.
+    #[error(
+        "Entity {0} is referenced, but it doesn't have a unique name in the written hierarchy"
+    )]
+    UnnamedEntityReference(Entity),
+    /// A handle field whose asset doesn't have a path.
+    #[error("A handle of type `{0}` doesn't have an asset path")]
+    HandleWithoutPath(&'static str),
+}
+
+struct EntityWriter<'a, 'w> {
+    writer: &'a BsnWriter<'w>,
+    /// The entities written with `#Name`.
+    names: HashMap<Entity, String>,
+    /// The [`Relationship`](bevy_ecs::relationship::Relationship) components, which are written as related scene lists.
+    relationships: HashSet<TypeId>,
+}
+
+impl EntityWriter<'_, '_> {
+    fn write_entity(
+        &self,
+        out: &mut String,
+        entity: Entity,
+        baseline: Option<Entity>,
+        indent: usize,
+    ) -> Result<(), BsnWriteError> {
+        let writer = self.writer;
+        let entity_ref = writer.entity(entity)?;
+        let baseline = baseline
+            .map(|baseline| writer.entity(baseline))
+            .transpose()?;
+        let line = |out: &mut String, entry: &str| {
+            out.push_str(&"    ".repeat(indent));
+            out.push_str(entry);
+            out.push('\n');
+        };
+
+        if let Some(name) = self.names.get(&entity) {
+            line(out, &format!("#{name}"));
+        } else if let Some(name) = entity_ref.get::<Name>()
+            && baseline.and_then(|baseline| baseline.get::<Name>()) != Some(name)
+        {
+            line(out, &format!("Name({})", quote(name.as_str())));
+        }
+
+        for id in entity_ref.archetype().components() {
+            let Some(type_id) = writer
+                .world
+                .components()
+                .get_info(*id)
+                .and_then(ComponentInfo::type_id)
+            else {
+                continue;
+            };
+            if type_id == TypeId::of::<Name>()
+                || writer.denied_components.contains(&type_id)
+                || self.relationships.contains(&type_id)
+            {
+                continue;
+            }
+            let Some(registration) = writer.registry.get(type_id) else {
+                continue;
+            };
+            let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+                continue;
+            };
+            if registration.contains::<ReflectRelatedScenes>() {
+                continue;
+            }
+            let Some(component) = reflect_component.reflect(entity_ref) else {
+                continue;
+            };
+            let inherited = baseline.and_then(|baseline| reflect_component.reflect(baseline));
+            if let Some(entry) =
+                self.component_entry(component, inherited, registration.data::<ReflectDefault>())?
+            {
+                line(out, &entry);
+            }
+        }
+
+        for (path, related, entities) in writer.related(entity_ref) {
+            let inherited = inherited_count(baseline, related);
+            if let Some(baseline) = baseline {
+                self.warn_modified_inherited(&entities, &related.related_entities(baseline));
+            }
+            if entities.len() <= inherited {
+                continue;
+            }
+            line(out, &format!("{path} ["));
+            for related_entity in entities.into_iter().skip(inherited) {
+                let mut entries = String::new();
+                self.write_entity(&mut entries, related_entity, None, indent + 2)?;
+                if entries.is_empty() {
+                    line(out, "    (),");
+                } else {
+                    line(out, "    (");
+                    out.push_str(&entries);
+                    line(out, "    ),");
+                }
+            }
+            line(out, "]");
+        }
+        Ok(())
+    }
+
+    /// Related entities from the inherited scene can't be patched in `.bsn`, so changes to them are lost.
+    fn warn_modified_inherited(&self, entities: &[Entity], baseline: &[Entity]) {
+        for (entity, baseline) in entities.iter().zip(baseline) {
+            let mut patch = String::new();
+            if self
+                .write_entity(&mut patch, *entity, Some(*baseline), 0)
+                .map_or(true, |_| !patch.is_empty())
+            {
+                warn!(
+                    "Entity {entity} comes from an inherited scene, so its changes can't be written as .bsn"
+                );
+            }
+        }
+    }
+
+    /// Writes a component entry, or returns `None` if the component is unchanged from the inherited scene.
+    fn component_entry(
+        &self,
+        component: &dyn Reflect,
+        inherited: Option<&dyn Reflect>,
+        default: Option<&ReflectDefault>,
+    ) -> Result<Option<String>, BsnWriteError> {
+        let component = component.as_partial_reflect();
+        let inherited = inherited.map(PartialReflect::as_partial_reflect);
+        if let Some(inherited) = inherited
+            && equal(component, inherited)
+        {
+            return Ok(None);
+        }
+        let type_name = self.type_name(component)?;
+        let Some(default) = default else {
+            // Components without a default value replace the inherited value, so they must be complete.
+            let value = self.write_value(component)?;
+            return Ok(Some(match component.reflect_ref() {
+                ReflectRef::Enum(_) => format!("{type_name}::{value}"),
+                _ => value,
+            }));
+        };
+        let default_value;
+        let base = match inherited {
+            Some(inherited) => inherited,
+            None => {
+                default_value = default.default();
+                default_value.as_partial_reflect()
+            }
+        };
+        let entry = match (component.reflect_ref(), base.reflect_ref()) {
+            (ReflectRef::Struct(value), ReflectRef::Struct(base)) => {
+                let fields = self.struct_patch(value, base)?;
+                if fields.is_empty() {
+                    type_name
+                } else {
+                    format!("{type_name} {{ {fields} }}")
+                }
+            }
+            (ReflectRef::TupleStruct(value), ReflectRef::TupleStruct(base)) => {
+                if equal(component, base.as_partial_reflect()) {
+                    type_name
+                } else {
+                    format!("{type_name}({})", self.tuple_struct_patch(value, base)?)
+                }
+            }
+            (ReflectRef::Enum(value), ReflectRef::Enum(base)) => {
+                if equal(component, base.as_partial_reflect()) {
+                    type_name
+                } else if value.variant_name() == base.variant_name() {
+                    // The loader patches the fields of the current variant.
+                    format!("{type_name}::{}", self.variant_patch(value, base)?)
+                } else {
+                    // A different variant replaces the current one, so the variant is written in full.
+                    format!("{type_name}::{}", self.write_value(component)?)
+                }
+            }
+            _ => {
+                return Err(BsnWriteError::UnsupportedValue(
+                    component.reflect_type_path().to_string(),
+                ))
+            }
+        };
+        Ok(Some(entry))
+    }
+
+    /// Writes the fields of `value` that differ from `base`, as `name: value` pairs.
+    fn struct_patch(&self, value: &dyn Struct, base: &dyn Struct) -> Result<String, BsnWriteError> {
+        let mut fields = Vec::new();
+        for (name, field) in value.iter_fields() {
+            let base_field = base.field(name);
+            if base_field.is_some_and(|base_field| equal(field, base_field)) {
+                continue;
+            }
+            fields.push(format!("{name}: {}", self.field_patch(field, base_field)?));
+        }
+        Ok(fields.join(", "))
+    }
+
+    /// Writes all fields of `value`, which patch the fields of `base`.
+    fn tuple_struct_patch(
+        &self,
+        value: &dyn TupleStruct,
+        base: &dyn TupleStruct,
+    ) -> Result<String, BsnWriteError> {
+        let fields = value
+            .iter_fields()
+            .enumerate()
+            .map(|(index, field)| self.field_patch(field, base.field(index)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(fields.join(", "))
+    }
+
+    /// Writes the fields of the enum `value`, which patch the fields of `base` that has the same variant.
+    fn variant_patch(
+        &self,
+        value: &dyn bevy_reflect::enums::Enum,
+        base: &dyn bevy_reflect::enums::Enum,
+    ) -> Result<String, BsnWriteError> {
+        let variant = value.variant_name();
+        Ok(match value.variant_type() {
+            bevy_reflect::enums::VariantType::Unit => variant.to_string(),
+            bevy_reflect::enums::VariantType::Tuple => {
+                let fields = value
+                    .iter_fields()
+                    .enumerate()
+                    .map(|(index, field)| self.field_patch(field.value(), base.field_at(index)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                format!("{variant}({})", fields.join(", "))
+            }
+            bevy_reflect::enums::VariantType::Struct => {
+                let mut fields = Vec::new();
+                for field in value.iter_fields() {
+                    let name = field.name().unwrap();
+                    let base_field = base.field(name);
+                    if base_field.is_some_and(|base_field| equal(field.value(), base_field)) {
+                        continue;
+                    }
+                    fields.push(format!(
+                        "{name}: {}",
+                        self.field_patch(field.value(), base_field)?
+                    ));
+                }
+                if fields.is_empty() {
+                    variant.to_string()
+                } else {
+                    format!("{variant} {{ {} }}", fields.join(", "))
+                }
+            }
+        })
+    }
+
+    /// Writes a field that patches `base`. Nested structs are patched field by field, like the loader does.
+    fn field_patch(
+        &self,
+        value: &dyn PartialReflect,
+        base: Option<&dyn PartialReflect>,
+    ) -> Result<String, BsnWriteError> {
+        if let Some(base) = base
+            && !self.is_literal(value)
+        {
+            match (value.reflect_ref(), base.reflect_ref()) {
+                (ReflectRef::Struct(value_struct), ReflectRef::Struct(base)) => {
+                    let type_name = self.type_name(value)?;
+                    let fields = self.struct_patch(value_struct, base)?;
+                    return Ok(if fields.is_empty() {
+                        type_name
+                    } else {
+                        format!("{type_name} {{ {fields} }}")
+                    });
+                }
+                (ReflectRef::TupleStruct(value_struct), ReflectRef::TupleStruct(base)) => {
+                    let type_name = self.type_name(value)?;
+                    return Ok(format!(
+                        "{type_name}({})",
+                        self.tuple_struct_patch(value_struct, base)?
+                    ));
+                }
+                _ => {}
+            }
+        }
+        self.write_value(value)
+    }
+
+    /// Writes a complete value.
+    fn write_value(&self, value: &dyn PartialReflect) -> Result<String, BsnWriteError> {
+        if let Some(literal) = self.literal(value)? {
+            return Ok(literal);
+        }
+        let list = |items: &mut dyn Iterator<Item = &dyn PartialReflect>| {
+            items
+                .map(|item| self.write_value(item))
+                .collect::<Result<Vec<_>, _>>()
+                .map(|items| items.join(", "))
+        };
+        Ok(match value.reflect_ref() {
+            ReflectRef::Struct(value_struct) => {
+                let type_name = self.type_name(value)?;
+                let fields = match self.default_value(value) {
+                    // The loader patches the default value of types that have one.
+                    Some(default) => match default.reflect_ref() {
+                        ReflectRef::Struct(default) => self.struct_patch(value_struct, default)?,
+                        _ => unreachable!(),
+                    },
+                    None => value_struct
+                        .iter_fields()
+                        .map(|(name, field)| Ok(format!("{name}: {}", self.write_value(field)?)))
+                        .collect::<Result<Vec<_>, BsnWriteError>>()?
+                        .join(", "),
+                };
+                if fields.is_empty() {
+                    type_name
+                } else {
+                    format!("{type_name} {{ {fields} }}")
+                }
+            }
+            ReflectRef::TupleStruct(value_struct) => {
+                let type_name = self.type_name(value)?;
+                let fields = match self.default_value(value) {
+                    Some(default) => match default.reflect_ref() {
+                        ReflectRef::TupleStruct(default) => {
+                            self.tuple_struct_patch(value_struct, default)?
+                        }
+                        _ => unreachable!(),
+                    },
+                    None => list(&mut value_struct.iter_fields())?,
+                };
+                format!("{type_name}({fields})")
+            }
+            ReflectRef::Tuple(tuple) => format!("({})", list(&mut tuple.iter_fields())?),
+            ReflectRef::List(items) => format!("[{}]", list(&mut items.iter())?),
+            ReflectRef::Array(items) => format!("[{}]", list(&mut items.iter())?),
+            ReflectRef::Enum(value) => {
+                let variant = value.variant_name();
+                match value.variant_type() {
+                    bevy_reflect::enums::VariantType::Unit => variant.to_string(),
+                    bevy_reflect::enums::VariantType::Tuple => format!(
+                        "{variant}({})",
+                        list(&mut value.iter_fields().map(|field| field.value()))?
+                    ),
+                    bevy_reflect::enums::VariantType::Struct => {
+                        let fields = value
+                            .iter_fields()
+                            .map(|field| {
+                                Ok(format!(
+                                    "{}: {}",
+                                    field.name().unwrap(),
+                                    self.write_value(field.value())?
+                                ))
+                            })
+                            .collect::<Result<Vec<_>, BsnWriteError>>()?;
+                        format!("{variant} {{ {} }}", fields.join(", "))
+                    }
+                }
+            }
+            _ => {
+                return Err(BsnWriteError::UnsupportedValue(
+                    value.reflect_type_path().to_string(),
+                ))
+            }
+        })
+    }
+
+    fn default_value(&self, value: &dyn PartialReflect) -> Option<Box<dyn Reflect>> {
+        let info = value.get_represented_type_info()?;
+        Some(
+            self.writer
+                .registry
+                .get_type_data::<ReflectDefault>(info.type_id())?
+                .default(),
+        )
+    }
+
+    fn is_literal(&self, value: &dyn PartialReflect) -> bool {
+        !matches!(self.literal(value), Ok(None))
+    }
+
+    /// Writes values that have a literal representation, or returns `None` for other values.
+    fn literal(&self, value: &dyn PartialReflect) -> Result<Option<String>, BsnWriteError> {
+        macro_rules! numbers {
+            ($($ty:ty),*) => {
+                $(
+                    if let Some(value) = value.try_downcast_ref::<$ty>() {
+                        return Ok(Some(value.to_string()));
+                    }
+                )*
+            };
+        }
+        macro_rules! floats {
+            ($($ty:ty),*) => {
+                $(
+                    if let Some(value) = value.try_downcast_ref::<$ty>() {
+                        if !value.is_finite() {
+                            return Err(BsnWriteError::UnsupportedValue(format!("{value} ({})", core::any::type_name::<$ty>())));
+                        }
+                        return Ok(Some(value.to_string()));
+                    }
+                )*
+            };
+        }
+        numbers!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+        floats!(f32, f64);
+        if let Some(value) = value.try_downcast_ref::<bool>() {
+            return Ok(Some(value.to_string()));
+        }
+        if let Some(value) = value.try_downcast_ref::<char>() {
+            let mut literal = String::from("'");
+            escape_into(&mut literal, *value);
+            literal.push('\'');
+            return Ok(Some(literal));
+        }
+        if let Some(value) = value.try_downcast_ref::<String>() {
+            return Ok(Some(quote(value)));
+        }
+        if let Some(value) = value.try_downcast_ref::<Cow<'static, str>>() {
+            return Ok(Some(quote(value)));
+        }
+        if let Some(value) = value.try_downcast_ref::<Name>() {
+            return Ok(Some(quote(value.as_str())));
+        }
+        if let Some(entity) = value.try_downcast_ref::<Entity>() {
+            return match self.names.get(entity) {
+                Some(name) => Ok(Some(format!("#{name}"))),
+                None => Err(BsnWriteError::UnnamedEntityReference(*entity)),
+            };
+        }
+        if let Some(info) = value.get_represented_type_info()
+            && let Some(reflect_handle) = self
+                .writer
+                .registry
+                .get_type_data::<ReflectHandle>(info.type_id())
+            && let Some(value) = value.try_as_reflect()
+            && let Some(handle) = reflect_handle.downcast_handle_untyped(value.as_any())
+        {
+            return match handle.path() {
+                Some(path) => Ok(Some(quote(&path.to_string()))),
+                None => Err(BsnWriteError::HandleWithoutPath(info.type_path())),
+            };
+        }
+        Ok(None)
+    }
+
+    /// Returns the shortest path that the loader resolves to the type of `value`.
+    fn type_name(&self, value: &dyn PartialReflect) -> Result<String, BsnWriteError> {
+        let unsupported = || BsnWriteError::UnsupportedValue(value.reflect_type_path().to_string());
+        let info = value.get_represented_type_info().ok_or_else(unsupported)?;
+        let short_path = info.type_path_table().short_path();
+        let path = if self.writer.registry.is_ambiguous(short_path) {
+            info.type_path()
+        } else {
+            short_path
+        };
+        if path.split("::").all(is_ident) {
+            Ok(path.to_string())
+        } else {
+            Err(unsupported())
+        }
+    }
+}
+
+fn equal(value: &dyn PartialReflect, base: &dyn PartialReflect) -> bool {
+    value.reflect_partial_eq(base).unwrap_or(false)
+}
+
+fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        escape_into(&mut quoted, c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn escape_into(out: &mut String, c: char) {
+    match c {
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        '\0' => out.push_str("\\0"),
+        '\\' => out.push_str("\\\\"),
+        '"' => out.push_str("\\\""),
+        '\'' => out.push_str("\\'"),
+        c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+        c => out.push(c),
+    }
+}
@@ -300,7 +300,6 @@
 //! }
 //!
 //! // Asset inheritance: `:` prefix with a string path to a ScenePatch asset
-//! // DISCLAIMER: .bsn file format is not yet released!
 //! bsn! {
 //!    :"enemy.bsn"
 //!    Health { max: 200 }
@@ -476,21 +475,42 @@
 //!
 //! ## .bsn Asset Format
 //!
-//! In future releases, Bevy intends to offer a `.bsn` asset format.
-//! This would allow you to define your scenes on disk,
-//! creating/modifying them in various authoring tools and using asset hot-reloading.
+//! Scenes can also be defined on disk as `.bsn` assets, which are loaded as [`ScenePatch`] assets
+//! by the [`BsnLoader`]. This allows creating and modifying them in authoring tools and using asset hot-reloading.
+//!
+//! The format uses the same syntax as the `bsn!` macro, making it easy to port your content between both forms:
+//!
+//! ```text
+//! :"enemy.bsn"
+//! #Boss
+//! Health { max: 200 }
+//! Visibility::Hidden
+//! Target { entity: #Boss }
+//! Children [
+//!     (Weapon { damage: 10.0 } Name("Sword")),
+//!     Shield
+//! ]
+//! ```
 //!
-//! This format is intended to have broad syntactic compatibility with the `bsn!` macro,
-//! making it easy to port your content between both the macro and the asset form.
+//! Unlike `bsn!` macro calls, `.bsn` assets are built using reflection, which comes with some limitations:
+//! - Expressions (`{...}`), function calls and constants are not supported.
+//! - Components must be registered in the [`AppTypeRegistry`] with `#[reflect(Component)]`. Types can be
+//!   written using their short or full type path.
+//! - Components that don't reflect [`Default`] must set every field whose type doesn't reflect [`Default`].
+//! - Related entities require the [`RelationshipTarget`] to register [`ReflectRelatedScenes`]. This is done for [`Children`].
+//! - Lists use `[...]`, and [`Handle`] fields are written as asset paths.
 //!
-//! Bevy does not currently have support for `.bsn` files:
-//! for now, you should use existing non-Bevy asset formats like glTF,
-//! search for ecosystem implementations or stick to `bsn!` macro calls.
+//! See [`BsnScene`] for more details.
 //!
-//! When planning, be aware that `.bsn` asset files, unlike `bsn!` macro calls,
-//! will not support expressions or other dynamic features directly.
+//! While the [`AssetServer`] watches for changes, entities with a [`ScenePatchInstance`] are updated in place when
+//! their `.bsn` asset (or an asset it inherits from) changes. Only the components, fields and related entities that
+//! changed in the asset are written, so state added at runtime is kept. A [`ScenePatchInstanceReloaded`] event is
+//! triggered on each updated instance.
 //!
 //! [`Template`]: bevy_ecs::template::Template
+//! [`AppTypeRegistry`]: bevy_ecs::reflect::AppTypeRegistry
+//! [`Children`]: bevy_ecs::hierarchy::Children
+//! [`Handle`]: bevy_asset::Handle
 //! [`FromTemplate`]: bevy_ecs::template::FromTemplate
 //! [`Asset`]: bevy_asset::Asset
 //! [`Entity`]: bevy_ecs::entity::Entity
@@ -518,6 +538,8 @@ pub mod macro_utils;
 
 extern crate alloc;
 
+mod bsn;
+mod reload;
 mod resolved_scene;
 mod scene;
 mod scene_list;
@@ -526,6 +548,8 @@ mod spawn;
 mod spawn_system;
 
 pub use bevy_scene_macros::*;
+pub use bsn::*;
+pub use reload::*;
 pub use resolved_scene::*;
 pub use scene::*;
 pub use scene_list::*;
@@ -547,6 +571,10 @@ impl Plugin for ScenePlugin {
             .init_resource::<WaitingScenes>()
             .init_asset::<ScenePatch>()
             .init_asset::<SceneListPatch>()
+            .init_asset_loader::<BsnLoader>()
+            .register_type::<Children>()
+            .register_type::<Name>()
+            .register_type_data::<Children, ReflectRelatedScenes>()
             .add_systems(
                 SpawnScene,
                 (resolve_scene_patches, spawn_queued)
@@ -561,17 +589,21 @@ impl Plugin for ScenePlugin {
 #[cfg(test)]
 mod tests {
     use crate::{self as bevy_scene, ScenePlugin};
-    use crate::{prelude::*, ScenePatch};
+    use crate::{
+        prelude::*, BsnErrorKind, BsnScene, BsnWriter, ScenePatch, ScenePatchInstanceReloaded,
+    };
     use alloc::sync::Arc;
     use bevy_app::{App, TaskPoolPlugin};
     use bevy_asset::io::memory::{Dir, MemoryAssetReader};
     use bevy_asset::io::{AssetSourceBuilder, AssetSourceId};
-    use bevy_asset::{Asset, AssetApp, AssetLoader, AssetPlugin, AssetServer, Assets, Handle};
+    use bevy_asset::{
+        Asset, AssetApp, AssetLoader, AssetPlugin, AssetServer, Assets, Handle, LoadState,
+    };
     use bevy_ecs::lifecycle::HookContext;
     use bevy_ecs::prelude::*;
     use bevy_ecs::relationship::Relationship;
     use bevy_ecs::world::DeferredWorld;
-    use bevy_reflect::TypePath;
+    use bevy_reflect::{std_traits::ReflectDefault, Reflect, TypePath};
     use std::path::Path;
     use std::sync::Mutex;
 
@@ -632,6 +664,35 @@ mod tests {
         assert_eq!(name.as_str(), "Y");
     }
 
+    fn bsn_test_app(dir: &Dir) -> App {
+        let mut app = App::new();
+        let dir = dir.clone();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSourceBuilder::new(move || Box::new(MemoryAssetReader { root: dir.clone() })),
+        );
+        app.add_plugins((
+            TaskPoolPlugin::default(),
+            AssetPlugin::default(),
+            ScenePlugin,
+        ));
+        app.finish();
+        app.cleanup();
+        app
+    }
+
+    fn load_resolved_patch(app: &mut App, path: &'static str) -> Handle<ScenePatch> {
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let handle = asset_server.load(path);
+        run_app_until(app, || {
+            if let Some(LoadState::Failed(err)) = asset_server.get_load_state(&handle) {
+                panic!("failed to load {path}: {err}");
+            }
+            asset_server.is_loaded_with_dependencies(&handle)
+        });
+        handle
+    }
+
     #[test]
     fn loaded_asset_inheritance_patching() {
         #[derive(Component, FromTemplate)]
@@ -643,7 +704,7 @@ mod tests {
 
         fn b() -> impl Scene {
             bsn! {
-                :"a.bsn"
+                :"a.fake"
                 Position { x: 1. }
                 Children [ #Y ]
             }
@@ -696,10 +757,11 @@ mod tests {
             }
         }
 
-        // Insert an asset that the fake loader can fake read.
-        dir.insert_asset_text(Path::new("a.bsn"), "");
+        // Insert an asset that the fake loader can fake read. `.bsn` files are read by the
+        // `BsnLoader`, so it uses an extension without a loader.
+        dir.insert_asset_text(Path::new("a.fake"), "");
         let asset_server = app.world().resource::<AssetServer>().clone();
-        let handle = asset_server.load("a.bsn");
+        let handle = asset_server.load("a.fake");
         assert!(app.world().get_resource::<Assets<ScenePatch>>().is_some());
         run_app_until(&mut app, || asset_server.is_loaded(&handle));
         let patch = app
@@ -730,6 +792,405 @@ mod tests {
         assert_eq!(name.as_str(), "Y");
     }
 
+    #[test]
+    fn bsn_asset_inheritance_patching() {
+        #[derive(Component, Reflect, Default, Clone)]
+        #[reflect(Component, Default)]
+        struct Position {
+            x: f32,
+            y: f32,
+            z: f32,
+        }
+
+        fn b() -> impl Scene {
+            bsn! {
+                :"a.bsn"
+                Position { x: 1. }
+                Children [ #Y ]
+            }
+        }
+
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("a.bsn"), "Position { y: 2. }\nChildren [ #X ]");
+        let mut app = bsn_test_app(&dir);
+        app.register_type::<Position>();
+        let handle = load_resolved_patch(&mut app, "a.bsn");
+        let patch = app
+            .world()
+            .resource::<Assets<ScenePatch>>()
+            .get(&handle)
+            .unwrap();
+        assert!(patch.resolved.is_some());
+
+        let world = app.world_mut();
+        let id = world.spawn_scene(b()).unwrap().id();
+        let root = world.entity(id);
+
+        let position = root.get::<Position>().unwrap();
+        assert_eq!(position.x, 1.);
+        assert_eq!(position.y, 2.);
+        assert_eq!(position.z, 0.);
+
+        let children = root.get::<Children>().unwrap();
+        assert_eq!(children.len(), 2);
+
+        let x = world.entity(children[0]);
+        let name = x.get::<Name>().unwrap();
+        assert_eq!(name.as_str(), "X");
+
+        let y = world.entity(children[1]);
+        let name = y.get::<Name>().unwrap();
+        assert_eq!(name.as_str(), "Y");
+    }
+
+    #[derive(Component, Reflect, Default, Clone, PartialEq, Debug)]
+    #[reflect(Component, Default)]
+    struct Stats {
+        health: u32,
+        speed: f32,
+        tags: Vec<String>,
+        offset: (i32, i32),
+        target: Option<Entity>,
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug)]
+    #[reflect(Component)]
+    enum Team {
+        Red,
+        Blue { score: u32 },
+    }
+
+    #[derive(Component, Reflect, Clone, PartialEq, Debug)]
+    #[reflect(Component)]
+    struct Target {
+        entity: Entity,
+        label: String,
+    }
+
+    #[test]
+    fn bsn_asset_components_and_children() {
+        let dir = Dir::default();
+        dir.insert_asset_text(
+            Path::new("stats.bsn"),
+            r#"
+            // The root entity.
+            #Root
+            Stats {
+                health: 10,
+                tags: ["a", "b"],
+                offset: (1, -2),
+                target: Some(#Child),
+            }
+            Team::Blue { score: 3 }
+            Children [
+                (#Child Stats { speed: 1.5 } Target { entity: #Root, label: "root" }),
+                Team::Red,
+            ]
+            "#,
+        );
+        let mut app = bsn_test_app(&dir);
+        app.register_type::<(Stats, Team, Target)>();
+        load_resolved_patch(&mut app, "stats.bsn");
+
+        let world = app.world_mut();
+        let root = world.spawn_scene(bsn! { :"stats.bsn" }).unwrap().id();
+        let children = world.entity(root).get::<Children>().unwrap().to_vec();
+        assert_eq!(children.len(), 2);
+        let (child, second) = (children[0], children[1]);
+
+        let root_entity = world.entity(root);
+        assert_eq!(root_entity.get::<Name>().unwrap().as_str(), "Root");
+        assert_eq!(
+            root_entity.get::<Stats>().unwrap(),
+            &Stats {
+                health: 10,
+                speed: 0.0,
+                tags: vec!["a".into(), "b".into()],
+                offset: (1, -2),
+                target: Some(child),
+            }
+        );
+        assert_eq!(root_entity.get::<Team>(), Some(&Team::Blue { score: 3 }));
+
+        let child_entity = world.entity(child);
+        assert_eq!(child_entity.get::<Name>().unwrap().as_str(), "Child");
+        assert_eq!(child_entity.get::<Stats>().unwrap().speed, 1.5);
+        assert_eq!(
+            child_entity.get::<Target>(),
+            Some(&Target {
+                entity: root,
+                label: "root".into()
+            })
+        );
+        assert_eq!(world.entity(second).get::<Team>(), Some(&Team::Red));
+    }
+
+    #[test]
+    fn bsn_asset_errors() {
+        let mut app = test_app();
+        app.register_type::<(Stats, Team, Target)>();
+        let registry = app.world().resource::<AppTypeRegistry>().clone();
+        let mut asset_server = app.world().resource::<AssetServer>().clone();
+        let mut parse = |source: &str| {
+            BsnScene::parse(source, &registry.read(), &mut asset_server)
+                .err()
+                .unwrap()
+        };
+
+        let err = parse("Stats {\n    health: \"ten\"\n}");
+        assert_eq!((err.line, err.column), (2, 13));
+        assert!(matches!(err.kind, BsnErrorKind::InvalidValue { .. }));
+
+        let err = parse("Stats\nUnknown");
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.kind, BsnErrorKind::UnknownType("Unknown".into()));
+
+        let err = parse("Stats { heath: 1 }");
+        assert_eq!((err.line, err.column), (1, 9));
+        assert!(matches!(err.kind, BsnErrorKind::UnknownField { .. }));
+
+        let err = parse("Team::Green");
+        assert!(matches!(err.kind, BsnErrorKind::UnknownVariant { .. }));
+
+        let err = parse("Target { label: \"a\" }");
+        assert!(matches!(err.kind, BsnErrorKind::MissingField { .. }));
+
+        let err = parse("Stats { health: 1 } @");
+        assert_eq!((err.line, err.column), (1, 21));
+        assert!(matches!(err.kind, BsnErrorKind::UnexpectedToken { .. }));
+
+        let err = parse("Stats\n  \u{2192}");
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.kind, BsnErrorKind::UnexpectedChar('\u{2192}'));
+
+        let err = parse("Stats { health: {1 + 1} }");
+        assert_eq!((err.line, err.column), (1, 17));
+        assert_eq!(err.kind, BsnErrorKind::UnsupportedExpression);
+
+        let err = parse("Stats :\"a.bsn\"");
+        assert_eq!(err.kind, BsnErrorKind::LateInheritance);
+
+        let err = parse(&format!("Stats {{ offset: {} }}", "(".repeat(100_000)));
+        assert!(matches!(err.kind, BsnErrorKind::TooDeeplyNested(_)));
+        let err = parse(&"Children [ ".repeat(100_000));
+        assert!(matches!(err.kind, BsnErrorKind::TooDeeplyNested(_)));
+    }
+
+    #[test]
+    fn bsn_writer_round_trip() {
+        let dir = Dir::default();
+        dir.insert_asset_text(
+            Path::new("stats.bsn"),
+            r#"
+            #Root
+            Stats { health: 10, offset: (1, -2) }
+            Team::Blue { score: 3 }
+            Children [
+                (#Child Target { entity: #Root, label: "root" }),
+            ]
+            "#,
+        );
+        let mut app = bsn_test_app(&dir);
+        app.register_type::<(Stats, Team, Target)>();
+        load_resolved_patch(&mut app, "stats.bsn");
+
+        let world = app.world_mut();
+        let root = world.spawn_scene(bsn! { :"stats.bsn" }).unwrap().id();
+        let child = world.entity(root).get::<Children>().unwrap()[0];
+        world.entity_mut(root).get_mut::<Stats>().unwrap().speed = 2.5;
+        world
+            .entity_mut(child)
+            .insert(Stats {
+                tags: vec!["quote\"d".into()],
+                target: Some(root),
+                ..Default::default()
+            })
+            .with_child((Name::new("Not an identifier"), Team::Red));
+
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        let bsn = BsnWriter::new(world, &registry).write(root).unwrap();
+        assert_eq!(
+            bsn,
+            r#"#Root
+Stats { health: 10, speed: 2.5, offset: (1, -2) }
+Team::Blue { score: 3 }
+Children [
+    (
+        #Child
+        Stats { tags: ["quote\"d"], target: Some(#Root) }
+        Target { entity: #Root, label: "root" }
+        Children [
+            (
+                Name("Not an identifier")
+                Team::Red
+            ),
+        ]
+    ),
+]
+"#
+        );
+
+        let mut asset_server = world.resource::<AssetServer>().clone();
+        let scene = BsnScene::parse(&bsn, &registry, &mut asset_server).unwrap();
+        drop(registry);
+        let copy = world.spawn_scene(scene).unwrap().id();
+        let copy_child = world.entity(copy).get::<Children>().unwrap()[0];
+        assert_eq!(
+            world.entity(copy).get::<Stats>(),
+            world.entity(root).get::<Stats>()
+        );
+        assert_eq!(
+            world.entity(copy_child).get::<Stats>().unwrap().target,
+            Some(copy)
+        );
+        let grandchild = world.entity(copy_child).get::<Children>().unwrap()[0];
+        assert_eq!(
+            world.entity(grandchild).get::<Name>().unwrap().as_str(),
+            "Not an identifier"
+        );
+    }
+
+    #[test]
+    fn bsn_writer_inherited_patch() {
+        let dir = Dir::default();
+        dir.insert_asset_text(
+            Path::new("base.bsn"),
+            "Stats { health: 10, speed: 1.5 }\nChildren [ Team::Red ]",
+        );
+        let mut app = bsn_test_app(&dir);
+        app.register_type::<(Stats, Team, Target)>();
+        load_resolved_patch(&mut app, "base.bsn");
+
+        let world = app.world_mut();
+        let baseline = world.spawn_scene(bsn! { :"base.bsn" }).unwrap().id();
+        let root = world.spawn_scene(bsn! { :"base.bsn" }).unwrap().id();
+        world.entity_mut(root).get_mut::<Stats>().unwrap().health = 20;
+        world.entity_mut(root).with_child(Team::Blue { score: 1 });
+
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let bsn = BsnWriter::new(world, &registry.read())
+            .inherit("base.bsn", baseline)
+            .write(root)
+            .unwrap();
+        assert_eq!(
+            bsn,
+            r#":"base.bsn"
+Stats { health: 20 }
+Children [
+    (
+        Team::Blue { score: 1 }
+    ),
+]
+"#
+        );
+    }
+
+    #[test]
+    fn bsn_hot_reload_instances() {
+        #[derive(Component)]
+        struct Marker;
+
+        let dir = Dir::default();
+        let path = Path::new("unit.bsn");
+        dir.insert_asset_text(
+            path,
+            "#Unit\nStats { health: 10, speed: 1.5 }\nChildren [ Team::Red, Target { entity: #Unit, label: \"a\" } ]",
+        );
+        let mut app = App::new();
+        let reader_dir = dir.clone();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSourceBuilder::new(move || {
+                Box::new(MemoryAssetReader {
+                    root: reader_dir.clone(),
+                })
+            }),
+        );
+        app.add_plugins((
+            TaskPoolPlugin::default(),
+            AssetPlugin {
+                watch_for_changes_override: Some(true),
+                ..Default::default()
+            },
+            ScenePlugin,
+        ));
+        let reloaded = Arc::new(Mutex::new(Vec::new()));
+        let observed = reloaded.clone();
+        // Snapshots of the scene are spawned in a separate world, so they don't trigger observers
+        let teams_added = Arc::new(Mutex::new(0));
+        let observed_teams = teams_added.clone();
+        app.register_type::<(Stats, Team, Target)>()
+            .add_observer(move |event: On<ScenePatchInstanceReloaded>| {
+                observed.lock().unwrap().push(event.entity);
+            })
+            .add_observer(move |_: On<Add, Team>| {
+                *observed_teams.lock().unwrap() += 1;
+            });
+        let handle = load_resolved_patch(&mut app, "unit.bsn");
+        let asset_server = app.world().resource::<AssetServer>().clone();
+
+        let world = app.world_mut();
+        let instance = world.spawn((ScenePatchInstance(handle), Marker)).id();
+        app.update();
+
+        let world = app.world_mut();
+        let scene_children = world.entity(instance).get::<Children>().unwrap().to_vec();
+        assert_eq!(scene_children.len(), 2);
+        assert_eq!(*teams_added.lock().unwrap(), 1);
+        world.entity_mut(instance).get_mut::<Stats>().unwrap().tags = vec!["runtime".into()];
+        let runtime_child = world.spawn((ChildOf(instance), Team::Red)).id();
+
+        // Change fields, replace a component and add a child
+        dir.insert_asset_text(
+            path,
+            "#Unit\nStats { health: 20, speed: 1.5 }\nChildren [ Team::Blue { score: 2 }, Stats, Target { entity: #Unit, label: \"b\" } ]",
+        );
+        asset_server.reload("unit.bsn");
+        run_app_until(&mut app, || !reloaded.lock().unwrap().is_empty());
+
+        let world = app.world_mut();
+        assert_eq!(*reloaded.lock().unwrap(), vec![instance]);
+        let root = world.entity(instance);
+        assert!(root.contains::<Marker>());
+        let stats = root.get::<Stats>().unwrap();
+        assert_eq!(stats.health, 20);
+        assert_eq!(stats.speed, 1.5);
+        assert_eq!(stats.tags, vec!["runtime".to_string()]);
+
+        let children = root.get::<Children>().unwrap().to_vec();
+        assert_eq!(children.len(), 4);
+        assert_eq!(
+            children[..3],
+            [scene_children[0], scene_children[1], runtime_child]
+        );
+        assert_eq!(
+            world.get::<Team>(scene_children[0]),
+            Some(&Team::Blue { score: 2 })
+        );
+        assert!(!world.entity(scene_children[1]).contains::<Target>());
+        assert!(world.entity(scene_children[1]).contains::<Stats>());
+        assert_eq!(world.get::<Team>(runtime_child), Some(&Team::Red));
+        let target = world.get::<Target>(children[3]).unwrap();
+        assert_eq!(target.entity, instance);
+        assert_eq!(target.label, "b");
+        assert_eq!(*teams_added.lock().unwrap(), 2);
+
+        // Remove children
+        reloaded.lock().unwrap().clear();
+        dir.insert_asset_text(
+            path,
+            "#Unit\nStats { health: 20, speed: 1.5 }\nChildren [ Team::Blue { score: 2 } ]",
+        );
+        asset_server.reload("unit.bsn");
+        run_app_until(&mut app, || !reloaded.lock().unwrap().is_empty());
+
+        let world = app.world();
+        let children = world.entity(instance).get::<Children>().unwrap().to_vec();
+        assert_eq!(children, [scene_children[0], runtime_child]);
+        assert!(world.get_entity(scene_children[1]).is_err());
+    }
+
     #[test]
     fn inline_scene_patching() {
         let mut app = test_app();
@@ -0,0 +1,541 @@
+use crate::{
+    ApplySceneError, ReflectRelatedScenes, ResolvedSceneRoot, ScenePatch, ScenePatchInstance,
+};
+use alloc::{boxed::Box, vec::Vec};
+use bevy_asset::{AssetId, AssetServer, Assets};
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    component::{Component, ComponentInfo},
+    entity::{Entity, EntityHashMap},
+    event::EntityEvent,
+    query::With,
+    reflect::{AppTypeRegistry, ReflectComponent},
+    world::World,
+};
+use bevy_platform::collections::HashSet;
+use bevy_reflect::{PartialReflect, Reflect, ReflectMut, ReflectRef, TypeRegistry};
+use core::any::TypeId;
+use tracing::{error, warn};
+
+/// Triggered on a [`ScenePatchInstance`] entity after a reloaded [`ScenePatch`] was applied to it.
+///
+/// Instances are only updated while the [`AssetServer`] is watching for changes. Only the components and fields that
+/// changed in the scene are written, so runtime state (including components and related entities added at runtime) is kept.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EntityEvent)]
+pub struct ScenePatchInstanceReloaded {
+    /// The [`ScenePatchInstance`] entity that was updated.
+    pub entity: Entity,
+    /// The [`ScenePatch`] that changed. This is either the instance's own scene, or a scene it inherits from.
+    pub changed: AssetId<ScenePatch>,
+}
+
+/// What the current resolution of a [`ScenePatchInstance`]'s scene spawned, which is diffed against the next resolution
+/// when the scene is reloaded.
+#[derive(Component)]
+pub(crate) struct SceneInstanceSnapshot(SnapshotNode);
+
+/// The reflected components of a scene entity, and its related scene entities.
+///
+/// Snapshots are captured in a separate [`World`], see [`capture_resolved`]. They are then mapped to the entities of
+/// the instance, and entity references in the component values point to these entities.
+struct SnapshotNode {
+    entity: Entity,
+    components: Vec<(TypeId, Box<dyn Reflect>)>,
+    /// The related entities, grouped by the [`TypeId`] of their [`RelationshipTarget`](bevy_ecs::relationship::RelationshipTarget).
+    related: Vec<(TypeId, Vec<SnapshotNode>)>,
+}
+
+impl SnapshotNode {
+    /// Captures the components of `entity` and its related entities that can be reflected.
+    fn capture(
+        world: &World,
+        registry: &TypeRegistry,
+        relationships: &HashSet<TypeId>,
+        entity: Entity,
+    ) -> Self {
+        let entity_ref = world.entity(entity);
+        let mut node = SnapshotNode {
+            entity,
+            components: Vec::new(),
+            related: Vec::new(),
+        };
+        for id in entity_ref.archetype().components() {
+            let Some(type_id) = world
+                .components()
+                .get_info(*id)
+                .and_then(ComponentInfo::type_id)
+            else {
+                continue;
+            };
+            let Some(registration) = registry.get(type_id) else {
+                continue;
+            };
+            if let Some(related) = registration.data::<ReflectRelatedScenes>() {
+                let nodes = related
+                    .related_entities(entity_ref)
+                    .into_iter()
+                    .map(|related| Self::capture(world, registry, relationships, related))
+                    .collect();
+                node.related.push((type_id, nodes));
+                continue;
+            }
+            if relationships.contains(&type_id) {
+                continue;
+            }
+            if let Some(value) = registration
+                .data::<ReflectComponent>()
+                .and_then(|reflect_component| reflect_component.reflect(entity_ref))
+                .and_then(|value| value.reflect_clone().ok())
+            {
+                node.components.push((type_id, value));
+            }
+        }
+        node
+    }
+
+    fn component(&self, type_id: TypeId) -> Option<&dyn Reflect> {
+        self.components
+            .iter()
+            .find(|(id, _)| *id == type_id)
+            .map(|(_, value)| &**value)
+    }
+
+    fn related(&self, type_id: TypeId) -> &[SnapshotNode] {
+        self.related
+            .iter()
+            .find(|(id, _)| *id == type_id)
+            .map_or(&[], |(_, nodes)| nodes)
+    }
+
+    /// Pairs the nodes of this snapshot with the entities of the instance spawned from the same scene, starting at `entity`.
+    ///
+    /// The scene's related entities are spawned last, so they are the last entities of the instance's related entities.
+    /// This must be called right after the instance was spawned, before its related entities can be reordered: reloads
+    /// then match the entities stored in the snapshot, see [`match_snapshot`](Self::match_snapshot).
+    fn match_instance(
+        &self,
+        world: &World,
+        registry: &TypeRegistry,
+        entity: Entity,
+        map: &mut EntityHashMap<Entity>,
+    ) {
+        map.insert(self.entity, entity);
+        let Ok(entity_ref) = world.get_entity(entity) else {
+            return;
+        };
+        for (type_id, nodes) in &self.related {
+            let Some(related) = registry.get_type_data::<ReflectRelatedScenes>(*type_id) else {
+                continue;
+            };
+            let entities = related.related_entities(entity_ref);
+            let skip = entities.len().saturating_sub(nodes.len());
+            for (node, entity) in nodes.iter().zip(entities.into_iter().skip(skip)) {
+                node.match_instance(world, registry, entity, map);
+            }
+        }
+    }
+
+    /// Pairs the nodes of this snapshot with the nodes of the `old` snapshot at the same position.
+    ///
+    /// Nodes without a counterpart in `old` were added to the scene, and are paired with a new empty entity.
+    fn match_snapshot(
+        &self,
+        old: Option<&SnapshotNode>,
+        world: &mut World,
+        map: &mut EntityHashMap<Entity>,
+    ) {
+        let entity = match old {
+            Some(old) => old.entity,
+            None => world.spawn_empty().id(),
+        };
+        map.insert(self.entity, entity);
+        for (type_id, nodes) in &self.related {
+            let old_nodes = old.map_or(&[][..], |old| old.related(*type_id));
+            for (index, node) in nodes.iter().enumerate() {
+                node.match_snapshot(old_nodes.get(index), world, map);
+            }
+        }
+    }
+
+    /// Maps this node's entity, and the entity references in its component values.
+    ///
+    /// Nodes which aren't in `map` don't have an entity in the instance, and are mapped to [`Entity::PLACEHOLDER`].
+    fn map_entities(&mut self, map: &EntityHashMap<Entity>) {
+        self.entity = map
+            .get(&self.entity)
+            .copied()
+            .unwrap_or(Entity::PLACEHOLDER);
+        for (_, value) in &mut self.components {
+            map_reflected_entities(value.as_partial_reflect_mut(), map);
+        }
+        for (_, nodes) in &mut self.related {
+            for node in nodes {
+                node.map_entities(map);
+            }
+        }
+    }
+
+    /// Inserts the components of this node and its related nodes into their entities, which were added to the instance.
+    fn insert_added(&self, world: &mut World, registry: &TypeRegistry) {
+        let Ok(mut entity) = world.get_entity_mut(self.entity) else {
+            // The entity was despawned, or isn't part of the instance.
+            return;
+        };
+        for (type_id, value) in &self.components {
+            if let Some(reflect_component) = registry.get_type_data::<ReflectComponent>(*type_id) {
+                reflect_component.insert(&mut entity, value.as_partial_reflect(), registry);
+            }
+        }
+        for (type_id, nodes) in &self.related {
+            let Some(related) = registry.get_type_data::<ReflectRelatedScenes>(*type_id) else {
+                continue;
+            };
+            for node in nodes {
+                node.insert_added(world, registry);
+                if let Ok(mut related_entity) = world.get_entity_mut(node.entity) {
+                    related.relate(&mut related_entity, self.entity);
+                }
+            }
+        }
+    }
+
+    /// Writes the changes between the `old` snapshot and this one to the instance.
+    ///
+    /// Both snapshots must be mapped to the entities of the instance, see [`match_snapshot`](Self::match_snapshot).
+    /// Related entities added to the scene only get the components captured in the snapshot.
+    fn apply_changes(&self, old: &SnapshotNode, world: &mut World, registry: &TypeRegistry) {
+        let Ok(mut entity) = world.get_entity_mut(self.entity) else {
+            // The entity was despawned at runtime.
+            return;
+        };
+        for (type_id, value) in &self.components {
+            let Some(reflect_component) = registry.get_type_data::<ReflectComponent>(*type_id)
+            else {
+                continue;
+            };
+            match old.component(*type_id) {
+                None => reflect_component.insert(&mut entity, value.as_partial_reflect(), registry),
+                Some(old_value) => {
+                    if let Some(mut current) = reflect_component.reflect_mut(&mut entity) {
+                        patch_value(
+                            old_value.as_partial_reflect(),
+                            value.as_partial_reflect(),
+                            current.as_partial_reflect_mut(),
+                        );
+                    }
+                }
+            }
+        }
+        for (type_id, _) in &old.components {
+            if self.component(*type_id).is_none()
+                && let Some(reflect_component) =
+                    registry.get_type_data::<ReflectComponent>(*type_id)
+            {
+                reflect_component.remove(&mut entity);
+            }
+        }
+
+        for (type_id, nodes) in &self.related {
+            let old_nodes = old.related(*type_id);
+            for (node, old) in nodes.iter().zip(old_nodes) {
+                node.apply_changes(old, world, registry);
+            }
+            let Some(related) = registry.get_type_data::<ReflectRelatedScenes>(*type_id) else {
+                continue;
+            };
+            for node in nodes.iter().skip(old_nodes.len()) {
+                node.insert_added(world, registry);
+                if let Ok(mut related_entity) = world.get_entity_mut(node.entity) {
+                    related.relate(&mut related_entity, self.entity);
+                }
+            }
+        }
+        for (type_id, old_nodes) in &old.related {
+            for old in old_nodes.iter().skip(self.related(*type_id).len()) {
+                if let Ok(entity) = world.get_entity_mut(old.entity) {
+                    entity.despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Maps every [`Entity`] in a reflected value. Unlike [`Component::map_entities`], this doesn't require the entity
+/// fields to be marked.
+fn map_reflected_entities(value: &mut dyn PartialReflect, map: &EntityHashMap<Entity>) {
+    if let Some(entity) = value.try_downcast_mut::<Entity>() {
+        *entity = map.get(entity).copied().unwrap_or(*entity);
+        return;
+    }
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(index) {
+                    map_reflected_entities(field, map);
+                }
+            }
+        }
+        ReflectMut::TupleStruct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    map_reflected_entities(field, map);
+                }
+            }
+        }
+        ReflectMut::Tuple(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_mut(index) {
+                    map_reflected_entities(field, map);
+                }
+            }
+        }
+        ReflectMut::List(value) => {
+            for index in 0..value.len() {
+                if let Some(item) = value.get_mut(index) {
+                    map_reflected_entities(item, map);
+                }
+            }
+        }
+        ReflectMut::Array(value) => {
+            for index in 0..value.len() {
+                if let Some(item) = value.get_mut(index) {
+                    map_reflected_entities(item, map);
+                }
+            }
+        }
+        ReflectMut::Enum(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_at_mut(index) {
+                    map_reflected_entities(field, map);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Writes the parts of `new` that differ from `old` to `current`, and keeps the rest of `current`.
+fn patch_value(
+    old: &dyn PartialReflect,
+    new: &dyn PartialReflect,
+    current: &mut dyn PartialReflect,
+) {
+    if old.reflect_partial_eq(new) == Some(true) {
+        return;
+    }
+    match (old.reflect_ref(), new.reflect_ref(), current.reflect_mut()) {
+        (ReflectRef::Struct(old), ReflectRef::Struct(new), ReflectMut::Struct(current)) => {
+            for index in 0..new.field_len() {
+                if let (Some(old), Some(new), Some(current)) = (
+                    old.field_at(index),
+                    new.field_at(index),
+                    current.field_at_mut(index),
+                ) {
+                    patch_value(old, new, current);
+                }
+            }
+        }
+        (
+            ReflectRef::TupleStruct(old),
+            ReflectRef::TupleStruct(new),
+            ReflectMut::TupleStruct(current),
+        ) => {
+            for index in 0..new.field_len() {
+                if let (Some(old), Some(new), Some(current)) =
+                    (old.field(index), new.field(index), current.field_mut(index))
+                {
+                    patch_value(old, new, current);
+                }
+            }
+        }
+        (ReflectRef::Tuple(old), ReflectRef::Tuple(new), ReflectMut::Tuple(current)) => {
+            for index in 0..new.field_len() {
+                if let (Some(old), Some(new), Some(current)) =
+                    (old.field(index), new.field(index), current.field_mut(index))
+                {
+                    patch_value(old, new, current);
+                }
+            }
+        }
+        (ReflectRef::Enum(old), ReflectRef::Enum(new), ReflectMut::Enum(current))
+            if old.variant_name() == new.variant_name()
+                && current.variant_name() == new.variant_name() =>
+        {
+            for index in 0..new.field_len() {
+                if let (Some(old), Some(new), Some(current)) = (
+                    old.field_at(index),
+                    new.field_at(index),
+                    current.field_at_mut(index),
+                ) {
+                    patch_value(old, new, current);
+                }
+            }
+        }
+        _ => {
+            if let Err(err) = current.try_apply(new) {
+                warn!("Failed to apply a reloaded scene value: {err}");
+            }
+        }
+    }
+}
+
+/// Records what the resolved scene spawns for the [`ScenePatchInstance`] `entity`, so it can be updated when the
+/// scene is reloaded. This is only done while the [`AssetServer`] watches for changes.
+pub(crate) fn track_instance(world: &mut World, entity: Entity, resolved: &ResolvedSceneRoot) {
+    if !world
+        .get_resource::<AssetServer>()
+        .is_some_and(AssetServer::watching_for_changes)
+    {
+        return;
+    }
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let Ok(mut snapshot) = capture_resolved(world, &registry, &relationships(&registry), resolved)
+    else {
+        return;
+    };
+    let mut map = EntityHashMap::default();
+    snapshot.match_instance(world, &registry, entity, &mut map);
+    snapshot.map_entities(&map);
+    world
+        .entity_mut(entity)
+        .insert(SceneInstanceSnapshot(snapshot));
+}
+
+/// Updates the tracked [`ScenePatchInstance`]s affected by the newly loaded [`ScenePatch`] `changed`, and triggers
+/// [`ScenePatchInstanceReloaded`] for them.
+///
+/// Scenes loaded from a path that inherit from `changed` are reloaded, so that they are resolved again. Their instances
+/// are updated once they have loaded.
+pub(crate) fn reload_instances(world: &mut World, changed: AssetId<ScenePatch>) {
+    let Some(asset_server) = world.get_resource::<AssetServer>().cloned() else {
+        return;
+    };
+    if !asset_server.watching_for_changes() {
+        return;
+    }
+    let mut instances =
+        world.query_filtered::<(Entity, &ScenePatchInstance), With<SceneInstanceSnapshot>>();
+    let patches = world.resource::<Assets<ScenePatch>>();
+    for (id, patch) in patches.iter() {
+        if patch.resolved.is_some()
+            && patch
+                .dependencies
+                .iter()
+                .any(|dependency| dependency.id() == changed.untyped())
+            && let Some(path) = asset_server.get_path(id)
+        {
+            asset_server.reload(path);
+        }
+    }
+    let reloaded = instances
+        .iter(world)
+        .filter(|(_, instance)| {
+            instance.id() == changed
+                || (instance.path().is_none() && depends_on(patches, instance.id(), changed))
+        })
+        .filter_map(|(entity, instance)| {
+            let resolved = patches.get(instance.id())?.resolved.clone()?;
+            Some((entity, resolved))
+        })
+        .collect::<Vec<_>>();
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let relationships = relationships(&registry);
+    for (entity, resolved) in reloaded {
+        match reload_instance(world, &registry, &relationships, entity, &resolved) {
+            Ok(()) => {
+                world
+                    .commands()
+                    .trigger(ScenePatchInstanceReloaded { entity, changed });
+            }
+            Err(err) => error!("Failed to reload scene (id: {changed}) on entity {entity}: {err}"),
+        }
+    }
+}
+
+fn reload_instance(
+    world: &mut World,
+    registry: &TypeRegistry,
+    relationships: &HashSet<TypeId>,
+    entity: Entity,
+    resolved: &ResolvedSceneRoot,
+) -> Result<(), ApplySceneError> {
+    let Some(SceneInstanceSnapshot(old)) = world.entity_mut(entity).take::<SceneInstanceSnapshot>()
+    else {
+        return Ok(());
+    };
+    // Capture the new version of the scene separately, to find out what changed
+    let mut new = match capture_resolved(world, registry, relationships, resolved) {
+        Ok(new) => new,
+        Err(err) => {
+            world.entity_mut(entity).insert(SceneInstanceSnapshot(old));
+            return Err(err);
+        }
+    };
+    let mut map = EntityHashMap::default();
+    new.match_snapshot(Some(&old), world, &mut map);
+    new.map_entities(&map);
+    new.apply_changes(&old, world, registry);
+    world.entity_mut(entity).insert(SceneInstanceSnapshot(new));
+    Ok(())
+}
+
+/// Captures what `resolved` spawns, by spawning it in a scratch [`World`] rather than in `world`, so that the hooks and
+/// observers of the app don't run for it.
+///
+/// The scratch world shares the [`AppTypeRegistry`] and [`AssetServer`] of `world` (if it has one), and borrows its
+/// [`Assets<ScenePatch>`] to spawn inherited scenes.
+fn capture_resolved(
+    world: &mut World,
+    registry: &TypeRegistry,
+    relationships: &HashSet<TypeId>,
+    resolved: &ResolvedSceneRoot,
+) -> Result<SnapshotNode, ApplySceneError> {
+    let mut scratch = World::new();
+    scratch.insert_resource(world.resource::<AppTypeRegistry>().clone());
+    if let Some(asset_server) = world.get_resource::<AssetServer>() {
+        scratch.insert_resource(asset_server.clone());
+    }
+    scratch.insert_resource(core::mem::take(
+        world
+            .resource_mut::<Assets<ScenePatch>>()
+            .bypass_change_detection(),
+    ));
+    let snapshot = resolved
+        .spawn(&mut scratch)
+        .map(|entity| entity.id())
+        .map(|entity| SnapshotNode::capture(&scratch, registry, relationships, entity));
+    if let Some(patches) = scratch.remove_resource::<Assets<ScenePatch>>() {
+        *world
+            .resource_mut::<Assets<ScenePatch>>()
+            .bypass_change_detection() = patches;
+    }
+    snapshot
+}
+
+/// The [`Relationship`](bevy_ecs::relationship::Relationship) components of related scenes, which are not captured.
+fn relationships(registry: &TypeRegistry) -> HashSet<TypeId> {
+    registry
+        .iter_with_data::<ReflectRelatedScenes>()
+        .map(|(_, related)| related.relationship_type_id())
+        .collect()
+}
+
+/// Returns true if the [`ScenePatch`] `patch` inherits from `dependency`, directly or indirectly.
+fn depends_on(
+    patches: &Assets<ScenePatch>,
+    patch: AssetId<ScenePatch>,
+    dependency: AssetId<ScenePatch>,
+) -> bool {
+    patches.get(patch).is_some_and(|patch| {
+        patch.dependencies.iter().any(|handle| {
+            handle.id() == dependency.untyped()
+                || handle
+                    .id()
+                    .try_typed::<ScenePatch>()
+                    .is_ok_and(|id| depends_on(patches, id, dependency))
+        })
+    })
+}
@@ -357,7 +357,7 @@ impl ResolvedScene {
     ) -> Result<(), ApplySceneError> {
         self.set_current_entity_in_scope(context);
         for template in &self.component_templates {
-            if skip_templates.should_skip((**template).type_id()) {
+            if skip_templates.should_skip((**template).template_type_id()) {
                 continue;
             }
             // SAFETY: bundle_writer is used with the same World across all template.apply calls,
@@ -458,8 +458,14 @@ impl ResolvedScene {
         &'a mut self,
         context: &mut ResolveContext,
     ) -> &'a mut T {
-        (self.get_or_insert_erased_template(context, TypeId::of::<T>(), || Box::new(T::default()))
-            as &mut dyn Any)
+        let template = self.get_or_insert_erased_template_box(context, TypeId::of::<T>(), || {
+            Box::new(T::default())
+        });
+        // Templates loaded from `.bsn` assets are stored in a reflected form under the same key.
+        if !(&**template as &dyn Any).is::<T>() {
+            *template = Box::new(crate::bsn::typed_template::<T>(&**template));
+        }
+        (&mut **template as &mut dyn Any)
             // PERF: this could be unchecked, given that we control what is stored here
             // The method isn't stable yet, and it would require making get_or_insert_erased_template unsafe
             .downcast_mut()
@@ -481,6 +487,16 @@ impl ResolvedScene {
         type_id: TypeId,
         default: fn() -> Box<dyn ErasedComponentTemplate>,
     ) -> &'a mut dyn ErasedComponentTemplate {
+        &mut **self.get_or_insert_erased_template_box(context, type_id, default)
+    }
+
+    /// Same as [`ResolvedScene::get_or_insert_erased_template`], but returns the boxed template, which allows replacing it.
+    pub(crate) fn get_or_insert_erased_template_box<'a>(
+        &'a mut self,
+        context: &mut ResolveContext,
+        type_id: TypeId,
+        default: impl FnOnce() -> Box<dyn ErasedComponentTemplate>,
+    ) -> &'a mut Box<dyn ErasedComponentTemplate> {
         let mut is_inherited = false;
         let index = self.template_indices.entry(type_id).or_insert_with(|| {
             let index = self.component_templates.len();
@@ -497,11 +513,7 @@ impl ResolvedScene {
             self.component_templates.push(value);
             index
         });
-        let template = self
-            .component_templates
-            .get_mut(*index)
-            .map(|value| &mut **value)
-            .unwrap();
+        let template = self.component_templates.get_mut(*index).unwrap();
 
         if is_inherited {
             self.inherited
@@ -559,6 +571,16 @@ impl ResolvedScene {
             .or_insert_with(RelatedResolvedScenes::new::<R>)
     }
 
+    /// Same as [`ResolvedScene::get_or_insert_related_resolved_scenes`], for the [`Relationship`] with the given `type_id`.
+    /// _For correctness, `new` should create the [`RelatedResolvedScenes`] of that [`Relationship`]_.
+    pub fn get_or_insert_related_resolved_scenes_erased(
+        &mut self,
+        type_id: TypeId,
+        new: impl FnOnce() -> RelatedResolvedScenes,
+    ) -> &mut RelatedResolvedScenes {
+        self.related.entry(type_id).or_insert_with(new)
+    }
+
     /// Configures this [`ResolvedScene`] to inherit from the given [`ScenePatch`].
     ///
     /// If this [`ResolvedScene`] already inherits from a scene, it will return [`InheritSceneError::MultipleInheritance`].
@@ -724,6 +746,12 @@ pub trait ErasedComponentTemplate: Any + Send + Sync {
 
     /// Clones this template. See [`Clone`].
     fn clone_template(&self) -> Box<dyn ErasedComponentTemplate>;
+
+    /// The [`TypeId`] this template is registered under in a [`ResolvedScene`]. This is the [`TypeId`] of the template itself,
+    /// unless it is a type-erased stand-in for another template type.
+    fn template_type_id(&self) -> TypeId {
+        TypeId::of::<Self>()
+    }
 }
 
 impl<T: Template<Output: Component> + Send + Sync + 'static> ErasedComponentTemplate for T {
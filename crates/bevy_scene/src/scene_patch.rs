@@ -106,6 +106,9 @@ pub enum SpawnSceneError {
 }
 
 /// A component that, when added, will queue applying the given [`ScenePatch`] after the scene and its dependencies have been loaded and resolved.
+///
+/// If the [`ScenePatch`] is reloaded while the [`AssetServer`] watches for changes, the changes are applied to the
+/// entity in place. See [`ScenePatchInstanceReloaded`](crate::ScenePatchInstanceReloaded).
 #[derive(Component, FromTemplate, Deref, DerefMut)]
 pub struct ScenePatchInstance(pub Handle<ScenePatch>);
 
@@ -1,4 +1,5 @@
 use crate::{
+    reload::{reload_instances, track_instance},
     ResolvedSceneRoot, Scene, SceneList, SceneListPatch, ScenePatch, ScenePatchInstance,
     SpawnSceneError,
 };
@@ -707,20 +708,24 @@ pub fn spawn_queued(
 
             world.resource_scope(|world, events: Mut<Messages<AssetEvent<ScenePatch>>>| {
                 for event in reader.read(&events) {
+                    if let AssetEvent::LoadedWithDependencies { id } = event {
+                        reload_instances(world, *id);
+                    }
                     let patches = world.resource::<Assets<ScenePatch>>();
                     if let AssetEvent::LoadedWithDependencies { id } = event
                         && let Some(resolved) = patches.get(*id).and_then(|p| p.resolved.clone())
                         && let Some(entities) = waiting.scene_entities.remove(id)
                     {
                         for entity in entities {
-                            if let Ok(mut entity_mut) = world.get_entity_mut(entity)
-                                && let Err(err) =
-                                    resolved.apply(&mut entity_mut, &mut bundle_scratch)
-                            {
-                                error!(
+                            let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+                                continue;
+                            };
+                            match resolved.apply(&mut entity_mut, &mut bundle_scratch) {
+                                Ok(()) => track_instance(world, entity, &resolved),
+                                Err(err) => error!(
                                     "Failed to apply scene (id: {}) to entity {entity}: {}",
                                     id, err
-                                );
+                                ),
                             }
                         }
                     }
@@ -794,6 +799,8 @@ impl QueuedScenes {
                         "Failed to apply scene (id: {id}, path: {path:?}) to \
                                     entity {entity}: {err}",
                     );
+                } else {
+                    track_instance(world, entity, &resolved);
                 }
             } else {
                 let entities = waiting_scenes
@@ -13,6 +13,9 @@
 // Required to make proc macros work in bevy itself.
 extern crate self as bevy_settings;
 
+extern crate alloc;
+
+use alloc::{collections::BTreeMap, sync::Arc};
 use core::any::TypeId;
 use core::time::Duration;
 use std::collections::HashMap;
@@ -26,13 +29,16 @@ use bevy_ecs::{
     world::World,
 };
 pub use bevy_ecs_macros::SettingsGroup;
-use bevy_log::warn;
+use bevy_log::{error, warn};
 use bevy_reflect::{
     prelude::ReflectDefault,
     serde::{TypedReflectDeserializer, TypedReflectSerializer},
     FromReflect, FromType, PartialReflect, ReflectMut, TypeInfo, TypePath, TypeRegistration,
     TypeRegistry,
 };
+use thiserror::Error;
+
+mod store;
 
 #[cfg(not(target_arch = "wasm32"))]
 mod store_fs;
@@ -42,11 +48,13 @@ mod store_wasm;
 
 use bevy_time::{Time, Timer, TimerMode};
 use serde::de::DeserializeSeed;
+pub use store::*;
+
 #[cfg(not(target_arch = "wasm32"))]
-use store_fs::PreferencesStore;
+pub use store_fs::FilePreferencesStore;
 
 #[cfg(target_arch = "wasm32")]
-use store_wasm::PreferencesStore;
+pub use store_wasm::WebStoragePreferencesStore;
 
 /// Plugin to orchestrate loading and saving of user preferences.
 ///
@@ -64,6 +72,20 @@ use store_wasm::PreferencesStore;
 /// Adding this plugin causes an immediate load of preferences (from either the filesystem or
 /// browser local storage, depending on platform).
 ///
+/// Preferences can be stored elsewhere, or in another format, by choosing a different
+/// [`PreferencesStore`] with [`PreferencesPlugin::with_store`]:
+///
+/// ```
+/// # use bevy_settings::{FilePreferencesStore, MemoryPreferencesStore, PreferencesPlugin};
+/// // Keep preferences in a folder that is synced between machines.
+/// let plugin = PreferencesPlugin::new("com.example.myapp")
+///     .with_store(FilePreferencesStore::in_dir("/home/user/Sync/myapp"));
+///
+/// // Don't persist anything, for example in tests.
+/// let plugin = PreferencesPlugin::new("com.example.myapp")
+///     .with_store(MemoryPreferencesStore::new());
+/// ```
+///
 /// When using this plugin, care must be taken to ensure that plugins execute in the proper order.
 /// Loading preferences causes registered settings to be inserted into the world as bevy resources.
 /// You cannot access these values before they are loaded, but you may want to use the loaded values
@@ -89,9 +111,46 @@ use store_wasm::PreferencesStore;
 /// Saving is crash-resistant: if the app crashes in the middle of a save, the preferences file
 /// will not be corrupted (it writes to a temporary file first, then uses atomic operations to
 /// replace the previous file).
+///
+/// # Migrations
+///
+/// Each preferences file records a version number (in its top-level `__version` key, which is
+/// reserved for this purpose and can't be used as the name of a settings group). When the layout of a settings group changes, for example because
+/// a field was renamed, register a migration with [`PreferencesPlugin::with_migration`] to
+/// transform files saved by older versions of the app. The current version of a file is one more
+/// than the highest version migrated from by the settings groups it contains, or 0 if there are
+/// no migrations.
+///
+/// ```
+/// # use bevy_ecs::{reflect::ReflectResource, resource::Resource};
+/// # use bevy_reflect::{prelude::ReflectDefault, Reflect};
+/// # use bevy_settings::{PreferencesPlugin, ReflectSettingsGroup, SettingsGroup};
+/// #[derive(Resource, SettingsGroup, Reflect, Default)]
+/// #[reflect(Resource, SettingsGroup, Default)]
+/// struct AudioSettings {
+///     // This used to be called `volume`.
+///     master_volume: f32,
+/// }
+///
+/// let plugin = PreferencesPlugin::new("com.example.myapp").with_migration::<AudioSettings>(
+///     0,
+///     |table| {
+///         let Some(audio) = table.get_mut("audio_settings").and_then(|v| v.as_table_mut()) else {
+///             return;
+///         };
+///         if let Some(volume) = audio.remove("volume") {
+///             audio.insert("master_volume".to_string(), volume);
+///         }
+///     },
+/// );
+/// ```
 pub struct PreferencesPlugin {
     /// The unique name of the application.
     pub app_name: String,
+    /// The migrations registered for each settings group.
+    migrations: Vec<RegisteredMigration>,
+    /// The store used to load and save preferences, if not the default one.
+    store: Option<Arc<dyn PreferencesStore>>,
 }
 
 impl PreferencesPlugin {
@@ -99,13 +158,79 @@ impl PreferencesPlugin {
     pub fn new(app_name: &str) -> Self {
         Self {
             app_name: app_name.to_string(),
+            migrations: Vec::new(),
+            store: None,
+        }
+    }
+
+    /// Set the [`PreferencesStore`] used to load and save preferences.
+    ///
+    /// By default, this is a [`FilePreferencesStore`] in the OS-specific directory for user
+    /// preferences, or a `WebStoragePreferencesStore` when targeting the web.
+    #[must_use]
+    pub fn with_store(mut self, store: impl PreferencesStore) -> Self {
+        self.store = Some(Arc::new(store));
+        self
+    }
+
+    /// Returns the [`PreferencesStore`] to use, creating the default one if none was set.
+    fn store(&self) -> Arc<dyn PreferencesStore> {
+        match &self.store {
+            Some(store) => store.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            None => Arc::new(FilePreferencesStore::new(&self.app_name)),
+            #[cfg(target_arch = "wasm32")]
+            None => Arc::new(WebStoragePreferencesStore::new(&self.app_name)),
         }
     }
+
+    /// Registers a migration for the file containing the settings group `T`, which transforms
+    /// the raw contents of that file from version `from_version` to `from_version + 1`.
+    ///
+    /// When a file is loaded, all the migrations from its saved version up to the current version
+    /// are run in order, before any values are deserialized. Migrations from the same version
+    /// registered by different settings groups sharing a file run in registration order.
+    #[must_use]
+    pub fn with_migration<T: SettingsGroup>(
+        mut self,
+        from_version: u32,
+        migration: impl Fn(&mut toml::Table) + Send + Sync + 'static,
+    ) -> Self {
+        self.migrations.push(RegisteredMigration {
+            type_id: TypeId::of::<T>(),
+            from_version,
+            migration: Arc::new(migration),
+        });
+        self
+    }
+}
+
+/// The top-level key in each preferences file which holds the file's version.
+///
+/// Settings groups with this name are ignored, so that they can't overwrite the version.
+const VERSION_KEY: &str = "__version";
+
+/// A function which transforms the contents of a settings file from one version to the next.
+type SettingsMigration = Arc<dyn Fn(&mut toml::Table) + Send + Sync>;
+
+/// A migration registered with [`PreferencesPlugin::with_migration`].
+struct RegisteredMigration {
+    /// The settings group the migration was registered for.
+    type_id: TypeId,
+    from_version: u32,
+    migration: SettingsMigration,
+}
+
+/// An error returned when a migration is registered from the last possible version, so the
+/// version it migrates to can't be represented.
+#[derive(Error, Debug)]
+#[error("a settings migration was registered from version {from_version}, which is the last possible version")]
+struct MigrationVersionOverflow {
+    from_version: u32,
 }
 
 impl Plugin for PreferencesPlugin {
     fn build(&self, app: &mut App) {
-        let app_name = self.app_name.clone();
         let world = app.world();
         let last_save = world.read_change_tick();
 
@@ -117,12 +242,17 @@ impl Plugin for PreferencesPlugin {
         let types = app_types.read();
 
         let world = app.world_mut();
-        let file_index = build_preferences_registry(&app_name, &types, last_save);
+        let mut file_index = build_preferences_registry(self.store(), &types, last_save);
+        if let Err(err) = add_migrations(&mut file_index, &self.migrations, &types) {
+            // Don't load files that can't be migrated, nor overwrite them when saving.
+            error!("Preferences will not be loaded or saved: {err}");
+            return;
+        }
 
         // Now load each of the toml files we discovered, and apply their properties to
         // the resources in the world.
         for (filename, manifest) in file_index.files.iter() {
-            load_settings_file(world, &app_name, filename, manifest, &types);
+            load_settings_file(world, &*file_index.store, filename, manifest, &types);
         }
 
         // Cache the index so that we don't have to do it again when saving (and also makes
@@ -192,6 +322,10 @@ impl<T: SettingsGroup + FromReflect + TypePath> FromType<T> for ReflectSettingsG
 struct PreferenceFileManifest {
     last_save: Tick,
     resource_types: Vec<TypeId>,
+    /// The current version of the file, which is written when saving it.
+    version: u32,
+    /// The migrations to run on the file when loading it, by the version they migrate from.
+    migrations: BTreeMap<u32, Vec<SettingsMigration>>,
 }
 
 /// Records the game tick when preferences were last loaded or saved. This is used to determine
@@ -199,8 +333,8 @@ struct PreferenceFileManifest {
 /// are associated with which resource types.
 #[derive(Resource)]
 struct PreferencesFileRegistry {
-    /// App name (from plugin)
-    app_name: String,
+    /// Where preferences are loaded from and saved to.
+    store: Arc<dyn PreferencesStore>,
 
     /// List of known preferences files, determined by scanning reflection registry.
     files: HashMap<&'static str, PreferenceFileManifest>,
@@ -287,11 +421,10 @@ fn save_preferences(world: &mut World, use_async: bool, force: bool) {
     for (filename, manifest) in registry.files.iter() {
         if force || has_preferences_changed(world, manifest) {
             let table = resources_to_toml(world, &types, manifest);
-            let store = PreferencesStore::new(&registry.app_name);
             if use_async {
-                store.save_async(filename, table);
+                registry.store.save_async(filename, table);
             } else {
-                store.save(filename, table);
+                registry.store.save(filename, table);
             }
         }
     }
@@ -380,6 +513,10 @@ fn resources_to_toml(
         };
     }
 
+    table.insert(
+        VERSION_KEY.to_string(),
+        toml::Value::Integer(manifest.version.into()),
+    );
     table
 }
 
@@ -389,14 +526,14 @@ fn resources_to_toml(
 /// Returns the [`PreferencesFileRegistry`] that tracks which resources are associated with
 /// which settings files.
 fn build_preferences_registry(
-    app_name: &str,
+    store: Arc<dyn PreferencesStore>,
     types: &TypeRegistry,
     last_save: Tick,
 ) -> PreferencesFileRegistry {
     // Build an index that remembers all of the resource types that are to be saved to
     // each individual settings file.
     let mut file_index = PreferencesFileRegistry {
-        app_name: app_name.to_string(),
+        store,
         files: HashMap::new(),
         save_timer: Timer::new(Duration::from_secs(1), TimerMode::Once),
     };
@@ -413,6 +550,14 @@ fn build_preferences_registry(
             continue;
         };
 
+        if reflect_group.settings_group_name == VERSION_KEY {
+            warn!(
+                "The settings group {} is named `{VERSION_KEY}`, which is reserved for the version of preferences files. It will not be loaded or saved.",
+                ty.type_info().type_path()
+            );
+            continue;
+        }
+
         // If no filename is specified, use "settings"
         let filename = reflect_group.settings_source.unwrap_or("settings");
         let pending_file = file_index
@@ -420,7 +565,7 @@ fn build_preferences_registry(
             .entry(filename)
             .or_insert(PreferenceFileManifest {
                 last_save,
-                resource_types: Vec::new(),
+                ..Default::default()
             });
         pending_file.last_save = last_save;
         pending_file.resource_types.push(ty.type_id());
@@ -429,19 +574,91 @@ fn build_preferences_registry(
     file_index
 }
 
+/// Adds the migrations registered with the plugin to the manifests of the files containing their
+/// settings groups, and updates the versions of those files.
+///
+/// Returns an error if a migration is registered from the last possible version.
+fn add_migrations(
+    file_index: &mut PreferencesFileRegistry,
+    migrations: &[RegisteredMigration],
+    types: &TypeRegistry,
+) -> Result<(), MigrationVersionOverflow> {
+    for registered in migrations {
+        let Some(reflect_group) = types
+            .get(registered.type_id)
+            .and_then(|ty| ty.data::<ReflectSettingsGroup>())
+        else {
+            warn!(
+                "A settings migration was registered for a type which is not a registered settings group"
+            );
+            continue;
+        };
+
+        let filename = reflect_group.settings_source.unwrap_or("settings");
+        let Some(manifest) = file_index.files.get_mut(filename) else {
+            continue;
+        };
+        let Some(to_version) = registered.from_version.checked_add(1) else {
+            return Err(MigrationVersionOverflow {
+                from_version: registered.from_version,
+            });
+        };
+        manifest.version = manifest.version.max(to_version);
+        manifest
+            .migrations
+            .entry(registered.from_version)
+            .or_default()
+            .push(registered.migration.clone());
+    }
+    Ok(())
+}
+
+/// Runs the migrations needed to bring the contents of a settings file up to the current version,
+/// and removes the version key from it.
+fn migrate_settings_table(
+    filename: &str,
+    table: &mut toml::Table,
+    manifest: &PreferenceFileManifest,
+) {
+    let version = match table.remove(VERSION_KEY) {
+        None => 0,
+        Some(toml::Value::Integer(version)) if version >= 0 => {
+            u32::try_from(version).unwrap_or(u32::MAX)
+        }
+        Some(version) => {
+            warn!("Invalid version {version} in preferences file {filename}, assuming version 0");
+            0
+        }
+    };
+
+    if version > manifest.version {
+        warn!(
+            "Preferences file {filename} has version {version}, which is newer than the current version {}. Loading it without migrating.",
+            manifest.version
+        );
+        return;
+    }
+
+    for (_, migrations) in manifest.migrations.range(version..manifest.version) {
+        for migration in migrations {
+            migration(table);
+        }
+    }
+}
+
 /// Loads a single settings file and applies its values to the world's resources.
 fn load_settings_file(
     world: &mut World,
-    app_name: &str,
+    store: &dyn PreferencesStore,
     filename: &str,
     manifest: &PreferenceFileManifest,
     types: &TypeRegistry,
 ) {
-    // Load the TOML file
-    let store = PreferencesStore::new(app_name);
-    let toml = store.load(filename);
-    if toml.is_none() {
-        warn!("Filename {filename}.toml not found");
+    // Load the preferences file
+    let mut toml = store.load(filename);
+    match &mut toml {
+        Some(toml) => migrate_settings_table(filename, toml, manifest),
+        None => warn!("Preferences file {filename} not found"),
     }
 
     apply_settings_to_world(world, toml.as_ref(), manifest, types);
@@ -641,14 +858,25 @@ mod tests {
         volume: f32,
     }
 
+    /// Test resource whose group name is reserved for the version of preferences files
+    #[derive(Resource, SettingsGroup, Reflect, Default)]
+    #[reflect(Resource, SettingsGroup, Default)]
+    #[settings_group(group = "__version", file = "reserved")]
+    struct ReservedSettings {
+        value: u32,
+    }
+
     #[test]
     fn test_build_registry_single_struct_resource() {
         let mut types = TypeRegistry::default();
         types.register::<CounterSettings>();
 
-        let registry = build_preferences_registry("test_app", &types, Tick::new(0));
+        let registry = build_preferences_registry(
+            Arc::new(MemoryPreferencesStore::new()),
+            &types,
+            Tick::new(0),
+        );
 
-        assert_eq!(registry.app_name, "test_app");
         assert_eq!(registry.files.len(), 1);
         assert!(registry.files.contains_key("settings"));
 
@@ -661,9 +889,12 @@ mod tests {
         let mut types = TypeRegistry::default();
         types.register::<CounterRefreshRateSettings>();
 
-        let registry = build_preferences_registry("test_app", &types, Tick::new(0));
+        let registry = build_preferences_registry(
+            Arc::new(MemoryPreferencesStore::new()),
+            &types,
+            Tick::new(0),
+        );
 
-        assert_eq!(registry.app_name, "test_app");
         assert_eq!(registry.files.len(), 1);
         assert!(registry.files.contains_key("settings"));
 
@@ -677,7 +908,11 @@ mod tests {
         types.register::<CounterSettings>();
         types.register::<ExtraCounterSettings>();
 
-        let registry = build_preferences_registry("test_app", &types, Tick::new(0));
+        let registry = build_preferences_registry(
+            Arc::new(MemoryPreferencesStore::new()),
+            &types,
+            Tick::new(0),
+        );
 
         // Both resources should be in the same file
         assert_eq!(registry.files.len(), 1);
@@ -694,7 +929,11 @@ mod tests {
         types.register::<CounterSettings>();
         types.register::<AudioSettings>();
 
-        let registry = build_preferences_registry("test_app", &types, Tick::new(0));
+        let registry = build_preferences_registry(
+            Arc::new(MemoryPreferencesStore::new()),
+            &types,
+            Tick::new(0),
+        );
 
         // Resources should be in different files
         assert_eq!(registry.files.len(), 2);
@@ -729,6 +968,7 @@ mod tests {
                 TypeId::of::<ExtraCounterSettings>(),
                 TypeId::of::<CounterRefreshRateSettings>(),
             ],
+            ..Default::default()
         };
 
         let table = resources_to_toml(&world, &types, &manifest);
@@ -904,6 +1144,7 @@ mod tests {
                 TypeId::of::<EnumSingleNewTypeVariant>(),
                 TypeId::of::<EnumMultiNewTypeVariant>(),
             ],
+            ..Default::default()
         };
 
         // Serialize to TOML
@@ -993,6 +1234,7 @@ mod tests {
                 TypeId::of::<CounterSettings>(),
                 TypeId::of::<CounterRefreshRateSettings>(),
             ],
+            ..Default::default()
         };
 
         // Serialize
@@ -1041,6 +1283,7 @@ mod tests {
                 TypeId::of::<ExtraCounterSettings>(),
                 TypeId::of::<CounterRefreshRateSettings>(),
             ],
+            ..Default::default()
         };
 
         // Apply the partial TOML
@@ -1058,4 +1301,192 @@ mod tests {
         let refresh_rate = world.get_resource::<CounterRefreshRateSettings>().unwrap();
         assert_eq!(*refresh_rate, CounterRefreshRateSettings::Fast);
     }
+
+    /// Plugin with migrations for the `settings` file: version 0 stored the counter in a
+    /// `[counter]` section, version 1 renamed `cnt` to `count` and stored `enabled` as a string.
+    fn counter_migrations() -> PreferencesPlugin {
+        PreferencesPlugin::new("test_app")
+            .with_migration::<CounterSettings>(0, |table| {
+                if let Some(counter) = table.remove("counter") {
+                    table.insert("counter_settings".to_string(), counter);
+                }
+            })
+            .with_migration::<CounterSettings>(1, |table| {
+                let counter = table["counter_settings"].as_table_mut().unwrap();
+                if let Some(count) = counter.remove("cnt") {
+                    counter.insert("count".to_string(), count);
+                }
+            })
+            .with_migration::<ExtraCounterSettings>(1, |table| {
+                let counter = table["counter_settings"].as_table_mut().unwrap();
+                if let Some(toml::Value::String(enabled)) = counter.get("enabled") {
+                    let enabled = enabled == "yes";
+                    counter.insert("enabled".to_string(), toml::Value::Boolean(enabled));
+                }
+            })
+    }
+
+    fn counter_registry(types: &TypeRegistry) -> PreferencesFileRegistry {
+        let mut registry = build_preferences_registry(
+            Arc::new(MemoryPreferencesStore::new()),
+            types,
+            Tick::new(0),
+        );
+        add_migrations(&mut registry, &counter_migrations().migrations, types).unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_add_migrations_sets_file_version() {
+        let mut types = TypeRegistry::default();
+        types.register::<CounterSettings>();
+        types.register::<ExtraCounterSettings>();
+        types.register::<AudioSettings>();
+
+        let registry = counter_registry(&types);
+
+        let settings_manifest = registry.files.get("settings").unwrap();
+        assert_eq!(settings_manifest.version, 2);
+        assert_eq!(settings_manifest.migrations[&0].len(), 1);
+        assert_eq!(settings_manifest.migrations[&1].len(), 2);
+
+        // Files without migrations stay at version 0
+        let audio_manifest = registry.files.get("audio").unwrap();
+        assert_eq!(audio_manifest.version, 0);
+        assert!(audio_manifest.migrations.is_empty());
+    }
+
+    #[test]
+    fn test_chained_migrations() {
+        let mut world = World::new();
+        let mut types = TypeRegistry::default();
+        types.register::<CounterSettings>();
+        types.register::<ExtraCounterSettings>();
+        world.insert_resource(CounterSettings::default());
+        world.insert_resource(ExtraCounterSettings::default());
+
+        let registry = counter_registry(&types);
+        let manifest = registry.files.get("settings").unwrap();
+
+        // A file without a version is migrated from version 0
+        let mut table: toml::Table = toml::from_str(
+            r#"
+            [counter]
+            cnt = 5
+            enabled = "yes"
+            "#,
+        )
+        .unwrap();
+        migrate_settings_table("settings", &mut table, manifest);
+        assert!(!table.contains_key(VERSION_KEY));
+        assert!(!table.contains_key("counter"));
+
+        apply_settings_to_world(&mut world, Some(&table), manifest, &types);
+        assert_eq!(world.resource::<CounterSettings>().count, 5);
+        assert!(world.resource::<ExtraCounterSettings>().enabled);
+
+        // A file at version 1 only runs the migrations from version 1
+        let mut table: toml::Table = toml::from_str(
+            r#"
+            __version = 1
+            [counter_settings]
+            cnt = 7
+            enabled = "no"
+            "#,
+        )
+        .unwrap();
+        migrate_settings_table("settings", &mut table, manifest);
+
+        apply_settings_to_world(&mut world, Some(&table), manifest, &types);
+        assert_eq!(world.resource::<CounterSettings>().count, 7);
+        assert!(!world.resource::<ExtraCounterSettings>().enabled);
+    }
+
+    #[test]
+    fn test_migration_from_last_version_is_rejected() {
+        let mut types = TypeRegistry::default();
+        types.register::<CounterSettings>();
+        let mut registry = build_preferences_registry(
+            Arc::new(MemoryPreferencesStore::new()),
+            &types,
+            Tick::new(0),
+        );
+
+        let plugin =
+            PreferencesPlugin::new("test_app").with_migration::<CounterSettings>(u32::MAX, |_| {});
+        assert!(add_migrations(&mut registry, &plugin.migrations, &types).is_err());
+    }
+
+    #[test]
+    fn test_reserved_group_name_is_ignored() {
+        let mut types = TypeRegistry::default();
+        types.register::<ReservedSettings>();
+        let registry = build_preferences_registry(
+            Arc::new(MemoryPreferencesStore::new()),
+            &types,
+            Tick::new(0),
+        );
+        assert!(!registry.files.contains_key("reserved"));
+    }
+
+    #[test]
+    fn test_current_version_is_saved_and_not_migrated() {
+        let mut world = World::new();
+        let mut types = TypeRegistry::default();
+        types.register::<CounterSettings>();
+        types.register::<ExtraCounterSettings>();
+        world.insert_resource(CounterSettings { count: 3 });
+        world.insert_resource(ExtraCounterSettings { enabled: true });
+
+        let registry = counter_registry(&types);
+        let manifest = registry.files.get("settings").unwrap();
+
+        let mut table = resources_to_toml(&world, &types, manifest);
+        assert_eq!(table[VERSION_KEY].as_integer(), Some(2));
+
+        // A file at the current version is loaded as is
+        migrate_settings_table("settings", &mut table, manifest);
+        assert!(!table.contains_key(VERSION_KEY));
+
+        *world.resource_mut::<CounterSettings>() = CounterSettings::default();
+        apply_settings_to_world(&mut world, Some(&table), manifest, &types);
+        assert_eq!(world.resource::<CounterSettings>().count, 3);
+
+        // Files saved by a newer version are loaded without migrating
+        let mut table: toml::Table = toml::from_str(
+            r#"
+            __version = 3
+            [counter_settings]
+            cnt = 7
+            "#,
+        )
+        .unwrap();
+        migrate_settings_table("settings", &mut table, manifest);
+        assert!(table["counter_settings"].get("cnt").is_some());
+    }
+
+    #[test]
+    fn test_plugin_uses_custom_store() {
+        let mut counter_section = toml::Table::new();
+        counter_section.insert("count".to_string(), toml::Value::Integer(42));
+        let mut table = toml::Table::new();
+        table.insert(
+            "counter_settings".to_string(),
+            toml::Value::Table(counter_section),
+        );
+        let store = MemoryPreferencesStore::new().with_file("settings", table);
+
+        let mut app = App::new();
+        app.register_type::<CounterSettings>();
+        app.add_plugins(PreferencesPlugin::new("test_app").with_store(store.clone()));
+
+        // Preferences are loaded from the store when the plugin is added
+        assert_eq!(app.world().resource::<CounterSettings>().count, 42);
+
+        app.world_mut().resource_mut::<CounterSettings>().count = 7;
+        SavePreferencesSync::Always.apply(app.world_mut());
+
+        let saved = store.get("settings").unwrap();
+        assert_eq!(saved["counter_settings"]["count"].as_integer(), Some(7));
+    }
 }
@@ -0,0 +1,165 @@
+use alloc::sync::Arc;
+use bevy_ecs::error::BevyError;
+use bevy_platform::sync::Mutex;
+use bevy_tasks::IoTaskPool;
+use std::collections::HashMap;
+
+/// Persistent storage for preferences files, used by the
+/// [`PreferencesPlugin`](crate::PreferencesPlugin) to load and save settings.
+///
+/// Each preferences file is identified by its name (without any file extension), and its
+/// contents are represented as a [`toml::Table`], regardless of how the store actually encodes
+/// them. Stores are responsible for reporting their own errors (for example, by logging them),
+/// since loading and saving preferences is never fatal to the app.
+///
+/// The default store is a [`FilePreferencesStore`](crate::FilePreferencesStore) on platforms with
+/// a filesystem, and a `WebStoragePreferencesStore` on the web. Use
+/// [`PreferencesPlugin::with_store`](crate::PreferencesPlugin::with_store) to choose another one.
+pub trait PreferencesStore: Send + Sync + 'static {
+    /// Loads the contents of a preferences file. If the file does not exist, or could not be
+    /// read, `None` will be returned.
+    ///
+    /// # Arguments
+    /// * `filename` - The name of the preferences file, without the file extension.
+    fn load(&self, filename: &str) -> Option<toml::Table>;
+
+    /// Saves the contents of a preferences file, replacing any previous contents.
+    ///
+    /// # Arguments
+    /// * `filename` - the name of the file to be saved
+    /// * `contents` - the contents of the file
+    fn save(&self, filename: &str, contents: toml::Table);
+
+    /// Saves the contents of a preferences file in another thread.
+    ///
+    /// By default, this calls [`PreferencesStore::save`] in an i/o task.
+    ///
+    /// # Arguments
+    /// * `filename` - the name of the file to be saved
+    /// * `contents` - the contents of the file
+    fn save_async(&self, filename: &str, contents: toml::Table) {
+        IoTaskPool::get().scope(|scope| {
+            scope.spawn(async {
+                self.save(filename, contents);
+            });
+        });
+    }
+}
+
+impl<T: PreferencesStore + ?Sized> PreferencesStore for Arc<T> {
+    fn load(&self, filename: &str) -> Option<toml::Table> {
+        (**self).load(filename)
+    }
+
+    fn save(&self, filename: &str, contents: toml::Table) {
+        (**self).save(filename, contents);
+    }
+
+    fn save_async(&self, filename: &str, contents: toml::Table) {
+        (**self).save_async(filename, contents);
+    }
+}
+
+/// A [`PreferencesStore`] which keeps preferences in memory. Nothing is persisted between runs of
+/// the app, which makes it useful for tests.
+///
+/// Clones of this store share the same contents, so a clone can be kept to inspect what the
+/// [`PreferencesPlugin`](crate::PreferencesPlugin) saved.
+#[derive(Clone, Default)]
+pub struct MemoryPreferencesStore {
+    files: Arc<Mutex<HashMap<String, toml::Table>>>,
+}
+
+impl MemoryPreferencesStore {
+    /// Construct a new, empty in-memory preferences store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a preferences file to the store, as if it had been saved previously.
+    #[must_use]
+    pub fn with_file(self, filename: &str, contents: toml::Table) -> Self {
+        self.save(filename, contents);
+        self
+    }
+
+    /// Returns the contents of a preferences file, if it has been saved.
+    pub fn get(&self, filename: &str) -> Option<toml::Table> {
+        self.files.lock().unwrap().get(filename).cloned()
+    }
+}
+
+impl PreferencesStore for MemoryPreferencesStore {
+    fn load(&self, filename: &str) -> Option<toml::Table> {
+        self.get(filename)
+    }
+
+    fn save(&self, filename: &str, contents: toml::Table) {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(filename.to_string(), contents);
+    }
+
+    fn save_async(&self, filename: &str, contents: toml::Table) {
+        // Nothing to wait for.
+        self.save(filename, contents);
+    }
+}
+
+/// The text format used to encode the preferences files of a
+/// [`FilePreferencesStore`](crate::FilePreferencesStore).
+///
+/// The default format is [`TomlFormat`]. Any format supported by `serde` which can represent
+/// TOML values can be used instead:
+///
+/// ```
+/// # use bevy_ecs::error::BevyError;
+/// # use bevy_settings::PreferencesFormat;
+/// struct JsonFormat;
+///
+/// impl PreferencesFormat for JsonFormat {
+///     fn extension(&self) -> &str {
+///         "json"
+///     }
+///
+///     fn serialize(&self, contents: &toml::Table) -> Result<String, BevyError> {
+///         Ok(serde_json::to_string_pretty(contents)?)
+///     }
+///
+///     fn deserialize(&self, text: &str) -> Result<toml::Table, BevyError> {
+///         Ok(serde_json::from_str(text)?)
+///     }
+/// }
+/// ```
+pub trait PreferencesFormat: Send + Sync + 'static {
+    /// The file extension used by this format, without the leading dot.
+    fn extension(&self) -> &str;
+
+    /// Encodes the contents of a preferences file.
+    fn serialize(&self, contents: &toml::Table) -> Result<String, BevyError>;
+
+    /// Decodes the contents of a preferences file.
+    fn deserialize(&self, text: &str) -> Result<toml::Table, BevyError>;
+}
+
+/// The default [`PreferencesFormat`], which stores preferences as TOML.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TomlFormat;
+
+impl PreferencesFormat for TomlFormat {
+    fn extension(&self) -> &str {
+        "toml"
+    }
+
+    fn serialize(&self, contents: &toml::Table) -> Result<String, BevyError> {
+        Ok(contents.to_string())
+    }
+
+    fn deserialize(&self, text: &str) -> Result<toml::Table, BevyError> {
+        match toml::from_str::<toml::Value>(text)? {
+            toml::Value::Table(table) => Ok(table),
+            _ => Err("Preferences file must be a table".into()),
+        }
+    }
+}
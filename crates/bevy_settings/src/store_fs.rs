@@ -1,20 +1,25 @@
+use crate::{PreferencesFormat, PreferencesStore, TomlFormat};
 use bevy_log::{debug, error, warn};
 use bevy_platform::dirs::preferences_dir;
-use bevy_tasks::IoTaskPool;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-/// Persistent storage which uses the local filesystem. Preferences will be located in the
-/// OS-specific directory for user preferences.
-pub(crate) struct PreferencesStore {
+/// Persistent storage which uses the local filesystem. By default, preferences will be located in
+/// the OS-specific directory for user preferences, and stored as TOML.
+pub struct FilePreferencesStore {
     base_path: Option<PathBuf>,
+    format: Box<dyn PreferencesFormat>,
 }
 
-impl PreferencesStore {
-    /// Construct a new filesystem preferences store.
+impl FilePreferencesStore {
+    /// Construct a new filesystem preferences store, which stores preferences in the OS-specific
+    /// directory for user preferences.
     ///
     /// # Arguments
     /// * `app_name` - The name of the application. See [`crate::PreferencesPlugin`] for usage.
-    pub(crate) fn new(app_name: &str) -> Self {
+    pub fn new(app_name: &str) -> Self {
         Self {
             base_path: if let Some(base_dir) = preferences_dir() {
                 let prefs_path = base_dir.join(app_name);
@@ -24,88 +29,81 @@ impl PreferencesStore {
                 warn!("Could not find user configuration directories");
                 None
             },
+            format: Box::new(TomlFormat),
         }
     }
 
-    /// Save a [`toml::Table`] to disk.
-    ///
-    /// # Arguments
-    /// * `filename` - the name of the file to be saved
-    /// * `contents` - the contents of the file
-    pub(crate) fn save(&self, filename: &str, contents: toml::Table) {
-        if let Some(base_path) = &self.base_path {
-            // Recursively create the preferences directory if it doesn't exist.
-            let mut dir_builder = fs::DirBuilder::new();
-            dir_builder.recursive(true);
-            if let Err(e) = dir_builder.create(base_path.clone()) {
-                warn!("Could not create preferences directory: {:?}", e);
-                return;
-            }
-
-            // Save preferences to temp file
-            let temp_path = base_path.join(format!("{filename}.toml.new"));
-            if let Err(e) = fs::write(&temp_path, contents.to_string()) {
-                error!("Error saving preferences file: {}", e);
-            }
-
-            // Replace old prefs file with new one.
-            let file_path = base_path.join(format!("{filename}.toml"));
-            if let Err(e) = fs::rename(&temp_path, file_path) {
-                warn!("Could not save preferences file: {:?}", e);
-            }
+    /// Construct a new filesystem preferences store, which stores preferences in the given
+    /// directory. This is useful for keeping preferences in a portable install directory, or in
+    /// a folder that is synced between machines.
+    pub fn in_dir(path: impl Into<PathBuf>) -> Self {
+        Self {
+            base_path: Some(path.into()),
+            format: Box::new(TomlFormat),
         }
     }
 
-    /// Save the contents of a [`toml::Table`] to disk in another thread.
-    ///
-    /// # Arguments
-    /// * `filename` - the name of the file to be saved
-    /// * `contents` - the contents of the file
-    pub(crate) fn save_async(&self, filename: &str, contents: toml::Table) {
-        if let Some(base_path) = &self.base_path {
-            IoTaskPool::get().scope(|scope| {
-                scope.spawn(async {
-                    // Recursively create the preferences directory if it doesn't exist.
-                    let mut dir_builder = fs::DirBuilder::new();
-                    dir_builder.recursive(true);
-                    if let Err(e) = dir_builder.create(base_path.clone()) {
-                        warn!("Could not create preferences directory: {:?}", e);
-                        return;
-                    }
-
-                    // Save preferences to temp file
-                    let temp_path = base_path.join(format!("{filename}.toml.new"));
-                    if let Err(e) = fs::write(&temp_path, contents.to_string()) {
-                        error!("Error saving preferences file: {}", e);
-                    }
+    /// Set the format used to encode preferences files.
+    #[must_use]
+    pub fn with_format(mut self, format: impl PreferencesFormat) -> Self {
+        self.format = Box::new(format);
+        self
+    }
 
-                    // Replace old prefs file with new one.
-                    let file_path = base_path.join(format!("{filename}.toml"));
-                    if let Err(e) = fs::rename(&temp_path, file_path) {
-                        warn!("Could not save preferences file: {:?}", e);
-                    }
-                });
-            });
-        }
+    /// Returns the directory preferences are stored in, if it could be determined.
+    pub fn base_path(&self) -> Option<&Path> {
+        self.base_path.as_deref()
     }
+}
 
-    /// Deserialize a [`toml::Table`] from disk. If the file does not exist, `None` will
-    /// be returned.
-    ///
-    /// # Arguments
-    /// * `filename` - The name of the preferences file, without the file extension.
-    pub(crate) fn load(&self, filename: &str) -> Option<toml::Table> {
+impl PreferencesStore for FilePreferencesStore {
+    fn load(&self, filename: &str) -> Option<toml::Table> {
         let Some(base_path) = &self.base_path else {
             return None;
         };
 
-        let file_path = base_path.join(format!("{filename}.toml"));
-        decode_toml_file(&file_path)
+        let file_path = base_path.join(format!("{filename}.{}", self.format.extension()));
+        decode_file(&file_path, &*self.format)
+    }
+
+    fn save(&self, filename: &str, contents: toml::Table) {
+        let Some(base_path) = &self.base_path else {
+            return;
+        };
+
+        let contents = match self.format.serialize(&contents) {
+            Ok(contents) => contents,
+            Err(e) => {
+                error!("Error encoding preferences file: {}", e);
+                return;
+            }
+        };
+
+        // Recursively create the preferences directory if it doesn't exist.
+        let mut dir_builder = fs::DirBuilder::new();
+        dir_builder.recursive(true);
+        if let Err(e) = dir_builder.create(base_path.clone()) {
+            warn!("Could not create preferences directory: {:?}", e);
+            return;
+        }
+
+        // Save preferences to temp file
+        let extension = self.format.extension();
+        let temp_path = base_path.join(format!("{filename}.{extension}.new"));
+        if let Err(e) = fs::write(&temp_path, contents) {
+            error!("Error saving preferences file: {}", e);
+        }
+
+        // Replace old prefs file with new one.
+        let file_path = base_path.join(format!("{filename}.{extension}"));
+        if let Err(e) = fs::rename(&temp_path, file_path) {
+            warn!("Could not save preferences file: {:?}", e);
+        }
     }
 }
 
-/// Load a preferences file from disk in TOML format.
-pub(crate) fn decode_toml_file(file: &PathBuf) -> Option<toml::Table> {
+/// Load a preferences file from disk in the given format.
+fn decode_file(file: &PathBuf, format: &dyn PreferencesFormat) -> Option<toml::Table> {
     if file.exists() && file.is_file() {
         let prefs_str = match fs::read_to_string(file) {
             Ok(prefs_str) => prefs_str,
@@ -115,18 +113,10 @@ pub(crate) fn decode_toml_file(file: &PathBuf) -> Option<toml::Table> {
             }
         };
 
-        let table_value = match toml::from_str::<toml::Value>(&prefs_str) {
-            Ok(table_value) => table_value,
+        match format.deserialize(&prefs_str) {
+            Ok(table) => Some(table),
             Err(e) => {
                 error!("Error parsing preferences file: {}", e);
-                return None;
-            }
-        };
-
-        match table_value {
-            toml::Value::Table(table) => Some(table),
-            _ => {
-                error!("Preferences file must be a table");
                 None
             }
         }
@@ -1,7 +1,7 @@
 use bevy_ecs::{system::Commands, world::World};
 use log::debug;
 
-use crate::state::{FreelyMutableState, NextState};
+use crate::state::{FreelyMutableState, NextState, StateStack};
 
 /// Extension trait for [`Commands`] adding `bevy_state` helpers.
 pub trait CommandsStatesExt {
@@ -22,6 +22,21 @@ pub trait CommandsStatesExt {
     /// Note that commands introduce sync points to the ECS schedule, so modifying `NextState`
     /// directly may be more efficient depending on your use-case.
     fn set_state_if_neq<S: FreelyMutableState>(&mut self, state: S);
+
+    /// Pushes a state on top of the [`StateStack<S>`](crate::prelude::StateStack), pausing the current state.
+    ///
+    /// The current state runs [`OnPause`](crate::prelude::OnPause) instead of [`OnExit`](crate::prelude::OnExit),
+    /// and is resumed when the pushed state is popped with [`pop_state`](Self::pop_state).
+    fn push_state<S: FreelyMutableState>(&mut self, state: S);
+
+    /// Pops the current state off the [`StateStack<S>`](crate::prelude::StateStack), resuming the state it covers.
+    ///
+    /// The resumed state runs [`OnResume`](crate::prelude::OnResume) instead of [`OnEnter`](crate::prelude::OnEnter).
+    /// Nothing happens if no state was pushed.
+    fn pop_state<S: FreelyMutableState>(&mut self);
+
+    /// Replaces the current state of the [`StateStack<S>`](crate::prelude::StateStack), leaving the states it covers paused.
+    fn replace_state<S: FreelyMutableState>(&mut self, state: S);
 }
 
 impl CommandsStatesExt for Commands<'_, '_> {
@@ -46,4 +61,22 @@ impl CommandsStatesExt for Commands<'_, '_> {
             next.set_if_neq(state);
         });
     }
+
+    fn push_state<S: FreelyMutableState>(&mut self, state: S) {
+        self.queue(move |w: &mut World| {
+            w.get_resource_or_init::<StateStack<S>>().push(state);
+        });
+    }
+
+    fn pop_state<S: FreelyMutableState>(&mut self) {
+        self.queue(move |w: &mut World| {
+            w.get_resource_or_init::<StateStack<S>>().pop();
+        });
+    }
+
+    fn replace_state<S: FreelyMutableState>(&mut self, state: S) {
+        self.queue(move |w: &mut World| {
+            w.get_resource_or_init::<StateStack<S>>().replace(state);
+        });
+    }
 }
@@ -25,6 +25,8 @@
 //!
 //! - 3 Transition Schedules - [`OnEnter<S>`](crate::state::OnEnter), [`OnExit<S>`](crate::state::OnExit) and [`OnTransition<S>`](crate::state::OnTransition) - which are used
 //!   to trigger systems specifically during matching transitions.
+//! - A [`StateStack<S>`](crate::state::StateStack) to push states on top of each other, which pauses and resumes
+//!   the covered state with the [`OnPause<S>`](crate::state::OnPause) and [`OnResume<S>`](crate::state::OnResume) schedules.
 //! - A [`StateTransitionEvent<S>`](crate::state::StateTransitionEvent) that gets fired when a given state changes.
 //! - The [`in_state<S>`](crate::condition::in_state) and [`state_changed<S>`](crate::condition::state_changed) run conditions - which are used
 //!   to determine whether a system should run based on the current state.
@@ -89,8 +91,8 @@ pub mod prelude {
         condition::*,
         state::{
             last_transition, ComputedStates, EnterSchedules, ExitSchedules, NextState, OnEnter,
-            OnExit, OnTransition, PreviousState, State, StateSet, StateTransition,
-            StateTransitionEvent, States, SubStates, TransitionSchedules,
+            OnExit, OnPause, OnResume, OnTransition, PreviousState, State, StateSet, StateStack,
+            StateTransition, StateTransitionEvent, States, SubStates, TransitionSchedules,
         },
         state_scoped::{DespawnOnEnter, DespawnOnExit, DespawnWhen},
     };
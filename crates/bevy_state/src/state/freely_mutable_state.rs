@@ -5,7 +5,10 @@ use bevy_ecs::{
     system::{Commands, IntoSystem, ResMut},
 };
 
-use super::{states::States, take_next_state, transitions::*, NextState, PreviousState, State};
+use super::{
+    state_stack::apply_state_stack, states::States, take_next_state, transitions::*, NextState,
+    PreviousState, State,
+};
 
 /// This trait allows a state to be mutated directly using the [`NextState<S>`](crate::state::NextState) resource.
 ///
@@ -26,7 +29,9 @@ pub trait FreelyMutableState: States {
 
         schedule
             .add_systems(
-                apply_state_transition::<Self>.in_set(ApplyStateTransition::<Self>::default()),
+                (apply_state_stack::<Self>, apply_state_transition::<Self>)
+                    .chain()
+                    .in_set(ApplyStateTransition::<Self>::default()),
             )
             .add_systems(
                 last_transition::<Self>
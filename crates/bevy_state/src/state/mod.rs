@@ -2,6 +2,7 @@ mod computed_states;
 mod freely_mutable_state;
 mod resources;
 mod state_set;
+mod state_stack;
 mod states;
 mod sub_states;
 mod transitions;
@@ -11,6 +12,7 @@ pub use computed_states::*;
 pub use freely_mutable_state::*;
 pub use resources::*;
 pub use state_set::*;
+pub use state_stack::*;
 pub use states::*;
 pub use sub_states::*;
 pub use transitions::*;
@@ -974,4 +976,147 @@ mod tests {
         assert_eq!(transitions[7], "sub enter");
         assert_eq!(transitions[8], "computed enter");
     }
+
+    #[test]
+    fn state_stack_pauses_and_resumes_covered_states() {
+        let mut world = World::new();
+        setup_state_transitions_in_world(&mut world);
+        MessageRegistry::register_message::<StateTransitionEvent<SimpleState>>(&mut world);
+        world.init_resource::<State<SimpleState>>();
+        world.init_resource::<NextState<SimpleState>>();
+        world.init_resource::<StateStack<SimpleState>>();
+        world.init_resource::<TransitionTracker>();
+        let mut schedules = world.resource_mut::<Schedules>();
+        let apply_changes = schedules.get_mut(StateTransition).unwrap();
+        SimpleState::register_state(apply_changes);
+
+        fn track(string: &'static str) -> impl Fn(ResMut<TransitionTracker>) {
+            move |mut transitions: ResMut<TransitionTracker>| transitions.0.push(string)
+        }
+        schedules.add_systems(OnExit(SimpleState::A), track("exit a"));
+        schedules.add_systems(OnPause(SimpleState::A), track("pause a"));
+        schedules.add_systems(OnResume(SimpleState::A), track("resume a"));
+        schedules.add_systems(OnEnter(SimpleState::B(true)), track("enter b"));
+        schedules.add_systems(OnExit(SimpleState::B(true)), track("exit b"));
+        schedules.add_systems(OnEnter(SimpleState::B(false)), track("enter b false"));
+        schedules.add_systems(OnExit(SimpleState::B(false)), track("exit b false"));
+
+        world
+            .resource_mut::<StateStack<SimpleState>>()
+            .push(SimpleState::B(true));
+        world.run_schedule(StateTransition);
+        assert_eq!(
+            world.resource::<State<SimpleState>>().0,
+            SimpleState::B(true)
+        );
+        assert_eq!(
+            world.resource::<StateStack<SimpleState>>().covered(),
+            &[SimpleState::A]
+        );
+        assert_eq!(
+            world.resource::<TransitionTracker>().0,
+            ["pause a", "enter b"]
+        );
+
+        world.resource_mut::<TransitionTracker>().0.clear();
+        world
+            .resource_mut::<StateStack<SimpleState>>()
+            .replace(SimpleState::B(false));
+        world.run_schedule(StateTransition);
+        assert_eq!(
+            world.resource::<State<SimpleState>>().0,
+            SimpleState::B(false)
+        );
+        assert_eq!(
+            world.resource::<TransitionTracker>().0,
+            ["exit b", "enter b false"]
+        );
+
+        world.resource_mut::<TransitionTracker>().0.clear();
+        world.resource_mut::<StateStack<SimpleState>>().pop();
+        world.run_schedule(StateTransition);
+        assert_eq!(world.resource::<State<SimpleState>>().0, SimpleState::A);
+        assert!(world
+            .resource::<StateStack<SimpleState>>()
+            .covered()
+            .is_empty());
+        assert_eq!(
+            world.resource::<TransitionTracker>().0,
+            ["exit b false", "resume a"]
+        );
+
+        // Popping the bottom of the stack does nothing.
+        world.resource_mut::<TransitionTracker>().0.clear();
+        world.resource_mut::<StateStack<SimpleState>>().pop();
+        world.run_schedule(StateTransition);
+        assert_eq!(world.resource::<State<SimpleState>>().0, SimpleState::A);
+        assert!(world.resource::<TransitionTracker>().0.is_empty());
+
+        // Regular transitions still exit the state.
+        world.insert_resource(NextState::Pending(SimpleState::B(true)));
+        world.run_schedule(StateTransition);
+        assert_eq!(
+            world.resource::<TransitionTracker>().0,
+            ["exit a", "enter b"]
+        );
+    }
+
+    #[test]
+    fn state_stack_is_unwound_by_next_state() {
+        let mut world = World::new();
+        setup_state_transitions_in_world(&mut world);
+        MessageRegistry::register_message::<StateTransitionEvent<SimpleState>>(&mut world);
+        world.init_resource::<State<SimpleState>>();
+        world.init_resource::<NextState<SimpleState>>();
+        world.init_resource::<StateStack<SimpleState>>();
+        world.init_resource::<TransitionTracker>();
+        let mut schedules = world.resource_mut::<Schedules>();
+        let apply_changes = schedules.get_mut(StateTransition).unwrap();
+        SimpleState::register_state(apply_changes);
+
+        fn track(string: &'static str) -> impl Fn(ResMut<TransitionTracker>) {
+            move |mut transitions: ResMut<TransitionTracker>| transitions.0.push(string)
+        }
+        schedules.add_systems(OnExit(SimpleState::A), track("exit a"));
+        schedules.add_systems(OnExit(SimpleState::B(true)), track("exit b"));
+        schedules.add_systems(OnEnter(SimpleState::B(false)), track("enter b false"));
+        schedules.add_systems(OnExit(SimpleState::B(false)), track("exit b false"));
+
+        world
+            .resource_mut::<StateStack<SimpleState>>()
+            .push(SimpleState::B(true));
+        world.run_schedule(StateTransition);
+        world
+            .resource_mut::<StateStack<SimpleState>>()
+            .push(SimpleState::B(false));
+        world.run_schedule(StateTransition);
+        assert_eq!(
+            world.resource::<StateStack<SimpleState>>().covered(),
+            &[SimpleState::A, SimpleState::B(true)]
+        );
+
+        // Setting the current state again doesn't leave the stack.
+        world.resource_mut::<TransitionTracker>().0.clear();
+        world.insert_resource(NextState::PendingIfNeq(SimpleState::B(false)));
+        world.run_schedule(StateTransition);
+        assert_eq!(world.resource::<StateStack<SimpleState>>().depth(), 3);
+        assert!(world.resource::<TransitionTracker>().0.is_empty());
+
+        world.insert_resource(NextState::Pending(SimpleState::A));
+        world.run_schedule(StateTransition);
+        assert_eq!(world.resource::<State<SimpleState>>().0, SimpleState::A);
+        let stack = world.resource::<StateStack<SimpleState>>();
+        assert!(stack.covered().is_empty());
+        assert_eq!(stack.unwound(), &[SimpleState::B(true), SimpleState::A]);
+        assert_eq!(
+            world.resource::<TransitionTracker>().0,
+            ["exit b false", "exit b", "exit a"]
+        );
+
+        world.run_schedule(StateTransition);
+        assert!(world
+            .resource::<StateStack<SimpleState>>()
+            .unwound()
+            .is_empty());
+    }
 }
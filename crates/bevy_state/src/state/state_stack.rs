@@ -0,0 +1,222 @@
+use alloc::vec::Vec;
+
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    resource::Resource,
+    system::{Res, ResMut},
+};
+use log::{debug, warn};
+
+use super::{
+    freely_mutable_state::FreelyMutableState,
+    resources::{NextState, State},
+    states::States,
+};
+
+/// A stack of states for [`State<S>`], allowing states to be paused and resumed.
+///
+/// The top of the stack is the current [`State<S>`]: [pushing](Self::push) a state covers the
+/// current one, which is then resumed when the pushed state is [popped](Self::pop).
+/// This is useful for overlays such as pause menus, which shouldn't tear down the state they
+/// are shown on top of.
+///
+/// Instead of [`OnExit`](crate::state::OnExit) and [`OnEnter`](crate::state::OnEnter), the
+/// covered state runs [`OnPause`](crate::state::OnPause) when a state is pushed on top of it,
+/// and [`OnResume`](crate::state::OnResume) when it becomes the top of the stack again.
+/// Entities marked with [`DespawnOnExit`](crate::state_scoped::DespawnOnExit) for a covered state
+/// are kept until that state is actually exited.
+///
+/// Setting the state through [`NextState<S>`] leaves the stack: the covered states are exited
+/// after the current one, from the top of the stack to the bottom, running their
+/// [`OnExit`](crate::state::OnExit) schedules and despawning their
+/// [`DespawnOnExit`](crate::state_scoped::DespawnOnExit) entities.
+///
+/// Like [`NextState<S>`], operations are applied during the
+/// [`StateTransition`](crate::state::StateTransition) schedule, and only the last operation
+/// queued before it runs is applied. They take precedence over a transition queued in
+/// [`NextState<S>`].
+///
+/// ```
+/// use bevy_state::prelude::*;
+/// use bevy_ecs::prelude::*;
+///
+/// #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
+/// enum GameState {
+///     #[default]
+///     MainMenu,
+///     InGame,
+///     Paused,
+/// }
+///
+/// fn pause(mut commands: Commands) {
+///     // Runs `OnPause(GameState::InGame)`, then `OnEnter(GameState::Paused)`.
+///     commands.push_state(GameState::Paused);
+/// }
+///
+/// fn unpause(mut commands: Commands) {
+///     // Runs `OnExit(GameState::Paused)`, then `OnResume(GameState::InGame)`.
+///     commands.pop_state::<GameState>();
+/// }
+/// ```
+#[derive(Resource, Debug)]
+pub struct StateStack<S: States> {
+    covered: Vec<S>,
+    unwound: Vec<S>,
+    pending: Option<StateStackOperation<S>>,
+    last_transition: Option<StateStackTransition>,
+}
+
+impl<S: States> Default for StateStack<S> {
+    fn default() -> Self {
+        Self {
+            covered: Vec::new(),
+            unwound: Vec::new(),
+            pending: None,
+            last_transition: None,
+        }
+    }
+}
+
+impl<S: States> StateStack<S> {
+    /// Returns the states covered by the current [`State<S>`], from the bottom of the stack to the top.
+    pub fn covered(&self) -> &[S] {
+        &self.covered
+    }
+
+    /// Returns the number of states in the stack, including the current [`State<S>`].
+    pub fn depth(&self) -> usize {
+        self.covered.len() + 1
+    }
+
+    /// Queues pushing `state` on top of the stack, pausing the current state.
+    pub fn push(&mut self, state: S) {
+        self.queue(StateStackOperation::Push(state));
+    }
+
+    /// Queues popping the current state off the stack, resuming the state it covers.
+    ///
+    /// Nothing happens if the current state doesn't cover any state.
+    pub fn pop(&mut self) {
+        self.queue(StateStackOperation::Pop);
+    }
+
+    /// Queues replacing the current state with `state`, leaving the covered states untouched.
+    ///
+    /// The covered states stay paused, and are resumed when `state` is [popped](Self::pop).
+    /// Use [`NextState<S>`] to exit them instead.
+    pub fn replace(&mut self, state: S) {
+        self.queue(StateStackOperation::Replace(state));
+    }
+
+    /// Returns the stack operation applied by the last run of the
+    /// [`StateTransition`](crate::state::StateTransition) schedule, if any.
+    pub fn last_transition(&self) -> Option<StateStackTransition> {
+        self.last_transition
+    }
+
+    /// Returns the covered states exited by the last run of the
+    /// [`StateTransition`](crate::state::StateTransition) schedule, because the state was set
+    /// through [`NextState<S>`], from the top of the stack to the bottom.
+    pub fn unwound(&self) -> &[S] {
+        &self.unwound
+    }
+
+    fn queue(&mut self, operation: StateStackOperation<S>) {
+        if let Some(pending) = &self.pending {
+            debug!("overwriting state stack operation {pending:?} with {operation:?}");
+        }
+        self.pending = Some(operation);
+    }
+}
+
+/// An operation queued on a [`StateStack<S>`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum StateStackOperation<S> {
+    Push(S),
+    Pop,
+    Replace(S),
+}
+
+/// The kind of operation a [`StateStack<S>`] applied to its state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateStackTransition {
+    /// A state was pushed, pausing the previous state.
+    Push,
+    /// A state was popped, resuming the state it covered.
+    Pop,
+    /// The current state was replaced.
+    Replace,
+}
+
+/// Turns the pending operation of the [`StateStack<S>`] into a transition in [`NextState<S>`].
+pub(crate) fn apply_state_stack<S: FreelyMutableState>(
+    stack: Option<ResMut<StateStack<S>>>,
+    current_state: Option<Res<State<S>>>,
+    next_state: Option<ResMut<NextState<S>>>,
+) {
+    let Some(mut stack) = stack else {
+        return;
+    };
+    if stack.last_transition.is_some() {
+        stack.bypass_change_detection().last_transition = None;
+    }
+    if !stack.unwound.is_empty() {
+        stack.bypass_change_detection().unwound.clear();
+    }
+    let Some(operation) = stack.bypass_change_detection().pending.take() else {
+        unwind_state_stack(&mut stack, current_state, next_state);
+        return;
+    };
+    let (Some(current_state), Some(mut next_state)) = (current_state, next_state) else {
+        warn!(
+            "Tried to apply {operation:?} to the stack of `{}`, but the state doesn't exist.",
+            core::any::type_name::<S>()
+        );
+        return;
+    };
+
+    let (transition, state) = match operation {
+        StateStackOperation::Push(state) => {
+            stack.covered.push(current_state.get().clone());
+            (StateStackTransition::Push, state)
+        }
+        StateStackOperation::Pop => {
+            let Some(state) = stack.covered.pop() else {
+                warn!(
+                    "Tried to pop the stack of `{}`, but no state is covered by {:?}.",
+                    core::any::type_name::<S>(),
+                    current_state.get()
+                );
+                return;
+            };
+            (StateStackTransition::Pop, state)
+        }
+        StateStackOperation::Replace(state) => (StateStackTransition::Replace, state),
+    };
+    stack.last_transition = Some(transition);
+    next_state.set(state);
+}
+
+/// Exits the covered states of the [`StateStack<S>`] when the state is set through
+/// [`NextState<S>`] rather than the stack.
+fn unwind_state_stack<S: FreelyMutableState>(
+    stack: &mut StateStack<S>,
+    current_state: Option<Res<State<S>>>,
+    next_state: Option<ResMut<NextState<S>>>,
+) {
+    if stack.covered.is_empty() {
+        return;
+    }
+    let (Some(current_state), Some(next_state)) = (current_state, next_state) else {
+        return;
+    };
+    let leaves_stack = match &*next_state {
+        NextState::Unchanged => false,
+        NextState::Pending(_) => true,
+        NextState::PendingIfNeq(state) => state != current_state.get(),
+    };
+    if leaves_stack {
+        stack.unwound = core::mem::take(&mut stack.covered);
+        stack.unwound.reverse();
+    }
+}
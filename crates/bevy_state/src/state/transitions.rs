@@ -9,6 +9,7 @@ use bevy_ecs::{
 
 use super::{
     resources::{PreviousState, State},
+    state_stack::{StateStack, StateStackTransition},
     states::States,
 };
 
@@ -24,6 +25,20 @@ pub struct OnEnter<S: States>(pub S);
 #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash, Default)]
 pub struct OnExit<S: States>(pub S);
 
+/// The label of a [`Schedule`] that **only** runs whenever the provided state is covered by
+/// another state pushed onto its [`StateStack`].
+///
+/// This schedule runs instead of [`OnExit`].
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct OnPause<S: States>(pub S);
+
+/// The label of a [`Schedule`] that **only** runs whenever the provided state becomes the
+/// current state again, after the state covering it was popped off its [`StateStack`].
+///
+/// This schedule runs instead of [`OnEnter`].
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct OnResume<S: States>(pub S);
+
 /// The label of a [`Schedule`] that **only** runs whenever [`State<S>`]
 /// exits AND enters the provided `exited` and `entered` states.
 ///
@@ -254,7 +269,11 @@ pub(crate) fn run_enter<S: States>(
         return;
     };
 
-    let _ = world.try_run_schedule(OnEnter(entered));
+    if last_stack_transition::<S>(world) == Some(StateStackTransition::Pop) {
+        let _ = world.try_run_schedule(OnResume(entered));
+    } else {
+        let _ = world.try_run_schedule(OnEnter(entered));
+    }
 }
 
 pub(crate) fn run_exit<S: States>(
@@ -271,7 +290,26 @@ pub(crate) fn run_exit<S: States>(
         return;
     };
 
-    let _ = world.try_run_schedule(OnExit(exited));
+    if last_stack_transition::<S>(world) == Some(StateStackTransition::Push) {
+        let _ = world.try_run_schedule(OnPause(exited));
+    } else {
+        let _ = world.try_run_schedule(OnExit(exited));
+        // The states covered by the exited state are left with it.
+        let unwound = world
+            .get_resource::<StateStack<S>>()
+            .map(|stack| stack.unwound().to_vec())
+            .unwrap_or_default();
+        for exited in unwound {
+            let _ = world.try_run_schedule(OnExit(exited));
+        }
+    }
+}
+
+/// Returns the operation applied by the [`StateStack<S>`] during this transition, if any.
+pub(crate) fn last_stack_transition<S: States>(world: &World) -> Option<StateStackTransition> {
+    world
+        .get_resource::<StateStack<S>>()
+        .and_then(StateStack::last_transition)
 }
 
 pub(crate) fn run_transition<S: States>(
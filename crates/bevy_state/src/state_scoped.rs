@@ -8,12 +8,12 @@ use bevy_ecs::{
     entity_disabling::Disabled,
     message::MessageReader,
     query::Allow,
-    system::{Commands, Query},
+    system::{Commands, Query, Res},
 };
 #[cfg(feature = "bevy_reflect")]
 use bevy_reflect::prelude::*;
 
-use crate::state::{StateTransitionEvent, States};
+use crate::state::{StateStack, StateStackTransition, StateTransitionEvent, States};
 
 /// Entities marked with this component will be despawned
 /// when a [`StateTransitionEvent<S>`] matching the given predicate is sent.
@@ -159,10 +159,14 @@ where
 /// Despawns entities marked with [`DespawnOnExit<S>`] when their state no
 /// longer matches the world state.
 ///
+/// Entities are kept while their state is paused by another state pushed onto its [`StateStack`],
+/// and despawned when it is [unwound](StateStack::unwound).
+///
 /// If the entity has already been despawned no warning will be emitted.
 pub fn despawn_entities_on_exit_state<S: States>(
     mut commands: Commands,
     mut transitions: MessageReader<StateTransitionEvent<S>>,
+    stack: Option<Res<StateStack<S>>>,
     query: Query<(Entity, &DespawnOnExit<S>), Allow<Disabled>>,
 ) {
     // We use the latest event, because state machine internals generate at most 1
@@ -177,8 +181,13 @@ pub fn despawn_entities_on_exit_state<S: States>(
     let Some(exited) = &transition.exited else {
         return;
     };
+    let stack = stack.as_deref();
+    if stack.and_then(StateStack::last_transition) == Some(StateStackTransition::Push) {
+        return;
+    }
+    let unwound = stack.map(StateStack::unwound).unwrap_or_default();
     for (entity, exit) in &query {
-        if exit.0 == *exited {
+        if exit.0 == *exited || unwound.contains(&exit.0) {
             commands.entity(entity).try_despawn();
         }
     }
@@ -237,10 +246,13 @@ impl<S: States + Default> Default for DespawnOnEnter<S> {
 /// Despawns entities marked with [`DespawnOnEnter<S>`] when their state
 /// matches the world state.
 ///
+/// Resuming a state after popping its [`StateStack`] doesn't count as entering it.
+///
 /// If the entity has already been despawned no warning will be emitted.
 pub fn despawn_entities_on_enter_state<S: States>(
     mut commands: Commands,
     mut transitions: MessageReader<StateTransitionEvent<S>>,
+    stack: Option<Res<StateStack<S>>>,
     query: Query<(Entity, &DespawnOnEnter<S>), Allow<Disabled>>,
 ) {
     // We use the latest event, because state machine internals generate at most 1
@@ -255,6 +267,9 @@ pub fn despawn_entities_on_enter_state<S: States>(
     let Some(entered) = &transition.entered else {
         return;
     };
+    if stack.and_then(|stack| stack.last_transition()) == Some(StateStackTransition::Pop) {
+        return;
+    }
     for (entity, enter) in &query {
         if enter.0 == *entered {
             commands.entity(entity).try_despawn();
@@ -271,6 +286,7 @@ mod tests {
     use crate::{
         app::{AppExtStates, StatesPlugin},
         prelude::CommandsStatesExt,
+        state::NextState,
     };
 
     #[test]
@@ -387,4 +403,72 @@ mod tests {
         // the app's next state is the same as its previous.
         assert!(app.world().get_entity(entity).is_ok());
     }
+
+    #[test]
+    fn despawn_on_exit_survives_push() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, States)]
+        enum State {
+            InGame,
+            Paused,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin);
+        app.insert_state(State::InGame);
+        app.update();
+
+        let in_game = app.world_mut().spawn(DespawnOnExit(State::InGame)).id();
+        let on_resume = app.world_mut().spawn(DespawnOnEnter(State::InGame)).id();
+
+        app.world_mut().commands().push_state(State::Paused);
+        app.update();
+        let paused = app.world_mut().spawn(DespawnOnExit(State::Paused)).id();
+        // entities of the paused state are kept.
+        assert!(app.world().get_entity(in_game).is_ok());
+
+        app.world_mut().commands().pop_state::<State>();
+        app.update();
+        assert_eq!(
+            app.world()
+                .resource::<bevy_state::state::State<State>>()
+                .get(),
+            &State::InGame
+        );
+        assert!(app.world().get_entity(paused).is_err());
+        // resuming a state doesn't enter it.
+        assert!(app.world().get_entity(on_resume).is_ok());
+        assert!(app.world().get_entity(in_game).is_ok());
+
+        app.world_mut().commands().replace_state(State::Paused);
+        app.update();
+        assert!(app.world().get_entity(in_game).is_err());
+    }
+
+    #[test]
+    fn despawn_on_exit_after_leaving_stack() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, States)]
+        enum State {
+            InGame,
+            Paused,
+            MainMenu,
+        }
+
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin);
+        app.insert_state(State::InGame);
+        app.update();
+
+        let in_game = app.world_mut().spawn(DespawnOnExit(State::InGame)).id();
+        app.world_mut().commands().push_state(State::Paused);
+        app.update();
+        let paused = app.world_mut().spawn(DespawnOnExit(State::Paused)).id();
+
+        // Leaving the stack exits the covered states too.
+        app.world_mut()
+            .resource_mut::<NextState<State>>()
+            .set(State::MainMenu);
+        app.update();
+        assert!(app.world().get_entity(paused).is_err());
+        assert!(app.world().get_entity(in_game).is_err());
+    }
 }
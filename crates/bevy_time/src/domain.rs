@@ -0,0 +1,343 @@
+use alloc::borrow::Cow;
+#[cfg(feature = "bevy_reflect")]
+use bevy_ecs::reflect::{ReflectComponent, ReflectResource};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    resource::Resource,
+    system::{Query, Res, ResMut, SystemParam},
+};
+use bevy_platform::collections::HashMap;
+#[cfg(feature = "bevy_reflect")]
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use core::time::Duration;
+
+use crate::{real::Real, time::Time, virt::Virtual};
+
+/// Assigns an entity to a named time domain of [`TimeDomains`].
+///
+/// The [`EntityTime`] system parameter uses the clock of this domain for the entity, instead
+/// of the generic [`Time`]. This allows slowing down or pausing groups of entities separately,
+/// such as enemies affected by bullet-time, or UI animations that should ignore slow motion.
+#[derive(Component, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Component, Clone, Debug, PartialEq, Hash)
+)]
+pub struct TimeDomain(pub Cow<'static, str>);
+
+impl TimeDomain {
+    /// Creates a [`TimeDomain`] with the given domain name.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
+
+    /// Returns the name of the domain.
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+/// The clocks of the named time domains, each with their own speed and pause state.
+///
+/// Every domain clock is a [`Time<Virtual>`] advanced from [`Time<Real>`] by
+/// [`update_time_domains`], independently of the global [`Time<Virtual>`] resource.
+/// Entities are assigned to a domain using the [`TimeDomain`] component.
+///
+/// Each domain also has a fixed clock, which is advanced before each run of the `FixedMain`
+/// schedule by the fixed timestep, scaled by the relative speed of the domain, and not at all
+/// while the domain is paused. Like the generic [`Time`], [`EntityTime`] uses the fixed clocks
+/// in the `FixedMain` schedules, and the virtual clocks elsewhere.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_time::{TimeDomain, TimeDomains};
+/// fn setup(mut commands: Commands, mut domains: ResMut<TimeDomains>) {
+///     // Enemies move at a fifth of their normal speed.
+///     domains.add("enemies").set_relative_speed(0.2);
+///     commands.spawn(TimeDomain::new("enemies"));
+/// }
+/// # bevy_ecs::system::assert_is_system(setup);
+/// ```
+#[derive(Resource, Debug, Default)]
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(Resource, Debug, Default)
+)]
+pub struct TimeDomains {
+    domains: HashMap<Cow<'static, str>, DomainClocks>,
+}
+
+/// The clocks of a single time domain.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect), reflect(Debug, Default))]
+struct DomainClocks {
+    /// The clock advanced each frame, which holds the speed and pause state of the domain.
+    virtual_clock: Time<Virtual>,
+    /// The clock advanced each fixed timestep.
+    fixed: Time,
+    /// The clock used in the current schedule, like the generic [`Time`].
+    current: Time,
+}
+
+impl TimeDomains {
+    /// Returns the clock of the domain named `name`, adding it if it doesn't exist yet.
+    pub fn add(&mut self, name: impl Into<Cow<'static, str>>) -> &mut Time<Virtual> {
+        &mut self.domains.entry(name.into()).or_default().virtual_clock
+    }
+
+    /// Inserts the clock of the domain named `name`, returning the previous clock of the domain.
+    pub fn insert(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        clock: Time<Virtual>,
+    ) -> Option<Time<Virtual>> {
+        let name = name.into();
+        if let Some(clocks) = self.domains.get_mut(&name) {
+            return Some(core::mem::replace(&mut clocks.virtual_clock, clock));
+        }
+        self.domains.insert(
+            name,
+            DomainClocks {
+                virtual_clock: clock,
+                ..Default::default()
+            },
+        );
+        None
+    }
+
+    /// Removes the domain named `name`, returning its clock.
+    pub fn remove(&mut self, name: &str) -> Option<Time<Virtual>> {
+        self.domains.remove(name).map(|clocks| clocks.virtual_clock)
+    }
+
+    /// Returns the clock of the domain named `name`.
+    pub fn get(&self, name: &str) -> Option<&Time<Virtual>> {
+        self.domains.get(name).map(|clocks| &clocks.virtual_clock)
+    }
+
+    /// Returns a mutable reference to the clock of the domain named `name`.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Time<Virtual>> {
+        self.domains
+            .get_mut(name)
+            .map(|clocks| &mut clocks.virtual_clock)
+    }
+
+    /// Returns the fixed clock of the domain named `name`.
+    pub fn get_fixed(&self, name: &str) -> Option<&Time> {
+        self.domains.get(name).map(|clocks| &clocks.fixed)
+    }
+
+    /// Returns an iterator over the names and clocks of all domains.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Time<Virtual>)> {
+        self.domains
+            .iter()
+            .map(|(name, clocks)| (name.as_ref(), &clocks.virtual_clock))
+    }
+
+    /// Advances the fixed clocks by `timestep`, scaled by the speed of each domain, and uses
+    /// them as the current clocks.
+    pub(crate) fn advance_fixed(&mut self, timestep: Duration) {
+        for clocks in self.domains.values_mut() {
+            let speed = if clocks.virtual_clock.is_paused() {
+                0.0
+            } else {
+                clocks.virtual_clock.relative_speed_f64()
+            };
+            clocks.fixed.advance_by(timestep.mul_f64(speed));
+            clocks.current = clocks.fixed;
+        }
+    }
+
+    /// Uses the virtual clocks as the current clocks.
+    pub(crate) fn use_virtual(&mut self) {
+        for clocks in self.domains.values_mut() {
+            clocks.current = clocks.virtual_clock.as_generic();
+        }
+    }
+
+    /// Returns the clock of the domain named `name` in the current schedule.
+    fn current(&self, name: &str) -> Option<&Time> {
+        self.domains.get(name).map(|clocks| &clocks.current)
+    }
+}
+
+/// Advances the clocks of [`TimeDomains`] based on the elapsed [`Time<Real>`].
+pub fn update_time_domains(real: Res<Time<Real>>, mut domains: ResMut<TimeDomains>) {
+    let raw_delta = real.delta();
+    for clocks in domains.domains.values_mut() {
+        clocks.virtual_clock.advance_with_raw_delta(raw_delta);
+    }
+    domains.use_virtual();
+}
+
+/// A [`SystemParam`] returning the time of each entity, according to its [`TimeDomain`].
+///
+/// Entities without a [`TimeDomain`], or assigned to a domain missing from [`TimeDomains`],
+/// use the generic [`Time`]. In the `FixedMain` schedules, entities in a domain use its fixed
+/// clock, see [`TimeDomains`].
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_time::{EntityTime, Timer};
+/// #[derive(Component)]
+/// struct Cooldown(Timer);
+///
+/// fn tick_cooldowns(time: EntityTime, mut cooldowns: Query<(Entity, &mut Cooldown)>) {
+///     for (entity, mut cooldown) in &mut cooldowns {
+///         cooldown.0.tick(time.delta(entity));
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(tick_cooldowns);
+/// ```
+#[derive(SystemParam)]
+pub struct EntityTime<'w, 's> {
+    time: Res<'w, Time>,
+    domains: Res<'w, TimeDomains>,
+    entities: Query<'w, 's, &'static TimeDomain>,
+}
+
+impl EntityTime<'_, '_> {
+    /// Returns the clock used by `entity`.
+    pub fn time(&self, entity: Entity) -> Time {
+        self.entities
+            .get(entity)
+            .ok()
+            .and_then(|domain| self.domains.current(domain.name()))
+            .copied()
+            .unwrap_or(*self.time)
+    }
+
+    /// Returns the clock used by entities in `domain`, or the generic [`Time`] for `None`.
+    ///
+    /// This is useful when the [`TimeDomain`] is already part of a query.
+    pub fn domain_time(&self, domain: Option<&TimeDomain>) -> Time {
+        domain
+            .and_then(|domain| self.domains.current(domain.name()))
+            .copied()
+            .unwrap_or(*self.time)
+    }
+
+    /// Returns how much time has advanced for `entity` since the last update, as a [`Duration`].
+    ///
+    /// This can be passed to [`Timer::tick`](crate::Timer::tick) or
+    /// [`Stopwatch::tick`](crate::Stopwatch::tick).
+    pub fn delta(&self, entity: Entity) -> Duration {
+        self.time(entity).delta()
+    }
+
+    /// Returns how much time has advanced for `entity` since the last update, as [`f32`] seconds.
+    pub fn delta_secs(&self, entity: Entity) -> f32 {
+        self.time(entity).delta_secs()
+    }
+
+    /// Returns how much time has advanced for `entity` since startup, as a [`Duration`].
+    pub fn elapsed(&self, entity: Entity) -> Duration {
+        self.time(entity).elapsed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Fixed, TimePlugin, TimeUpdateStrategy};
+    use alloc::vec::Vec;
+    use bevy_app::{App, FixedUpdate};
+    use bevy_ecs::system::RunSystemOnce;
+
+    #[test]
+    fn entities_use_the_clock_of_their_domain() {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        {
+            let mut domains = app.world_mut().resource_mut::<TimeDomains>();
+            domains.add("slow").set_relative_speed(0.5);
+            domains.add("paused").pause();
+        }
+        app.world_mut()
+            .resource_mut::<Time<Virtual>>()
+            .set_relative_speed(2.0);
+
+        let default = app.world_mut().spawn_empty().id();
+        let slow = app.world_mut().spawn(TimeDomain::new("slow")).id();
+        let paused = app.world_mut().spawn(TimeDomain::new("paused")).id();
+        let missing = app.world_mut().spawn(TimeDomain::new("missing")).id();
+
+        // The first update has no delta.
+        app.update();
+        app.update();
+
+        let deltas = app
+            .world_mut()
+            .run_system_once(move |time: EntityTime| {
+                [default, slow, paused, missing].map(|entity| time.delta(entity))
+            })
+            .unwrap();
+        assert_eq!(
+            deltas,
+            [
+                Duration::from_millis(200),
+                Duration::from_millis(50),
+                Duration::ZERO,
+                Duration::from_millis(200),
+            ]
+        );
+    }
+
+    #[derive(Resource, Default)]
+    struct FixedDeltas(Vec<[Duration; 3]>);
+
+    #[test]
+    fn fixed_update_uses_the_fixed_clock_of_domains() {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .init_resource::<FixedDeltas>()
+            .insert_resource(TimeUpdateStrategy::FixedTimesteps(2));
+        {
+            let mut domains = app.world_mut().resource_mut::<TimeDomains>();
+            domains.add("slow").set_relative_speed(0.5);
+            domains.add("paused").pause();
+        }
+
+        let default = app.world_mut().spawn_empty().id();
+        let slow = app.world_mut().spawn(TimeDomain::new("slow")).id();
+        let paused = app.world_mut().spawn(TimeDomain::new("paused")).id();
+        app.add_systems(
+            FixedUpdate,
+            move |time: EntityTime, mut deltas: ResMut<FixedDeltas>| {
+                deltas
+                    .0
+                    .push([default, slow, paused].map(|entity| time.delta(entity)));
+            },
+        );
+
+        // The first update has no delta.
+        app.update();
+        app.update();
+
+        let timestep = app.world().resource::<Time<Fixed>>().timestep();
+        let deltas = &app.world().resource::<FixedDeltas>().0;
+        assert_eq!(deltas.len(), 2);
+        assert!(deltas
+            .iter()
+            .all(|deltas| *deltas == [timestep, timestep / 2, Duration::ZERO]));
+        let domains = app.world().resource::<TimeDomains>();
+        assert_eq!(domains.get_fixed("slow").unwrap().elapsed(), timestep);
+        assert_eq!(
+            domains.get_fixed("paused").unwrap().elapsed(),
+            Duration::ZERO
+        );
+
+        // Outside of the fixed schedules, the virtual clocks of the domains are used again.
+        let delta = app
+            .world_mut()
+            .run_system_once(move |time: EntityTime| time.delta(slow))
+            .unwrap();
+        assert_eq!(delta, timestep);
+    }
+}
@@ -4,7 +4,7 @@ use bevy_ecs::world::World;
 use bevy_reflect::Reflect;
 use core::time::Duration;
 
-use crate::{time::Time, virt::Virtual};
+use crate::{domain::TimeDomains, time::Time, virt::Virtual};
 
 /// The fixed timestep game clock following virtual time.
 ///
@@ -249,12 +249,19 @@ pub fn run_fixed_main_schedule(world: &mut World) {
     // Run the schedule until we run out of accumulated time
     let _ = world.try_schedule_scope(FixedMain, |world, schedule| {
         while world.resource_mut::<Time<Fixed>>().expend() {
-            *world.resource_mut::<Time>() = world.resource::<Time<Fixed>>().as_generic();
+            let fixed_time = world.resource::<Time<Fixed>>().as_generic();
+            *world.resource_mut::<Time>() = fixed_time;
+            if let Some(mut domains) = world.get_resource_mut::<TimeDomains>() {
+                domains.advance_fixed(fixed_time.delta());
+            }
             schedule.run(world);
         }
     });
 
     *world.resource_mut::<Time>() = world.resource::<Time<Virtual>>().as_generic();
+    if let Some(mut domains) = world.get_resource_mut::<TimeDomains>() {
+        domains.use_virtual();
+    }
 }
 
 #[cfg(test)]
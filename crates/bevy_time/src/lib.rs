@@ -15,6 +15,7 @@ extern crate alloc;
 /// Common run conditions
 pub mod common_conditions;
 mod delayed_commands;
+mod domain;
 mod fixed;
 mod real;
 mod stopwatch;
@@ -23,6 +24,7 @@ mod timer;
 mod virt;
 
 pub use delayed_commands::*;
+pub use domain::*;
 pub use fixed::*;
 pub use real::*;
 pub use stopwatch::*;
@@ -35,7 +37,10 @@ pub use virt::*;
 /// This includes the most common types in this crate, re-exported for your convenience.
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::{DelayedCommandsExt, Fixed, Real, Time, Timer, TimerMode, Virtual};
+    pub use crate::{
+        DelayedCommandsExt, EntityTime, Fixed, Real, Time, TimeDomain, TimeDomains, Timer,
+        TimerMode, Virtual,
+    };
 }
 
 use bevy_app::{prelude::*, RunFixedMainLoop};
@@ -69,6 +74,7 @@ impl Plugin for TimePlugin {
             .init_resource::<Time<Real>>()
             .init_resource::<Time<Virtual>>()
             .init_resource::<Time<Fixed>>()
+            .init_resource::<TimeDomains>()
             .init_resource::<TimeUpdateStrategy>();
 
         #[cfg(feature = "bevy_reflect")]
@@ -76,12 +82,15 @@ impl Plugin for TimePlugin {
             app.register_type::<Time>()
                 .register_type::<Time<Real>>()
                 .register_type::<Time<Virtual>>()
-                .register_type::<Time<Fixed>>();
+                .register_type::<Time<Fixed>>()
+                .register_type::<TimeDomain>()
+                .register_type::<TimeDomains>();
         }
 
         app.add_systems(
             First,
-            time_system
+            (time_system, update_time_domains)
+                .chain()
                 .in_set(TimeSystems)
                 .ambiguous_with(message_update_system),
         )
@@ -12,7 +12,11 @@ use bevy_ecs::component::ComponentCloneBehavior;
 use bevy_ecs::relationship::RelationshipHookMode;
 
 #[cfg(feature = "serialize")]
-use {crate::serde::DynamicWorldSerializer, bevy_reflect::TypeRegistry, serde::Serialize};
+use {
+    crate::serde::{BinaryWorldSerializer, DynamicWorldSerializer},
+    bevy_reflect::TypeRegistry,
+    serde::Serialize,
+};
 
 /// A collection of serializable resources and dynamic entities.
 ///
@@ -229,6 +233,18 @@ impl DynamicWorld {
     pub fn serialize(&self, registry: &TypeRegistry) -> Result<String, ron::Error> {
         serialize_ron(DynamicWorldSerializer::new(self, registry))
     }
+
+    /// Serialize this dynamic world into the compact binary world format (`.scn.bin`).
+    ///
+    /// The binary format is written with [`postcard`] and lists each type path only once, so it is much smaller
+    /// and faster to load than [`serialize`](Self::serialize), at the cost of not being human-readable.
+    /// To deserialize the format, use the [`BinaryWorldAssetLoader`].
+    ///
+    /// [`BinaryWorldAssetLoader`]: crate::BinaryWorldAssetLoader
+    #[cfg(feature = "serialize")]
+    pub fn serialize_binary(&self, registry: &TypeRegistry) -> Result<Vec<u8>, postcard::Error> {
+        postcard::to_allocvec(&BinaryWorldSerializer::new(self, registry))
+    }
 }
 
 /// Serialize a given Rust data structure into rust object notation (ron).
@@ -21,12 +21,18 @@ mod world_asset_loader;
 mod world_asset_spawner;
 mod world_filter;
 
+#[cfg(feature = "serialize")]
+mod migration;
+#[cfg(feature = "rollback")]
+pub mod rollback;
 #[cfg(feature = "serialize")]
 pub mod serde;
 
 pub use components::*;
 pub use dynamic_world::*;
 pub use dynamic_world_builder::*;
+#[cfg(feature = "serialize")]
+pub use migration::*;
 pub use world_asset::*;
 pub use world_asset_loader::*;
 pub use world_asset_spawner::*;
@@ -59,7 +65,9 @@ impl Plugin for WorldSerializationPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<DynamicWorld>()
             .init_asset::<WorldAsset>()
+            .init_resource::<AppWorldMigrations>()
             .init_asset_loader::<WorldAssetLoader>()
+            .init_asset_loader::<BinaryWorldAssetLoader>()
             .init_resource::<WorldInstanceSpawner>()
             .add_systems(
                 SpawnScene,
@@ -297,6 +305,78 @@ mod tests {
         assert_eq!(child_of.0, child_root);
     }
 
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn load_ron_and_binary_worlds() {
+        use bevy_app::TaskPoolPlugin;
+        use bevy_asset::{
+            io::{
+                memory::{Dir, MemoryAssetReader},
+                AssetSourceBuilder, AssetSourceId,
+            },
+            AssetApp, AssetServer, LoadState,
+        };
+        use std::path::Path;
+
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Circle>();
+        world.spawn(Circle { radius: 7.0 });
+        let dynamic_world = DynamicWorld::from_world(&world);
+        let registry = world.resource::<AppTypeRegistry>().read();
+
+        // `.scn.bin` must not be picked up by the `.scn` loader.
+        let dir = Dir::default();
+        dir.insert_asset_text(
+            Path::new("world.scn"),
+            &dynamic_world.serialize(&registry).unwrap(),
+        );
+        dir.insert_asset(
+            Path::new("world.scn.bin"),
+            dynamic_world.serialize_binary(&registry).unwrap(),
+        );
+
+        let mut app = App::new();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSourceBuilder::new(move || Box::new(MemoryAssetReader { root: dir.clone() })),
+        )
+        .add_plugins((
+            TaskPoolPlugin::default(),
+            AssetPlugin::default(),
+            WorldSerializationPlugin,
+        ))
+        .register_type::<Circle>();
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        for path in ["world.scn", "world.scn.bin"] {
+            let handle = asset_server.load::<DynamicWorld>(path);
+            for _ in 0..1000 {
+                app.update();
+                match asset_server.get_load_state(&handle) {
+                    Some(LoadState::Loaded) => break,
+                    Some(LoadState::Failed(err)) => panic!("failed to load {path}: {err}"),
+                    _ => {}
+                }
+            }
+
+            let worlds = app.world().resource::<Assets<DynamicWorld>>();
+            let loaded = worlds
+                .get(&handle)
+                .unwrap_or_else(|| panic!("{path} was not loaded"));
+            assert_eq!(loaded.entities.len(), 1);
+            let circle = &loaded.entities[0].components[0];
+            assert_eq!(
+                circle.reflect_partial_eq(&Circle { radius: 7.0 }),
+                Some(true),
+                "{path} was not loaded correctly"
+            );
+        }
+    }
+
     #[test]
     fn dynamic_world_spawns_and_respawns_after_change() {
         let mut app = App::new();
@@ -0,0 +1,793 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::{
+    any::TypeId,
+    fmt::{Debug, Formatter},
+};
+
+use bevy_asset::HandleDeserializeProcessor;
+use bevy_ecs::resource::Resource;
+use bevy_platform::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use bevy_reflect::{
+    enums::{DynamicEnum, DynamicVariant},
+    list::DynamicList,
+    map::DynamicMap,
+    serde::{ReflectDeserializerProcessor, SerializationData, TypedReflectDeserializer},
+    structs::{DynamicStruct, Struct, StructInfo},
+    tuple::DynamicTuple,
+    NamedField, PartialReflect, TypeInfo, TypePath, TypeRegistration, TypeRegistry,
+};
+use bevy_utils::TypeIdMap;
+use serde::{
+    de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use thiserror::Error;
+
+/// Migrations which bring the types in worlds saved by older versions of an app up to date.
+///
+/// Each world serialized with [`DynamicWorldSerializer::with_migrations`] records a schema version,
+/// and the current version of every type with migrations. When a [`WorldDeserializer`] is given
+/// the same migrations with [`WorldDeserializer::with_migrations`], the types saved at an older
+/// version are migrated before they are converted with [`FromReflect`]:
+///
+/// - Type paths registered as aliases with [`register_alias`] resolve to their new type, for
+///   types which were renamed or moved to another module.
+/// - The [`TypeMigration`]s registered with [`register_migration`] rename, remove and transform
+///   the fields of structs, in order, from their saved version up to their current version.
+///
+/// Worlds with a schema version newer than [`schema_version`], or with types saved at a newer
+/// version than they have now, fail to load.
+///
+/// Worlds without versions, such as those written by [`DynamicWorld::serialize`], are loaded as if
+/// they were at the current version, so that only type aliases apply to them. Use
+/// [`set_migrate_unversioned`] to migrate them from version 0 instead, for example to load the saves
+/// written before migrations were introduced.
+///
+/// Versions are written in every format, but some formats, such as `postcard` and the
+/// [`BinaryWorldSerializer`] format, save the fields of structs without their names. Type aliases
+/// and version checks still apply to them, but types with field migrations to run fail to load
+/// instead of being migrated.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_reflect::{PartialReflect, Reflect};
+/// # use bevy_world_serialization::{TypeMigration, WorldMigrations};
+/// #[derive(Component, Reflect, Default)]
+/// #[reflect(Component)]
+/// struct Player {
+///     // This used to be called `hp`, and was a `u32` until version 1.
+///     health: f32,
+///     name: String,
+/// }
+///
+/// let mut migrations = WorldMigrations::default();
+/// migrations
+///     .set_schema_version(2)
+///     // `Player` used to be in the `game::actors` module.
+///     .register_alias::<Player>("game::actors::Player")
+///     .register_migration::<Player>(0, TypeMigration::new().rename_field("hp", "health"))
+///     .register_migration::<Player>(
+///         1,
+///         TypeMigration::new().map_field("health", |health| {
+///             let health = health.try_downcast_ref::<u64>().copied().unwrap_or_default();
+///             Box::new(health as f32)
+///         }),
+///     );
+/// ```
+///
+/// [`DynamicWorldSerializer::with_migrations`]: crate::serde::DynamicWorldSerializer::with_migrations
+/// [`WorldDeserializer`]: crate::serde::WorldDeserializer
+/// [`WorldDeserializer::with_migrations`]: crate::serde::WorldDeserializer::with_migrations
+/// [`BinaryWorldSerializer`]: crate::serde::BinaryWorldSerializer
+/// [`FromReflect`]: bevy_reflect::FromReflect
+/// [`register_alias`]: Self::register_alias
+/// [`register_migration`]: Self::register_migration
+/// [`schema_version`]: Self::schema_version
+/// [`DynamicWorld::serialize`]: crate::DynamicWorld::serialize
+/// [`set_migrate_unversioned`]: Self::set_migrate_unversioned
+#[derive(Clone, Default)]
+pub struct WorldMigrations {
+    schema_version: u32,
+    migrate_unversioned: bool,
+    aliases: HashMap<String, TypeId>,
+    types: TypeIdMap<TypeMigrations>,
+}
+
+/// The migrations registered for a single type.
+#[derive(Clone)]
+struct TypeMigrations {
+    type_path: &'static str,
+    /// The current version of the type, one more than the highest version it is migrated from.
+    version: u32,
+    /// The migration steps, by the version they migrate from.
+    steps: BTreeMap<u32, Vec<FieldMigration>>,
+}
+
+impl WorldMigrations {
+    /// The current schema version, which is written to each serialized world.
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Sets the current schema version.
+    ///
+    /// Bump it when saved worlds change in a way which older versions of the app can't load.
+    pub fn set_schema_version(&mut self, version: u32) -> &mut Self {
+        self.schema_version = version;
+        self
+    }
+
+    /// Whether worlds without versions are migrated from version 0, rather than loaded as if they
+    /// were at the current version.
+    pub fn migrate_unversioned(&self) -> bool {
+        self.migrate_unversioned
+    }
+
+    /// Sets whether worlds without versions are migrated from version 0, rather than loaded as if
+    /// they were at the current version.
+    pub fn set_migrate_unversioned(&mut self, migrate: bool) -> &mut Self {
+        self.migrate_unversioned = migrate;
+        self
+    }
+
+    /// The current version of the type `T`, which is one more than the highest version it has a
+    /// migration from, or 0 if it has none.
+    pub fn type_version<T: 'static>(&self) -> u32 {
+        self.types
+            .get(&TypeId::of::<T>())
+            .map_or(0, |migrations| migrations.version)
+    }
+
+    /// Registers `old_type_path` as an alias of `T`, so that values saved with that type path
+    /// are loaded as `T`.
+    pub fn register_alias<T: TypePath>(&mut self, old_type_path: impl Into<String>) -> &mut Self {
+        self.aliases.insert(old_type_path.into(), TypeId::of::<T>());
+        self
+    }
+
+    /// Registers a migration for the struct `T`, which transforms its fields from version
+    /// `from_version` to `from_version + 1`.
+    ///
+    /// Migrations from the same version run in registration order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from_version` is [`u32::MAX`]. See [`try_register_migration`] for a non-panicking
+    /// version.
+    ///
+    /// [`try_register_migration`]: Self::try_register_migration
+    pub fn register_migration<T: Struct + TypePath>(
+        &mut self,
+        from_version: u32,
+        migration: TypeMigration,
+    ) -> &mut Self {
+        match self.try_register_migration::<T>(from_version, migration) {
+            Ok(migrations) => migrations,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Registers a migration for the struct `T`, which transforms its fields from version
+    /// `from_version` to `from_version + 1`.
+    ///
+    /// Migrations from the same version run in registration order. Returns an error, without
+    /// registering the migration, if `from_version` is [`u32::MAX`].
+    pub fn try_register_migration<T: Struct + TypePath>(
+        &mut self,
+        from_version: u32,
+        migration: TypeMigration,
+    ) -> Result<&mut Self, MigrationVersionOverflow> {
+        let to_version = from_version
+            .checked_add(1)
+            .ok_or(MigrationVersionOverflow {
+                type_path: T::type_path(),
+            })?;
+        let migrations = self
+            .types
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| TypeMigrations {
+                type_path: T::type_path(),
+                version: 0,
+                steps: BTreeMap::new(),
+            });
+        migrations.version = migrations.version.max(to_version);
+        migrations
+            .steps
+            .entry(from_version)
+            .or_default()
+            .extend(migration.steps);
+        Ok(self)
+    }
+
+    /// The current version of each type with migrations, by type path.
+    pub(crate) fn type_versions(&self) -> BTreeMap<&'static str, u32> {
+        self.types
+            .values()
+            .map(|migrations| (migrations.type_path, migrations.version))
+            .collect()
+    }
+
+    /// Finds the registration for `type_path`, or for the type it is an alias of.
+    fn registration<'r>(
+        &self,
+        registry: &'r TypeRegistry,
+        type_path: &str,
+    ) -> Option<&'r TypeRegistration> {
+        registry.get_with_type_path(type_path).or_else(|| {
+            self.aliases
+                .get(type_path)
+                .and_then(|type_id| registry.get(*type_id))
+        })
+    }
+}
+
+impl Debug for WorldMigrations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WorldMigrations")
+            .field("schema_version", &self.schema_version)
+            .field("migrate_unversioned", &self.migrate_unversioned)
+            .field("aliases", &self.aliases)
+            .field("type_versions", &self.type_versions())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`Resource`] storing the [`WorldMigrations`] of an app, which are used when loading
+/// [`DynamicWorld`] assets.
+///
+/// [`DynamicWorld`]: crate::DynamicWorld
+#[derive(Resource, Clone, Default, Debug)]
+pub struct AppWorldMigrations(pub Arc<RwLock<WorldMigrations>>);
+
+impl AppWorldMigrations {
+    /// Takes a read lock on the underlying [`WorldMigrations`].
+    pub fn read(&self) -> RwLockReadGuard<'_, WorldMigrations> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Takes a write lock on the underlying [`WorldMigrations`].
+    pub fn write(&self) -> RwLockWriteGuard<'_, WorldMigrations> {
+        self.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// An error returned by [`WorldMigrations::try_register_migration`] for a migration from the last
+/// possible version.
+#[derive(Error, Debug)]
+#[error(
+    "cannot register a migration for `{type_path}` from version {}, as it is the last version",
+    u32::MAX
+)]
+pub struct MigrationVersionOverflow {
+    /// The type path of the struct the migration was registered for.
+    pub type_path: &'static str,
+}
+
+/// The changes to the fields of a struct from one version to the next, registered with
+/// [`WorldMigrations::register_migration`].
+///
+/// The fields of a struct saved at an older version are deserialized as their current type if the
+/// struct still has a field with their (renamed) name, and as plain values otherwise:
+/// booleans, `i64`, `u64`, `f64`, `char`, [`String`], [`DynamicEnum`] for options,
+/// [`DynamicList`] for sequences, and [`DynamicStruct`] (or [`DynamicMap`], for keys which aren't
+/// strings) for maps. The steps then run in order on the [`DynamicStruct`] holding these fields,
+/// which must only contain fields of the current type at the end.
+#[derive(Clone, Default)]
+pub struct TypeMigration {
+    steps: Vec<FieldMigration>,
+}
+
+#[derive(Clone)]
+enum FieldMigration {
+    Rename {
+        from: String,
+        to: String,
+    },
+    Remove(String),
+    Map {
+        field: String,
+        map: Arc<dyn Fn(Box<dyn PartialReflect>) -> Box<dyn PartialReflect> + Send + Sync>,
+    },
+    Transform(Arc<dyn Fn(&mut DynamicStruct) + Send + Sync>),
+}
+
+impl TypeMigration {
+    /// Creates an empty migration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renames the field `from` to `to`.
+    #[must_use]
+    pub fn rename_field(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.steps.push(FieldMigration::Rename {
+            from: from.into(),
+            to: to.into(),
+        });
+        self
+    }
+
+    /// Removes the field `field`. Its saved value is skipped without being deserialized.
+    #[must_use]
+    pub fn remove_field(mut self, field: impl Into<String>) -> Self {
+        self.steps.push(FieldMigration::Remove(field.into()));
+        self
+    }
+
+    /// Replaces the value of the field `field` with the result of `map`, if it is set.
+    ///
+    /// The saved value is always deserialized as a plain value, so this can change the type of a
+    /// field which keeps its name.
+    #[must_use]
+    pub fn map_field(
+        mut self,
+        field: impl Into<String>,
+        map: impl Fn(Box<dyn PartialReflect>) -> Box<dyn PartialReflect> + Send + Sync + 'static,
+    ) -> Self {
+        self.steps.push(FieldMigration::Map {
+            field: field.into(),
+            map: Arc::new(map),
+        });
+        self
+    }
+
+    /// Runs `transform` on the fields of the struct, for example to compute a new field from
+    /// several old ones.
+    #[must_use]
+    pub fn transform(
+        mut self,
+        transform: impl Fn(&mut DynamicStruct) + Send + Sync + 'static,
+    ) -> Self {
+        self.steps
+            .push(FieldMigration::Transform(Arc::new(transform)));
+        self
+    }
+}
+
+impl FieldMigration {
+    fn apply(&self, value: &mut DynamicStruct) {
+        match self {
+            FieldMigration::Rename { from, to } => {
+                if let Some((_, field)) = value.remove_by_name(from) {
+                    value.insert_boxed(to.clone(), field);
+                }
+            }
+            FieldMigration::Remove(field) => {
+                value.remove_by_name(field);
+            }
+            FieldMigration::Map { field, map } => {
+                if let Some((_, old)) = value.remove_by_name(field) {
+                    value.insert_boxed(field.clone(), map(old));
+                }
+            }
+            FieldMigration::Transform(transform) => transform(value),
+        }
+    }
+}
+
+/// The [`WorldMigrations`] to run on a world being deserialized, along with the versions its
+/// types were saved at.
+pub struct WorldMigrator<'a> {
+    migrations: &'a WorldMigrations,
+    saved_versions: TypeIdMap<u32>,
+}
+
+impl<'a> WorldMigrator<'a> {
+    /// Checks the versions a world was saved with against the current versions in `migrations`.
+    ///
+    /// `versions` is the schema version and type versions of the world, or `None` if it was saved
+    /// without versions.
+    pub(crate) fn new<E: Error>(
+        migrations: &'a WorldMigrations,
+        registry: &TypeRegistry,
+        versions: Option<(u32, BTreeMap<String, u32>)>,
+    ) -> Result<Self, E> {
+        let Some((schema_version, type_versions)) = versions else {
+            // Types without a saved version are migrated from version 0
+            let saved_versions = if migrations.migrate_unversioned {
+                TypeIdMap::default()
+            } else {
+                migrations
+                    .types
+                    .iter()
+                    .map(|(type_id, migrations)| (*type_id, migrations.version))
+                    .collect()
+            };
+            return Ok(Self {
+                migrations,
+                saved_versions,
+            });
+        };
+
+        if schema_version > migrations.schema_version {
+            return Err(E::custom(format_args!(
+                "the world was saved with schema version {schema_version}, which is newer than the current version {}",
+                migrations.schema_version
+            )));
+        }
+
+        let mut saved_versions = TypeIdMap::default();
+        for (type_path, version) in type_versions {
+            // Types which no longer exist are reported if they are used
+            let Some(registration) = migrations.registration(registry, &type_path) else {
+                continue;
+            };
+            let current = migrations
+                .types
+                .get(&registration.type_id())
+                .map_or(0, |migrations| migrations.version);
+            if version > current {
+                return Err(E::custom(format_args!(
+                    "`{type_path}` was saved at version {version}, which is newer than its current version {current}"
+                )));
+            }
+            saved_versions.insert(registration.type_id(), version);
+        }
+
+        Ok(Self {
+            migrations,
+            saved_versions,
+        })
+    }
+
+    /// The migrations being run.
+    pub(crate) fn migrations(&self) -> &'a WorldMigrations {
+        self.migrations
+    }
+
+    /// The migration steps to run on values of the given type, if it was saved at an older version.
+    fn pending_steps(&self, type_id: TypeId) -> Option<Vec<&'a FieldMigration>> {
+        let migrations = self.migrations.types.get(&type_id)?;
+        let saved = self.saved_versions.get(&type_id).copied().unwrap_or(0);
+        (saved < migrations.version).then(|| {
+            migrations
+                .steps
+                .range(saved..)
+                .flat_map(|(_, steps)| steps)
+                .collect()
+        })
+    }
+}
+
+/// Deserializes a type path into its registration, resolving the aliases of `migrations`.
+pub(crate) struct TypePathDeserializer<'a> {
+    pub(crate) registry: &'a TypeRegistry,
+    pub(crate) migrations: Option<&'a WorldMigrations>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for TypePathDeserializer<'a> {
+    type Value = &'a TypeRegistration;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for TypePathDeserializer<'a> {
+    type Value = &'a TypeRegistration;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("type path")
+    }
+
+    fn visit_str<E>(self, type_path: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        let registration = match self.migrations {
+            Some(migrations) => migrations.registration(self.registry, type_path),
+            None => self.registry.get_with_type_path(type_path),
+        };
+        registration.ok_or_else(|| {
+            Error::custom(format_args!(
+                "no registration found for `{type_path}`: it is not registered, and isn't an alias registered in `WorldMigrations`"
+            ))
+        })
+    }
+}
+
+/// A [`ReflectDeserializerProcessor`] which migrates the structs saved at an older version, and
+/// deserializes asset handles.
+pub(crate) struct MigrationProcessor<'a, 'b> {
+    pub(crate) migrator: Option<&'a WorldMigrator<'a>>,
+    pub(crate) handles: HandleDeserializeProcessor<'b>,
+}
+
+impl ReflectDeserializerProcessor for MigrationProcessor<'_, '_> {
+    fn try_deserialize<'de, D>(
+        &mut self,
+        registration: &TypeRegistration,
+        registry: &TypeRegistry,
+        deserializer: D,
+    ) -> Result<Result<Box<dyn PartialReflect>, D>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Some(steps) = self
+            .migrator
+            .and_then(|migrator| migrator.pending_steps(registration.type_id()))
+        else {
+            return self
+                .handles
+                .try_deserialize(registration, registry, deserializer);
+        };
+        let TypeInfo::Struct(info) = registration.type_info() else {
+            return Err(Error::custom(format_args!(
+                "`{}` has migrations, but is not a struct",
+                registration.type_info().type_path()
+            )));
+        };
+
+        let value = deserializer.deserialize_struct(
+            info.type_path_table().ident().unwrap_or_default(),
+            info.field_names(),
+            MigratedStructVisitor {
+                info,
+                registration,
+                registry,
+                steps,
+                processor: self,
+            },
+        )?;
+        Ok(Ok(Box::new(value)))
+    }
+}
+
+struct MigratedStructVisitor<'p, 'a, 'b> {
+    info: &'static StructInfo,
+    registration: &'p TypeRegistration,
+    registry: &'p TypeRegistry,
+    steps: Vec<&'a FieldMigration>,
+    processor: &'p mut MigrationProcessor<'a, 'b>,
+}
+
+/// How a saved field is deserialized.
+enum SavedField<'a> {
+    /// The field is removed by a migration, so it is skipped.
+    Removed,
+    /// The field is deserialized as its current type.
+    Typed(&'a NamedField),
+    /// The field is deserialized as a plain value.
+    Plain,
+}
+
+impl MigratedStructVisitor<'_, '_, '_> {
+    fn saved_field(&self, name: &str) -> SavedField<'static> {
+        let mut name = name;
+        let mut plain = false;
+        for step in &self.steps {
+            match step {
+                FieldMigration::Rename { from, to } if from == name => name = to,
+                FieldMigration::Remove(field) if field == name => return SavedField::Removed,
+                FieldMigration::Map { field, .. } if field == name => plain = true,
+                _ => {}
+            }
+        }
+        match self.info.field(name) {
+            Some(field) if !plain => SavedField::Typed(field),
+            _ => SavedField::Plain,
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for MigratedStructVisitor<'_, '_, '_> {
+    type Value = DynamicStruct;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        write!(formatter, "struct `{}`", self.info.type_path())
+    }
+
+    fn visit_seq<A>(self, _seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        Err(Error::custom(format_args!(
+            "cannot migrate `{}`, because its fields were saved without their names",
+            self.info.type_path()
+        )))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut value = DynamicStruct::default();
+        while let Some(FieldName(name)) = map.next_key()? {
+            match self.saved_field(&name) {
+                SavedField::Removed => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+                SavedField::Typed(field) => {
+                    let registration = self.registry.get(field.type_id()).ok_or_else(|| {
+                        Error::custom(format_args!(
+                            "no registration found for `{}`",
+                            field.type_path()
+                        ))
+                    })?;
+                    let field = map.next_value_seed(TypedReflectDeserializer::with_processor(
+                        registration,
+                        self.registry,
+                        &mut *self.processor,
+                    ))?;
+                    value.insert_boxed(name, field);
+                }
+                SavedField::Plain => {
+                    value.insert_boxed(name, map.next_value_seed(PlainValueDeserializer)?);
+                }
+            }
+        }
+
+        for step in &self.steps {
+            step.apply(&mut value);
+        }
+
+        if let Some(serialization_data) = self.registration.data::<SerializationData>() {
+            for (index, skipped_field) in serialization_data.iter_skipped() {
+                if let Some(field) = self.info.field_at(*index) {
+                    value.insert_boxed(
+                        field.name(),
+                        skipped_field.generate_default().into_partial_reflect(),
+                    );
+                }
+            }
+        }
+
+        for index in 0..value.field_len() {
+            let name = value.name_at(index).unwrap_or_default();
+            if self.info.field(name).is_none() {
+                return Err(Error::custom(format_args!(
+                    "`{}` has no field `{name}` after migrating it, rename or remove the field in a migration",
+                    self.info.type_path()
+                )));
+            }
+        }
+
+        value.set_represented_type(Some(self.registration.type_info()));
+        Ok(value)
+    }
+}
+
+/// The name of a saved field.
+struct FieldName(String);
+
+impl<'de> Deserialize<'de> for FieldName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FieldNameVisitor;
+
+        impl<'de> Visitor<'de> for FieldNameVisitor {
+            type Value = FieldName;
+
+            fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+                formatter.write_str("field name")
+            }
+
+            fn visit_str<E>(self, name: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(FieldName(name.into()))
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldNameVisitor)
+    }
+}
+
+/// Deserializes a value without knowing its type, into plain values and dynamic types.
+struct PlainValueDeserializer;
+
+impl<'de> DeserializeSeed<'de> for PlainValueDeserializer {
+    type Value = Box<dyn PartialReflect>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for PlainValueDeserializer {
+    type Value = Box<dyn PartialReflect>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Box::new(v))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Box::new(v))
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Box::new(v))
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Box::new(v))
+    }
+
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> {
+        Ok(Box::new(v))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Box::new(String::from(v)))
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Box::new(v.to_vec()))
+    }
+
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(Box::new(()))
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(Box::new(DynamicEnum::new("None", DynamicVariant::Unit)))
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = self.deserialize(deserializer)?;
+        let variant = DynamicVariant::Tuple(DynamicTuple::from_iter([value]));
+        Ok(Box::new(DynamicEnum::new("Some", variant)))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut list = DynamicList::default();
+        while let Some(value) = seq.next_element_seed(PlainValueDeserializer)? {
+            list.push_box(value);
+        }
+        Ok(Box::new(list))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::new();
+        while let Some(entry) =
+            map.next_entry_seed(PlainValueDeserializer, PlainValueDeserializer)?
+        {
+            entries.push(entry);
+        }
+
+        if entries
+            .iter()
+            .all(|(key, _)| key.try_downcast_ref::<String>().is_some())
+        {
+            let value: DynamicStruct = entries
+                .into_iter()
+                .map(|(key, value)| (key.try_downcast_ref::<String>().unwrap().clone(), value))
+                .collect();
+            Ok(Box::new(value))
+        } else {
+            Ok(Box::new(DynamicMap::from_iter(entries)))
+        }
+    }
+}
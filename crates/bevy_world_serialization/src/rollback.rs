@@ -0,0 +1,761 @@
+//! Snapshots of the world taken each fixed tick, used to roll back and re-simulate the world.
+//!
+//! This is meant for rollback networking: when late input arrives for a past tick, the world is
+//! restored to the snapshot of that tick, and the [`FixedMain`] schedule is run again up to the
+//! present with the corrected input.
+//!
+//! Only entities marked with [`Rollback`] are captured, and only the components and resources
+//! allowed by the [`RollbackPlugin`] are saved, in a compact binary form.
+
+use alloc::collections::VecDeque;
+use core::any::TypeId;
+
+use bevy_app::{App, FixedLast, FixedMain, Plugin};
+use bevy_asset::{uuid::Uuid, AssetPath, AssetServer, LoadFromPath, UntypedHandle};
+use bevy_ecs::{
+    component::Component,
+    entity::{Entity, EntityHashMap, EntityHashSet},
+    event::Event,
+    query::With,
+    reflect::{AppTypeRegistry, ReflectComponent},
+    resource::Resource,
+    world::{error::TryRunScheduleError, World},
+};
+use bevy_reflect::{std_traits::ReflectDefault, PartialReflect, Reflect, ReflectRef, TypeRegistry};
+use bevy_time::{Fixed, Time, Virtual};
+use serde::de::DeserializeSeed;
+use thiserror::Error;
+
+use crate::{
+    serde::{DynamicWorldSerializer, WorldDeserializer},
+    DynamicEntity, DynamicWorldBuilder, WorldFilter, WorldInstanceSpawnError,
+};
+
+/// Marks an entity whose components are captured in rollback snapshots.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component, Default, Debug, Clone)]
+pub struct Rollback;
+
+/// Adds a ring buffer of [`RollbackSnapshots`], captured at the end of each fixed tick.
+///
+/// The components and resources to capture must be allowed explicitly, and registered in the
+/// [`AppTypeRegistry`]. Use [`rollback`] to restore the world to a past tick and re-simulate it.
+///
+/// ```
+/// # use bevy_app::App;
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_reflect::Reflect;
+/// # use bevy_world_serialization::rollback::RollbackPlugin;
+/// #[derive(Component, Reflect)]
+/// #[reflect(Component)]
+/// struct Position(f32);
+///
+/// # let mut app = App::new();
+/// app.register_type::<Position>()
+///     .add_plugins(RollbackPlugin::default().allow_component::<Position>());
+/// ```
+pub struct RollbackPlugin {
+    /// The number of snapshots kept, after which the oldest snapshot is dropped.
+    pub capacity: usize,
+    /// The components captured on [`Rollback`] entities.
+    pub components: WorldFilter,
+    /// The resources captured.
+    pub resources: WorldFilter,
+}
+
+impl Default for RollbackPlugin {
+    fn default() -> Self {
+        Self {
+            capacity: RollbackSnapshots::DEFAULT_CAPACITY,
+            components: WorldFilter::deny_all(),
+            resources: WorldFilter::deny_all(),
+        }
+    }
+}
+
+impl RollbackPlugin {
+    /// Sets the number of snapshots kept.
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Allows the component `T` to be captured.
+    #[must_use]
+    pub fn allow_component<T: Component>(mut self) -> Self {
+        self.components = self.components.allow::<T>();
+        self
+    }
+
+    /// Allows the resource `T` to be captured.
+    #[must_use]
+    pub fn allow_resource<T: Resource>(mut self) -> Self {
+        self.resources = self.resources.allow::<T>();
+        self
+    }
+}
+
+impl Plugin for RollbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Rollback>()
+            .insert_resource(RollbackSnapshots {
+                capacity: self.capacity,
+                components: self.components.clone(),
+                resources: self.resources.clone(),
+                tick: 0,
+                snapshots: VecDeque::new(),
+            })
+            .init_resource::<RollbackEntityMap>()
+            .add_systems(FixedLast, save_rollback_snapshot);
+    }
+}
+
+/// A snapshot of the [`Rollback`] entities and resources, at the end of a fixed tick.
+pub struct RollbackSnapshot {
+    /// The fixed tick the snapshot was captured at.
+    pub tick: u64,
+    /// The captured [`DynamicWorld`](crate::DynamicWorld), in a binary form.
+    pub data: Vec<u8>,
+    /// The fixed time at the tick, if the world has one.
+    pub time: Option<Time<Fixed>>,
+}
+
+/// The ring buffer of [`RollbackSnapshot`]s of the [`RollbackPlugin`].
+#[derive(Resource)]
+pub struct RollbackSnapshots {
+    capacity: usize,
+    components: WorldFilter,
+    resources: WorldFilter,
+    tick: u64,
+    snapshots: VecDeque<RollbackSnapshot>,
+}
+
+impl RollbackSnapshots {
+    /// The default number of snapshots kept: one second of ticks at the default fixed timestep.
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Returns the last fixed tick that was simulated. The first tick is `1`.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns the snapshot captured at `tick`, if it is still in the buffer.
+    pub fn get(&self, tick: u64) -> Option<&RollbackSnapshot> {
+        let oldest = self.snapshots.front()?.tick;
+        self.snapshots
+            .get(usize::try_from(tick.checked_sub(oldest)?).ok()?)
+    }
+
+    /// Returns an iterator over the snapshots in the buffer, from the oldest to the newest.
+    pub fn iter(&self) -> impl Iterator<Item = &RollbackSnapshot> {
+        self.snapshots.iter()
+    }
+
+    /// Returns the filter of the components captured on [`Rollback`] entities.
+    pub fn components(&self) -> &WorldFilter {
+        &self.components
+    }
+
+    /// Returns the filter of the resources captured.
+    pub fn resources(&self) -> &WorldFilter {
+        &self.resources
+    }
+
+    fn push(&mut self, snapshot: RollbackSnapshot) {
+        if self.snapshots.len() >= self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+}
+
+/// Maps the entities of [`RollbackSnapshot`]s to the entities that currently represent them.
+///
+/// When a snapshot is restored, entities that were despawned since are respawned with a new
+/// [`Entity`], and [`RollbackRespawn`] is triggered. The mapping is kept so that older
+/// snapshots are restored onto the same entity, until these snapshots are dropped from the
+/// buffer. It can be modified to point a snapshot entity to another entity, for example when a
+/// networked entity is spawned again by the server.
+#[derive(Resource, Default, Debug)]
+pub struct RollbackEntityMap {
+    /// The entity representing each snapshot entity, and the newest tick whose snapshot may
+    /// contain the snapshot entity.
+    entities: EntityHashMap<(Entity, u64)>,
+}
+
+impl RollbackEntityMap {
+    /// Returns the entity currently representing `snapshot_entity`.
+    pub fn get(&self, snapshot_entity: Entity) -> Entity {
+        self.entities
+            .get(&snapshot_entity)
+            .map_or(snapshot_entity, |(entity, _)| *entity)
+    }
+
+    /// Maps `snapshot_entity` to `entity`.
+    ///
+    /// `tick` is the newest tick whose snapshot may contain `snapshot_entity`, usually
+    /// [`RollbackSnapshots::tick`]. The mapping is dropped with the snapshot of that tick.
+    pub fn insert(&mut self, snapshot_entity: Entity, entity: Entity, tick: u64) {
+        self.entities.insert(snapshot_entity, (entity, tick));
+    }
+
+    /// Removes the mapping of `snapshot_entity`, returning the entity it was mapped to.
+    pub fn remove(&mut self, snapshot_entity: Entity) -> Option<Entity> {
+        self.entities
+            .remove(&snapshot_entity)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Returns the number of mapped snapshot entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if no snapshot entity is mapped.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Drops the mappings that are only needed by snapshots older than `oldest_tick`.
+    fn prune(&mut self, oldest_tick: u64) {
+        self.entities.retain(|_, (_, tick)| *tick >= oldest_tick);
+    }
+}
+
+/// Triggered when restoring a snapshot respawns an entity that was despawned since.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RollbackRespawn {
+    /// The entity in the restored snapshot.
+    pub snapshot_entity: Entity,
+    /// The entity spawned to represent it.
+    pub entity: Entity,
+}
+
+/// An error restoring a [`RollbackSnapshot`].
+#[derive(Error, Debug)]
+pub enum RollbackError {
+    /// No snapshot was captured at the tick, or it was dropped from the buffer.
+    #[error("no snapshot is available for tick {0}")]
+    UnknownTick(u64),
+    /// The snapshot couldn't be serialized or deserialized.
+    #[error("invalid snapshot: {0}")]
+    Serialization(#[from] postcard::Error),
+    /// The snapshot couldn't be written to the world.
+    #[error(transparent)]
+    Spawn(#[from] WorldInstanceSpawnError),
+    /// The [`FixedMain`] schedule couldn't be run to re-simulate the world.
+    #[error(transparent)]
+    Resimulate(#[from] TryRunScheduleError),
+    /// The snapshot contains an asset handle, but the world has no [`AssetServer`] to load it.
+    #[error("the snapshot contains the asset handle `{0}`, but the world has no `AssetServer`")]
+    MissingAssetServer(AssetPath<'static>),
+}
+
+/// Captures a [`RollbackSnapshot`] of the current fixed tick.
+pub fn save_rollback_snapshot(world: &mut World) -> bevy_ecs::error::Result {
+    let Some(mut snapshots) = world.get_resource_mut::<RollbackSnapshots>() else {
+        return Ok(());
+    };
+    snapshots.tick += 1;
+    let tick = snapshots.tick;
+
+    let mut rollback_entities = world.query_filtered::<Entity, With<Rollback>>();
+    let snapshots = world.resource::<RollbackSnapshots>();
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let dynamic_world = DynamicWorldBuilder::from_world(world, &registry)
+        .with_component_filter(snapshots.components.clone())
+        .with_resource_filter(snapshots.resources.clone())
+        .extract_entities(rollback_entities.iter(world))
+        .extract_resources()
+        .build();
+    let data = postcard::to_allocvec(&DynamicWorldSerializer::new(&dynamic_world, &registry))
+        .map_err(RollbackError::from)?;
+    drop(registry);
+
+    let time = world.get_resource::<Time<Fixed>>().copied();
+    let mut snapshots = world.resource_mut::<RollbackSnapshots>();
+    snapshots.push(RollbackSnapshot { tick, data, time });
+    let oldest_tick = snapshots
+        .snapshots
+        .front()
+        .map_or(tick, |snapshot| snapshot.tick);
+    if let Some(mut entity_map) = world.get_resource_mut::<RollbackEntityMap>() {
+        entity_map.prune(oldest_tick);
+    }
+    Ok(())
+}
+
+/// Restores the world to the snapshot of `tick`, then runs [`FixedMain`] again up to the last
+/// simulated tick, capturing new snapshots along the way.
+///
+/// [`Time<Fixed>`] is advanced by its timestep for each re-simulated tick, as it was when these
+/// ticks were first simulated.
+///
+/// This must be called outside of the fixed main loop, for example from an exclusive system in
+/// `PreUpdate`. Returns the number of re-simulated ticks.
+pub fn rollback(world: &mut World, tick: u64) -> Result<u64, RollbackError> {
+    let present = world.resource::<RollbackSnapshots>().tick;
+    let present_time = world.get_resource::<Time<Fixed>>().copied();
+    restore_snapshot(world, tick)?;
+
+    for _ in tick..present {
+        if let Some(mut time) = world.get_resource_mut::<Time<Fixed>>() {
+            let timestep = time.timestep();
+            time.advance_by(timestep);
+            let time = time.as_generic();
+            *world.resource_mut::<Time>() = time;
+        }
+        world.try_run_schedule(FixedMain)?;
+    }
+    // Keep the overstep accumulated since the last tick
+    if let Some(time) = present_time {
+        world.insert_resource(time);
+    }
+    if let Some(time) = world.get_resource::<Time<Virtual>>() {
+        *world.resource_mut::<Time>() = time.as_generic();
+    }
+    Ok(present.saturating_sub(tick))
+}
+
+/// Restores the world to the snapshot of `tick`, without re-simulating.
+///
+/// [`Rollback`] entities spawned after the snapshot are despawned, and entities despawned since
+/// are respawned, see [`RollbackEntityMap`]. [`Time<Fixed>`] is restored to the time of the
+/// snapshot. Snapshots newer than `tick` are dropped.
+///
+/// Asset handles in the snapshot are loaded with the [`AssetServer`], and restoring fails with
+/// [`RollbackError::MissingAssetServer`] if the world has none.
+pub fn restore_snapshot(world: &mut World, tick: u64) -> Result<(), RollbackError> {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let (dynamic_world, time) = {
+        let snapshots = world.resource::<RollbackSnapshots>();
+        let snapshot = snapshots
+            .get(tick)
+            .ok_or(RollbackError::UnknownTick(tick))?;
+        let mut load_from_path = SnapshotAssets {
+            asset_server: world.get_resource::<AssetServer>().cloned(),
+            missing: None,
+        };
+        let dynamic_world = WorldDeserializer {
+            type_registry: &registry,
+            load_from_path: &mut load_from_path,
+        }
+        .deserialize(&mut postcard::Deserializer::from_bytes(&snapshot.data))?;
+        if let Some(path) = load_from_path.missing {
+            return Err(RollbackError::MissingAssetServer(path));
+        }
+        (dynamic_world, snapshot.time)
+    };
+    if let Some(time) = time {
+        world.insert_resource(time);
+    }
+
+    let mut snapshots = world.resource_mut::<RollbackSnapshots>();
+    snapshots.snapshots.retain(|snapshot| snapshot.tick <= tick);
+    snapshots.tick = tick;
+    let components = snapshots.components.clone();
+
+    // Entities outside of the snapshot are mapped to themselves, so that references to them
+    // are kept as-is.
+    let mut referenced = EntityHashSet::default();
+    for value in dynamic_world
+        .entities
+        .iter()
+        .flat_map(|entity| &entity.components)
+        .chain(&dynamic_world.resources)
+    {
+        collect_entities(value.as_partial_reflect(), &mut referenced);
+    }
+    let mut entity_map: EntityHashMap<Entity> = referenced
+        .into_iter()
+        .filter(|entity| world.get_entity(*entity).is_ok())
+        .map(|entity| (entity, entity))
+        .collect();
+    let mut restored = EntityHashSet::default();
+    let mut respawned = Vec::new();
+    for dynamic_entity in &dynamic_world.entities {
+        let snapshot_entity = dynamic_entity.entity;
+        let entity = world.resource::<RollbackEntityMap>().get(snapshot_entity);
+        let entity = if world.get_entity(entity).is_ok() {
+            entity
+        } else {
+            let entity = world.spawn(Rollback).id();
+            world
+                .resource_mut::<RollbackEntityMap>()
+                .insert(snapshot_entity, entity, tick);
+            respawned.push(RollbackRespawn {
+                snapshot_entity,
+                entity,
+            });
+            entity
+        };
+        entity_map.insert(snapshot_entity, entity);
+        restored.insert(entity);
+    }
+
+    let mut rollback_entities = world.query_filtered::<Entity, With<Rollback>>();
+    let spawned_since: Vec<_> = rollback_entities
+        .iter(world)
+        .filter(|entity| !restored.contains(entity))
+        .collect();
+    for entity in spawned_since {
+        world.despawn(entity);
+    }
+    for dynamic_entity in &dynamic_world.entities {
+        let entity = entity_map[&dynamic_entity.entity];
+        remove_components_added_since(world, entity, dynamic_entity, &components, &registry);
+    }
+
+    dynamic_world.write_to_world_with(world, &mut entity_map, &registry)?;
+    for respawn in respawned {
+        world.trigger(respawn);
+    }
+    Ok(())
+}
+
+/// Collects the entities referenced by a reflected `value`.
+fn collect_entities(value: &dyn PartialReflect, entities: &mut EntityHashSet) {
+    if let Some(entity) = value.try_downcast_ref::<Entity>() {
+        entities.insert(*entity);
+        return;
+    }
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for (_, field) in value.iter_fields() {
+                collect_entities(field, entities);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for field in value.iter_fields() {
+                collect_entities(field, entities);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for field in value.iter_fields() {
+                collect_entities(field, entities);
+            }
+        }
+        ReflectRef::List(value) => {
+            for item in value.iter() {
+                collect_entities(item, entities);
+            }
+        }
+        ReflectRef::Array(value) => {
+            for item in value.iter() {
+                collect_entities(item, entities);
+            }
+        }
+        ReflectRef::Map(value) => {
+            for (key, item) in value.iter() {
+                collect_entities(key, entities);
+                collect_entities(item, entities);
+            }
+        }
+        ReflectRef::Set(value) => {
+            for item in value.iter() {
+                collect_entities(item, entities);
+            }
+        }
+        ReflectRef::Enum(value) => {
+            for field in value.iter_fields() {
+                collect_entities(field.value(), entities);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Removes the captured components of `entity` that are missing from its snapshot.
+fn remove_components_added_since(
+    world: &mut World,
+    entity: Entity,
+    dynamic_entity: &DynamicEntity,
+    components: &WorldFilter,
+    registry: &TypeRegistry,
+) {
+    let snapshot_types: Vec<TypeId> = dynamic_entity
+        .components
+        .iter()
+        .filter_map(|component| component.get_represented_type_info())
+        .map(bevy_reflect::TypeInfo::type_id)
+        .collect();
+    let added_since: Vec<_> = world
+        .entity(entity)
+        .archetype()
+        .components()
+        .iter()
+        .filter_map(|&id| world.components().get_info(id)?.type_id())
+        .filter(|type_id| {
+            components.is_allowed_by_id(*type_id) && !snapshot_types.contains(type_id)
+        })
+        .filter_map(|type_id| registry.get(type_id)?.data::<ReflectComponent>().cloned())
+        .collect();
+    for reflect_component in added_since {
+        reflect_component.remove(&mut world.entity_mut(entity));
+    }
+}
+
+/// Loads the asset handles of snapshots.
+struct SnapshotAssets {
+    asset_server: Option<AssetServer>,
+    /// The first asset path that couldn't be loaded because there is no [`AssetServer`].
+    missing: Option<AssetPath<'static>>,
+}
+
+impl LoadFromPath for SnapshotAssets {
+    fn load_from_path_erased(
+        &mut self,
+        type_id: TypeId,
+        path: AssetPath<'static>,
+    ) -> UntypedHandle {
+        let Some(asset_server) = &mut self.asset_server else {
+            // The snapshot is rejected once deserialized, so this handle is never used.
+            self.missing.get_or_insert(path);
+            return UntypedHandle::Uuid {
+                type_id,
+                uuid: Uuid::nil(),
+            };
+        };
+        asset_server.load_from_path_erased(type_id, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, FixedUpdate, TaskPoolPlugin};
+    use bevy_asset::{
+        io::{memory::MemoryAssetReader, AssetSourceBuilder, AssetSourceId},
+        Asset, AssetApp, AssetPlugin, Handle,
+    };
+    use bevy_ecs::prelude::*;
+    use bevy_reflect::Reflect;
+    use bevy_time::{TimePlugin, TimeUpdateStrategy};
+    use core::time::Duration;
+
+    use super::*;
+
+    #[derive(Component, Reflect, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Position(i32);
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct Frozen;
+
+    #[derive(Component, Reflect, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Target(#[entities] Entity);
+
+    #[derive(Resource, Reflect, Default)]
+    #[reflect(Resource)]
+    struct Steps(u32);
+
+    #[derive(Asset, Reflect)]
+    struct Sprite;
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct Skin(Handle<Sprite>);
+
+    /// The input of every tick, which isn't rolled back.
+    #[derive(Resource)]
+    struct Input(i32);
+
+    #[derive(Resource, Default)]
+    struct Respawns(Vec<RollbackRespawn>);
+
+    /// The elapsed fixed time seen by every simulated tick.
+    #[derive(Resource, Default)]
+    struct Elapsed(Vec<Duration>);
+
+    fn movement(
+        input: Res<Input>,
+        mut steps: ResMut<Steps>,
+        mut positions: Query<&mut Position, Without<Frozen>>,
+    ) {
+        steps.0 += 1;
+        for mut position in &mut positions {
+            position.0 += input.0;
+        }
+    }
+
+    #[test]
+    fn rollback_and_resimulate() {
+        let mut app = App::new();
+        app.add_plugins((
+            TimePlugin,
+            RollbackPlugin::default()
+                .allow_component::<Position>()
+                .allow_component::<Frozen>()
+                .allow_component::<Target>()
+                .allow_resource::<Steps>(),
+        ))
+        .register_type::<Position>()
+        .register_type::<Frozen>()
+        .register_type::<Target>()
+        .register_type::<Steps>()
+        .init_resource::<Steps>()
+        .init_resource::<Respawns>()
+        .insert_resource(Input(1))
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(1))
+        .add_systems(FixedUpdate, movement)
+        .add_observer(
+            |respawn: On<RollbackRespawn>, mut respawns: ResMut<Respawns>| {
+                respawns.0.push(*respawn);
+            },
+        );
+
+        // Entities outside of the snapshots keep being referenced as-is
+        let anchor = app.world_mut().spawn_empty().id();
+        let player = app
+            .world_mut()
+            .spawn((Rollback, Position(0), Target(anchor)))
+            .id();
+        let doomed = app.world_mut().spawn((Rollback, Position(0))).id();
+        while app.world().resource::<RollbackSnapshots>().tick() < 3 {
+            app.update();
+        }
+        let world = app.world_mut();
+        assert_eq!(world.get::<Position>(player), Some(&Position(3)));
+        assert_eq!(world.resource::<RollbackSnapshots>().iter().count(), 3);
+
+        world.despawn(doomed);
+        world.entity_mut(player).insert(Frozen);
+        let late = world.spawn((Rollback, Position(10))).id();
+
+        // The input of ticks 2 and 3 was actually 5.
+        world.resource_mut::<Input>().0 = 5;
+        assert_eq!(rollback(world, 1).unwrap(), 2);
+        assert_eq!(world.resource::<RollbackSnapshots>().tick(), 3);
+        assert_eq!(world.resource::<Steps>().0, 3);
+        assert_eq!(world.get::<Position>(player), Some(&Position(11)));
+        assert!(world.get::<Frozen>(player).is_none());
+        assert_eq!(world.get::<Target>(player), Some(&Target(anchor)));
+        assert!(world.get_entity(late).is_err());
+
+        let respawns = &world.resource::<Respawns>().0;
+        assert_eq!(respawns.len(), 1);
+        assert_eq!(respawns[0].snapshot_entity, doomed);
+        let respawned = respawns[0].entity;
+        assert_eq!(world.get::<Position>(respawned), Some(&Position(11)));
+
+        // Older snapshots are restored onto the respawned entity.
+        restore_snapshot(world, 1).unwrap();
+        assert_eq!(world.resource::<RollbackSnapshots>().tick(), 1);
+        assert_eq!(world.get::<Position>(respawned), Some(&Position(1)));
+        assert_eq!(world.resource::<Respawns>().0.len(), 1);
+        assert_eq!(world.resource::<RollbackEntityMap>().get(doomed), respawned);
+
+        assert!(matches!(
+            restore_snapshot(world, 2),
+            Err(RollbackError::UnknownTick(2))
+        ));
+    }
+
+    #[test]
+    fn resimulation_advances_fixed_time() {
+        let mut app = App::new();
+        app.add_plugins((TimePlugin, RollbackPlugin::default()))
+            .init_resource::<Elapsed>()
+            .insert_resource(TimeUpdateStrategy::FixedTimesteps(1))
+            .add_systems(
+                FixedUpdate,
+                |time: Res<Time>, mut elapsed: ResMut<Elapsed>| {
+                    elapsed.0.push(time.elapsed());
+                },
+            );
+
+        while app.world().resource::<RollbackSnapshots>().tick() < 3 {
+            app.update();
+        }
+        let world = app.world_mut();
+        let present = *world.resource::<Time<Fixed>>();
+        let elapsed = core::mem::take(&mut world.resource_mut::<Elapsed>().0);
+        assert_eq!(elapsed.len(), 3);
+
+        assert_eq!(rollback(world, 1).unwrap(), 2);
+        assert_eq!(world.resource::<Elapsed>().0, elapsed[1..]);
+        assert_eq!(world.resource::<Time<Fixed>>().elapsed(), present.elapsed());
+        assert_eq!(
+            world.resource::<Time>().elapsed(),
+            world.resource::<Time<Virtual>>().elapsed()
+        );
+
+        restore_snapshot(world, 1).unwrap();
+        assert_eq!(world.resource::<Time<Fixed>>().elapsed(), elapsed[0]);
+    }
+
+    #[test]
+    fn entity_map_is_pruned_with_snapshots() {
+        let mut app = App::new();
+        app.add_plugins((
+            TimePlugin,
+            RollbackPlugin::default()
+                .with_capacity(2)
+                .allow_component::<Position>(),
+        ))
+        .register_type::<Position>()
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(1));
+
+        let doomed = app.world_mut().spawn((Rollback, Position(0))).id();
+        let run_until = |app: &mut App, tick: u64| {
+            while app.world().resource::<RollbackSnapshots>().tick() < tick {
+                app.update();
+            }
+        };
+        run_until(&mut app, 1);
+        app.world_mut().despawn(doomed);
+        restore_snapshot(app.world_mut(), 1).unwrap();
+        let respawned = app.world().resource::<RollbackEntityMap>().get(doomed);
+        assert_ne!(respawned, doomed);
+
+        // The snapshot of tick 1 is still in the buffer.
+        run_until(&mut app, 2);
+        assert_eq!(app.world().resource::<RollbackEntityMap>().len(), 1);
+
+        // Once it's dropped, no snapshot refers to the despawned entity anymore.
+        run_until(&mut app, 3);
+        assert!(app.world().resource::<RollbackSnapshots>().get(1).is_none());
+        assert!(app.world().resource::<RollbackEntityMap>().is_empty());
+        assert!(app.world().get::<Position>(respawned).is_some());
+    }
+
+    #[test]
+    fn asset_handles_need_an_asset_server() {
+        let mut app = App::new();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSourceBuilder::new(|| {
+                Box::new(MemoryAssetReader {
+                    root: Default::default(),
+                })
+            }),
+        )
+        .add_plugins((
+            TaskPoolPlugin::default(),
+            AssetPlugin::default(),
+            TimePlugin,
+            RollbackPlugin::default().allow_component::<Skin>(),
+        ))
+        .init_asset::<Sprite>()
+        .register_type::<Handle<Sprite>>()
+        .register_type::<Skin>()
+        .insert_resource(TimeUpdateStrategy::FixedTimesteps(1));
+
+        let handle = app.world().resource::<AssetServer>().load("skin.png");
+        app.world_mut().spawn((Rollback, Skin(handle)));
+        while app.world().resource::<RollbackSnapshots>().tick() < 1 {
+            app.update();
+        }
+
+        let world = app.world_mut();
+        restore_snapshot(world, 1).unwrap();
+        world.remove_resource::<AssetServer>();
+        assert!(matches!(
+            restore_snapshot(world, 1),
+            Err(RollbackError::MissingAssetServer(path)) if path == AssetPath::from("skin.png")
+        ));
+    }
+}
@@ -1,22 +1,24 @@
 //! `serde` serialization and deserialization implementation for Bevy worlds.
 
-use crate::{DynamicEntity, DynamicWorld};
+use crate::{
+    migration::{MigrationProcessor, TypePathDeserializer},
+    DynamicEntity, DynamicWorld, WorldMigrations, WorldMigrator,
+};
+use alloc::collections::BTreeMap;
 use bevy_asset::{
     EphemeralHandleBehavior, HandleDeserializeProcessor, HandleSerializeProcessor, LoadFromPath,
 };
 use bevy_ecs::entity::Entity;
 use bevy_platform::collections::HashSet;
 use bevy_reflect::{
-    serde::{
-        ReflectDeserializer, TypeRegistrationDeserializer, TypedReflectDeserializer,
-        TypedReflectSerializer,
-    },
-    PartialReflect, ReflectFromReflect, TypeRegistry,
+    serde::{ReflectDeserializer, TypedReflectDeserializer, TypedReflectSerializer},
+    PartialReflect, ReflectFromReflect, TypeRegistration, TypeRegistry,
 };
+use bevy_utils::TypeIdMap;
 use core::fmt::Formatter;
 use serde::{
     de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor},
-    ser::{SerializeMap, SerializeStruct},
+    ser::{self, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple},
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
@@ -26,6 +28,10 @@ pub const WORLD_STRUCT: &str = "World";
 pub const WORLD_RESOURCES: &str = "resources";
 /// Name of the serialized entities field in a world struct.
 pub const WORLD_ENTITIES: &str = "entities";
+/// Name of the serialized schema version field in a world struct.
+pub const WORLD_VERSION: &str = "version";
+/// Name of the serialized type versions field in a world struct.
+pub const WORLD_TYPE_VERSIONS: &str = "type_versions";
 
 /// Name of the serialized entity struct type.
 pub const ENTITY_STRUCT: &str = "Entity";
@@ -74,14 +80,53 @@ impl<'a> DynamicWorldSerializer<'a> {
     pub fn new(world: &'a DynamicWorld, registry: &'a TypeRegistry) -> Self {
         DynamicWorldSerializer { world, registry }
     }
-}
 
-impl<'a> Serialize for DynamicWorldSerializer<'a> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    /// Writes the schema version and type versions of `migrations` before the world, so that it can be
+    /// migrated when it is loaded by a later version of the app.
+    ///
+    /// The world must be read by a [`WorldDeserializer`] with the same migrations, see
+    /// [`WorldDeserializer::with_migrations`].
+    pub fn with_migrations(self, migrations: &'a WorldMigrations) -> WithMigrations<'a, Self> {
+        WithMigrations {
+            inner: self,
+            migrations,
+        }
+    }
+
+    fn serialize_with<S>(
+        &self,
+        migrations: Option<&WorldMigrations>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct(WORLD_STRUCT, 2)?;
+        // Fields are read by name in human-readable formats, so the versions of unversioned
+        // worlds can be left out. Other formats read the fields in order, and always have them.
+        let human_readable = serializer.is_human_readable();
+        let len = if human_readable && migrations.is_none() {
+            2
+        } else {
+            4
+        };
+        let mut state = serializer.serialize_struct(WORLD_STRUCT, len)?;
+        match migrations {
+            Some(migrations) if human_readable => {
+                state.serialize_field(WORLD_VERSION, &migrations.schema_version())?;
+                state.serialize_field(WORLD_TYPE_VERSIONS, &migrations.type_versions())?;
+            }
+            None if human_readable => {}
+            _ => {
+                state.serialize_field(
+                    WORLD_VERSION,
+                    &migrations.map(WorldMigrations::schema_version),
+                )?;
+                state.serialize_field(
+                    WORLD_TYPE_VERSIONS,
+                    &migrations.map(WorldMigrations::type_versions),
+                )?;
+            }
+        }
         state.serialize_field(
             WORLD_RESOURCES,
             &WorldMapSerializer {
@@ -100,6 +145,34 @@ impl<'a> Serialize for DynamicWorldSerializer<'a> {
     }
 }
 
+impl<'a> Serialize for DynamicWorldSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.serialize_with(None, serializer)
+    }
+}
+
+impl<'a> Serialize for WithMigrations<'a, DynamicWorldSerializer<'a>> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.inner.serialize_with(Some(self.migrations), serializer)
+    }
+}
+
+/// A world serializer or deserializer which writes or migrates the versions of
+/// [`WorldMigrations`].
+///
+/// This is created by the `with_migrations` method of [`DynamicWorldSerializer`],
+/// [`BinaryWorldSerializer`], [`WorldDeserializer`] and [`BinaryWorldDeserializer`].
+pub struct WithMigrations<'a, T> {
+    inner: T,
+    migrations: &'a WorldMigrations,
+}
+
 /// Handles serialization of multiple entities as a map of entity id to serialized entity.
 pub struct EntitiesSerializer<'a> {
     /// The entities to serialize.
@@ -205,8 +278,10 @@ impl<'a> Serialize for WorldMapSerializer<'a> {
 }
 
 #[derive(Deserialize)]
-#[serde(field_identifier, rename_all = "lowercase")]
+#[serde(field_identifier, rename_all = "snake_case")]
 enum WorldField {
+    Version,
+    TypeVersions,
     Resources,
     Entities,
 }
@@ -225,27 +300,99 @@ pub struct WorldDeserializer<'a> {
     pub load_from_path: &'a mut dyn LoadFromPath,
 }
 
-impl<'a, 'de> DeserializeSeed<'de> for WorldDeserializer<'a> {
-    type Value = DynamicWorld;
+impl<'a> WorldDeserializer<'a> {
+    /// Runs `migrations` on the types saved at an older version, if the world was serialized with
+    /// [`DynamicWorldSerializer::with_migrations`].
+    ///
+    /// Worlds saved without versions are loaded as the current version, unless
+    /// [`WorldMigrations::set_migrate_unversioned`] is set.
+    pub fn with_migrations(self, migrations: &'a WorldMigrations) -> WithMigrations<'a, Self> {
+        WithMigrations {
+            inner: self,
+            migrations,
+        }
+    }
 
-    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    fn deserialize_with<'de, D>(
+        self,
+        migrations: Option<&'a WorldMigrations>,
+        deserializer: D,
+    ) -> Result<DynamicWorld, D::Error>
     where
         D: Deserializer<'de>,
     {
         deserializer.deserialize_struct(
             WORLD_STRUCT,
-            &[WORLD_RESOURCES, WORLD_ENTITIES],
+            &[
+                WORLD_VERSION,
+                WORLD_TYPE_VERSIONS,
+                WORLD_RESOURCES,
+                WORLD_ENTITIES,
+            ],
             WorldVisitor {
                 type_registry: self.type_registry,
                 load_from_path: self.load_from_path,
+                migrations,
             },
         )
     }
 }
 
+impl<'a, 'de> DeserializeSeed<'de> for WorldDeserializer<'a> {
+    type Value = DynamicWorld;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize_with(None, deserializer)
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for WithMigrations<'a, WorldDeserializer<'a>> {
+    type Value = DynamicWorld;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner
+            .deserialize_with(Some(self.migrations), deserializer)
+    }
+}
+
 struct WorldVisitor<'a> {
     type_registry: &'a TypeRegistry,
     load_from_path: &'a mut dyn LoadFromPath,
+    migrations: Option<&'a WorldMigrations>,
+}
+
+/// Creates the migrator for a world saved with the given versions, if there are migrations to run.
+fn world_migrator<'a, E: Error>(
+    migrations: Option<&'a WorldMigrations>,
+    registry: &TypeRegistry,
+    version: Option<u32>,
+    type_versions: Option<BTreeMap<String, u32>>,
+) -> Result<Option<WorldMigrator<'a>>, E> {
+    let versions = (version.is_some() || type_versions.is_some()).then(|| {
+        (
+            version.unwrap_or_default(),
+            type_versions.unwrap_or_default(),
+        )
+    });
+    migrations
+        .map(|migrations| WorldMigrator::new(migrations, registry, versions))
+        .transpose()
+}
+
+impl<'a> WorldVisitor<'a> {
+    fn migrator<E: Error>(
+        &self,
+        version: Option<u32>,
+        type_versions: Option<BTreeMap<String, u32>>,
+    ) -> Result<Option<WorldMigrator<'a>>, E> {
+        world_migrator(self.migrations, self.type_registry, version, type_versions)
+    }
 }
 
 impl<'a, 'de> Visitor<'de> for WorldVisitor<'a> {
@@ -259,17 +406,27 @@ impl<'a, 'de> Visitor<'de> for WorldVisitor<'a> {
     where
         A: SeqAccess<'de>,
     {
+        let version = seq
+            .next_element::<Option<u32>>()?
+            .ok_or_else(|| Error::missing_field(WORLD_VERSION))?;
+        let type_versions = seq
+            .next_element::<Option<BTreeMap<String, u32>>>()?
+            .ok_or_else(|| Error::missing_field(WORLD_TYPE_VERSIONS))?;
+        let migrator = self.migrator(version, type_versions)?;
+
         let resources = seq
-            .next_element_seed(WorldMapDeserializer {
+            .next_element_seed(WorldMapVisitor {
                 registry: self.type_registry,
                 load_from_path: self.load_from_path,
+                migrator: migrator.as_ref(),
             })?
             .ok_or_else(|| Error::missing_field(WORLD_RESOURCES))?;
 
         let entities = seq
-            .next_element_seed(WorldEntitiesDeserializer {
+            .next_element_seed(WorldEntitiesVisitor {
                 type_registry: self.type_registry,
                 load_from_path: self.load_from_path,
+                migrator: migrator.as_ref(),
             })?
             .ok_or_else(|| Error::missing_field(WORLD_ENTITIES))?;
 
@@ -283,26 +440,57 @@ impl<'a, 'de> Visitor<'de> for WorldVisitor<'a> {
     where
         A: MapAccess<'de>,
     {
+        let mut version = None;
+        let mut type_versions = None;
+        // The migrator is created from the versions once the first resources or entities are found.
+        let mut migrator = None;
         let mut resources = None;
         let mut entities = None;
         while let Some(key) = map.next_key()? {
             match key {
+                WorldField::Version | WorldField::TypeVersions
+                    if resources.is_some() || entities.is_some() =>
+                {
+                    return Err(Error::custom(format_args!(
+                        "`{WORLD_VERSION}` and `{WORLD_TYPE_VERSIONS}` must come before `{WORLD_RESOURCES}` and `{WORLD_ENTITIES}`"
+                    )));
+                }
+                WorldField::Version => {
+                    if version.is_some() {
+                        return Err(Error::duplicate_field(WORLD_VERSION));
+                    }
+                    version = Some(map.next_value()?);
+                }
+                WorldField::TypeVersions => {
+                    if type_versions.is_some() {
+                        return Err(Error::duplicate_field(WORLD_TYPE_VERSIONS));
+                    }
+                    type_versions = Some(map.next_value()?);
+                }
                 WorldField::Resources => {
                     if resources.is_some() {
                         return Err(Error::duplicate_field(WORLD_RESOURCES));
                     }
-                    resources = Some(map.next_value_seed(WorldMapDeserializer {
+                    if entities.is_none() {
+                        migrator = self.migrator(version, type_versions.take())?;
+                    }
+                    resources = Some(map.next_value_seed(WorldMapVisitor {
                         registry: self.type_registry,
                         load_from_path: self.load_from_path,
+                        migrator: migrator.as_ref(),
                     })?);
                 }
                 WorldField::Entities => {
                     if entities.is_some() {
                         return Err(Error::duplicate_field(WORLD_ENTITIES));
                     }
-                    entities = Some(map.next_value_seed(WorldEntitiesDeserializer {
+                    if resources.is_none() {
+                        migrator = self.migrator(version, type_versions.take())?;
+                    }
+                    entities = Some(map.next_value_seed(WorldEntitiesVisitor {
                         type_registry: self.type_registry,
                         load_from_path: self.load_from_path,
+                        migrator: migrator.as_ref(),
                     })?);
                 }
             }
@@ -333,16 +521,30 @@ impl<'a, 'de> DeserializeSeed<'de> for WorldEntitiesDeserializer<'a> {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_map(WorldEntitiesVisitor {
+        WorldEntitiesVisitor {
             type_registry: self.type_registry,
             load_from_path: self.load_from_path,
-        })
+            migrator: None,
+        }
+        .deserialize(deserializer)
     }
 }
 
 struct WorldEntitiesVisitor<'a> {
     type_registry: &'a TypeRegistry,
     load_from_path: &'a mut dyn LoadFromPath,
+    migrator: Option<&'a WorldMigrator<'a>>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for WorldEntitiesVisitor<'a> {
+    type Value = Vec<DynamicEntity>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
 }
 
 impl<'a, 'de> Visitor<'de> for WorldEntitiesVisitor<'a> {
@@ -358,10 +560,11 @@ impl<'a, 'de> Visitor<'de> for WorldEntitiesVisitor<'a> {
     {
         let mut entities = Vec::new();
         while let Some(entity) = map.next_key::<Entity>()? {
-            let entity = map.next_value_seed(WorldEntityDeserializer {
+            let entity = map.next_value_seed(WorldEntityVisitor {
                 entity,
-                type_registry: self.type_registry,
+                registry: self.type_registry,
                 load_from_path: self.load_from_path,
+                migrator: self.migrator,
             })?;
             entities.push(entity);
         }
@@ -387,15 +590,13 @@ impl<'a, 'de> DeserializeSeed<'de> for WorldEntityDeserializer<'a> {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_struct(
-            ENTITY_STRUCT,
-            &[ENTITY_FIELD_COMPONENTS],
-            WorldEntityVisitor {
-                entity: self.entity,
-                registry: self.type_registry,
-                load_from_path: self.load_from_path,
-            },
-        )
+        WorldEntityVisitor {
+            entity: self.entity,
+            registry: self.type_registry,
+            load_from_path: self.load_from_path,
+            migrator: None,
+        }
+        .deserialize(deserializer)
     }
 }
 
@@ -403,6 +604,18 @@ struct WorldEntityVisitor<'a> {
     entity: Entity,
     registry: &'a TypeRegistry,
     load_from_path: &'a mut dyn LoadFromPath,
+    migrator: Option<&'a WorldMigrator<'a>>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for WorldEntityVisitor<'a> {
+    type Value = DynamicEntity;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(ENTITY_STRUCT, &[ENTITY_FIELD_COMPONENTS], self)
+    }
 }
 
 impl<'a, 'de> Visitor<'de> for WorldEntityVisitor<'a> {
@@ -417,9 +630,10 @@ impl<'a, 'de> Visitor<'de> for WorldEntityVisitor<'a> {
         A: SeqAccess<'de>,
     {
         let components = seq
-            .next_element_seed(WorldMapDeserializer {
+            .next_element_seed(WorldMapVisitor {
                 registry: self.registry,
                 load_from_path: self.load_from_path,
+                migrator: self.migrator,
             })?
             .ok_or_else(|| Error::missing_field(ENTITY_FIELD_COMPONENTS))?;
 
@@ -441,9 +655,10 @@ impl<'a, 'de> Visitor<'de> for WorldEntityVisitor<'a> {
                         return Err(Error::duplicate_field(ENTITY_FIELD_COMPONENTS));
                     }
 
-                    components = Some(map.next_value_seed(WorldMapDeserializer {
+                    components = Some(map.next_value_seed(WorldMapVisitor {
                         registry: self.registry,
                         load_from_path: self.load_from_path,
+                        migrator: self.migrator,
                     })?);
                 }
             }
@@ -474,16 +689,30 @@ impl<'a, 'de> DeserializeSeed<'de> for WorldMapDeserializer<'a> {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_map(WorldMapVisitor {
+        WorldMapVisitor {
             registry: self.registry,
             load_from_path: self.load_from_path,
-        })
+            migrator: None,
+        }
+        .deserialize(deserializer)
     }
 }
 
 struct WorldMapVisitor<'a> {
     registry: &'a TypeRegistry,
     load_from_path: &'a mut dyn LoadFromPath,
+    migrator: Option<&'a WorldMigrator<'a>>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for WorldMapVisitor<'a> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
 }
 
 impl<'a, 'de> Visitor<'de> for WorldMapVisitor<'a> {
@@ -511,9 +740,10 @@ impl<'a, 'de> Visitor<'de> for WorldMapVisitor<'a> {
     {
         let mut added = <HashSet<_>>::default();
         let mut entries = Vec::new();
-        while let Some(registration) =
-            map.next_key_seed(TypeRegistrationDeserializer::new(self.registry))?
-        {
+        while let Some(registration) = map.next_key_seed(TypePathDeserializer {
+            registry: self.registry,
+            migrations: self.migrator.map(WorldMigrator::migrations),
+        })? {
             if !added.insert(registration.type_id()) {
                 return Err(Error::custom(format_args!(
                     "duplicate reflect type: `{}`",
@@ -524,125 +754,654 @@ impl<'a, 'de> Visitor<'de> for WorldMapVisitor<'a> {
             let value = map.next_value_seed(TypedReflectDeserializer::with_processor(
                 registration,
                 self.registry,
-                &mut HandleDeserializeProcessor {
-                    load_from_path: self.load_from_path,
+                &mut MigrationProcessor {
+                    migrator: self.migrator,
+                    handles: HandleDeserializeProcessor {
+                        load_from_path: self.load_from_path,
+                    },
                 },
             ))?;
 
-            // Attempt to convert using FromReflect.
-            let value = self
-                .registry
-                .get(registration.type_id())
-                .and_then(|tr| tr.data::<ReflectFromReflect>())
-                .and_then(|fr| fr.from_reflect(value.as_partial_reflect()))
-                .map(PartialReflect::into_partial_reflect)
-                .unwrap_or(value);
-
-            entries.push(value);
+            entries.push(from_reflect_or_dynamic(self.registry, registration, value));
         }
 
         Ok(entries)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use crate::{
-        serde::{DynamicWorldSerializer, WorldDeserializer},
-        DynamicWorld, DynamicWorldBuilder,
-    };
-    use bevy_asset::{Asset, AssetPath, Handle, LoadFromPath, ReflectAsset, UntypedHandle};
-    use bevy_ecs::{
-        entity::{Entity, EntityHashMap},
-        prelude::{Component, ReflectComponent, ReflectResource, Resource, World},
-        query::{With, Without},
-        reflect::AppTypeRegistry,
-        world::FromWorld,
-    };
-    use bevy_reflect::{Reflect, ReflectDeserialize, ReflectSerialize};
-    use core::any::TypeId;
-    use ron;
-    use serde::{de::DeserializeSeed, Deserialize, Serialize};
-    use std::io::BufReader;
-    use uuid::Uuid;
-
-    #[derive(Component, Reflect, Default)]
-    #[reflect(Component)]
-    struct Foo(i32);
-    #[derive(Component, Reflect, Default)]
-    #[reflect(Component)]
-    struct Bar(i32);
-    #[derive(Component, Reflect, Default)]
-    #[reflect(Component)]
-    struct Baz(i32);
-
-    #[derive(Asset, Reflect)]
-    #[reflect(Asset)]
-    struct FakeMesh;
-
-    #[derive(Component, Reflect, Clone, PartialEq, Eq, Debug)]
-    #[reflect(Component)]
-    struct FakeMesh3d(Handle<FakeMesh>);
-
-    // De/serialize as hex.
-    mod qux {
-        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+/// Serializer for a [`DynamicWorld`], in a compact form meant for binary formats such as `postcard`.
+///
+/// Unlike [`DynamicWorldSerializer`], which writes the type path of every value, each type path is written once
+/// in a table at the start, and values refer to it by its index. This makes the output much smaller and faster to
+/// read for worlds with many entities. Use [`BinaryWorldDeserializer`] to read it back.
+///
+/// See also [`DynamicWorld::serialize_binary`], which serializes with `postcard`.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_world_serialization::{DynamicWorld, serde::BinaryWorldSerializer};
+/// # let mut world = World::default();
+/// # world.insert_resource(AppTypeRegistry::default());
+/// let registry = world.resource::<AppTypeRegistry>();
+/// let registry = registry.read();
+/// let dynamic_world = DynamicWorld::from_world(&world);
+///
+/// let serializer = BinaryWorldSerializer::new(&dynamic_world, &registry);
+/// let bytes = postcard::to_allocvec(&serializer);
+/// ```
+pub struct BinaryWorldSerializer<'a> {
+    /// The dynamic world to serialize.
+    pub world: &'a DynamicWorld,
+    /// The type registry containing the types present in the dynamic world.
+    pub registry: &'a TypeRegistry,
+}
 
-        pub fn serialize<S>(value: &u32, serializer: S) -> Result<S::Ok, S::Error>
-        where
-            S: Serializer,
-        {
-            serializer.serialize_str(&format!("{value:X}"))
-        }
+impl<'a> BinaryWorldSerializer<'a> {
+    /// Create a new serializer from a [`DynamicWorld`] and an associated [`TypeRegistry`].
+    ///
+    /// The type registry must contain all types present in the [`DynamicWorld`].
+    pub fn new(world: &'a DynamicWorld, registry: &'a TypeRegistry) -> Self {
+        BinaryWorldSerializer { world, registry }
+    }
 
-        pub fn deserialize<'de, D>(deserializer: D) -> Result<u32, D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            u32::from_str_radix(<&str as Deserialize>::deserialize(deserializer)?, 16)
-                .map_err(Error::custom)
+    /// Writes the schema version and type versions of `migrations` before the world, so that it can be
+    /// migrated when it is loaded by a later version of the app.
+    ///
+    /// The world must be read by a [`BinaryWorldDeserializer`] with the same migrations, see
+    /// [`BinaryWorldDeserializer::with_migrations`]. Since the fields of structs are saved without their
+    /// names, only type aliases apply to the world: types with field migrations to run fail to load.
+    pub fn with_migrations(self, migrations: &'a WorldMigrations) -> WithMigrations<'a, Self> {
+        WithMigrations {
+            inner: self,
+            migrations,
         }
     }
 
-    #[derive(Component, Copy, Clone, Reflect, Debug, PartialEq, Serialize, Deserialize)]
-    #[reflect(Component, Serialize, Deserialize)]
-    struct Qux(#[serde(with = "qux")] u32);
+    fn serialize_with<S>(
+        &self,
+        migrations: Option<&WorldMigrations>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut types = Vec::new();
+        let mut indices = TypeIdMap::default();
+        let values = self.world.resources.iter().chain(
+            self.world
+                .entities
+                .iter()
+                .flat_map(|entity| &entity.components),
+        );
+        for value in values {
+            let type_info = value.get_represented_type_info().ok_or_else(|| {
+                ser::Error::custom(format_args!(
+                    "`{}` does not represent a type",
+                    value.reflect_type_path()
+                ))
+            })?;
+            indices.entry(type_info.type_id()).or_insert_with(|| {
+                types.push(type_info.type_path());
+                types.len() - 1
+            });
+        }
 
-    #[derive(Component, Reflect, Default)]
-    #[reflect(Component)]
-    struct MyComponent {
-        foo: [usize; 3],
-        bar: (f32, f32),
-        baz: MyEnum,
+        let mut state = serializer.serialize_tuple(5)?;
+        state.serialize_element(&migrations.map(WorldMigrations::schema_version))?;
+        state.serialize_element(&migrations.map(WorldMigrations::type_versions))?;
+        state.serialize_element(&types)?;
+        state.serialize_element(&BinaryValuesSerializer {
+            entries: &self.world.resources,
+            indices: &indices,
+            registry: self.registry,
+        })?;
+        state.serialize_element(&BinaryEntitiesSerializer {
+            entities: &self.world.entities,
+            indices: &indices,
+            registry: self.registry,
+        })?;
+        state.end()
     }
+}
 
-    #[derive(Reflect, Default)]
-    enum MyEnum {
-        #[default]
-        Unit,
-        Tuple(String),
-        Struct {
-            value: u32,
-        },
+impl<'a> Serialize for BinaryWorldSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.serialize_with(None, serializer)
     }
+}
 
-    #[derive(Resource, Reflect, Default)]
-    #[reflect(Resource)]
-    struct MyResource {
-        foo: i32,
+impl<'a> Serialize for WithMigrations<'a, BinaryWorldSerializer<'a>> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.inner.serialize_with(Some(self.migrations), serializer)
     }
+}
 
-    #[derive(Clone, Component, Reflect, PartialEq)]
-    #[reflect(Component, PartialEq)]
-    struct MyEntityRef(#[entities] Entity);
+struct BinaryEntitiesSerializer<'a> {
+    entities: &'a [DynamicEntity],
+    indices: &'a TypeIdMap<usize>,
+    registry: &'a TypeRegistry,
+}
 
-    impl FromWorld for MyEntityRef {
-        fn from_world(_world: &mut World) -> Self {
-            Self(Entity::PLACEHOLDER)
+impl<'a> Serialize for BinaryEntitiesSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_seq(Some(self.entities.len()))?;
+        for entity in self.entities {
+            state.serialize_element(&(
+                entity.entity,
+                BinaryValuesSerializer {
+                    entries: &entity.components,
+                    indices: self.indices,
+                    registry: self.registry,
+                },
+            ))?;
         }
+        state.end()
     }
+}
 
-    fn create_world() -> World {
+/// Serializes values as a sequence of (type index, value) pairs.
+struct BinaryValuesSerializer<'a> {
+    entries: &'a [Box<dyn PartialReflect>],
+    indices: &'a TypeIdMap<usize>,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for BinaryValuesSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_seq(Some(self.entries.len()))?;
+        for entry in self.entries {
+            // Every type was added to the table by `BinaryWorldSerializer`
+            let index = self.indices[&entry.get_represented_type_info().unwrap().type_id()];
+            state.serialize_element(&(
+                index,
+                TypedReflectSerializer::with_processor(
+                    entry.as_partial_reflect(),
+                    self.registry,
+                    &HandleSerializeProcessor {
+                        ephemeral_handle_behavior: EphemeralHandleBehavior::Warn,
+                    },
+                ),
+            ))?;
+        }
+        state.end()
+    }
+}
+
+/// Handles deserialization of a world written by [`BinaryWorldSerializer`].
+pub struct BinaryWorldDeserializer<'a> {
+    /// Type registry in which the components and resources types used in the world to deserialize are registered.
+    pub type_registry: &'a TypeRegistry,
+    /// The [`LoadFromPath`] implementation allowing us to deserialize asset handles.
+    pub load_from_path: &'a mut dyn LoadFromPath,
+}
+
+impl<'a> BinaryWorldDeserializer<'a> {
+    /// Runs `migrations` on the types saved at an older version, if the world was serialized with
+    /// [`BinaryWorldSerializer::with_migrations`].
+    ///
+    /// Worlds saved without versions are loaded as the current version, unless
+    /// [`WorldMigrations::set_migrate_unversioned`] is set.
+    pub fn with_migrations(self, migrations: &'a WorldMigrations) -> WithMigrations<'a, Self> {
+        WithMigrations {
+            inner: self,
+            migrations,
+        }
+    }
+
+    fn deserialize_with<'de, D>(
+        self,
+        migrations: Option<&'a WorldMigrations>,
+        deserializer: D,
+    ) -> Result<DynamicWorld, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(
+            5,
+            BinaryWorldVisitor {
+                type_registry: self.type_registry,
+                load_from_path: self.load_from_path,
+                migrations,
+            },
+        )
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for BinaryWorldDeserializer<'a> {
+    type Value = DynamicWorld;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize_with(None, deserializer)
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for WithMigrations<'a, BinaryWorldDeserializer<'a>> {
+    type Value = DynamicWorld;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner
+            .deserialize_with(Some(self.migrations), deserializer)
+    }
+}
+
+struct BinaryWorldVisitor<'a> {
+    type_registry: &'a TypeRegistry,
+    load_from_path: &'a mut dyn LoadFromPath,
+    migrations: Option<&'a WorldMigrations>,
+}
+
+impl<'a, 'de> Visitor<'de> for BinaryWorldVisitor<'a> {
+    type Value = DynamicWorld;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("binary world")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let version = seq
+            .next_element::<Option<u32>>()?
+            .ok_or_else(|| Error::missing_field(WORLD_VERSION))?;
+        let type_versions = seq
+            .next_element::<Option<BTreeMap<String, u32>>>()?
+            .ok_or_else(|| Error::missing_field(WORLD_TYPE_VERSIONS))?;
+        let migrator = world_migrator(self.migrations, self.type_registry, version, type_versions)?;
+
+        let types = seq
+            .next_element_seed(TypeTableDeserializer {
+                registry: self.type_registry,
+                migrations: self.migrations,
+            })?
+            .ok_or_else(|| Error::invalid_length(2, &self))?;
+        let resources = seq
+            .next_element_seed(BinaryValuesDeserializer {
+                types: &types,
+                registry: self.type_registry,
+                load_from_path: self.load_from_path,
+                migrator: migrator.as_ref(),
+            })?
+            .ok_or_else(|| Error::missing_field(WORLD_RESOURCES))?;
+        let entities = seq
+            .next_element_seed(BinaryEntitiesDeserializer {
+                types: &types,
+                registry: self.type_registry,
+                load_from_path: self.load_from_path,
+                migrator: migrator.as_ref(),
+            })?
+            .ok_or_else(|| Error::missing_field(WORLD_ENTITIES))?;
+
+        Ok(DynamicWorld {
+            resources,
+            entities,
+        })
+    }
+}
+
+/// Deserializes the table of type paths into their registrations.
+struct TypeTableDeserializer<'a> {
+    registry: &'a TypeRegistry,
+    migrations: Option<&'a WorldMigrations>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for TypeTableDeserializer<'a> {
+    type Value = Vec<&'a TypeRegistration>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for TypeTableDeserializer<'a> {
+    type Value = Vec<&'a TypeRegistration>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("list of type paths")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut types = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(registration) = seq.next_element_seed(TypePathDeserializer {
+            registry: self.registry,
+            migrations: self.migrations,
+        })? {
+            types.push(registration);
+        }
+        Ok(types)
+    }
+}
+
+struct BinaryEntitiesDeserializer<'a> {
+    types: &'a [&'a TypeRegistration],
+    registry: &'a TypeRegistry,
+    load_from_path: &'a mut dyn LoadFromPath,
+    migrator: Option<&'a WorldMigrator<'a>>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for BinaryEntitiesDeserializer<'a> {
+    type Value = Vec<DynamicEntity>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for BinaryEntitiesDeserializer<'a> {
+    type Value = Vec<DynamicEntity>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("list of entities")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut entities = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(entity) = seq.next_element_seed(BinaryEntityDeserializer {
+            types: self.types,
+            registry: self.registry,
+            load_from_path: self.load_from_path,
+            migrator: self.migrator,
+        })? {
+            entities.push(entity);
+        }
+        Ok(entities)
+    }
+}
+
+struct BinaryEntityDeserializer<'a> {
+    types: &'a [&'a TypeRegistration],
+    registry: &'a TypeRegistry,
+    load_from_path: &'a mut dyn LoadFromPath,
+    migrator: Option<&'a WorldMigrator<'a>>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for BinaryEntityDeserializer<'a> {
+    type Value = DynamicEntity;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for BinaryEntityDeserializer<'a> {
+    type Value = DynamicEntity;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("entity and its components")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let entity = seq
+            .next_element::<Entity>()?
+            .ok_or_else(|| Error::invalid_length(0, &"entity and its components"))?;
+        let components = seq
+            .next_element_seed(BinaryValuesDeserializer {
+                types: self.types,
+                registry: self.registry,
+                load_from_path: self.load_from_path,
+                migrator: self.migrator,
+            })?
+            .ok_or_else(|| Error::missing_field(ENTITY_FIELD_COMPONENTS))?;
+        Ok(DynamicEntity { entity, components })
+    }
+}
+
+/// Deserializes a sequence of (type index, value) pairs, with unique types.
+struct BinaryValuesDeserializer<'a> {
+    types: &'a [&'a TypeRegistration],
+    registry: &'a TypeRegistry,
+    load_from_path: &'a mut dyn LoadFromPath,
+    migrator: Option<&'a WorldMigrator<'a>>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for BinaryValuesDeserializer<'a> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for BinaryValuesDeserializer<'a> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("list of reflect values")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut added = <HashSet<_>>::default();
+        let mut entries = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(entry) = seq.next_element_seed(BinaryValueDeserializer {
+            types: self.types,
+            registry: self.registry,
+            load_from_path: self.load_from_path,
+            migrator: self.migrator,
+        })? {
+            let type_info = entry.get_represented_type_info().unwrap();
+            if !added.insert(type_info.type_id()) {
+                return Err(Error::custom(format_args!(
+                    "duplicate reflect type: `{}`",
+                    type_info.type_path(),
+                )));
+            }
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+struct BinaryValueDeserializer<'a> {
+    types: &'a [&'a TypeRegistration],
+    registry: &'a TypeRegistry,
+    load_from_path: &'a mut dyn LoadFromPath,
+    migrator: Option<&'a WorldMigrator<'a>>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for BinaryValueDeserializer<'a> {
+    type Value = Box<dyn PartialReflect>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for BinaryValueDeserializer<'a> {
+    type Value = Box<dyn PartialReflect>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> core::fmt::Result {
+        formatter.write_str("type index and reflect value")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let index = seq
+            .next_element::<usize>()?
+            .ok_or_else(|| Error::invalid_length(0, &self))?;
+        let registration = *self
+            .types
+            .get(index)
+            .ok_or_else(|| Error::custom(format_args!("invalid type index: {index}")))?;
+        let value = seq
+            .next_element_seed(TypedReflectDeserializer::with_processor(
+                registration,
+                self.registry,
+                &mut MigrationProcessor {
+                    migrator: self.migrator,
+                    handles: HandleDeserializeProcessor {
+                        load_from_path: self.load_from_path,
+                    },
+                },
+            ))?
+            .ok_or_else(|| Error::invalid_length(1, &"type index and reflect value"))?;
+        Ok(from_reflect_or_dynamic(self.registry, registration, value))
+    }
+}
+
+/// Attempts to convert a deserialized value to its concrete type using `FromReflect`.
+fn from_reflect_or_dynamic(
+    registry: &TypeRegistry,
+    registration: &TypeRegistration,
+    value: Box<dyn PartialReflect>,
+) -> Box<dyn PartialReflect> {
+    registry
+        .get(registration.type_id())
+        .and_then(|tr| tr.data::<ReflectFromReflect>())
+        .and_then(|fr| fr.from_reflect(value.as_partial_reflect()))
+        .map(PartialReflect::into_partial_reflect)
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        serde::{
+            BinaryWorldDeserializer, BinaryWorldSerializer, DynamicWorldSerializer,
+            WorldDeserializer,
+        },
+        serialize_ron, DynamicWorld, DynamicWorldBuilder, TypeMigration, WorldMigrations,
+    };
+    use bevy_asset::{Asset, AssetPath, Handle, LoadFromPath, ReflectAsset, UntypedHandle};
+    use bevy_ecs::{
+        entity::{Entity, EntityHashMap},
+        prelude::{Component, ReflectComponent, ReflectResource, Resource, World},
+        query::{With, Without},
+        reflect::AppTypeRegistry,
+        world::FromWorld,
+    };
+    use bevy_reflect::{Reflect, ReflectDeserialize, ReflectSerialize};
+    use core::any::TypeId;
+    use ron;
+    use serde::{de::DeserializeSeed, Deserialize, Serialize};
+    use std::io::BufReader;
+    use uuid::Uuid;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Foo(i32);
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Bar(i32);
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Baz(i32);
+
+    #[derive(Asset, Reflect)]
+    #[reflect(Asset)]
+    struct FakeMesh;
+
+    #[derive(Component, Reflect, Clone, PartialEq, Eq, Debug)]
+    #[reflect(Component)]
+    struct FakeMesh3d(Handle<FakeMesh>);
+
+    // De/serialize as hex.
+    mod qux {
+        use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S>(value: &u32, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&format!("{value:X}"))
+        }
+
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<u32, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            u32::from_str_radix(<&str as Deserialize>::deserialize(deserializer)?, 16)
+                .map_err(Error::custom)
+        }
+    }
+
+    #[derive(Component, Copy, Clone, Reflect, Debug, PartialEq, Serialize, Deserialize)]
+    #[reflect(Component, Serialize, Deserialize)]
+    struct Qux(#[serde(with = "qux")] u32);
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct MyComponent {
+        foo: [usize; 3],
+        bar: (f32, f32),
+        baz: MyEnum,
+    }
+
+    #[derive(Reflect, Default)]
+    enum MyEnum {
+        #[default]
+        Unit,
+        Tuple(String),
+        Struct {
+            value: u32,
+        },
+    }
+
+    #[derive(Resource, Reflect, Default)]
+    #[reflect(Resource)]
+    struct MyResource {
+        foo: i32,
+    }
+
+    #[derive(Clone, Component, Reflect, PartialEq)]
+    #[reflect(Component, PartialEq)]
+    struct MyEntityRef(#[entities] Entity);
+
+    impl FromWorld for MyEntityRef {
+        fn from_world(_world: &mut World) -> Self {
+            Self(Entity::PLACEHOLDER)
+        }
+    }
+
+    fn create_world() -> World {
         let mut world = World::new();
         let registry = AppTypeRegistry::default();
         {
@@ -920,11 +1679,11 @@ mod tests {
 
         assert_eq!(
             vec![
-                0, 1, 253, 255, 255, 255, 15, 1, 51, 98, 101, 118, 121, 95, 119, 111, 114, 108,
-                100, 95, 115, 101, 114, 105, 97, 108, 105, 122, 97, 116, 105, 111, 110, 58, 58,
-                115, 101, 114, 100, 101, 58, 58, 116, 101, 115, 116, 115, 58, 58, 77, 121, 67, 111,
-                109, 112, 111, 110, 101, 110, 116, 1, 2, 3, 102, 102, 166, 63, 205, 204, 108, 64,
-                1, 12, 72, 101, 108, 108, 111, 32, 87, 111, 114, 108, 100, 33
+                0, 0, 0, 1, 253, 255, 255, 255, 15, 1, 51, 98, 101, 118, 121, 95, 119, 111, 114,
+                108, 100, 95, 115, 101, 114, 105, 97, 108, 105, 122, 97, 116, 105, 111, 110, 58,
+                58, 115, 101, 114, 100, 101, 58, 58, 116, 101, 115, 116, 115, 58, 58, 77, 121, 67,
+                111, 109, 112, 111, 110, 101, 110, 116, 1, 2, 3, 102, 102, 166, 63, 205, 204, 108,
+                64, 1, 12, 72, 101, 108, 108, 111, 32, 87, 111, 114, 108, 100, 33
             ],
             serialized_world
         );
@@ -963,12 +1722,12 @@ mod tests {
 
         assert_eq!(
             vec![
-                146, 128, 129, 206, 255, 255, 255, 253, 145, 129, 217, 51, 98, 101, 118, 121, 95,
-                119, 111, 114, 108, 100, 95, 115, 101, 114, 105, 97, 108, 105, 122, 97, 116, 105,
-                111, 110, 58, 58, 115, 101, 114, 100, 101, 58, 58, 116, 101, 115, 116, 115, 58, 58,
-                77, 121, 67, 111, 109, 112, 111, 110, 101, 110, 116, 147, 147, 1, 2, 3, 146, 202,
-                63, 166, 102, 102, 202, 64, 108, 204, 205, 129, 165, 84, 117, 112, 108, 101, 172,
-                72, 101, 108, 108, 111, 32, 87, 111, 114, 108, 100, 33
+                148, 192, 192, 128, 129, 206, 255, 255, 255, 253, 145, 129, 217, 51, 98, 101, 118,
+                121, 95, 119, 111, 114, 108, 100, 95, 115, 101, 114, 105, 97, 108, 105, 122, 97,
+                116, 105, 111, 110, 58, 58, 115, 101, 114, 100, 101, 58, 58, 116, 101, 115, 116,
+                115, 58, 58, 77, 121, 67, 111, 109, 112, 111, 110, 101, 110, 116, 147, 147, 1, 2,
+                3, 146, 202, 63, 166, 102, 102, 202, 64, 108, 204, 205, 129, 165, 84, 117, 112,
+                108, 101, 172, 72, 101, 108, 108, 111, 32, 87, 111, 114, 108, 100, 33
             ],
             buf
         );
@@ -987,6 +1746,354 @@ mod tests {
         assert_world_eq(&dynamic_world, &deserialized_world);
     }
 
+    #[test]
+    fn should_roundtrip_binary() {
+        let mut world = create_world();
+
+        for i in 0..10 {
+            world.spawn((
+                Foo(i),
+                MyComponent {
+                    foo: [1, 2, 3],
+                    bar: (1.3, 3.7),
+                    baz: MyEnum::Tuple("Hello World!".to_string()),
+                },
+            ));
+        }
+        world.insert_resource(MyResource { foo: 123 });
+
+        let registry = world.resource::<AppTypeRegistry>();
+        let registry = &registry.read();
+
+        let dynamic_world = DynamicWorld::from_world(&world);
+
+        let bytes = dynamic_world.serialize_binary(registry).unwrap();
+        let verbose =
+            postcard::to_allocvec(&DynamicWorldSerializer::new(&dynamic_world, registry)).unwrap();
+        assert!(bytes.len() < verbose.len() / 2);
+
+        let world_deserializer = BinaryWorldDeserializer {
+            type_registry: registry,
+            load_from_path: &mut FakeHandleCreator,
+        };
+        let mut deserializer = postcard::Deserializer::from_bytes(&bytes);
+        let deserialized_world = world_deserializer.deserialize(&mut deserializer).unwrap();
+
+        assert_eq!(1, deserialized_world.resources.len());
+        assert_world_eq(&dynamic_world, &deserialized_world);
+    }
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Player {
+        name: String,
+        health: Health,
+        speed: f32,
+    }
+
+    #[derive(Reflect, Default, Debug, PartialEq)]
+    struct Health {
+        current: u32,
+        max: u32,
+    }
+
+    /// Migrations for `Player`: version 0 was called `OldPlayer`, with `title`, `hp` and `mana`
+    /// fields, and version 1 stored `health` as a number.
+    fn player_migrations() -> WorldMigrations {
+        let mut migrations = WorldMigrations::default();
+        migrations
+            .set_schema_version(1)
+            .register_alias::<Player>("game::OldPlayer")
+            .register_migration::<Player>(
+                0,
+                TypeMigration::new()
+                    .rename_field("title", "name")
+                    .rename_field("hp", "health")
+                    .remove_field("mana"),
+            )
+            .register_migration::<Player>(
+                1,
+                TypeMigration::new().map_field("health", |health| {
+                    let current = health
+                        .try_downcast_ref::<u64>()
+                        .copied()
+                        .unwrap_or_default();
+                    Box::new(Health {
+                        current: current as u32,
+                        max: 10,
+                    })
+                }),
+            );
+        migrations
+    }
+
+    fn create_player_world() -> World {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<Player>();
+            registry.register::<Health>();
+            registry.register::<String>();
+            registry.register_type_data::<String, ReflectSerialize>();
+        }
+        world.insert_resource(registry);
+        world
+    }
+
+    fn deserialize_migrated(
+        world: &World,
+        input: &str,
+        migrations: &WorldMigrations,
+    ) -> Result<DynamicWorld, ron::Error> {
+        let registry = world.resource::<AppTypeRegistry>().read();
+        let mut deserializer = ron::de::Deserializer::from_str(input).unwrap();
+        WorldDeserializer {
+            type_registry: &registry,
+            load_from_path: &mut FakeHandleCreator,
+        }
+        .with_migrations(migrations)
+        .deserialize(&mut deserializer)
+    }
+
+    fn deserialized_player(dynamic_world: &DynamicWorld) -> &Player {
+        dynamic_world.entities[0].components[0]
+            .try_downcast_ref::<Player>()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_migrate_old_types() {
+        let world = create_player_world();
+        let mut migrations = player_migrations();
+        migrations.set_migrate_unversioned(true);
+
+        // A world without versions is migrated from version 0 when opted in
+        let input = r#"(
+  resources: {},
+  entities: {
+    4294967293: (
+      components: {
+        "game::OldPlayer": (title: "Alice", hp: 7, speed: 1.5, mana: 3),
+      },
+    ),
+  },
+)"#;
+        let dynamic_world = deserialize_migrated(&world, input, &migrations).unwrap();
+        assert_eq!(
+            &Player {
+                name: "Alice".to_string(),
+                health: Health {
+                    current: 7,
+                    max: 10
+                },
+                speed: 1.5,
+            },
+            deserialized_player(&dynamic_world)
+        );
+
+        // A type saved at version 1 only runs the migrations from version 1
+        let input = r#"(
+  version: 1,
+  type_versions: {
+    "bevy_world_serialization::serde::tests::Player": 1,
+  },
+  resources: {},
+  entities: {
+    4294967293: (
+      components: {
+        "bevy_world_serialization::serde::tests::Player": (name: "Bob", health: 5, speed: 2.0),
+      },
+    ),
+  },
+)"#;
+        let dynamic_world = deserialize_migrated(&world, input, &migrations).unwrap();
+        assert_eq!(
+            &Player {
+                name: "Bob".to_string(),
+                health: Health {
+                    current: 5,
+                    max: 10
+                },
+                speed: 2.0,
+            },
+            deserialized_player(&dynamic_world)
+        );
+    }
+
+    #[test]
+    fn should_not_migrate_unversioned_worlds() {
+        let world = create_player_world();
+        let migrations = player_migrations();
+
+        // A world written by `DynamicWorld::serialize` is already at the current version
+        let input = r#"(
+  resources: {},
+  entities: {
+    4294967293: (
+      components: {
+        "bevy_world_serialization::serde::tests::Player": (name: "Eve", health: (current: 2, max: 3), speed: 1.0),
+      },
+    ),
+  },
+)"#;
+        let dynamic_world = deserialize_migrated(&world, input, &migrations).unwrap();
+        assert_eq!(
+            &Player {
+                name: "Eve".to_string(),
+                health: Health { current: 2, max: 3 },
+                speed: 1.0,
+            },
+            deserialized_player(&dynamic_world)
+        );
+    }
+
+    #[test]
+    fn should_roundtrip_with_migrations() {
+        let mut world = create_player_world();
+        let migrations = player_migrations();
+        world.spawn(Player {
+            name: "Carol".to_string(),
+            health: Health { current: 3, max: 4 },
+            speed: 0.5,
+        });
+
+        let dynamic_world = DynamicWorld::from_world(&world);
+        let output = {
+            let registry = world.resource::<AppTypeRegistry>().read();
+            serialize_ron(
+                DynamicWorldSerializer::new(&dynamic_world, &registry).with_migrations(&migrations),
+            )
+            .unwrap()
+        };
+        assert!(output.starts_with(
+            r#"(
+  version: 1,
+  type_versions: {
+    "bevy_world_serialization::serde::tests::Player": 2,
+  },"#
+        ));
+
+        // The current version is loaded without migrating
+        let dynamic_world = deserialize_migrated(&world, &output, &migrations).unwrap();
+        assert_eq!(
+            &Player {
+                name: "Carol".to_string(),
+                health: Health { current: 3, max: 4 },
+                speed: 0.5,
+            },
+            deserialized_player(&dynamic_world)
+        );
+
+        // Formats which aren't human-readable are versioned too
+        let registry = world.resource::<AppTypeRegistry>().read();
+        let bytes = postcard::to_allocvec(
+            &DynamicWorldSerializer::new(&dynamic_world, &registry).with_migrations(&migrations),
+        )
+        .unwrap();
+        let dynamic_world = WorldDeserializer {
+            type_registry: &registry,
+            load_from_path: &mut FakeHandleCreator,
+        }
+        .with_migrations(&migrations)
+        .deserialize(&mut postcard::Deserializer::from_bytes(&bytes))
+        .unwrap();
+        assert_eq!(
+            &Player {
+                name: "Carol".to_string(),
+                health: Health { current: 3, max: 4 },
+                speed: 0.5,
+            },
+            deserialized_player(&dynamic_world)
+        );
+
+        let bytes = postcard::to_allocvec(
+            &BinaryWorldSerializer::new(&dynamic_world, &registry).with_migrations(&migrations),
+        )
+        .unwrap();
+        let dynamic_world = BinaryWorldDeserializer {
+            type_registry: &registry,
+            load_from_path: &mut FakeHandleCreator,
+        }
+        .with_migrations(&migrations)
+        .deserialize(&mut postcard::Deserializer::from_bytes(&bytes))
+        .unwrap();
+        assert_eq!(
+            &Player {
+                name: "Carol".to_string(),
+                health: Health { current: 3, max: 4 },
+                speed: 0.5,
+            },
+            deserialized_player(&dynamic_world)
+        );
+    }
+
+    #[test]
+    fn should_check_binary_versions() {
+        let mut world = create_player_world();
+        world.spawn(Player::default());
+        let dynamic_world = DynamicWorld::from_world(&world);
+        let registry = world.resource::<AppTypeRegistry>().read();
+
+        // Saved before `Player` had any migrations, so at version 0
+        let bytes = postcard::to_allocvec(
+            &BinaryWorldSerializer::new(&dynamic_world, &registry)
+                .with_migrations(&WorldMigrations::default()),
+        )
+        .unwrap();
+        assert!(BinaryWorldDeserializer {
+            type_registry: &registry,
+            load_from_path: &mut FakeHandleCreator,
+        }
+        .with_migrations(&WorldMigrations::default())
+        .deserialize(&mut postcard::Deserializer::from_bytes(&bytes))
+        .is_ok());
+
+        // `postcard` doesn't keep the message of the error, which is that the fields of `Player`
+        // were saved without their names
+        assert!(BinaryWorldDeserializer {
+            type_registry: &registry,
+            load_from_path: &mut FakeHandleCreator,
+        }
+        .with_migrations(&player_migrations())
+        .deserialize(&mut postcard::Deserializer::from_bytes(&bytes))
+        .is_err());
+    }
+
+    #[test]
+    fn should_fail_to_migrate() {
+        let world = create_player_world();
+        let migrations = player_migrations();
+        let error = |version: u32, component: &str| {
+            let input = format!(
+                "(version: {version}, type_versions: {{}}, resources: {{}}, entities: {{4294967293: (components: {{{component}}})}})"
+            );
+            let Err(error) = deserialize_migrated(&world, &input, &migrations) else {
+                panic!("expected `{component}` to fail to load");
+            };
+            error.to_string()
+        };
+
+        assert!(error(0, r#""game::Unknown": ()"#)
+            .contains("no registration found for `game::Unknown`"));
+        assert!(error(2, r#""game::OldPlayer": ()"#)
+            .contains("schema version 2, which is newer than the current version 1"));
+        assert!(error(
+            0,
+            r#""game::OldPlayer": (title: "Dave", hp: 1, speed: 1.0, level: 3)"#
+        )
+        .contains("has no field `level` after migrating it"));
+    }
+
+    #[test]
+    fn should_reject_migration_from_last_version() {
+        let mut migrations = player_migrations();
+        assert!(migrations
+            .try_register_migration::<Player>(u32::MAX, TypeMigration::new())
+            .is_err());
+        assert_eq!(migrations.type_version::<Player>(), 2);
+    }
+
     /// A crude equality checker for [`DynamicWorld`], used solely for testing purposes.
     fn assert_world_eq(expected: &DynamicWorld, received: &DynamicWorld) {
         assert_eq!(
@@ -6,7 +6,10 @@ use bevy_reflect::{TypePath, TypeRegistryArc};
 
 #[cfg(feature = "serialize")]
 use {
-    crate::{serde::WorldDeserializer, DynamicWorld},
+    crate::{
+        serde::{BinaryWorldDeserializer, WorldDeserializer},
+        AppWorldMigrations, DynamicWorld,
+    },
     bevy_asset::{io::Reader, AssetLoader, LoadContext},
     serde::de::DeserializeSeed,
     thiserror::Error,
@@ -14,7 +17,10 @@ use {
 
 /// Asset loader for a Bevy dynamic world (`.scn` / `.scn.ron`).
 ///
-/// The loader handles assets serialized with [`DynamicWorld::serialize`].
+/// The loader handles assets serialized with [`DynamicWorld::serialize`]. Worlds serialized with
+/// [`DynamicWorldSerializer::with_migrations`] are migrated with the [`AppWorldMigrations`] of the app.
+///
+/// [`DynamicWorldSerializer::with_migrations`]: crate::serde::DynamicWorldSerializer::with_migrations
 #[derive(Debug, TypePath)]
 pub struct WorldAssetLoader {
     #[cfg_attr(
@@ -22,6 +28,8 @@ pub struct WorldAssetLoader {
         expect(dead_code, reason = "only used with `serialize` feature")
     )]
     type_registry: TypeRegistryArc,
+    #[cfg(feature = "serialize")]
+    migrations: AppWorldMigrations,
 }
 
 impl FromWorld for WorldAssetLoader {
@@ -29,6 +37,8 @@ impl FromWorld for WorldAssetLoader {
         let type_registry = world.resource::<AppTypeRegistry>();
         WorldAssetLoader {
             type_registry: type_registry.0.clone(),
+            #[cfg(feature = "serialize")]
+            migrations: world.get_resource_or_init::<AppWorldMigrations>().clone(),
         }
     }
 }
@@ -66,6 +76,7 @@ impl AssetLoader for WorldAssetLoader {
             load_from_path: load_context,
         };
         Ok(scene_deserializer
+            .with_migrations(&self.migrations.read())
             .deserialize(&mut deserializer)
             .map_err(|e| deserializer.span_error(e))?)
     }
@@ -74,3 +85,69 @@ impl AssetLoader for WorldAssetLoader {
         &["scn", "scn.ron"]
     }
 }
+
+/// Asset loader for a Bevy dynamic world in the compact binary format (`.scn.bin`).
+///
+/// The loader handles assets serialized with [`DynamicWorld::serialize_binary`]. Worlds serialized with
+/// [`BinaryWorldSerializer::with_migrations`] are migrated with the [`AppWorldMigrations`] of the app.
+///
+/// [`BinaryWorldSerializer::with_migrations`]: crate::serde::BinaryWorldSerializer::with_migrations
+#[cfg(feature = "serialize")]
+#[derive(Debug, TypePath)]
+pub struct BinaryWorldAssetLoader {
+    type_registry: TypeRegistryArc,
+    migrations: AppWorldMigrations,
+}
+
+#[cfg(feature = "serialize")]
+impl FromWorld for BinaryWorldAssetLoader {
+    fn from_world(world: &mut World) -> Self {
+        let type_registry = world.resource::<AppTypeRegistry>();
+        BinaryWorldAssetLoader {
+            type_registry: type_registry.0.clone(),
+            migrations: world.get_resource_or_init::<AppWorldMigrations>().clone(),
+        }
+    }
+}
+
+/// Possible errors that can be produced by [`BinaryWorldAssetLoader`]
+#[cfg(feature = "serialize")]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum BinaryWorldAssetLoaderError {
+    /// An [IO Error](std::io::Error)
+    #[error("Error while trying to read the world file: {0}")]
+    Io(#[from] std::io::Error),
+    /// A [Postcard Error](postcard::Error)
+    #[error("Could not parse binary world: {0}")]
+    Postcard(#[from] postcard::Error),
+}
+
+#[cfg(feature = "serialize")]
+impl AssetLoader for BinaryWorldAssetLoader {
+    type Asset = DynamicWorld;
+    type Settings = ();
+    type Error = BinaryWorldAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut deserializer = postcard::Deserializer::from_bytes(&bytes);
+        let scene_deserializer = BinaryWorldDeserializer {
+            type_registry: &self.type_registry.read(),
+            load_from_path: load_context,
+        };
+        Ok(scene_deserializer
+            .with_migrations(&self.migrations.read())
+            .deserialize(&mut deserializer)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["scn.bin"]
+    }
+}